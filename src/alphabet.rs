@@ -0,0 +1,396 @@
+use super::{Automaton, AutomatonError, DFA, NFA};
+use std::{
+	collections::{BTreeSet, HashSet},
+	fmt,
+	hash::Hash,
+};
+
+/// Trait for classifying raw inputs into the symbols used as inputs of an automaton.
+///
+/// Implemented by `Exact` for raw inputs which are symbols themselves, by `Ranges` for range tables
+/// & by closures returning an optional symbol.
+pub trait Alphabet<R> {
+	/// Symbol type used as input of the automaton.
+	type Symbol;
+
+	/// Returns the symbol of a raw input.
+	/// Returns `None` if the raw input does not belong to any symbol.
+	fn classify(&self, raw: &R) -> Option<Self::Symbol>;
+}
+
+/// Alphabet using raw inputs as symbols.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Exact;
+
+impl<R> Alphabet<R> for Exact
+where
+	R: Clone,
+{
+	type Symbol = R;
+
+	fn classify(&self, raw: &R) -> Option<R> {
+		Some(raw.clone())
+	}
+}
+
+/// Alphabet mapping inclusive ranges of raw inputs, e.g. chars or bytes, to symbol classes.
+///
+/// Ranges are expected to be disjoint.
+/// Raw inputs outside of all ranges belong to the default class, if there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranges<R, C> {
+	ranges: Vec<(R, R, C)>,
+	default: Option<C>,
+}
+
+impl<R, C> Ranges<R, C>
+where
+	R: Ord,
+{
+	/// Creates a new range table without ranges & default class.
+	pub fn new() -> Self {
+		Self {
+			ranges: Vec::new(),
+			default: None,
+		}
+	}
+
+	/// Adds an inclusive range of raw inputs belonging to a class.
+	pub fn with_range(mut self, start: R, end: R, class: C) -> Self {
+		let position = self.ranges.partition_point(|(other, _, _)| *other < start);
+		self.ranges.insert(position, (start, end, class));
+		self
+	}
+
+	/// Sets the class of raw inputs outside of all ranges.
+	pub fn with_default(mut self, class: C) -> Self {
+		self.default = Some(class);
+		self
+	}
+}
+
+impl<R, C> Default for Ranges<R, C>
+where
+	R: Ord,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<R, C> Alphabet<R> for Ranges<R, C>
+where
+	R: Ord,
+	C: Clone,
+{
+	type Symbol = C;
+
+	fn classify(&self, raw: &R) -> Option<C> {
+		let position = self.ranges.partition_point(|(start, _, _)| start <= raw);
+		position
+			.checked_sub(1)
+			.map(|position| &self.ranges[position])
+			.filter(|(_, end, _)| raw <= end)
+			.map(|(_, _, class)| class)
+			.or(self.default.as_ref())
+			.cloned()
+	}
+}
+
+impl<R, C, F> Alphabet<R> for F
+where
+	F: Fn(&R) -> Option<C>,
+{
+	type Symbol = C;
+
+	fn classify(&self, raw: &R) -> Option<C> {
+		self(raw)
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Declares the inputs accepted by the automaton.
+	///
+	/// Existing transitions for other inputs are kept.
+	pub fn declare_alphabet<V>(&mut self, alphabet: V)
+	where
+		V: IntoIterator<Item = I>,
+	{
+		self.alphabet = Some(alphabet.into_iter().collect());
+	}
+
+	/// Returns the declared alphabet.
+	/// Returns `None` if no alphabet was declared.
+	pub fn alphabet(&self) -> Option<&HashSet<I>> {
+		self.alphabet.as_ref()
+	}
+
+	/// Performs a single state transition, validating the input against the declared alphabet.
+	/// Returns an `AutomatonError::UnknownInput` error without changing the current state if the input is not part of it.
+	/// Returns an `AutomatonError::InexistentState` error without changing the current state if the transition leads to an undeclared state.
+	///
+	/// Without a declared alphabet & undeclared states, this behaves like `step`.
+	pub fn try_step(&mut self, input: &I) -> Result<(), AutomatonError<S, I>>
+	where
+		I: Clone,
+		T: Default,
+	{
+		if !self.knows(input) {
+			return Err(AutomatonError::UnknownInput(input.clone()));
+		}
+		let dangling = self
+			.current
+			.and_then(|current| self.state(current))
+			.and_then(|state| state.transitions.get(input))
+			.map(|(next, _)| *next)
+			.filter(|next| self.states[*next].is_none());
+		if let Some(next) = dangling {
+			return Err(AutomatonError::InexistentState(
+				self.ids[next].clone(),
+				Vec::new(),
+			));
+		}
+		self.step(input);
+		Ok(())
+	}
+
+	/// Performs a single state transition for the symbol of a raw input.
+	/// If the raw input does not belong to any symbol, the automaton goes into an invalid state.
+	pub fn step_classified<R, A>(&mut self, alphabet: &A, raw: &R)
+	where
+		A: Alphabet<R, Symbol = I>,
+		T: Default,
+	{
+		match alphabet.classify(raw) {
+			Some(symbol) => self.step(&symbol),
+			None => self.current = None,
+		}
+	}
+
+	/// Runs the automaton on a sequence of raw inputs classified into symbols.
+	/// This restores the current state after the execution.
+	pub fn run_classified<'a, R, A, V>(&mut self, alphabet: &A, raws: V) -> bool
+	where
+		A: Alphabet<R, Symbol = I>,
+		V: IntoIterator<Item = &'a R>,
+		R: 'a,
+		T: Default,
+	{
+		let current = self.current;
+		for raw in raws {
+			self.step_classified(alphabet, raw);
+		}
+		let result = self.accepts();
+		self.current = current;
+		result
+	}
+
+	/// Checks whether an input is part of the declared alphabet or no alphabet was declared.
+	pub(crate) fn knows(&self, input: &I) -> bool {
+		match &self.alphabet {
+			Some(alphabet) => alphabet.contains(input),
+			None => true,
+		}
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Declares the inputs accepted by the automaton.
+	///
+	/// Existing transitions for other inputs are kept.
+	pub fn declare_alphabet<V>(&mut self, alphabet: V)
+	where
+		V: IntoIterator<Item = I>,
+	{
+		self.alphabet = Some(alphabet.into_iter().collect());
+	}
+
+	/// Returns the declared alphabet.
+	/// Returns `None` if no alphabet was declared.
+	pub fn alphabet(&self) -> Option<&HashSet<I>> {
+		self.alphabet.as_ref()
+	}
+
+	/// Performs a single state transition, validating the input against the declared alphabet.
+	/// Returns an `AutomatonError::UnknownInput` error without changing the current states if the input is not part of it.
+	///
+	/// Without a declared alphabet, this behaves like `step`.
+	pub fn try_step(&mut self, input: &I) -> Result<(), AutomatonError<S, I>>
+	where
+		I: Clone,
+		T: Default,
+	{
+		if !self.knows(input) {
+			return Err(AutomatonError::UnknownInput(input.clone()));
+		}
+		self.step(input);
+		Ok(())
+	}
+
+	/// Performs a single state transition for the symbol of a raw input.
+	/// If the raw input does not belong to any symbol, the automaton goes into an invalid state.
+	pub fn step_classified<R, A>(&mut self, alphabet: &A, raw: &R)
+	where
+		A: Alphabet<R, Symbol = I>,
+		T: Default,
+	{
+		match alphabet.classify(raw) {
+			Some(symbol) => self.step(&symbol),
+			None => self.set_current_indices(BTreeSet::new()),
+		}
+	}
+
+	/// Runs the automaton on a sequence of raw inputs classified into symbols.
+	/// This restores the current state after the execution.
+	pub fn run_classified<'a, R, A, V>(&mut self, alphabet: &A, raws: V) -> bool
+	where
+		A: Alphabet<R, Symbol = I>,
+		V: IntoIterator<Item = &'a R>,
+		R: 'a,
+		T: Default,
+	{
+		let current = self.current_indices().clone();
+		for raw in raws {
+			self.step_classified(alphabet, raw);
+		}
+		let result = self.accepts();
+		self.set_current_indices(current);
+		result
+	}
+
+	/// Checks whether an input is part of the declared alphabet or no alphabet was declared.
+	pub(crate) fn knows(&self, input: &I) -> bool {
+		match &self.alphabet {
+			Some(alphabet) => alphabet.contains(input),
+			None => true,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dfa() {
+		let mut dfa = DFA::<u8, &str>::with_state(0, false);
+		dfa.add_state(1, true);
+		dfa.declare_alphabet(vec!["open", "close"]);
+		dfa.add_transition((0, "open", 1)).unwrap();
+		assert!(
+			dfa.add_transition((1, "clsoe", 0)).is_err(),
+			"Missing error on transition for unknown input"
+		);
+		assert!(
+			matches!(
+				dfa.try_step(&"opne"),
+				Err(AutomatonError::UnknownInput("opne"))
+			),
+			"Missing error on unknown input"
+		);
+		assert_eq!(
+			Some(&0),
+			dfa.get_current(),
+			"State changed on unknown input"
+		);
+		dfa.try_step(&"open").unwrap();
+		assert!(dfa.accepts(), "Incorrect result after step");
+
+		// known inputs without transition still lead to an invalid state
+		dfa.try_step(&"close").unwrap();
+		assert_eq!(None, dfa.get_current(), "Missing transition taken");
+	}
+
+	#[test]
+	fn nfa() {
+		let mut nfa = NFA::<u8, char>::with_state(0, true);
+		nfa.declare_alphabet(vec!['a']);
+		nfa.add_transition((0, 'a', 0)).unwrap();
+		let yaml = serde_yaml::to_string(&nfa).unwrap();
+		let mut nfa: NFA<u8, char> = serde_yaml::from_str(&yaml).unwrap();
+		assert!(
+			nfa.alphabet().unwrap().contains(&'a'),
+			"Alphabet not serialized"
+		);
+		assert!(
+			nfa.try_step(&'b').is_err(),
+			"Missing error on unknown input"
+		);
+		nfa.try_step(&'a').unwrap();
+		assert!(nfa.accepts(), "Incorrect result after step");
+
+		let mut dfa: DFA<_, _> = nfa.into();
+		assert!(dfa.try_step(&'b').is_err(), "Alphabet not converted");
+	}
+
+	#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+	enum Class {
+		#[default]
+		Letter,
+		Digit,
+		Other,
+	}
+
+	#[test]
+	fn ranges() {
+		let classes = Ranges::new()
+			.with_range('a', 'z', Class::Letter)
+			.with_range('0', '9', Class::Digit)
+			.with_range('A', 'Z', Class::Letter);
+		assert_eq!(
+			Some(Class::Letter),
+			classes.classify(&'q'),
+			"Incorrect class"
+		);
+		assert_eq!(
+			Some(Class::Digit),
+			classes.classify(&'0'),
+			"Incorrect class"
+		);
+		assert_eq!(None, classes.classify(&'_'), "Class of unknown input");
+		let classes = classes.with_default(Class::Other);
+		assert_eq!(
+			Some(Class::Other),
+			classes.classify(&'_'),
+			"Incorrect default class"
+		);
+
+		// identifiers starting with a letter
+		let mut dfa = DFA::<u8, Class>::with_state(0, false);
+		dfa.add_state(1, true);
+		dfa.add_transition((0, Class::Letter, 1)).unwrap();
+		dfa.add_transition((1, Class::Letter, 1)).unwrap();
+		dfa.add_transition((1, Class::Digit, 1)).unwrap();
+		let word: Vec<_> = "abc123".chars().collect();
+		assert!(dfa.run_classified(&classes, &word), "Identifier rejected");
+		assert!(
+			!dfa.run_classified(&classes, &['1', 'a']),
+			"Invalid identifier accepted"
+		);
+	}
+
+	#[test]
+	#[allow(clippy::manual_is_multiple_of)]
+	fn closure() {
+		let parity = |raw: &u32| Some(raw % 2 == 0);
+		let mut nfa = NFA::<u8, bool>::with_state(0, true);
+		nfa.add_transition((0, true, 0)).unwrap();
+		assert!(
+			nfa.run_classified(&parity, &[2, 4, 8]),
+			"Even numbers rejected"
+		);
+		assert!(!nfa.run_classified(&parity, &[2, 3]), "Odd number accepted");
+		assert!(
+			DFA::<u8, char>::with_state(0, true).run_classified(&Exact, &[]),
+			"Empty word rejected"
+		);
+	}
+}
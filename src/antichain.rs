@@ -0,0 +1,179 @@
+//! Universality & inclusion checks using antichains of sets of states.
+//!
+//! Instead of constructing all subsets of states, only sets which are not subsumed by a smaller set are explored.
+//! A smaller set of states can only accept fewer words, so it suffices to search for counterexamples from it.
+
+use super::{graph::Graph, NFA};
+use std::{collections::BTreeSet, fmt, hash::Hash};
+
+impl<'a, S, I> Graph<'a, S, I>
+where
+	I: Eq,
+{
+	/// Computes the states reached from a set of states with an input.
+	fn post(&self, set: &BTreeSet<usize>, input: &I) -> BTreeSet<usize> {
+		set.iter()
+			.flat_map(|state| &self.edges[*state])
+			.filter(|(el, _)| *el == input)
+			.map(|(_, next)| *next)
+			.collect()
+	}
+
+	/// Checks whether a set of states contains an accepting state.
+	fn accepting(&self, set: &BTreeSet<usize>) -> bool {
+		set.iter().any(|state| self.accepts[*state])
+	}
+
+	/// Checks whether the graph accepts every word over the inputs of its transitions.
+	fn is_universal(&self) -> bool {
+		let mut alphabet: Vec<&I> = Vec::new();
+		for (input, _) in self.edges.iter().flatten() {
+			if !alphabet.contains(input) {
+				alphabet.push(*input);
+			}
+		}
+		let initial: BTreeSet<usize> = self.initial.iter().cloned().collect();
+		if !self.accepting(&initial) {
+			return false;
+		}
+		let mut antichain = vec![initial.clone()];
+		let mut stack = vec![initial];
+		while let Some(set) = stack.pop() {
+			for input in &alphabet {
+				let next = self.post(&set, input);
+				if !self.accepting(&next) {
+					return false;
+				}
+				if !insert(&mut antichain, &next) {
+					continue;
+				}
+				stack.push(next);
+			}
+		}
+		true
+	}
+
+	/// Checks whether every word accepted by the graph is accepted by another graph.
+	fn is_included_in<R>(&self, other: &Graph<R, I>) -> bool {
+		let initial: BTreeSet<usize> = other.initial.iter().cloned().collect();
+		let mut antichains = vec![Vec::new(); self.len()];
+		let mut stack = Vec::new();
+		for state in &self.initial {
+			if insert(&mut antichains[*state], &initial) {
+				stack.push((*state, initial.clone()));
+			}
+		}
+		while let Some((state, set)) = stack.pop() {
+			if self.accepts[state] && !other.accepting(&set) {
+				return false;
+			}
+			for (input, next) in &self.edges[state] {
+				let post = other.post(&set, input);
+				if insert(&mut antichains[*next], &post) {
+					stack.push((*next, post));
+				}
+			}
+		}
+		true
+	}
+}
+
+/// Inserts a set into an antichain of minimal sets, removing all sets it subsumes.
+/// Returns `false` if the set is already subsumed by a set of the antichain.
+fn insert(antichain: &mut Vec<BTreeSet<usize>>, set: &BTreeSet<usize>) -> bool {
+	if antichain.iter().any(|el| el.is_subset(set)) {
+		return false;
+	}
+	antichain.retain(|el| !set.is_subset(el));
+	antichain.push(set.clone());
+	true
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Checks whether the NFA accepts every word over the inputs used in its transitions.
+	///
+	/// This avoids the determinization of the NFA by only exploring minimal sets of states.
+	pub fn is_universal(&self) -> bool {
+		self.graph().is_universal()
+	}
+
+	/// Checks whether every word accepted by the NFA is also accepted by another NFA.
+	///
+	/// Unlike `is_subset_of`, this avoids the determinization of the other NFA by only exploring minimal sets of its states.
+	pub fn is_included_in<R, U>(&self, other: &NFA<R, I, U>) -> bool
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		self.graph().is_included_in(&other.graph())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::{hashmap, hashset};
+
+	/// Accepts words over 'a' & 'b' with an 'a' at the second to last position.
+	fn second_last_a() -> NFA<u8, char> {
+		NFA::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![0, 1], 'b' => hashset![0])),
+				1 => (false, hashmap!('a' => hashset![2], 'b' => hashset![2])),
+				2 => (true, hashmap!())
+			),
+		)
+	}
+
+	#[test]
+	fn universal() {
+		let all = NFA::<u8, char>::from_map(
+			hashset![0, 1],
+			hashmap!(
+				0 => (true, hashmap!('a' => hashset![0])),
+				1 => (true, hashmap!('a' => hashset![1], 'b' => hashset![0, 1]))
+			),
+		);
+		assert!(all.is_universal(), "Universal NFA not detected");
+		assert!(
+			!second_last_a().is_universal(),
+			"Non-universal NFA detected as universal"
+		);
+	}
+
+	#[test]
+	fn inclusion() {
+		let nfa = second_last_a();
+		let aa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![1])),
+				1 => (false, hashmap!('a' => hashset![2])),
+				2 => (true, hashmap!())
+			),
+		);
+		assert!(aa.is_included_in(&nfa), "Inclusion not detected");
+		assert!(!nfa.is_included_in(&aa), "Incorrect inclusion detected");
+		assert!(nfa.is_included_in(&nfa), "NFA not included in itself");
+	}
+
+	#[test]
+	fn epsilon() {
+		assert!(
+			crate::regex::compile("a*").unwrap().is_universal(),
+			"Universal NFA not detected"
+		);
+		assert!(
+			!crate::regex::compile("(a|b)*b").unwrap().is_universal(),
+			"Non-universal NFA detected as universal"
+		);
+		let ab = crate::regex::compile("ab").unwrap();
+		let stars = crate::regex::compile("a*b*").unwrap();
+		assert!(ab.is_included_in(&stars), "Inclusion not detected");
+		assert!(!stars.is_included_in(&ab), "Incorrect inclusion detected");
+	}
+}
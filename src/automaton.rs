@@ -1,6 +1,10 @@
 use std::fmt;
 
 /// Trait representing an abstract automaton.
+///
+/// An automaton has an initial state, which only changes with `set_initial`, & a current state.
+/// Stepping changes the current state & may lead into an invalid state, in which all further inputs are rejected.
+/// `reset` returns to the initial state, `run` executes from the current state & leaves it unchanged.
 pub trait Automaton<S, I>
 where
 	Self: Default,
@@ -23,6 +27,7 @@ where
 	fn with_state(id: S, accept: bool) -> Self {
 		let mut automaton = Self::new();
 		automaton.add_state(id.clone(), accept);
+		automaton.set_initial(Self::new_state(id.clone()));
 		automaton.set_current(Self::new_state(id));
 		automaton
 	}
@@ -39,6 +44,7 @@ where
 		for (id, accept) in states {
 			automaton.add_state(id, accept);
 		}
+		automaton.set_initial(initial.clone());
 		automaton.set_current(initial);
 		automaton
 	}
@@ -48,7 +54,7 @@ where
 		initial: Self::State,
 		states: V,
 		transitions: T,
-	) -> Result<Self, AutomatonError<S>>
+	) -> Result<Self, AutomatonError<S, I>>
 	where
 		V: IntoIterator<Item = (S, bool)>,
 		T: IntoIterator<Item = Self::Transition>,
@@ -68,7 +74,16 @@ where
 
 	/// Adds a new transition to the automaton.
 	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
-	fn add_transition(&mut self, transition: Self::Transition) -> Result<(), AutomatonError<S>>;
+	/// Returns an `AutomatonError::UnknownInput` error if the automaton rejects the input.
+	fn add_transition(&mut self, transition: Self::Transition) -> Result<(), AutomatonError<S, I>>;
+
+	/// Updates the initial state.
+	/// If the automaton does not have the passed state, the initial state will be invalid.
+	fn set_initial(&mut self, state: Self::State);
+
+	/// Gets the initial state.
+	/// Returns None if the initial state is invalid.
+	fn get_initial(&self) -> Option<&Self::State>;
 
 	/// Updates the current state.
 	/// If the automaton does not have the passed state, it will go into an invalid state.
@@ -78,6 +93,14 @@ where
 	/// Returns None if the current state is invalid.
 	fn get_current(&self) -> Option<&Self::State>;
 
+	/// Resets the current state to the initial state.
+	/// This recovers the automaton from an invalid state reached by stepping.
+	fn reset(&mut self) {
+		if let Some(initial) = self.get_initial().cloned() {
+			self.set_current(initial);
+		}
+	}
+
 	/// Checks whether the current state is accepting.
 	fn accepts(&self) -> bool;
 
@@ -85,7 +108,7 @@ where
 	fn step(&mut self, input: &I);
 
 	/// Runs the automaton on a sequence of inputs.
-	/// This automatically resets the automaton after the execution.
+	/// This restores the current state after the execution.
 	fn run<'a, V>(&mut self, inputs: V) -> bool
 	where
 		V: IntoIterator<Item = &'a I>,
@@ -106,22 +129,101 @@ where
 	}
 }
 
+/// Opaque handle to a state of an automaton.
+///
+/// Handles are cheap to copy & compare and only valid for the automaton they were obtained from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StateId(pub(crate) usize);
+
 /// Enum representing an error.
+///
+/// Errors of operations validating inputs carry the input type `I`, other errors use `()`.
+///
+/// States in errors are inexistent, so they have no display names & are shown by their IDs.
+/// The suggested states are declared and shown by their display names.
+/// Use `describe` to show the IDs differently.
 #[derive(Debug)]
-pub enum AutomatonError<S>
+pub enum AutomatonError<S, I = ()>
 where
 	S: fmt::Debug,
 {
-	InexistentState(S),
+	/// Inexistent state with the names of similar states as suggestions, which may be empty.
+	InexistentState(S, Vec<String>),
+	InvalidStateId(StateId),
+	LimitExceeded(usize),
+	/// Input outside of the declared alphabet.
+	UnknownInput(I),
+}
+
+impl<S, I> AutomatonError<S, I>
+where
+	S: fmt::Debug,
+{
+	/// Drops the input of an `AutomatonError::UnknownInput` error, keeping all other errors.
+	pub fn without_input(self) -> AutomatonError<S> {
+		match self {
+			Self::InexistentState(state, suggestions) => {
+				AutomatonError::InexistentState(state, suggestions)
+			}
+			Self::InvalidStateId(id) => AutomatonError::InvalidStateId(id),
+			Self::LimitExceeded(limit) => AutomatonError::LimitExceeded(limit),
+			Self::UnknownInput(_) => AutomatonError::UnknownInput(()),
+		}
+	}
 }
 
-impl<S> fmt::Display for AutomatonError<S>
+impl<S, I> AutomatonError<S, I>
 where
 	S: fmt::Debug,
+	I: fmt::Debug,
+{
+	/// Describes the error, using a function to name the inexistent states involved.
+	pub fn describe<F>(&self, name: F) -> String
+	where
+		F: Fn(&S) -> String,
+	{
+		match self {
+			Self::InexistentState(state, suggestions) if suggestions.is_empty() => {
+				format!("Inexistent State \"{}\"", name(state))
+			}
+			Self::InexistentState(state, suggestions) => format!(
+				"Inexistent State \"{}\", did you mean {}?",
+				name(state),
+				quoted(suggestions)
+			),
+			_ => self.to_string(),
+		}
+	}
+}
+
+impl<S, I> fmt::Display for AutomatonError<S, I>
+where
+	S: fmt::Debug,
+	I: fmt::Debug,
 {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
-			Self::InexistentState(state) => write!(f, "Inexistent State ID \"{:?}\"", state),
+			Self::InexistentState(state, suggestions) if suggestions.is_empty() => {
+				write!(f, "Inexistent State ID \"{:?}\"", state)
+			}
+			Self::InexistentState(state, suggestions) => write!(
+				f,
+				"Inexistent State ID \"{:?}\", did you mean {}?",
+				state,
+				quoted(suggestions)
+			),
+			Self::InvalidStateId(StateId(index)) => write!(f, "Invalid State handle {}", index),
+			Self::LimitExceeded(limit) => write!(f, "Limit of {} States exceeded", limit),
+			Self::UnknownInput(input) => write!(f, "Unknown Input {:?}", input),
 		}
 	}
 }
+
+/// Joins suggested state names in quotes.
+fn quoted(suggestions: &[String]) -> String {
+	suggestions
+		.iter()
+		.map(|name| format!("\"{}\"", name))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
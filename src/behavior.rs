@@ -0,0 +1,167 @@
+use super::{InputKey, DFA};
+use std::{fmt, hash::Hash};
+
+/// Guard deciding whether a transition may be taken.
+type Guard<C, E> = Box<dyn Fn(&C, &E) -> bool>;
+
+/// Action performed when a transition is taken.
+type Action<C, E> = Box<dyn Fn(&mut C, &E)>;
+
+/// Transition payload with an optional guard & action on a user context of type `C` for events of type `E`.
+pub struct Behavior<C, E> {
+	guard: Option<Guard<C, E>>,
+	action: Option<Action<C, E>>,
+	internal: bool,
+}
+
+impl<C, E> Behavior<C, E> {
+	/// Creates a new behavior without guard & action.
+	pub fn new() -> Self {
+		Self {
+			guard: None,
+			action: None,
+			internal: false,
+		}
+	}
+
+	/// Sets the guard deciding whether the transition may be taken.
+	pub fn with_guard<F>(mut self, guard: F) -> Self
+	where
+		F: Fn(&C, &E) -> bool + 'static,
+	{
+		self.guard = Some(Box::new(guard));
+		self
+	}
+
+	/// Sets the action performed when the transition is taken.
+	pub fn with_action<F>(mut self, action: F) -> Self
+	where
+		F: Fn(&mut C, &E) + 'static,
+	{
+		self.action = Some(Box::new(action));
+		self
+	}
+
+	/// Marks the transition as internal.
+	///
+	/// Internal self-transitions only perform their action without leaving & re-entering the state.
+	/// Transitions to other states are always external.
+	pub fn internal(mut self) -> Self {
+		self.internal = true;
+		self
+	}
+
+	/// Checks whether the transition is marked as internal.
+	pub fn is_internal(&self) -> bool {
+		self.internal
+	}
+
+	/// Checks whether the guard allows the transition.
+	/// Returns `true` if there is no guard.
+	pub fn allows(&self, ctx: &C, event: &E) -> bool {
+		self.guard.as_ref().is_none_or(|guard| guard(ctx, event))
+	}
+
+	/// Performs the action, if there is one.
+	pub fn perform(&self, ctx: &mut C, event: &E) {
+		if let Some(action) = &self.action {
+			action(ctx, event);
+		}
+	}
+}
+
+impl<C, E> Default for Behavior<C, E> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C, E> fmt::Debug for Behavior<C, E> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Behavior")
+			.field("guard", &self.guard.is_some())
+			.field("action", &self.action.is_some())
+			.field("internal", &self.internal)
+			.finish()
+	}
+}
+
+impl<S, I, C, E> DFA<S, I, Behavior<C, E>>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+	E: InputKey<Key = I>,
+{
+	/// Performs a single state transition for an event, passing a user context to the guard & action of the transition.
+	/// Returns whether a transition was taken.
+	///
+	/// If the guard rejects the transition, the current state is kept.
+	/// If there is no transition for the event, the automaton goes into an invalid state.
+	pub fn step_with(&mut self, event: &E, ctx: &mut C) -> bool {
+		let current = match self.current {
+			Some(current) => current,
+			None => return false,
+		};
+		let next = self.transition(current, &event.key());
+		match next {
+			Some((next, behavior)) => {
+				if !behavior.allows(ctx, event) {
+					return false;
+				}
+				behavior.perform(ctx, event);
+				self.current = Some(*next);
+				true
+			}
+			None => {
+				self.current = None;
+				false
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	/// Counter allowing up to 3 increments.
+	fn counter() -> DFA<&'static str, char, Behavior<u32, char>> {
+		let mut dfa = DFA::with_state("idle", true);
+		dfa.add_transition_with_payload(
+			("idle", '+', "idle"),
+			Behavior::new()
+				.with_guard(|count: &u32, _: &char| *count < 3)
+				.with_action(|count: &mut u32, _: &char| *count += 1),
+		)
+		.unwrap();
+		dfa.add_state("done", false);
+		dfa.add_transition(("idle", 'x', "done")).unwrap();
+		dfa
+	}
+
+	#[test]
+	fn context() {
+		let mut dfa = counter();
+		let mut count = 0;
+		for _ in 0..5 {
+			dfa.step_with(&'+', &mut count);
+		}
+		assert_eq!(3, count, "Guard not respected");
+		assert!(!dfa.step_with(&'+', &mut count), "Guarded transition taken");
+		assert_eq!(Some(&"idle"), dfa.get_current(), "State changed by guard");
+		assert!(
+			dfa.step_with(&'x', &mut count),
+			"Unguarded transition not taken"
+		);
+		assert!(!dfa.accepts(), "Incorrect result after step");
+	}
+
+	#[test]
+	fn missing() {
+		let mut dfa = counter();
+		let mut count = 0;
+		assert!(!dfa.step_with(&'-', &mut count), "Missing transition taken");
+		assert_eq!(None, dfa.get_current(), "Missing transition taken");
+	}
+}
@@ -0,0 +1,295 @@
+use super::NFA;
+use std::{
+	collections::HashMap,
+	fmt,
+	hash::Hash,
+	iter::{self, FromIterator},
+};
+
+/// Number of bits per word.
+const BITS: usize = u64::BITS as usize;
+
+/// Set of small integers stored as bits, e.g. indices of states.
+///
+/// The set grows as necessary when inserting.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+	words: Vec<u64>,
+}
+
+impl BitSet {
+	/// Creates a new empty set.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a new empty set with room for integers below `len`.
+	pub fn with_capacity(len: usize) -> Self {
+		Self {
+			words: vec![0; len.div_ceil(BITS)],
+		}
+	}
+
+	/// Inserts an integer into the set.
+	pub fn insert(&mut self, value: usize) {
+		let word = value / BITS;
+		if word >= self.words.len() {
+			self.words.resize(word + 1, 0);
+		}
+		self.words[word] |= 1 << (value % BITS);
+	}
+
+	/// Checks whether the set contains an integer.
+	pub fn contains(&self, value: usize) -> bool {
+		self.words
+			.get(value / BITS)
+			.is_some_and(|word| word & (1 << (value % BITS)) != 0)
+	}
+
+	/// Checks whether the set is empty.
+	pub fn is_empty(&self) -> bool {
+		self.words.iter().all(|word| *word == 0)
+	}
+
+	/// Returns the number of integers in the set.
+	pub fn len(&self) -> usize {
+		self.words
+			.iter()
+			.map(|word| word.count_ones() as usize)
+			.sum()
+	}
+
+	/// Removes all integers from the set, keeping its capacity.
+	pub fn clear(&mut self) {
+		self.words.iter_mut().for_each(|word| *word = 0);
+	}
+
+	/// Adds all integers of another set, one word at a time.
+	pub fn union_with(&mut self, other: &Self) {
+		if other.words.len() > self.words.len() {
+			self.words.resize(other.words.len(), 0);
+		}
+		for (word, other) in self.words.iter_mut().zip(&other.words) {
+			*word |= other;
+		}
+	}
+
+	/// Checks whether the set shares an integer with another set, one word at a time.
+	pub fn intersects(&self, other: &Self) -> bool {
+		self.words
+			.iter()
+			.zip(&other.words)
+			.any(|(word, other)| word & other != 0)
+	}
+
+	/// Returns an iterator over the integers in ascending order.
+	pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+		self.words.iter().enumerate().flat_map(|(i, word)| {
+			let mut word = *word;
+			iter::from_fn(move || {
+				if word == 0 {
+					None
+				} else {
+					let bit = word.trailing_zeros() as usize;
+					word &= word - 1;
+					Some(i * BITS + bit)
+				}
+			})
+		})
+	}
+}
+
+impl FromIterator<usize> for BitSet {
+	fn from_iter<V>(iter: V) -> Self
+	where
+		V: IntoIterator<Item = usize>,
+	{
+		let mut set = Self::new();
+		for value in iter {
+			set.insert(value);
+		}
+		set
+	}
+}
+
+/// Simulation of an NFA keeping the current states as bitset over the state indices.
+///
+/// The successors of each state & input are precomputed as bitsets, so a step combines them with bitwise or.
+/// The simulation borrows the NFA & leaves its current states unchanged.
+#[derive(Debug)]
+pub struct BitSimulation<'a, S, I, T = ()>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	nfa: &'a NFA<S, I, T>,
+	successors: Vec<HashMap<&'a I, BitSet>>,
+	accepting: BitSet,
+	initial: BitSet,
+	current: BitSet,
+}
+
+impl<'a, S, I, T> BitSimulation<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Creates a new simulation starting from the current states of an NFA.
+	pub fn new(nfa: &'a NFA<S, I, T>) -> Self {
+		let accepting = nfa
+			.declared()
+			.filter(|(_, state)| state.accepts)
+			.map(|(index, _)| index)
+			.collect();
+		let len = nfa.states.len();
+		let successors = nfa
+			.states
+			.iter()
+			.map(|state| {
+				state
+					.iter()
+					.flat_map(|state| &state.transitions)
+					.map(|(input, targets)| {
+						let targets = targets.keys().cloned().collect();
+						let mut set = BitSet::with_capacity(len);
+						for target in nfa.epsilon_closure(&targets) {
+							set.insert(target);
+						}
+						(input, set)
+					})
+					.collect()
+			})
+			.collect();
+		Self {
+			nfa,
+			successors,
+			accepting,
+			initial: nfa
+				.epsilon_closure(nfa.initial_indices())
+				.into_iter()
+				.collect(),
+			current: nfa
+				.epsilon_closure(nfa.current_indices())
+				.into_iter()
+				.collect(),
+		}
+	}
+
+	/// Returns the IDs of the current states.
+	pub fn current(&self) -> impl Iterator<Item = &S> + '_ {
+		self.current.iter().map(move |index| &self.nfa.ids[index])
+	}
+
+	/// Resets the current states to the initial states of the NFA.
+	pub fn reset(&mut self) {
+		self.current = self.initial.clone();
+	}
+
+	/// Checks whether any current state is accepting.
+	pub fn accepts(&self) -> bool {
+		self.current.intersects(&self.accepting)
+	}
+
+	/// Performs a single state transition.
+	pub fn step(&mut self, input: &I) {
+		let mut next = BitSet::with_capacity(self.successors.len());
+		for index in self.current.iter() {
+			if let Some(successors) = self.successors[index].get(input) {
+				next.union_with(successors);
+			}
+		}
+		self.current = next;
+	}
+
+	/// Runs the simulation on a sequence of inputs, starting from the current states.
+	/// The current states are not changed.
+	pub fn run<'b, V>(&mut self, inputs: V) -> bool
+	where
+		V: IntoIterator<Item = &'b I>,
+		I: 'b,
+	{
+		let current = self.current.clone();
+		for input in inputs {
+			self.step(input);
+		}
+		let result = self.accepts();
+		self.current = current;
+		result
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Creates a simulation keeping the current states as bitset, starting from the current states.
+	pub fn bit_simulation(&self) -> BitSimulation<'_, S, I, T> {
+		BitSimulation::new(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	#[test]
+	fn set() {
+		let mut set: BitSet = vec![3, 64, 130].into_iter().collect();
+		assert!(set.contains(64), "Missing integer");
+		assert!(!set.contains(65), "Incorrect integer");
+		assert_eq!(
+			vec![3, 64, 130],
+			set.iter().collect::<Vec<_>>(),
+			"Incorrect iteration"
+		);
+		set.union_with(&vec![1, 200].into_iter().collect());
+		assert_eq!(5, set.len(), "Incorrect union");
+		assert!(
+			!set.intersects(&vec![2, 65].into_iter().collect()),
+			"Incorrect intersection"
+		);
+		set.clear();
+		assert!(set.is_empty(), "Set not cleared");
+	}
+
+	#[test]
+	fn simulate() {
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, false);
+		nfa.add_state(2, true);
+		nfa.add_transition((0, 'a', 0)).unwrap();
+		nfa.add_transition((0, 'b', 0)).unwrap();
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		nfa.add_transition((1, 'b', 2)).unwrap();
+		let mut simulation = nfa.bit_simulation();
+		assert!(simulation.run(&['b', 'a', 'b']), "Word rejected");
+		assert!(!simulation.run(&['a', 'b', 'a']), "Word accepted");
+		simulation.step(&'a');
+		let mut current: Vec<_> = simulation.current().cloned().collect();
+		current.sort();
+		assert_eq!(vec![0, 1], current, "Incorrect current states");
+		simulation.step(&'c');
+		assert_eq!(0, simulation.current().count(), "Incorrect current states");
+		simulation.reset();
+		assert_eq!(
+			vec![&0],
+			simulation.current().collect::<Vec<_>>(),
+			"Incorrect reset"
+		);
+	}
+
+	#[test]
+	fn dangling() {
+		let yaml =
+			r"{initial: [0], states: {0: {transitions: {a: [0, 1, 2]}}, 1: {accepts: true}}}";
+		let nfa: NFA<u8, char> = serde_yaml::from_str(yaml).unwrap();
+		let mut simulation = nfa.bit_simulation();
+		simulation.step(&'a');
+		let mut current: Vec<_> = simulation.current().cloned().collect();
+		current.sort();
+		assert_eq!(vec![0, 1], current, "Undeclared state in successors");
+		assert!(simulation.accepts(), "Incorrect acceptance");
+	}
+}
@@ -0,0 +1,159 @@
+use super::{product::Product, Automaton, DFA};
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Connective joining a component language to a boolean combination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Connective {
+	And,
+	AndNot,
+	Or,
+	OrNot,
+}
+
+/// Builder for a DFA accepting a boolean combination of the languages of several DFAs.
+///
+/// Components are combined from left to right, e.g. `a.and(b).or(c)` is evaluated as `(a & b) | c`.
+/// The first component only contributes its language, negated for `and_not` & `or_not`.
+#[derive(Debug)]
+pub struct BooleanCombination<'a, S, I, T = ()>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	components: Vec<(Connective, &'a DFA<S, I, T>)>,
+}
+
+impl<'a, S, I, T> BooleanCombination<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Creates a new combination without components.
+	pub fn new() -> Self {
+		Self {
+			components: Vec::new(),
+		}
+	}
+
+	/// Intersects the combination with the language of a DFA.
+	pub fn and(self, dfa: &'a DFA<S, I, T>) -> Self {
+		self.with(Connective::And, dfa)
+	}
+
+	/// Intersects the combination with the complement of the language of a DFA.
+	pub fn and_not(self, dfa: &'a DFA<S, I, T>) -> Self {
+		self.with(Connective::AndNot, dfa)
+	}
+
+	/// Unites the combination with the language of a DFA.
+	pub fn or(self, dfa: &'a DFA<S, I, T>) -> Self {
+		self.with(Connective::Or, dfa)
+	}
+
+	/// Unites the combination with the complement of the language of a DFA.
+	pub fn or_not(self, dfa: &'a DFA<S, I, T>) -> Self {
+		self.with(Connective::OrNot, dfa)
+	}
+
+	/// Adds a component with a connective.
+	fn with(mut self, connective: Connective, dfa: &'a DFA<S, I, T>) -> Self {
+		self.components.push((connective, dfa));
+		self
+	}
+
+	/// Evaluates the combination for the acceptance of each component.
+	fn evaluate(&self, accepts: impl Iterator<Item = bool>) -> bool {
+		let mut result = None;
+		for ((connective, _), accepts) in self.components.iter().zip(accepts) {
+			let value = match connective {
+				Connective::And | Connective::Or => accepts,
+				Connective::AndNot | Connective::OrNot => !accepts,
+			};
+			result = Some(match (result, connective) {
+				(None, _) => value,
+				(Some(result), Connective::And | Connective::AndNot) => result && value,
+				(Some(result), Connective::Or | Connective::OrNot) => result || value,
+			});
+		}
+		result.unwrap_or(false)
+	}
+
+	/// Compiles the combination into a single product DFA.
+	///
+	/// States are tuples holding the state of each component, in the order the components were added.
+	/// Components which can no longer accept are represented by `None`, which keeps the product small.
+	/// Words are formed over the inputs used in the transitions of any component.
+	pub fn compile(&self) -> DFA<Vec<Option<S>>, I> {
+		let graphs: Vec<_> = self.components.iter().map(|(_, dfa)| dfa.graph()).collect();
+		// the dead tuple is only needed if it is accepting
+		let complete = self.evaluate(self.components.iter().map(|_| false));
+		let product = Product::new(&graphs, complete);
+		let labels = product.labels(&graphs);
+		let mut dfa = DFA::from_states_map(product.table.iter().enumerate().map(|(i, row)| {
+			let accepts = self.evaluate(product.accepts(&graphs, i));
+			let transitions = row
+				.iter()
+				.map(|(input, next)| ((*input).clone(), (labels[*next].clone(), ())))
+				.collect::<HashMap<_, _>>();
+			(labels[i].clone(), (accepts, transitions))
+		}));
+		dfa.set_initial(labels[0].clone());
+		dfa.set_current(labels[0].clone());
+		dfa
+	}
+}
+
+impl<'a, S, I, T> Default for BooleanCombination<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::hashmap;
+
+	/// Accepts words over 'a' & 'b' containing an input.
+	fn contains(input: char) -> DFA<u8, char> {
+		let other = if input == 'a' { 'b' } else { 'a' };
+		DFA::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!(input => 1, other => 0)),
+				1 => (true, hashmap!('a' => 1, 'b' => 1))
+			),
+		)
+	}
+
+	#[test]
+	fn combination() {
+		let (a, b) = (contains('a'), contains('b'));
+		let mut only_a = BooleanCombination::new().and(&a).and_not(&b).compile();
+		assert!(only_a.run(&['a', 'a']), "Word without 'b' rejected");
+		assert!(!only_a.run(&['a', 'b']), "Word with 'b' accepted");
+		assert!(!only_a.run(&[]), "Empty word accepted");
+
+		let mut either = BooleanCombination::new().and_not(&a).or(&b).compile();
+		assert!(either.run(&[]), "Empty word rejected");
+		assert!(either.run(&['a', 'b']), "Word with 'b' rejected");
+		assert!(!either.run(&['a']), "Word with only 'a' accepted");
+	}
+
+	#[test]
+	fn pruning() {
+		let dead = DFA::<u8, char>::from_map(0, hashmap!(0 => (false, hashmap!('a' => 0))));
+		let a = contains('a');
+		let dfa = BooleanCombination::new().or(&dead).or(&a).compile();
+		assert_eq!(
+			Some(&vec![None, Some(0)]),
+			dfa.get_initial(),
+			"Dead component not pruned"
+		);
+		assert_eq!(2, dfa.graph().len(), "Incorrect number of product states");
+	}
+}
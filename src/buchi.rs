@@ -0,0 +1,230 @@
+use super::{graph::Graph, AutomatonError};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+/// A nondeterministic Büchi automaton over infinite words.
+///
+/// An infinite word is accepted if there is a run on it visiting accepting states infinitely often.
+/// Infinite words are given as lassos, i.e. a finite prefix followed by a cycle repeated forever, see `accepts_lasso`.
+/// Stepping the automaton on inputs tracks the set of states reachable by the inputs read so far.
+#[derive(Debug, Clone)]
+pub struct Buchi<S, I>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+{
+	ids: Vec<S>,
+	index: HashMap<S, usize>,
+	accepts: Vec<bool>,
+	transitions: Vec<HashMap<I, BTreeSet<usize>>>,
+	initial: BTreeSet<usize>,
+	current: BTreeSet<usize>,
+}
+
+impl<S, I> Default for Buchi<S, I>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+{
+	fn default() -> Self {
+		Self {
+			ids: Vec::new(),
+			index: HashMap::new(),
+			accepts: Vec::new(),
+			transitions: Vec::new(),
+			initial: BTreeSet::new(),
+			current: BTreeSet::new(),
+		}
+	}
+}
+
+impl<S, I> Buchi<S, I>
+where
+	S: Clone + Eq + Hash + fmt::Debug,
+	I: Eq + Hash,
+{
+	/// Creates a new Büchi automaton without states.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a new state or updates whether an existing state accepts.
+	pub fn add_state(&mut self, id: S, accepts: bool) {
+		match self.index.get(&id) {
+			Some(index) => self.accepts[*index] = accepts,
+			None => {
+				self.index.insert(id.clone(), self.ids.len());
+				self.ids.push(id);
+				self.accepts.push(accepts);
+				self.transitions.push(HashMap::new());
+			}
+		}
+	}
+
+	/// Checks whether a state exists.
+	pub fn has_state(&self, id: &S) -> bool {
+		self.index.contains_key(id)
+	}
+
+	/// Adds an initial state & resets the automaton.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn add_initial(&mut self, id: S) -> Result<(), AutomatonError<S>> {
+		let index = self.index_of(id)?;
+		self.initial.insert(index);
+		self.reset();
+		Ok(())
+	}
+
+	/// Adds a transition.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	pub fn add_transition(&mut self, transition: (S, I, S)) -> Result<(), AutomatonError<S>> {
+		let (prev, input, next) = transition;
+		let prev = self.index_of(prev)?;
+		let next = self.index_of(next)?;
+		self.transitions[prev]
+			.entry(input)
+			.or_default()
+			.insert(next);
+		Ok(())
+	}
+
+	/// Returns the current states in order of their addition.
+	pub fn current(&self) -> Vec<&S> {
+		self.current.iter().map(|index| &self.ids[*index]).collect()
+	}
+
+	/// Checks whether one of the current states is accepting.
+	///
+	/// A run is accepting if this holds after infinitely many inputs of its word.
+	pub fn is_accepting(&self) -> bool {
+		self.current.iter().any(|index| self.accepts[*index])
+	}
+
+	/// Performs a single state transition for an input from all current states.
+	/// The automaton has no current states left if none of them has a transition for the input.
+	pub fn step(&mut self, input: &I) {
+		self.current = self.successors(&self.current, input);
+	}
+
+	/// Resets the automaton to its initial states.
+	pub fn reset(&mut self) {
+		self.current = self.initial.clone();
+	}
+
+	/// Checks whether the automaton accepts the infinite word formed by a prefix followed by a cycle repeated forever.
+	/// An empty cycle forms no infinite word & is rejected.
+	///
+	/// The check searches the product of the automaton & the positions in the cycle for a reachable cycle
+	/// through an accepting state, which takes time linear in the product.
+	pub fn accepts_lasso(&self, prefix: &[I], cycle: &[I]) -> bool {
+		if cycle.is_empty() {
+			return false;
+		}
+		let start = prefix.iter().fold(self.initial.clone(), |current, input| {
+			self.successors(&current, input)
+		});
+
+		// state `q` at position `k` in the cycle is numbered `q * len + k`
+		let len = cycle.len();
+		let node = |state: usize, position: usize| state * len + position;
+		let mut edges = vec![Vec::new(); self.ids.len() * len];
+		for state in 0..self.ids.len() {
+			for (position, input) in cycle.iter().enumerate() {
+				for next in self.transitions[state].get(input).into_iter().flatten() {
+					edges[node(state, position)].push((&(), node(*next, (position + 1) % len)));
+				}
+			}
+		}
+		let graph = Graph {
+			labels: self.ids.iter().flat_map(|id| vec![id; len]).collect(),
+			accepts: self
+				.accepts
+				.iter()
+				.flat_map(|accepts| vec![*accepts; len])
+				.collect(),
+			edges,
+			initial: start.into_iter().map(|state| node(state, 0)).collect(),
+		};
+		graph
+			.components(&graph.reachable())
+			.into_iter()
+			.any(|component| {
+				let cyclic = match component.as_slice() {
+					[node] => graph.edges[*node].iter().any(|(_, next)| next == node),
+					_ => true,
+				};
+				cyclic && component.iter().any(|node| graph.accepts[*node])
+			})
+	}
+
+	/// Returns the states reachable from a set of states by an input.
+	fn successors(&self, current: &BTreeSet<usize>, input: &I) -> BTreeSet<usize> {
+		current
+			.iter()
+			.filter_map(|index| self.transitions[*index].get(input))
+			.flatten()
+			.cloned()
+			.collect()
+	}
+
+	/// Looks up the index of a state or returns an `AutomatonError::InexistentState` error.
+	fn index_of(&self, id: S) -> Result<usize, AutomatonError<S>> {
+		self.index
+			.get(&id)
+			.cloned()
+			.ok_or(AutomatonError::InexistentState(id, Vec::new()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Builds an automaton over `a` & `b` from transitions, with state 1 accepting & state 0 initial.
+	fn buchi(transitions: &[(u8, char, u8)]) -> Buchi<u8, char> {
+		let mut buchi = Buchi::new();
+		buchi.add_state(0, false);
+		buchi.add_state(1, true);
+		buchi.add_initial(0).unwrap();
+		for transition in transitions {
+			buchi.add_transition(*transition).unwrap();
+		}
+		buchi
+	}
+
+	#[test]
+	fn infinitely_many() {
+		// deterministic, accepts words with infinitely many `a`
+		let mut buchi = buchi(&[(0, 'a', 1), (0, 'b', 0), (1, 'a', 1), (1, 'b', 0)]);
+		assert!(buchi.accepts_lasso(&['b'], &['a', 'b']), "Lasso rejected");
+		assert!(buchi.accepts_lasso(&[], &['a']), "Lasso rejected");
+		assert!(!buchi.accepts_lasso(&['a', 'a'], &['b']), "Lasso accepted");
+		assert!(!buchi.accepts_lasso(&['a'], &[]), "Finite word accepted");
+		buchi.step(&'b');
+		buchi.step(&'a');
+		assert_eq!(vec![&1], buchi.current(), "Incorrect state");
+		assert!(buchi.is_accepting(), "Accepting state not visited");
+	}
+
+	#[test]
+	fn eventually_always() {
+		// nondeterministic, accepts words with finitely many `b`
+		let mut buchi = buchi(&[(0, 'a', 0), (0, 'b', 0), (0, 'a', 1), (1, 'a', 1)]);
+		assert!(
+			buchi.accepts_lasso(&['b', 'a', 'b'], &['a']),
+			"Lasso rejected"
+		);
+		assert!(!buchi.accepts_lasso(&[], &['a', 'b']), "Lasso accepted");
+		buchi.step(&'a');
+		assert_eq!(vec![&0, &1], buchi.current(), "Incorrect states");
+		buchi.step(&'b');
+		assert!(!buchi.is_accepting(), "Accepting state visited");
+		assert!(
+			buchi.add_initial(2).is_err(),
+			"Inexistent state added as initial"
+		);
+	}
+}
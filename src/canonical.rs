@@ -0,0 +1,272 @@
+//! Constructors for common DFAs, serving as building blocks for combinations of languages.
+//!
+//! States are numbered starting with `0`, which is the initial state.
+//! The constructors taking an alphabet declare it on the DFA & only add transitions for its inputs.
+
+use super::{numeric, Automaton, AutomatonError, DFA};
+use std::{collections::HashSet, fmt, hash::Hash};
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Default,
+{
+	/// Creates a DFA accepting no words, not even the empty word.
+	///
+	/// The DFA has a single rejecting initial state with the default ID & no transitions.
+	/// This is the identity of union & absorbs intersection & concatenation.
+	pub fn empty_language() -> Self {
+		Self::with_state(S::default(), false)
+	}
+
+	/// Creates a DFA accepting only the empty word.
+	///
+	/// The DFA has a single accepting initial state with the default ID & no transitions.
+	/// This is the identity of concatenation.
+	pub fn epsilon_only() -> Self {
+		Self::with_state(S::default(), true)
+	}
+
+	/// Creates a DFA accepting all words over an alphabet, including the empty word.
+	///
+	/// The DFA has a single accepting initial state with the default ID looping on each input & declares the alphabet.
+	/// This is the identity of intersection over the same alphabet.
+	/// For an empty alphabet, it only accepts the empty word like `epsilon_only`.
+	pub fn universal<V>(alphabet: V) -> Self
+	where
+		V: IntoIterator<Item = I>,
+	{
+		let mut dfa = Self::epsilon_only();
+		dfa.declare_alphabet(alphabet);
+		let inputs: Vec<_> = dfa.alphabet.iter().flatten().cloned().collect();
+		for input in inputs {
+			dfa.add_transition((S::default(), input, S::default()))
+				.map_err(AutomatonError::without_input)
+				.expect("Transition for undeclared input");
+		}
+		dfa
+	}
+}
+
+/// Returns a DFA accepting all words over an alphabet, see `DFA::universal`.
+pub fn all_words_over<I, V>(alphabet: V) -> DFA<usize, I>
+where
+	I: Default + Clone + Eq + Hash,
+	V: IntoIterator<Item = I>,
+{
+	DFA::universal(alphabet)
+}
+
+/// Returns a DFA accepting the numbers divisible by `n`, written with the most significant digit first in a base.
+/// Inputs are the digits from `0` to `base - 1` & the empty word is treated as `0`.
+///
+/// Panics if `n` is `0` or `base` is less than `2`.
+pub fn divisible_by(n: usize, base: u32) -> DFA<usize, u32> {
+	numeric::congruent(n, 0, base)
+}
+
+/// Returns a DFA accepting the words over an alphabet containing a word as contiguous subsequence.
+/// Inputs of the word are added to the alphabet.
+///
+/// The states count the length of the longest prefix of the word matched so far, like in the Knuth-Morris-Pratt algorithm.
+pub fn contains_substring<I, V>(alphabet: V, word: &[I]) -> DFA<usize, I>
+where
+	I: Default + Clone + Eq + Hash,
+	V: IntoIterator<Item = I>,
+{
+	// length of the longest proper prefix which is also a suffix of each prefix
+	let mut fallback = vec![0; word.len()];
+	for i in 1..word.len() {
+		let mut length = fallback[i - 1];
+		while length > 0 && word[i] != word[length] {
+			length = fallback[length - 1];
+		}
+		if word[i] == word[length] {
+			length += 1;
+		}
+		fallback[i] = length;
+	}
+	let next = |mut state: usize, input: &I| {
+		if state == word.len() {
+			return state;
+		}
+		while state > 0 && word[state] != *input {
+			state = fallback[state - 1];
+		}
+		if word[state] == *input {
+			state + 1
+		} else {
+			0
+		}
+	};
+	let alphabet = alphabet.into_iter().chain(word.iter().cloned());
+	from_fn(alphabet, word.len() + 1, |state| state == word.len(), next)
+}
+
+/// Returns a DFA accepting the words over an alphabet with an even number of a symbol.
+/// The symbol is added to the alphabet.
+pub fn parity<I, V>(alphabet: V, symbol: I) -> DFA<usize, I>
+where
+	I: Default + Clone + Eq + Hash,
+	V: IntoIterator<Item = I>,
+{
+	let alphabet = alphabet.into_iter().chain(Some(symbol.clone()));
+	from_fn(
+		alphabet,
+		2,
+		|state| state == 0,
+		|state, input| {
+			if *input == symbol {
+				1 - state
+			} else {
+				state
+			}
+		},
+	)
+}
+
+/// Returns a DFA accepting the words over an alphabet with a length of `r` modulo `k`.
+///
+/// Panics if `k` is `0`.
+pub fn length_mod<I, V>(alphabet: V, k: usize, r: usize) -> DFA<usize, I>
+where
+	I: Default + Clone + Eq + Hash,
+	V: IntoIterator<Item = I>,
+{
+	assert!(k > 0, "Length modulo zero");
+	from_fn(
+		alphabet,
+		k,
+		|state| state == r % k,
+		|state, _| (state + 1) % k,
+	)
+}
+
+/// Builds a complete DFA over an alphabet with states from `0` to `states - 1` from functions for acceptance & transitions.
+pub(crate) fn from_fn<I, V, A, F>(alphabet: V, states: usize, accepts: A, next: F) -> DFA<usize, I>
+where
+	I: Default + Clone + Eq + Hash,
+	V: IntoIterator<Item = I>,
+	A: Fn(usize) -> bool,
+	F: Fn(usize, &I) -> usize,
+{
+	let alphabet: HashSet<_> = alphabet.into_iter().collect();
+	let mut dfa = DFA::from_states_map((0..states).map(|state| {
+		let transitions: Vec<_> = alphabet
+			.iter()
+			.map(|input| (input.clone(), (next(state, input), ())))
+			.collect();
+		(state, (accepts(state), transitions))
+	}));
+	dfa.declare_alphabet(alphabet);
+	dfa.set_initial(0);
+	dfa.reset();
+	dfa
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::BooleanCombination;
+	use maplit::hashmap;
+
+	/// Accepts words over `a` & `b` starting with `a`.
+	fn dfa() -> DFA<u8, char> {
+		DFA::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1)),
+				1 => (true, hashmap!('a' => 1, 'b' => 1))
+			),
+		)
+	}
+
+	#[test]
+	fn constructors() {
+		let mut empty = DFA::<u8, char>::empty_language();
+		assert!(!empty.run(&[]), "Empty word accepted");
+		assert_eq!(Some(&0), empty.get_current(), "Missing initial state");
+
+		let mut epsilon = DFA::<u8, char>::epsilon_only();
+		assert!(epsilon.run(&[]), "Empty word rejected");
+		assert!(!epsilon.run(&['a']), "Nonempty word accepted");
+
+		let mut universal = DFA::<u8, char>::universal(vec!['a', 'b']);
+		assert!(universal.run(&[]), "Empty word rejected");
+		assert!(universal.run(&['b', 'a']), "Word rejected");
+		assert!(universal.try_step(&'c').is_err(), "Alphabet not declared");
+		let mut none = DFA::<u8, char>::universal(Vec::new());
+		assert!(none.run(&[]), "Empty word rejected for empty alphabet");
+		assert!(!none.run(&['a']), "Word accepted for empty alphabet");
+	}
+
+	#[test]
+	fn identities() {
+		let dfa = dfa();
+		let empty = DFA::empty_language();
+		let universal = DFA::universal(vec!['a', 'b']);
+		let epsilon = DFA::<u8, char>::epsilon_only();
+		let mut union = BooleanCombination::new().and(&dfa).or(&empty).compile();
+		let mut intersection = BooleanCombination::new()
+			.and(&dfa)
+			.and(&universal)
+			.compile();
+		let mut concat = epsilon.concat(&dfa);
+		let mut absorbed = BooleanCombination::new().and(&dfa).and(&empty).compile();
+		let mut reference = self::dfa();
+		for word in [vec![], vec!['a'], vec!['b', 'a'], vec!['a', 'b', 'b']] {
+			let expected = reference.run(&word);
+			assert_eq!(expected, union.run(&word), "Union not identity");
+			assert_eq!(
+				expected,
+				intersection.run(&word),
+				"Intersection not identity"
+			);
+			assert_eq!(expected, concat.run(&word), "Concatenation not identity");
+			assert!(!absorbed.run(&word), "Intersection not absorbed");
+		}
+	}
+
+	#[test]
+	fn divisible() {
+		let mut three = divisible_by(3, 10);
+		assert!(three.run(&[1, 2, 3]), "Multiple rejected");
+		assert!(!three.run(&[1, 2, 4]), "Non-multiple accepted");
+		assert!(three.run(&[]), "Empty word rejected");
+		let mut binary = divisible_by(5, 2);
+		assert!(binary.run(&[1, 0, 1, 0]), "Multiple rejected");
+		assert!(!binary.run(&[1, 1]), "Non-multiple accepted");
+	}
+
+	#[test]
+	fn substring() {
+		let mut dfa = contains_substring(vec!['a', 'b'], &['a', 'b', 'a', 'b', 'b']);
+		assert!(
+			dfa.run(&['a', 'b', 'a', 'b', 'a', 'b', 'b']),
+			"Overlapping occurrence rejected"
+		);
+		assert!(
+			!dfa.run(&['a', 'b', 'a', 'b', 'a', 'b']),
+			"Word without occurrence accepted"
+		);
+		let mut empty = contains_substring(vec!['a'], &[]);
+		assert!(empty.run(&[]), "Empty word rejected");
+	}
+
+	#[test]
+	fn counting() {
+		let mut even = parity(vec!['a', 'b'], 'b');
+		assert!(even.run(&['b', 'a', 'b']), "Even word rejected");
+		assert!(!even.run(&['b', 'a']), "Odd word accepted");
+		let mut length = length_mod(vec!['a', 'b'], 3, 4);
+		assert!(length.run(&['a']), "Word rejected");
+		assert!(length.run(&['a', 'b', 'a', 'b']), "Word rejected");
+		assert!(!length.run(&['a', 'b']), "Word accepted");
+		assert_eq!(
+			Some(3),
+			all_words_over(vec!['a', 'b']).count_words_up_to(1),
+			"Incorrect words"
+		);
+	}
+}
@@ -0,0 +1,172 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{
+	collections::{BTreeSet, HashMap, HashSet},
+	fmt,
+	hash::Hash,
+};
+
+impl<'a, S, I> Graph<'a, S, I>
+where
+	I: Eq + Hash,
+{
+	/// Searches for the shortest word of at most `max_len` inputs leading from the initial states to a set of states violating the predicate.
+	///
+	/// Words leading to an empty set of states are not checked.
+	fn check_invariant<F>(&self, max_len: usize, predicate: F) -> Option<Vec<&'a I>>
+	where
+		F: Fn(&BTreeSet<usize>) -> bool,
+	{
+		let initial: BTreeSet<usize> = self.initial.iter().cloned().collect();
+		if initial.is_empty() {
+			return None;
+		}
+		let mut parents: Vec<Option<(usize, &'a I)>> = vec![None];
+		let mut sets = vec![initial.clone()];
+		let mut visited = HashSet::new();
+		visited.insert(initial);
+		let mut level = vec![0];
+		for len in 0..=max_len {
+			let mut next_level = Vec::new();
+			for i in level {
+				if !predicate(&sets[i]) {
+					let mut word = Vec::with_capacity(len);
+					let mut current = i;
+					while let Some((parent, input)) = parents[current] {
+						word.push(input);
+						current = parent;
+					}
+					word.reverse();
+					return Some(word);
+				}
+				if len == max_len {
+					continue;
+				}
+				let mut successors: HashMap<&'a I, BTreeSet<usize>> = HashMap::new();
+				for state in &sets[i] {
+					for (input, next) in &self.edges[*state] {
+						successors.entry(*input).or_default().insert(*next);
+					}
+				}
+				for (input, set) in successors {
+					if visited.insert(set.clone()) {
+						parents.push(Some((i, input)));
+						sets.push(set);
+						next_level.push(sets.len() - 1);
+					}
+				}
+			}
+			level = next_level;
+		}
+		None
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Checks a predicate for all states reached from the initial state by words of at most `max_len` inputs.
+	/// Returns the shortest word leading to a state violating the predicate.
+	///
+	/// Words are formed over the inputs used in the transitions of the DFA.
+	/// Words leading to an invalid state are not checked.
+	pub fn check_invariant<F>(&self, max_len: usize, predicate: F) -> Option<Vec<I>>
+	where
+		F: Fn(&S) -> bool,
+	{
+		let graph = self.graph();
+		graph
+			.check_invariant(max_len, |set| {
+				set.iter().all(|state| predicate(graph.labels[*state]))
+			})
+			.map(|word| word.into_iter().cloned().collect())
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Checks a predicate for all sets of states reached from the initial states by words of at most `max_len` inputs.
+	/// Returns the shortest word leading to a set of states violating the predicate.
+	///
+	/// Words are formed over the inputs used in the transitions of the NFA.
+	/// Words leading to an empty set of states are not checked.
+	pub fn check_invariant<F>(&self, max_len: usize, predicate: F) -> Option<Vec<I>>
+	where
+		F: Fn(&HashSet<S>) -> bool,
+	{
+		let graph = self.graph();
+		graph
+			.check_invariant(max_len, |set| {
+				predicate(
+					&set.iter()
+						.map(|state| graph.labels[*state].clone())
+						.collect(),
+				)
+			})
+			.map(|word| word.into_iter().cloned().collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::{hashmap, hashset};
+
+	#[test]
+	fn dfa() {
+		// counter which overflows after 3 increments
+		let dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (true, hashmap!('+' => 1)),
+				1 => (true, hashmap!('+' => 2, '-' => 0)),
+				2 => (true, hashmap!('+' => 3, '-' => 1)),
+				3 => (true, hashmap!('-' => 2))
+			),
+		);
+		assert_eq!(
+			None,
+			dfa.check_invariant(2, |state| *state < 3),
+			"Counterexample beyond bound"
+		);
+		assert_eq!(
+			Some(vec!['+', '+', '+']),
+			dfa.check_invariant(5, |state| *state < 3),
+			"Incorrect counterexample"
+		);
+	}
+
+	#[test]
+	fn nfa() {
+		// mutual exclusion of states 1 & 2
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![1], 'b' => hashset![0, 2])),
+				1 => (false, hashmap!('a' => hashset![1])),
+				2 => (true, hashmap!('a' => hashset![2]))
+			),
+		);
+		let exclusive = |set: &HashSet<u8>| !(set.contains(&1) && set.contains(&2));
+		assert_eq!(
+			Some(vec!['b', 'a']),
+			nfa.check_invariant(4, exclusive),
+			"Incorrect counterexample"
+		);
+	}
+
+	#[test]
+	fn epsilon() {
+		// state 5 accepts after the initial 'a'
+		let nfa = crate::regex::compile("ab*").unwrap();
+		assert_eq!(
+			Some(vec!['a']),
+			nfa.check_invariant(3, |set| !set.contains(&5)),
+			"Incorrect counterexample"
+		);
+	}
+}
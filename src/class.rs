@@ -0,0 +1,294 @@
+use super::{Alphabet, DFA};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, hash::Hash, str::FromStr};
+
+/// Transition label matching a class of chars.
+///
+/// Labels are written as a single char like `"a"`, an inclusive range like `"a-z"` or `"default"`.
+/// The default class matches any char not matched by another label of the same state.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CharClass {
+	/// Inclusive range of chars.
+	Range(char, char),
+
+	/// Any char not matched by another label.
+	#[default]
+	Default,
+}
+
+impl CharClass {
+	/// Checks whether the class contains a char.
+	/// The default class contains no chars on its own.
+	pub fn contains(&self, c: char) -> bool {
+		match self {
+			Self::Range(start, end) => (*start..=*end).contains(&c),
+			Self::Default => false,
+		}
+	}
+
+	/// Checks whether the class shares chars with another class.
+	pub fn overlaps(&self, other: &Self) -> bool {
+		match (self, other) {
+			(Self::Range(start, end), Self::Range(other_start, other_end)) => {
+				start <= other_end && other_start <= end
+			}
+			_ => false,
+		}
+	}
+}
+
+impl fmt::Display for CharClass {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Range(start, end) if start == end => write!(f, "{}", start),
+			Self::Range(start, end) => write!(f, "{}-{}", start, end),
+			Self::Default => write!(f, "default"),
+		}
+	}
+}
+
+/// Error for a malformed char class label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseClassError(String);
+
+impl fmt::Display for ParseClassError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Invalid char class \"{}\"", self.0)
+	}
+}
+
+impl FromStr for CharClass {
+	type Err = ParseClassError;
+
+	fn from_str(label: &str) -> Result<Self, Self::Err> {
+		if label == "default" {
+			return Ok(Self::Default);
+		}
+		let chars: Vec<char> = label.chars().collect();
+		match chars.as_slice() {
+			[c] => Ok(Self::Range(*c, *c)),
+			[start, '-', end] if start <= end => Ok(Self::Range(*start, *end)),
+			_ => Err(ParseClassError(label.into())),
+		}
+	}
+}
+
+impl Serialize for CharClass {
+	fn serialize<Z>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+	where
+		Z: Serializer,
+	{
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for CharClass {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let label = String::deserialize(deserializer)?;
+		label.parse().map_err(de::Error::custom)
+	}
+}
+
+/// Error for overlapping char classes, which can not be classified independent of the current state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlapError(pub CharClass, pub CharClass);
+
+impl fmt::Display for OverlapError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"Overlapping char classes \"{}\" & \"{}\"",
+			self.0, self.1
+		)
+	}
+}
+
+/// Returns the pairs of distinct overlapping classes in order.
+fn overlapping<V>(classes: V) -> Vec<(CharClass, CharClass)>
+where
+	V: IntoIterator<Item = CharClass>,
+{
+	let mut classes: Vec<_> = classes.into_iter().collect();
+	classes.sort();
+	classes.dedup();
+	let mut overlapping = Vec::new();
+	for (i, class) in classes.iter().enumerate() {
+		for other in classes
+			.iter()
+			.skip(i + 1)
+			.filter(|other| class.overlaps(other))
+		{
+			overlapping.push((*class, *other));
+		}
+	}
+	overlapping
+}
+
+/// Alphabet classifying chars into the class containing them.
+///
+/// Chars not contained in any class belong to the default class.
+/// Classes are looked up independent of the current state, so they have to be disjoint.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CharClasses(Vec<CharClass>);
+
+impl CharClasses {
+	/// Creates a new alphabet from a set of classes.
+	/// Returns an `OverlapError` for the first pair of distinct classes sharing chars.
+	pub fn new<V>(classes: V) -> Result<Self, OverlapError>
+	where
+		V: IntoIterator<Item = CharClass>,
+	{
+		let mut classes: Vec<_> = classes.into_iter().collect();
+		classes.sort();
+		classes.dedup();
+		match overlapping(classes.iter().cloned()).first() {
+			Some((class, other)) => Err(OverlapError(*class, *other)),
+			None => Ok(Self(classes)),
+		}
+	}
+}
+
+impl Alphabet<char> for CharClasses {
+	type Symbol = CharClass;
+
+	fn classify(&self, c: &char) -> Option<CharClass> {
+		let class = self.0.iter().find(|class| class.contains(*c));
+		Some(class.cloned().unwrap_or_default())
+	}
+}
+
+impl<S, T> DFA<S, CharClass, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+{
+	/// Returns the alphabet of the classes on the transitions of the automaton.
+	/// Returns an `OverlapError` if classes overlap, see `overlapping_classes`.
+	pub fn char_classes(&self) -> Result<CharClasses, OverlapError> {
+		CharClasses::new(self.classes())
+	}
+
+	/// Returns the pairs of distinct overlapping classes on the transitions of the automaton, across all states.
+	pub fn overlapping_classes(&self) -> Vec<(CharClass, CharClass)> {
+		overlapping(self.classes())
+	}
+
+	/// Returns an iterator over the classes on the transitions of the automaton.
+	fn classes(&self) -> impl Iterator<Item = CharClass> + '_ {
+		self.declared()
+			.flat_map(|(_, state)| state.transitions.keys().cloned())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse() {
+		assert_eq!(
+			Ok(CharClass::Range('a', 'z')),
+			"a-z".parse(),
+			"Incorrect range"
+		);
+		assert_eq!(
+			Ok(CharClass::Range('-', '-')),
+			"-".parse(),
+			"Incorrect char"
+		);
+		assert_eq!(
+			Ok(CharClass::Default),
+			"default".parse(),
+			"Incorrect default"
+		);
+		assert!("z-a".parse::<CharClass>().is_err(), "Reversed range parsed");
+		assert!("ab".parse::<CharClass>().is_err(), "Malformed label parsed");
+		assert_eq!(
+			"0-9",
+			CharClass::Range('0', '9').to_string(),
+			"Incorrect label"
+		);
+	}
+
+	#[test]
+	fn deserialize() {
+		let yaml = r#"
+initial: 0
+states:
+  0:
+    accepts: false
+    transitions: {"a-z": 1, "_": 1, "default": 2}
+  1:
+    accepts: true
+    transitions: {"a-z": 1, "0-9": 1, "_": 1}
+  2:
+    accepts: false
+    transitions: {}
+"#;
+		let mut dfa: DFA<u8, CharClass> = serde_yaml::from_str(yaml).unwrap();
+		let classes = dfa.char_classes().unwrap();
+		let chars = |text: &str| text.chars().collect::<Vec<_>>();
+		assert!(
+			dfa.run_classified(&classes, &chars("snake_case2")),
+			"Identifier rejected"
+		);
+		assert!(
+			!dfa.run_classified(&classes, &chars("2fast")),
+			"Invalid identifier accepted"
+		);
+		assert!(dfa.overlapping_classes().is_empty(), "Incorrect overlaps");
+
+		let invalid = yaml.replace("\"0-9\"", "\"9-0\"");
+		assert!(
+			serde_yaml::from_str::<DFA<u8, CharClass>>(&invalid).is_err(),
+			"Missing error on invalid label"
+		);
+		let overlapping = yaml.replace("\"_\": 1, \"default\"", "\"m-p\": 1, \"default\"");
+		let dfa: DFA<u8, CharClass> = serde_yaml::from_str(&overlapping).unwrap();
+		assert_eq!(
+			vec![(CharClass::Range('a', 'z'), CharClass::Range('m', 'p'))],
+			dfa.overlapping_classes(),
+			"Incorrect overlaps"
+		);
+
+		// classes overlapping across states are rejected as well
+		let nested = r#"{initial: 0, states: {0: {transitions: {"a-z": 1}}, 1: {accepts: true, transitions: {"a-f": 1}}}}"#;
+		let dfa: DFA<u8, CharClass> = serde_yaml::from_str(nested).unwrap();
+		assert_eq!(
+			vec![(CharClass::Range('a', 'f'), CharClass::Range('a', 'z'))],
+			dfa.overlapping_classes(),
+			"Overlap across states not detected"
+		);
+		assert_eq!(
+			Err(OverlapError(
+				CharClass::Range('a', 'f'),
+				CharClass::Range('a', 'z')
+			)),
+			dfa.char_classes(),
+			"Overlapping classes classified"
+		);
+	}
+
+	#[test]
+	fn classify() {
+		let classes =
+			CharClasses::new(vec![CharClass::Range('a', 'f'), CharClass::Range('g', 'z')]).unwrap();
+		assert_eq!(
+			Some(CharClass::Range('a', 'f')),
+			classes.classify(&'b'),
+			"Incorrect class"
+		);
+		assert_eq!(
+			Some(CharClass::Range('g', 'z')),
+			classes.classify(&'x'),
+			"Incorrect class"
+		);
+		assert_eq!(
+			Some(CharClass::Default),
+			classes.classify(&'1'),
+			"Default class not used"
+		);
+	}
+}
@@ -0,0 +1,383 @@
+//! Generation of Rust source code implementing a `DFA`.
+//!
+//! The generated code is a single dependency-free function taking a slice of inputs & returning whether the DFA accepts them.
+//! It is meant to be written to a file from a build script & included via `include!`.
+//!
+//! Inputs are restricted to primitive types implementing `Primitive`, i.e. `bool`, `char`, `&str` & integers,
+//! which are written as literal patterns.
+//! States are renumbered & unreachable states are omitted.
+//!
+//! DFAs over bytes can also be compiled into a dense matcher using `generate_dense`, meant for scanning large inputs.
+
+use super::DFA;
+use std::{collections::HashMap, fmt, fmt::Write, hash::Hash};
+
+/// Trait for primitive inputs which can be written as literal patterns in generated code.
+pub trait Primitive {
+	/// Path of the type in generated code.
+	const TYPE: &'static str;
+
+	/// Returns the input written as a literal.
+	fn literal(&self) -> String;
+}
+
+macro_rules! impl_primitive {
+	($($ty:ty),*) => {
+		$(
+			impl Primitive for $ty {
+				const TYPE: &'static str = stringify!($ty);
+
+				fn literal(&self) -> String {
+					format!("{:?}", self)
+				}
+			}
+		)*
+	};
+}
+
+impl_primitive!(bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, &str);
+
+/// Style of the generated code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+	/// A `match` over the current state & input.
+	Match,
+
+	/// A constant transition table indexed by state & input class.
+	Table,
+}
+
+/// Generates a public function with the given name implementing the DFA.
+pub fn generate<S, I, T>(dfa: &DFA<S, I, T>, name: &str, style: Style) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug + Primitive,
+{
+	let machine = Machine::new(dfa);
+	let mut code = String::new();
+	writeln!(code, "pub fn {}(inputs: &[{}]) -> bool {{", name, I::TYPE).unwrap();
+	if machine.accepts.is_empty() {
+		code.push_str("\tlet _ = inputs;\n\tfalse\n}\n");
+		return code;
+	}
+	writeln!(
+		code,
+		"\tconst ACCEPTS: [bool; {}] = {:?};",
+		machine.accepts.len(),
+		machine.accepts
+	)
+	.unwrap();
+	match style {
+		Style::Match => machine.write_match(&mut code),
+		Style::Table => machine.write_table(&mut code),
+	}
+	code.push_str("\tACCEPTS[state]\n}\n");
+	code
+}
+
+/// Number of bytes processed per iteration of the unrolled loop of `generate_dense`.
+const UNROLL: usize = 8;
+
+/// Generates a public function with the given name implementing a DFA over bytes using a dense transition table.
+///
+/// Bytes are mapped to input classes by a 256 entry table & the dead state loops to itself,
+/// so the inner loop processes chunks of 8 bytes without branches & only checks for the dead state once per chunk.
+pub fn generate_dense<S, T>(dfa: &DFA<S, u8, T>, name: &str) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+{
+	let machine = Machine::new(dfa);
+	let mut code = String::new();
+	writeln!(code, "pub fn {}(inputs: &[u8]) -> bool {{", name).unwrap();
+	if machine.accepts.is_empty() {
+		code.push_str("\tlet _ = inputs;\n\tfalse\n}\n");
+		return code;
+	}
+	let dead = machine.transitions.len();
+	let mut accepts = machine.accepts.clone();
+	accepts.push(false);
+	let mut classes = [machine.inputs.len(); 256];
+	for (class, input) in machine.inputs.iter().enumerate() {
+		classes[**input as usize] = class;
+	}
+	let mut table: Vec<Vec<usize>> = machine
+		.transitions
+		.iter()
+		.map(|edges| {
+			let mut row = vec![dead; machine.inputs.len() + 1];
+			for (class, next) in edges {
+				row[*class] = *next;
+			}
+			row
+		})
+		.collect();
+	table.push(vec![dead; machine.inputs.len() + 1]);
+	writeln!(
+		code,
+		"\tconst ACCEPTS: [bool; {}] = {:?};",
+		accepts.len(),
+		accepts
+	)
+	.unwrap();
+	writeln!(code, "\tconst CLASSES: [usize; 256] = {:?};", classes).unwrap();
+	writeln!(
+		code,
+		"\tconst TABLE: [[usize; {}]; {}] = {:?};",
+		machine.inputs.len() + 1,
+		table.len(),
+		table
+	)
+	.unwrap();
+	writeln!(
+		code,
+		"\tlet mut state = 0;\n\tlet mut chunks = inputs.chunks_exact({});\n\tfor chunk in &mut chunks {{",
+		UNROLL
+	)
+	.unwrap();
+	for i in 0..UNROLL {
+		writeln!(
+			code,
+			"\t\tstate = TABLE[state][CLASSES[chunk[{}] as usize]];",
+			i
+		)
+		.unwrap();
+	}
+	writeln!(
+		code,
+		"\t\tif state == {} {{\n\t\t\treturn false;\n\t\t}}\n\t}}",
+		dead
+	)
+	.unwrap();
+	code.push_str(
+		"\tfor input in chunks.remainder() {\n\t\tstate = TABLE[state][CLASSES[*input as usize]];\n\t}\n",
+	);
+	code.push_str("\tACCEPTS[state]\n}\n");
+	code
+}
+
+/// Renumbered DFA with inputs in a deterministic order.
+struct Machine<'a, I> {
+	accepts: Vec<bool>,
+	inputs: Vec<&'a I>,
+	transitions: Vec<Vec<(usize, usize)>>,
+}
+
+impl<'a, I> Machine<'a, I>
+where
+	I: Eq + Hash + fmt::Debug + Primitive,
+{
+	fn new<S, T>(dfa: &'a DFA<S, I, T>) -> Self
+	where
+		S: Default + Clone + Eq + Hash + fmt::Debug,
+		I: Default,
+	{
+		let graph = dfa.graph();
+		let mut inputs: Vec<&I> = graph
+			.edges
+			.iter()
+			.flatten()
+			.map(|(input, _)| *input)
+			.collect();
+		inputs.sort_by_cached_key(|input| input.literal());
+		inputs.dedup();
+		let classes: HashMap<&I, usize> = inputs
+			.iter()
+			.enumerate()
+			.map(|(i, input)| (*input, i))
+			.collect();
+
+		// number states in breadth-first order from the initial state
+		let mut numbers = HashMap::new();
+		let mut order = graph.initial.clone();
+		let mut accepts = Vec::new();
+		let mut transitions = Vec::new();
+		if let Some(initial) = order.first() {
+			numbers.insert(*initial, 0);
+		}
+		let mut i = 0;
+		while i < order.len() {
+			let state = order[i];
+			let mut edges: Vec<_> = graph.edges[state]
+				.iter()
+				.map(|(input, next)| (classes[input], *next))
+				.collect();
+			edges.sort();
+			let edges = edges
+				.into_iter()
+				.map(|(class, next)| {
+					let number = *numbers.entry(next).or_insert_with(|| {
+						order.push(next);
+						order.len() - 1
+					});
+					(class, number)
+				})
+				.collect();
+			accepts.push(graph.accepts[state]);
+			transitions.push(edges);
+			i += 1;
+		}
+		Self {
+			accepts,
+			inputs,
+			transitions,
+		}
+	}
+
+	fn write_match(&self, code: &mut String) {
+		code.push_str(
+			"\tlet mut state = 0;\n\tfor input in inputs {\n\t\tstate = match (state, *input) {\n",
+		);
+		for (state, edges) in self.transitions.iter().enumerate() {
+			for (class, next) in edges {
+				writeln!(
+					code,
+					"\t\t\t({}, {}) => {},",
+					state,
+					self.inputs[*class].literal(),
+					next
+				)
+				.unwrap();
+			}
+		}
+		code.push_str("\t\t\t_ => return false,\n\t\t};\n\t}\n");
+	}
+
+	fn write_table(&self, code: &mut String) {
+		let dead = self.transitions.len();
+		let table: Vec<Vec<usize>> = self
+			.transitions
+			.iter()
+			.map(|edges| {
+				let mut row = vec![dead; self.inputs.len()];
+				for (class, next) in edges {
+					row[*class] = *next;
+				}
+				row
+			})
+			.collect();
+		writeln!(
+			code,
+			"\tconst TABLE: [[usize; {}]; {}] = {:?};",
+			self.inputs.len(),
+			table.len(),
+			table
+		)
+		.unwrap();
+		code.push_str(
+			"\tlet mut state = 0;\n\tfor input in inputs {\n\t\tlet class = match *input {\n",
+		);
+		for (class, input) in self.inputs.iter().enumerate() {
+			writeln!(code, "\t\t\t{} => {},", input.literal(), class).unwrap();
+		}
+		code.push_str("\t\t\t_ => return false,\n\t\t};\n");
+		writeln!(
+			code,
+			"\t\tstate = TABLE[state][class];\n\t\tif state == {} {{\n\t\t\treturn false;\n\t\t}}\n\t}}",
+			dead
+		)
+		.unwrap();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	fn dfa() -> DFA<&'static str, char> {
+		DFA::from_map(
+			"start",
+			hashmap!(
+				"start" => (false, hashmap!('a' => "end")),
+				"end" => (true, hashmap!('b' => "end", 'c' => "start")),
+				"unreachable" => (true, hashmap!('a' => "start"))
+			),
+		)
+	}
+
+	#[test]
+	fn generate_match() {
+		let code = generate(&dfa(), "matches", Style::Match);
+		assert_eq!(
+			"pub fn matches(inputs: &[char]) -> bool {
+	const ACCEPTS: [bool; 2] = [false, true];
+	let mut state = 0;
+	for input in inputs {
+		state = match (state, *input) {
+			(0, 'a') => 1,
+			(1, 'b') => 1,
+			(1, 'c') => 0,
+			_ => return false,
+		};
+	}
+	ACCEPTS[state]
+}
+",
+			code,
+			"Incorrect match code"
+		);
+	}
+
+	#[test]
+	fn generate_table() {
+		let code = generate(&dfa(), "matches", Style::Table);
+		assert!(
+			code.contains("const TABLE: [[usize; 3]; 2] = [[1, 2, 2], [2, 1, 0]];"),
+			"Incorrect transition table"
+		);
+		assert!(code.contains("'c' => 2,"), "Incorrect input classes");
+	}
+
+	#[test]
+	fn generate_dense() {
+		let dfa = DFA::<u8, u8>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!(b'a' => 1)),
+				1 => (true, hashmap!(b'b' => 1))
+			),
+		);
+		let code = super::generate_dense(&dfa, "matches");
+		assert!(
+			code.contains("const ACCEPTS: [bool; 3] = [false, true, false];"),
+			"Incorrect accepting states"
+		);
+		assert!(
+			code.contains("const TABLE: [[usize; 3]; 3] = [[1, 2, 2], [2, 1, 2], [2, 2, 2]];"),
+			"Incorrect transition table"
+		);
+		assert!(
+			code.contains("state = TABLE[state][CLASSES[chunk[7] as usize]];"),
+			"Loop not unrolled"
+		);
+		let mut classes = [2; 256];
+		classes[b'a' as usize] = 0;
+		classes[b'b' as usize] = 1;
+		assert!(
+			code.contains(&format!("const CLASSES: [usize; 256] = {:?};", classes)),
+			"Incorrect byte classes"
+		);
+	}
+
+	#[test]
+	fn generate_empty() {
+		let dfa = DFA::<u8, u8>::new();
+		let code = generate(&dfa, "never", Style::Table);
+		assert_eq!(
+			"pub fn never(inputs: &[u8]) -> bool {\n\tlet _ = inputs;\n\tfalse\n}\n", code,
+			"Incorrect code for empty DFA"
+		);
+	}
+
+	#[test]
+	fn generate_str() {
+		let dfa = DFA::<u8, &str>::from_map(0, hashmap!(0 => (true, hashmap!("go" => 0))));
+		let code = generate(&dfa, "matches", Style::Match);
+		assert!(
+			code.starts_with("pub fn matches(inputs: &[&str]) -> bool {"),
+			"Incorrect input type"
+		);
+		assert!(code.contains("(0, \"go\") => 0,"), "Incorrect pattern");
+	}
+}
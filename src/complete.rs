@@ -0,0 +1,138 @@
+use super::DFA;
+use std::{collections::HashSet, fmt, hash::Hash};
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Default + Clone,
+{
+	/// Returns an equivalent DFA with a transition for every input of an alphabet from every state.
+	///
+	/// States are wrapped in `Some` & missing transitions lead to the sink state `None`, which loops to itself & rejects.
+	/// The sink state is the initial state if the DFA has none.
+	/// Transitions for inputs outside the alphabet are dropped & the alphabet is declared.
+	/// Transitions to inexistent states lead to the sink state & carry the default payload, like all new transitions.
+	pub fn complete<V>(&self, alphabet: V) -> DFA<Option<S>, I, T>
+	where
+		V: IntoIterator<Item = I>,
+	{
+		let alphabet: HashSet<I> = alphabet.into_iter().collect();
+		let sink = (
+			None,
+			(
+				false,
+				alphabet
+					.iter()
+					.map(|input| (input.clone(), (None, T::default())))
+					.collect::<Vec<_>>(),
+			),
+		);
+		let mut dfa = DFA::from_states_map(
+			self.declared()
+				.map(|(index, state)| {
+					let transitions = alphabet
+						.iter()
+						.map(|input| {
+							let next = match self.transition(index, input) {
+								Some((next, payload)) if self.states[*next].is_some() => {
+									(Some(self.ids[*next].clone()), payload.clone())
+								}
+								_ => (None, T::default()),
+							};
+							(input.clone(), next)
+						})
+						.collect();
+					(Some(self.ids[index].clone()), (state.accepts, transitions))
+				})
+				.chain(Some(sink)),
+		);
+		let initial = self.initial.map(|index| self.ids[index].clone());
+		dfa.initial = dfa.index_of(&initial);
+		dfa.current = dfa.initial;
+		dfa.alphabet = Some(alphabet);
+		for (index, name) in &self.names {
+			let index = dfa.index[&Some(self.ids[*index].clone())];
+			dfa.names.insert(index, name.clone());
+		}
+		dfa
+	}
+
+	/// Returns a DFA accepting exactly the words over an alphabet rejected by the DFA.
+	///
+	/// The DFA is completed over the alphabet like in `complete` before flipping the acceptance of all states,
+	/// so the sink state `None` accepts.
+	pub fn complement<V>(&self, alphabet: V) -> DFA<Option<S>, I, T>
+	where
+		V: IntoIterator<Item = I>,
+	{
+		let mut dfa = self.complete(alphabet);
+		for state in dfa.states.iter_mut().flatten() {
+			state.accepts = !state.accepts;
+		}
+		dfa
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	/// Accepts words over `a` & `b` starting with `ab`.
+	fn dfa() -> DFA<u8, char> {
+		DFA::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1)),
+				1 => (false, hashmap!('b' => 2)),
+				2 => (true, hashmap!('a' => 2, 'b' => 2))
+			),
+		)
+	}
+
+	#[test]
+	fn complete() {
+		let mut dfa = dfa();
+		dfa.set_name(&2, "Prefix").unwrap();
+		let mut complete = dfa.complete(vec!['a', 'b']);
+		assert_eq!(4, complete.stats().states, "Missing sink state");
+		assert!(
+			complete.stats().deterministic,
+			"Nondeterministic completion"
+		);
+		assert_eq!(
+			Some(&Some(0)),
+			complete.get_current(),
+			"Incorrect initial state"
+		);
+		complete.step(&'b');
+		assert_eq!(
+			Some(&None),
+			complete.get_current(),
+			"Sink state not entered"
+		);
+		complete.reset();
+		assert!(complete.run(&['a', 'b', 'a']), "Word rejected");
+		assert_eq!(Some("Prefix"), complete.name(&Some(2)), "Name not kept");
+	}
+
+	#[test]
+	fn complement() {
+		let mut complement = dfa().complement(vec!['a', 'b']);
+		for word in [vec![], vec!['a'], vec!['b', 'a', 'b'], vec!['a', 'b', 'b']] {
+			assert_eq!(
+				!dfa().run(&word),
+				complement.run(&word),
+				"Incorrect result for {:?}",
+				word
+			);
+		}
+		assert!(complement.try_step(&'c').is_err(), "Alphabet not declared");
+
+		let mut universal = DFA::<u8, char>::new().complement(vec!['a']);
+		assert!(universal.run(&[]), "Empty word rejected");
+		assert!(universal.run(&['a', 'a']), "Word rejected");
+	}
+}
@@ -0,0 +1,232 @@
+use super::{graph::Graph, Automaton, NFA};
+use std::{collections::HashSet, fmt, hash::Hash};
+
+/// Transition graph of a composed automaton with states numbered by index.
+struct Parts<'a, I> {
+	accepts: Vec<bool>,
+	edges: Vec<Vec<(&'a I, usize)>>,
+	initial: Vec<usize>,
+}
+
+impl<'a, I> Parts<'a, I>
+where
+	I: Default + Clone + Eq + Hash,
+{
+	/// Creates new parts from a graph.
+	///
+	/// The graph of an NFA has its epsilon transitions resolved, so the parts have none.
+	fn new<S>(graph: &Graph<'a, S, I>) -> Self {
+		Self {
+			accepts: graph.accepts.clone(),
+			edges: graph.edges.clone(),
+			initial: graph.initial.clone(),
+		}
+	}
+
+	/// Returns the transitions of all initial states.
+	fn initial_edges(&self) -> Vec<(&'a I, usize)> {
+		self.initial
+			.iter()
+			.flat_map(|state| self.edges[*state].iter().cloned())
+			.collect()
+	}
+
+	/// Checks whether the empty word is accepted.
+	fn accepts_empty(&self) -> bool {
+		self.initial.iter().any(|state| self.accepts[*state])
+	}
+
+	/// Appends the states of other parts without connecting them.
+	/// Returns the offset of the appended state indices.
+	fn append(&mut self, other: &Parts<'a, I>) -> usize {
+		let offset = self.accepts.len();
+		self.accepts.extend(other.accepts.iter().cloned());
+		self.edges
+			.extend(other.edges.iter().map(|edges| shifted(edges, offset)));
+		offset
+	}
+
+	/// Adds a fresh state without transitions, returning its index.
+	fn fresh(&mut self, accepts: bool) -> usize {
+		self.accepts.push(accepts);
+		self.edges.push(Vec::new());
+		self.accepts.len() - 1
+	}
+
+	/// Builds an NFA with the index of each state as its ID.
+	fn build(self) -> NFA<usize, I> {
+		let mut nfa = NFA::new();
+		for (state, accepts) in self.accepts.into_iter().enumerate() {
+			nfa.add_state(state, accepts);
+		}
+		for (state, edges) in self.edges.into_iter().enumerate() {
+			for (input, next) in edges {
+				nfa.add_transition((state, input.clone(), next)).ok();
+			}
+		}
+		let initial: HashSet<_> = self.initial.into_iter().collect();
+		nfa.set_initial(initial.clone());
+		nfa.set_current(initial);
+		nfa
+	}
+}
+
+/// Returns transitions with their target indices shifted by an offset.
+fn shifted<'a, I>(edges: &[(&'a I, usize)], offset: usize) -> Vec<(&'a I, usize)> {
+	edges
+		.iter()
+		.map(|(input, next)| (*input, next + offset))
+		.collect()
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Constructs an NFA accepting the words formed by a word accepted by the NFA followed by a word accepted by another NFA.
+	///
+	/// States are numbered by index, starting with the states of the NFA followed by the states of the other NFA.
+	pub fn then<R, U>(&self, other: &NFA<R, I, U>) -> NFA<usize, I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		let (first, second) = (self.graph(), other.graph());
+		let (mut parts, second) = (Parts::new(&first), Parts::new(&second));
+		let continuation = shifted(&second.initial_edges(), first.len());
+		for state in 0..first.len() {
+			if parts.accepts[state] {
+				parts.edges[state].extend(continuation.iter().cloned());
+				parts.accepts[state] = second.accepts_empty();
+			}
+		}
+		parts.append(&second);
+		parts.build()
+	}
+
+	/// Constructs an NFA accepting the words accepted by either the NFA or another NFA.
+	///
+	/// States are numbered by index, starting with the states of the NFA followed by the states of the other NFA.
+	pub fn or<R, U>(&self, other: &NFA<R, I, U>) -> NFA<usize, I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		let (first, second) = (self.graph(), other.graph());
+		let (mut parts, second) = (Parts::new(&first), Parts::new(&second));
+		let offset = parts.append(&second);
+		parts
+			.initial
+			.extend(second.initial.iter().map(|state| state + offset));
+		parts.build()
+	}
+
+	/// Constructs an NFA accepting the words accepted by either the NFA or another NFA, see `or`.
+	pub fn union<R, U>(&self, other: &NFA<R, I, U>) -> NFA<usize, I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		self.or(other)
+	}
+
+	/// Constructs an NFA accepting any number of consecutive words accepted by the NFA, including none.
+	///
+	/// States are numbered by index, starting with the states of the NFA followed by a new initial state.
+	pub fn repeat(&self) -> NFA<usize, I> {
+		let graph = self.graph();
+		let mut parts = Parts::new(&graph);
+		let continuation = parts.initial_edges();
+		for state in 0..graph.len() {
+			if parts.accepts[state] {
+				parts.edges[state].extend(continuation.iter().cloned());
+			}
+		}
+		let initial = parts.fresh(true);
+		parts.edges[initial] = continuation;
+		parts.initial = vec![initial];
+		parts.build()
+	}
+
+	/// Constructs an NFA accepting the words accepted by the NFA & the empty word.
+	///
+	/// States are numbered by index, starting with the states of the NFA followed by a new initial state.
+	pub fn optional(&self) -> NFA<usize, I> {
+		let graph = self.graph();
+		let mut parts = Parts::new(&graph);
+		let initial = parts.fresh(true);
+		parts.initial.push(initial);
+		parts.build()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::{hashmap, hashset};
+
+	/// Accepts a single input.
+	fn single(input: char) -> NFA<u8, char> {
+		NFA::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!(input => hashset![1])),
+				1 => (true, hashmap!())
+			),
+		)
+	}
+
+	#[test]
+	fn then_or() {
+		let mut nfa = single('a').then(&single('b').or(&single('c')));
+		assert!(nfa.run(&['a', 'b']), "Composed word rejected");
+		assert!(nfa.run(&['a', 'c']), "Composed word rejected");
+		assert!(!nfa.run(&['a']), "Incomplete word accepted");
+		assert!(!nfa.run(&['b']), "Word without prefix accepted");
+		assert!(!nfa.run(&['a', 'b', 'c']), "Overlong word accepted");
+	}
+
+	#[test]
+	fn repeat_optional() {
+		let mut nfa = single('a').then(&single('b')).repeat();
+		assert!(nfa.run(&[]), "Empty word rejected");
+		assert!(nfa.run(&['a', 'b', 'a', 'b']), "Repeated word rejected");
+		assert!(!nfa.run(&['a', 'b', 'a']), "Incomplete word accepted");
+
+		let mut nfa = single('a').optional().then(&single('b'));
+		assert!(nfa.run(&['b']), "Word without optional part rejected");
+		assert!(nfa.run(&['a', 'b']), "Word with optional part rejected");
+		assert!(
+			!nfa.run(&['a', 'a', 'b']),
+			"Repeated optional part accepted"
+		);
+	}
+
+	#[test]
+	fn union() {
+		let mut epsilon = single('b');
+		epsilon.add_state(2, false);
+		epsilon.add_epsilon((2, 0)).unwrap();
+		epsilon.set_initial(hashset![2]);
+		let mut nfa = single('a').union(&epsilon);
+		assert!(nfa.run(&['a']), "Word of first NFA rejected");
+		assert!(nfa.run(&['b']), "Word behind epsilon transition rejected");
+		assert!(!nfa.run(&[]), "Empty word accepted");
+		let mut optional = nfa.union(&NFA::<u8, char>::with_state(0, true));
+		assert!(optional.run(&[]), "Empty word rejected");
+		assert!(!nfa.run(&['a', 'b']), "Mixed word accepted");
+	}
+
+	#[test]
+	fn epsilon() {
+		let stars = crate::regex::compile("a*").unwrap();
+		let mut nfa = stars.then(&single('b'));
+		assert!(nfa.run(&['b']), "Word without repetitions rejected");
+		assert!(nfa.run(&['a', 'a', 'b']), "Word with repetitions rejected");
+		let mut nfa = single('b').or(&stars);
+		assert!(nfa.run(&[]), "Empty word rejected");
+		assert!(nfa.run(&['a', 'a']), "Word of second NFA rejected");
+		let mut nfa = crate::regex::compile("ab?").unwrap().repeat();
+		assert!(nfa.run(&['a', 'a', 'b']), "Repeated word rejected");
+		let mut nfa = crate::regex::compile("a|b").unwrap().optional();
+		assert!(nfa.run(&['b']), "Word of NFA rejected");
+	}
+}
@@ -0,0 +1,163 @@
+use super::{Automaton, AutomatonError, DFA, NFA};
+use std::{fmt, hash::Hash};
+
+/// State of an automaton concatenating two automatons.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Concat<S> {
+	/// State of the first automaton.
+	First(S),
+
+	/// State of the second automaton.
+	Second(S),
+}
+
+impl<S> Default for Concat<S>
+where
+	S: Default,
+{
+	fn default() -> Self {
+		Self::First(S::default())
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Constructs an NFA accepting the words formed by a word accepted by the DFA followed by a word accepted by another DFA.
+	///
+	/// States of the NFA are tagged with the automaton they belong to.
+	/// Matching inputs switch from the first to the second automaton at the seam between both words.
+	pub fn concat<U>(&self, other: &DFA<S, I, U>) -> NFA<Concat<S>, I> {
+		let (first, second) = (self.graph(), other.graph());
+		let mut nfa = NFA::new();
+		let second_initial = second.initial.first().cloned();
+		let empty_second = second_initial.is_some_and(|state| second.accepts[state]);
+		for (state, id) in first.labels.iter().enumerate() {
+			let accepts = first.accepts[state] && empty_second;
+			nfa.add_state(Concat::First((*id).clone()), accepts);
+		}
+		for (state, id) in second.labels.iter().enumerate() {
+			nfa.add_state(Concat::Second((*id).clone()), second.accepts[state]);
+		}
+		for (state, edges) in first.edges.iter().enumerate() {
+			let id = Concat::First(first.labels[state].clone());
+			for (input, next) in edges {
+				let next = Concat::First(first.labels[*next].clone());
+				nfa.add_transition((id.clone(), (*input).clone(), next))
+					.map_err(AutomatonError::without_input)
+					.expect("Transition between inexistent states");
+			}
+
+			// accepting states of the first automaton continue like the initial state of the second
+			if let (true, Some(initial)) = (first.accepts[state], second_initial) {
+				for (input, next) in &second.edges[initial] {
+					let next = Concat::Second(second.labels[*next].clone());
+					nfa.add_transition((id.clone(), (*input).clone(), next))
+						.map_err(AutomatonError::without_input)
+						.expect("Transition between inexistent states");
+				}
+			}
+		}
+		for (state, edges) in second.edges.iter().enumerate() {
+			let id = Concat::Second(second.labels[state].clone());
+			for (input, next) in edges {
+				let next = Concat::Second(second.labels[*next].clone());
+				nfa.add_transition((id.clone(), (*input).clone(), next))
+					.map_err(AutomatonError::without_input)
+					.expect("Transition between inexistent states");
+			}
+		}
+		if let Some(initial) = first.initial.first() {
+			let initial =
+				NFA::<Concat<S>, I>::new_state(Concat::First(first.labels[*initial].clone()));
+			nfa.set_initial(initial.clone());
+			nfa.set_current(initial);
+		}
+		nfa
+	}
+
+	/// Returns all positions at which a sequence of inputs can be split into a word accepted by the DFA followed by a word accepted by another DFA.
+	///
+	/// Both automatons start from their initial state.
+	pub fn concat_splits<U>(&self, other: &DFA<S, I, U>, inputs: &[I]) -> Vec<usize> {
+		let (first, second) = (self.graph(), other.graph());
+		let (first_table, second_table) = (first.table(), second.table());
+		let mut current = first.initial.first().cloned();
+		let mut candidates = Vec::new();
+		for position in 0..=inputs.len() {
+			if let (Some(state), Some(initial)) = (current, second.initial.first()) {
+				if first.accepts[state] {
+					candidates.push((position, *initial));
+				}
+			}
+			if let Some(input) = inputs.get(position) {
+				current = current.and_then(|state| first_table[state].get(input).cloned());
+				candidates = candidates
+					.into_iter()
+					.filter_map(|(split, state)| {
+						second_table[state].get(input).map(|next| (split, *next))
+					})
+					.collect();
+			}
+		}
+		candidates
+			.into_iter()
+			.filter(|(_, state)| second.accepts[*state])
+			.map(|(split, _)| split)
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::hashmap;
+
+	/// Accepts one or more 'h' as header & any number of 'b' as body.
+	fn parts() -> (DFA<u8, char>, DFA<u8, char>) {
+		let header = DFA::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('h' => 1)),
+				1 => (true, hashmap!('h' => 1))
+			),
+		);
+		let body = DFA::from_map(0, hashmap!(0 => (true, hashmap!('b' => 0))));
+		(header, body)
+	}
+
+	#[test]
+	fn concat() {
+		let (header, body) = parts();
+		let mut nfa = header.concat(&body);
+		assert!(nfa.run(&['h']), "Word with empty body rejected");
+		assert!(nfa.run(&['h', 'h', 'b', 'b']), "Concatenated word rejected");
+		assert!(!nfa.run(&['b']), "Word without header accepted");
+		assert!(!nfa.run(&['h', 'b', 'h']), "Interleaved word accepted");
+		assert!(
+			nfa.has_state(&Concat::Second(0)),
+			"Missing state of second automaton"
+		);
+	}
+
+	#[test]
+	fn splits() {
+		let (header, body) = parts();
+		assert_eq!(
+			vec![2],
+			header.concat_splits(&body, &['h', 'h', 'b']),
+			"Incorrect split position"
+		);
+		assert_eq!(
+			vec![1, 2],
+			header.concat_splits(&header, &['h', 'h', 'h']),
+			"Incorrect ambiguous split positions"
+		);
+		assert!(
+			header.concat_splits(&body, &['b']).is_empty(),
+			"Split of rejected word"
+		);
+	}
+}
@@ -0,0 +1,395 @@
+use super::{Trace, DFA};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "serde_json")]
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, fmt, hash::Hash};
+#[cfg(feature = "serde_json")]
+use std::{
+	fs::{self, File},
+	io::{self, BufReader},
+	path::{Path, PathBuf},
+};
+
+/// First point at which a trace diverges from the model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence<S, I> {
+	/// Position of the diverging record in the trace.
+	pub position: usize,
+
+	/// State of the model before the diverging input.
+	pub state: S,
+
+	/// Input the model has no transition for.
+	pub input: I,
+}
+
+/// Result of checking a trace against a model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceReport<S, I> {
+	/// Number of inputs the model accepted before diverging or reaching the end of the trace.
+	pub conforming: usize,
+
+	/// First divergence of the trace, if there is one.
+	pub divergence: Option<Divergence<S, I>>,
+
+	/// State of the model after the conforming inputs.
+	/// Is `None` if the model has no valid initial state.
+	pub state: Option<S>,
+}
+
+impl<S, I> ConformanceReport<S, I> {
+	/// Checks whether the trace conforms to the model.
+	pub fn is_conformant(&self) -> bool {
+		self.divergence.is_none() && self.state.is_some()
+	}
+}
+
+/// Aggregated results of checking many traces against a model.
+///
+/// Traces are identified by keys, e.g. their paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceSummary<S, I, K>
+where
+	S: Eq + Hash,
+{
+	/// Number of checked traces.
+	pub traces: usize,
+
+	/// Number of conformant traces.
+	pub conformant: usize,
+
+	/// Number of divergences per state the model was in when diverging.
+	pub violations: HashMap<S, usize>,
+
+	/// Representative counterexample per state, from the trace with the smallest key.
+	pub counterexamples: HashMap<S, (K, Divergence<S, I>)>,
+
+	/// Traces which could not be read with the reason, ordered by key.
+	/// These are not counted as checked traces.
+	pub unreadable: Vec<(K, String)>,
+}
+
+impl<S, I, K> Default for ConformanceSummary<S, I, K>
+where
+	S: Eq + Hash,
+{
+	fn default() -> Self {
+		Self {
+			traces: 0,
+			conformant: 0,
+			violations: HashMap::new(),
+			counterexamples: HashMap::new(),
+			unreadable: Vec::new(),
+		}
+	}
+}
+
+impl<S, I, K> ConformanceSummary<S, I, K>
+where
+	S: Clone + Eq + Hash,
+	K: Ord,
+{
+	/// Adds the report for a trace to the summary.
+	fn add(mut self, key: K, report: ConformanceReport<S, I>) -> Self {
+		self.traces += 1;
+		if report.is_conformant() {
+			self.conformant += 1;
+		}
+		if let Some(divergence) = report.divergence {
+			*self.violations.entry(divergence.state.clone()).or_default() += 1;
+			self.keep(key, divergence);
+		}
+		self
+	}
+
+	/// Adds a trace which could not be read to the summary.
+	#[cfg(feature = "serde_json")]
+	fn add_unreadable(mut self, key: K, reason: String) -> Self {
+		self.unreadable.push((key, reason));
+		self
+	}
+
+	/// Combines the summaries of two disjoint sets of traces, e.g. checked on different machines.
+	pub fn merge(mut self, other: Self) -> Self {
+		self.traces += other.traces;
+		self.conformant += other.conformant;
+		for (state, count) in other.violations {
+			*self.violations.entry(state).or_default() += count;
+		}
+		for (key, divergence) in other.counterexamples.into_values() {
+			self.keep(key, divergence);
+		}
+		self.unreadable.extend(other.unreadable);
+		self.unreadable.sort_by(|(a, _), (b, _)| a.cmp(b));
+		self
+	}
+
+	/// Keeps a counterexample if it is from a trace with a smaller key than the current one for its state.
+	fn keep(&mut self, key: K, divergence: Divergence<S, I>) {
+		let smaller = self
+			.counterexamples
+			.get(&divergence.state)
+			.is_none_or(|(kept, _)| key < *kept);
+		if smaller {
+			self.counterexamples
+				.insert(divergence.state.clone(), (key, divergence));
+		}
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Runs the model over a recorded trace, starting from the initial state.
+	/// Reports the first input the model has no transition for.
+	///
+	/// The current state of the model is not changed.
+	pub fn check_trace(&self, trace: &Trace<I>) -> ConformanceReport<S, I> {
+		let mut state = self.initial;
+		let mut conforming = 0;
+		let mut divergence = None;
+		if let Some(mut current) = state {
+			for (position, input) in trace.inputs().enumerate() {
+				match self.transition(current, input) {
+					Some((next, _)) => {
+						current = *next;
+						conforming += 1;
+					}
+					None => {
+						divergence = Some(Divergence {
+							position,
+							state: self.ids[current].clone(),
+							input: input.clone(),
+						});
+						break;
+					}
+				}
+			}
+			state = Some(current);
+		}
+		ConformanceReport {
+			conforming,
+			divergence,
+			state: state.map(|index| self.ids[index].clone()),
+		}
+	}
+
+	/// Checks many traces identified by keys & aggregates the results.
+	pub fn check_traces<K, V>(&self, traces: V) -> ConformanceSummary<S, I, K>
+	where
+		K: Ord,
+		V: IntoIterator<Item = (K, Trace<I>)>,
+	{
+		traces
+			.into_iter()
+			.fold(ConformanceSummary::default(), |summary, (key, trace)| {
+				let report = self.check_trace(&trace);
+				summary.add(key, report)
+			})
+	}
+
+	/// Checks many traces identified by keys in parallel & aggregates the results.
+	#[cfg(feature = "rayon")]
+	pub fn par_check_traces<K, V>(&self, traces: V) -> ConformanceSummary<S, I, K>
+	where
+		S: Send + Sync,
+		I: Send + Sync,
+		T: Sync,
+		K: Ord + Send,
+		V: IntoParallelIterator<Item = (K, Trace<I>)>,
+	{
+		traces
+			.into_par_iter()
+			.fold(ConformanceSummary::default, |summary, (key, trace)| {
+				let report = self.check_trace(&trace);
+				summary.add(key, report)
+			})
+			.reduce(ConformanceSummary::default, ConformanceSummary::merge)
+	}
+
+	/// Checks all trace files in JSON lines format in a directory, identified by their paths.
+	/// Files are read & checked one after another.
+	///
+	/// Files which can not be read are reported in the summary as unreadable.
+	/// Returns an error if the directory can not be read.
+	#[cfg(all(feature = "serde_json", not(feature = "rayon")))]
+	pub fn check_trace_dir<P>(&self, dir: P) -> io::Result<ConformanceSummary<S, I, PathBuf>>
+	where
+		P: AsRef<Path>,
+		I: DeserializeOwned,
+	{
+		Ok(trace_files(dir)?
+			.into_iter()
+			.map(|path| self.check_trace_file(path))
+			.fold(ConformanceSummary::default(), ConformanceSummary::merge))
+	}
+
+	/// Checks all trace files in JSON lines format in a directory, identified by their paths.
+	/// Files are read & checked individually in parallel.
+	///
+	/// Files which can not be read are reported in the summary as unreadable.
+	/// Returns an error if the directory can not be read.
+	#[cfg(all(feature = "serde_json", feature = "rayon"))]
+	pub fn check_trace_dir<P>(&self, dir: P) -> io::Result<ConformanceSummary<S, I, PathBuf>>
+	where
+		P: AsRef<Path>,
+		S: Send + Sync,
+		I: DeserializeOwned + Send + Sync,
+		T: Sync,
+	{
+		Ok(trace_files(dir)?
+			.into_par_iter()
+			.map(|path| self.check_trace_file(path))
+			.reduce(ConformanceSummary::default, ConformanceSummary::merge))
+	}
+
+	/// Checks a trace file, reporting it as unreadable if it can not be read.
+	#[cfg(feature = "serde_json")]
+	fn check_trace_file(&self, path: PathBuf) -> ConformanceSummary<S, I, PathBuf>
+	where
+		I: DeserializeOwned,
+	{
+		let summary = ConformanceSummary::default();
+		match File::open(&path).and_then(|file| Trace::read_json_lines(BufReader::new(file))) {
+			Ok(trace) => {
+				let report = self.check_trace(&trace);
+				summary.add(path, report)
+			}
+			Err(err) => summary.add_unreadable(path, err.to_string()),
+		}
+	}
+}
+
+/// Returns the paths of the files in a directory.
+#[cfg(feature = "serde_json")]
+fn trace_files<P>(dir: P) -> io::Result<Vec<PathBuf>>
+where
+	P: AsRef<Path>,
+{
+	let mut paths = Vec::new();
+	for entry in fs::read_dir(dir)? {
+		let path = entry?.path();
+		if path.is_file() {
+			paths.push(path);
+		}
+	}
+	Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Automaton, Record};
+	use maplit::hashmap;
+	use std::time::Duration;
+
+	/// Creates a trace with the given inputs.
+	fn trace(inputs: &[char]) -> Trace<char> {
+		Trace {
+			records: inputs
+				.iter()
+				.enumerate()
+				.map(|(i, input)| Record {
+					time: Duration::from_millis(i as u64),
+					input: *input,
+				})
+				.collect(),
+		}
+	}
+
+	/// Model alternating between `a` & `b`.
+	fn model() -> DFA<u8, char> {
+		DFA::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1)),
+				1 => (true, hashmap!('b' => 0))
+			),
+		)
+	}
+
+	#[test]
+	fn check() {
+		let mut dfa = model();
+		dfa.step(&'a');
+		let report = dfa.check_trace(&trace(&['a', 'b', 'a']));
+		assert!(report.is_conformant(), "Conformant trace rejected");
+		assert_eq!(Some(1), report.state, "Incorrect final state");
+
+		let report = dfa.check_trace(&trace(&['a', 'b', 'b', 'a']));
+		assert_eq!(
+			Some(Divergence {
+				position: 2,
+				state: 0,
+				input: 'b'
+			}),
+			report.divergence,
+			"Incorrect divergence"
+		);
+		assert_eq!(2, report.conforming, "Incorrect conforming inputs");
+		assert_eq!(Some(&1), dfa.get_current(), "Current state changed");
+	}
+
+	#[test]
+	fn summary() {
+		let dfa = model();
+		let traces = vec![
+			(3, trace(&['a', 'a'])),
+			(1, trace(&['a', 'b'])),
+			(2, trace(&['b'])),
+			(0, trace(&['a', 'b', 'b'])),
+		];
+		let summary = dfa.check_traces(traces.clone());
+		assert_eq!(4, summary.traces, "Incorrect trace count");
+		assert_eq!(1, summary.conformant, "Incorrect conformant count");
+		assert_eq!(
+			hashmap!(0 => 2, 1 => 1),
+			summary.violations,
+			"Incorrect violations"
+		);
+		assert_eq!(
+			Some(&0),
+			summary.counterexamples.get(&0).map(|(key, _)| key),
+			"Incorrect counterexample"
+		);
+
+		#[cfg(feature = "rayon")]
+		assert_eq!(
+			summary,
+			dfa.par_check_traces(traces),
+			"Incorrect parallel summary"
+		);
+	}
+
+	#[cfg(feature = "serde_json")]
+	#[test]
+	fn dir() {
+		let dir = std::env::temp_dir().join(format!("finite-traces-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		for (name, inputs) in &[("a.jsonl", &['a', 'b'][..]), ("b.jsonl", &['b'][..])] {
+			let file = File::create(dir.join(name)).unwrap();
+			trace(inputs).write_json_lines(file).unwrap();
+		}
+		fs::write(dir.join("c.jsonl"), "not a trace").unwrap();
+		let summary = model().check_trace_dir(&dir).unwrap();
+		fs::remove_dir_all(&dir).unwrap();
+		assert_eq!(2, summary.traces, "Incorrect trace count");
+		assert_eq!(
+			vec![&dir.join("c.jsonl")],
+			summary
+				.unreadable
+				.iter()
+				.map(|(path, _)| path)
+				.collect::<Vec<_>>(),
+			"Incorrect unreadable traces"
+		);
+		assert_eq!(
+			Some(&dir.join("b.jsonl")),
+			summary.counterexamples.get(&0).map(|(path, _)| path),
+			"Incorrect counterexample"
+		);
+	}
+}
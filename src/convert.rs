@@ -0,0 +1,605 @@
+use super::{
+	determinize::LIMIT,
+	intern::Interner,
+	nfa::{Edge, State},
+	Automaton, AutomatonError, DFA, NFA,
+};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+/// Policy for the display names of states during a conversion.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Naming {
+	/// Keeps the display names.
+	/// A state combining several named states is named after all of them.
+	#[default]
+	Keep,
+
+	/// Drops all display names.
+	Drop,
+}
+
+/// Options for explicit conversions between DFAs & NFAs.
+///
+/// By default, at most 65536 states are created, only states reachable from the initial & current states are kept,
+/// dead states are kept and display names are kept.
+#[derive(Debug, Clone)]
+pub struct Conversion {
+	limit: usize,
+	reachable_only: bool,
+	trim_dead: bool,
+	naming: Naming,
+}
+
+impl Default for Conversion {
+	fn default() -> Self {
+		Self {
+			limit: LIMIT,
+			reachable_only: true,
+			trim_dead: false,
+			naming: Naming::Keep,
+		}
+	}
+}
+
+impl Conversion {
+	/// Creates new conversion options with the defaults.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the maximum number of states of the converted automaton.
+	pub fn limit(mut self, limit: usize) -> Self {
+		self.limit = limit;
+		self
+	}
+
+	/// Sets whether to only keep states reachable from the initial & current states.
+	pub fn reachable_only(mut self, reachable_only: bool) -> Self {
+		self.reachable_only = reachable_only;
+		self
+	}
+
+	/// Sets whether to drop states of a DFA created from an NFA from which no accepting state is reachable.
+	/// The DFA has no initial state if the initial state is dropped.
+	pub fn trim_dead(mut self, trim_dead: bool) -> Self {
+		self.trim_dead = trim_dead;
+		self
+	}
+
+	/// Sets the policy for the display names of states.
+	pub fn naming(mut self, naming: Naming) -> Self {
+		self.naming = naming;
+		self
+	}
+}
+
+/// Summary of a conversion of an NFA into a DFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionReport {
+	/// Number of declared states of the NFA.
+	pub states: usize,
+
+	/// Number of subsets of states explored.
+	pub explored: usize,
+
+	/// Number of explored subsets dropped as dead states.
+	pub dead: usize,
+}
+
+impl ConversionReport {
+	/// Returns the number of nonempty subsets of states of the NFA, which bounds the number of states of the DFA.
+	pub fn possible(&self) -> f64 {
+		2f64.powf(self.states as f64) - 1.0
+	}
+
+	/// Returns the fraction of nonempty subsets of states which were not explored.
+	pub fn unreachable_fraction(&self) -> f64 {
+		let possible = self.possible();
+		if possible > 0.0 {
+			1.0 - self.explored as f64 / possible
+		} else {
+			0.0
+		}
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Ord + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Default + Clone,
+{
+	/// Converts the NFA into an equivalent DFA using the powerset construction.
+	/// Returns an `AutomatonError::LimitExceeded` error if the DFA would have more states than the limit of the options.
+	///
+	/// A transition of the DFA combines multiple transitions of the NFA.
+	/// It carries the payload of the one with the highest priority.
+	/// Transitions with equal priority are ordered by their source & target states.
+	/// Epsilon transitions are followed, so every state of the DFA is closed under them.
+	pub fn to_dfa(
+		&self,
+		options: &Conversion,
+	) -> Result<DFA<BTreeSet<S>, I, T>, AutomatonError<S>> {
+		self.to_dfa_checked(options).map(|(dfa, _)| dfa)
+	}
+
+	/// Converts the NFA into an equivalent DFA like `to_dfa`, returning a report of the conversion along with it.
+	#[allow(clippy::type_complexity)]
+	pub fn to_dfa_checked(
+		&self,
+		options: &Conversion,
+	) -> Result<(DFA<BTreeSet<S>, I, T>, ConversionReport), AutomatonError<S>> {
+		self.powerset(options, |_, set| self.subset_ids(set))
+	}
+
+	/// Converts the NFA into an equivalent DFA like `to_dfa`, renaming each set of states through a function.
+	/// The function has to return distinct IDs for distinct sets.
+	pub fn to_dfa_with<R, F>(
+		&self,
+		options: &Conversion,
+		mut rename: F,
+	) -> Result<DFA<R, I, T>, AutomatonError<S>>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+		F: FnMut(&BTreeSet<S>) -> R,
+	{
+		self.powerset(options, |_, set| rename(&self.subset_ids(set)))
+			.map(|(dfa, _)| dfa)
+	}
+
+	/// Converts the NFA into an equivalent DFA like `to_dfa`, numbering the states in order of exploration.
+	/// The initial state is numbered `0` if there is one.
+	///
+	/// No sets of states are built as IDs, which keeps the DFA small & fast for further operations.
+	pub fn to_dfa_numbered(
+		&self,
+		options: &Conversion,
+	) -> Result<DFA<usize, I, T>, AutomatonError<S>> {
+		self.powerset(options, |number, _| number)
+			.map(|(dfa, _)| dfa)
+	}
+
+	/// Performs the powerset construction, labeling each kept subset with a function of its number & state indices.
+	#[allow(clippy::type_complexity)]
+	fn powerset<R, F>(
+		&self,
+		options: &Conversion,
+		mut label: F,
+	) -> Result<(DFA<R, I, T>, ConversionReport), AutomatonError<S>>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+		F: FnMut(usize, &[usize]) -> R,
+	{
+		let sets = if options.reachable_only {
+			self.reachable_subsets(options.limit)?
+		} else {
+			self.all_subsets(options.limit)?
+		};
+		let rows: Vec<_> = sets
+			.iter()
+			.map(|set| {
+				let (accepts, transitions) = self.subset_state(set);
+				let transitions: Vec<_> = transitions
+					.into_iter()
+					.map(|(input, (next, payload))| {
+						let next = sets.get(&next).expect("Successor subset not interned");
+						(input, (next, payload))
+					})
+					.collect();
+				(accepts, transitions)
+			})
+			.collect();
+		let keep = if options.trim_dead {
+			coreachable(&rows)
+		} else {
+			vec![true; rows.len()]
+		};
+
+		// labels of the kept subsets, numbered in order of exploration
+		let mut labels = vec![None; sets.len()];
+		let mut kept = 0;
+		for (i, set) in sets.iter().enumerate() {
+			if keep[i] {
+				labels[i] = Some(label(kept, set));
+				kept += 1;
+			}
+		}
+		let mut dfa = DFA::from_states_map(rows.into_iter().zip(&labels).filter_map(
+			|((accepts, transitions), id)| {
+				let transitions: Vec<_> = transitions
+					.into_iter()
+					.filter_map(|(input, (next, payload))| {
+						labels[next].clone().map(|next| (input, (next, payload)))
+					})
+					.collect();
+				id.clone().map(|id| (id, (accepts, transitions)))
+			},
+		));
+		let subset = |set: BTreeSet<usize>| {
+			let set: Vec<_> = set.into_iter().collect();
+			sets.get(&set).and_then(|number| labels[number].clone())
+		};
+		if let Some(initial) = subset(self.epsilon_closure(self.initial_indices())) {
+			dfa.set_initial(initial);
+		}
+		if let Some(current) = subset(self.epsilon_closure(self.current_indices())) {
+			dfa.set_current(current);
+		}
+		dfa.alphabet = self.alphabet.clone();
+		if options.naming == Naming::Keep {
+			for (set, id) in sets.iter().zip(&labels) {
+				let index = id.as_ref().and_then(|id| dfa.index_of(id));
+				if let Some(index) =
+					index.filter(|_| set.iter().any(|i| self.names.contains_key(i)))
+				{
+					let names: Vec<_> = set
+						.iter()
+						.map(|i| self.display_name(&self.ids[*i]))
+						.collect();
+					dfa.names.insert(index, format!("{{{}}}", names.join(", ")));
+				}
+			}
+		}
+		let report = ConversionReport {
+			states: self.declared().count(),
+			explored: sets.len(),
+			dead: sets.len() - kept,
+		};
+		Ok((dfa, report))
+	}
+
+	/// Returns the IDs of a set of state indices.
+	fn subset_ids(&self, set: &[usize]) -> BTreeSet<S> {
+		set.iter().map(|i| self.ids[*i].clone()).collect()
+	}
+
+	/// Returns the nonempty subsets of declared states reachable from the initial & current states.
+	/// Returns an `AutomatonError::LimitExceeded` error if there are more than `limit` subsets.
+	fn reachable_subsets(&self, limit: usize) -> Result<Interner, AutomatonError<S>> {
+		let mut sets = Interner::new();
+		// initial states are explored first
+		let mut queue: Vec<Vec<usize>> = vec![
+			self.epsilon_closure(self.current_indices()),
+			self.epsilon_closure(self.initial_indices()),
+		]
+		.into_iter()
+		.filter(|set| !set.is_empty())
+		.map(|set| set.into_iter().collect())
+		.collect();
+		while let Some(set) = queue.pop() {
+			if sets.get(&set).is_some() {
+				continue;
+			}
+			if sets.len() >= limit {
+				return Err(AutomatonError::LimitExceeded(limit));
+			}
+			queue.extend(
+				self.subset_state(&set)
+					.1
+					.into_iter()
+					.map(|(_, (next, _))| next),
+			);
+			sets.intern(&set);
+		}
+		Ok(sets)
+	}
+
+	/// Returns all nonempty subsets of declared states, starting with the initial subset.
+	/// Returns an `AutomatonError::LimitExceeded` error if there are more than `limit` subsets.
+	fn all_subsets(&self, limit: usize) -> Result<Interner, AutomatonError<S>> {
+		let declared: Vec<_> = self.declared().map(|(i, _)| i).collect();
+		let size = 1usize
+			.checked_shl(declared.len() as u32)
+			.filter(|size| size - 1 <= limit)
+			.ok_or(AutomatonError::LimitExceeded(limit))?;
+		let mut sets = Interner::new();
+		let initial: Vec<_> = self
+			.epsilon_closure(self.initial_indices())
+			.into_iter()
+			.collect();
+		if !initial.is_empty() {
+			sets.intern(&initial);
+		}
+		for i in 1..size {
+			let set: Vec<_> = declared
+				.iter()
+				.enumerate()
+				.filter(|(j, _)| i & (1 << j) != 0)
+				.map(|(_, index)| *index)
+				.collect();
+			sets.intern(&set);
+		}
+		Ok(sets)
+	}
+
+	/// Returns whether a subset of states accepts & the transitions combining the transitions of its states.
+	#[allow(clippy::type_complexity)]
+	fn subset_state(&self, set: &[usize]) -> (bool, Vec<(I, (Vec<usize>, T))>) {
+		let mut accepts = false;
+		let mut transition_map: HashMap<&I, (BTreeSet<usize>, Option<(u32, &S, &S, &Edge<T>)>)> =
+			HashMap::new();
+		for index in set {
+			if let Some(State {
+				accepts: accepting,
+				transitions,
+				..
+			}) = self.state(*index)
+			{
+				accepts |= accepting;
+				for (input, next) in transitions {
+					for (el, edge) in next.iter().filter(|(el, _)| self.states[**el].is_some()) {
+						let (set, first) = transition_map.entry(input).or_default();
+						set.insert(*el);
+						let key = (edge.priority, &self.ids[*index], &self.ids[*el], edge);
+						if first.is_none_or(|(priority, prev, next, _)| {
+							(key.0, key.1, key.2) < (priority, prev, next)
+						}) {
+							*first = Some(key);
+						}
+					}
+				}
+			}
+		}
+		let transitions = transition_map
+			.into_iter()
+			.map(|(input, (set, edge))| {
+				let payload = edge
+					.map(|(_, _, _, edge)| edge.payload.clone())
+					.unwrap_or_default();
+				let next = self.epsilon_closure(&set).into_iter().collect();
+				(input.clone(), (next, payload))
+			})
+			.collect();
+		(accepts, transitions)
+	}
+}
+
+/// Returns for each numbered state whether an accepting state is reachable from it.
+#[allow(clippy::type_complexity)]
+fn coreachable<I, T>(rows: &[(bool, Vec<(I, (usize, T))>)]) -> Vec<bool> {
+	let mut predecessors = vec![Vec::new(); rows.len()];
+	for (i, (_, transitions)) in rows.iter().enumerate() {
+		for (_, (next, _)) in transitions {
+			predecessors[*next].push(i);
+		}
+	}
+	let mut coreachable: Vec<_> = rows.iter().map(|(accepts, _)| *accepts).collect();
+	let mut stack: Vec<_> = (0..rows.len()).filter(|i| coreachable[*i]).collect();
+	while let Some(state) = stack.pop() {
+		for prev in &predecessors[state] {
+			if !coreachable[*prev] {
+				coreachable[*prev] = true;
+				stack.push(*prev);
+			}
+		}
+	}
+	coreachable
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Clone,
+{
+	/// Converts the DFA into an equivalent NFA.
+	/// Returns an `AutomatonError::LimitExceeded` error if the NFA would have more states than the limit of the options.
+	pub fn to_nfa(&self, options: &Conversion) -> Result<NFA<S, I, T>, AutomatonError<S>> {
+		let mut keep = vec![!options.reachable_only; self.states.len()];
+		if options.reachable_only {
+			let mut queue: Vec<_> = self.initial.iter().chain(&self.current).cloned().collect();
+			while let Some(index) = queue.pop() {
+				if !keep[index] && self.states[index].is_some() {
+					keep[index] = true;
+					queue.extend(self.declared_transitions(index));
+				}
+			}
+		}
+		let count = self.declared().filter(|(index, _)| keep[*index]).count();
+		if count > options.limit {
+			return Err(AutomatonError::LimitExceeded(options.limit));
+		}
+		let mut nfa = NFA::from_parts(
+			self.ids.clone(),
+			self.index.clone(),
+			self.states
+				.iter()
+				.enumerate()
+				.map(|(index, state)| {
+					state.as_ref().filter(|_| keep[index]).map(|state| {
+						State::new(
+							state.accepts,
+							state
+								.transitions
+								.iter()
+								.map(|(input, (next, payload))| {
+									let edge = Edge::new(payload.clone());
+									(input.clone(), Some((*next, edge)).into_iter().collect())
+								})
+								.collect(),
+						)
+					})
+				})
+				.collect(),
+			self.initial.into_iter().collect(),
+			self.current.into_iter().collect(),
+		);
+		nfa.alphabet = self.alphabet.clone();
+		if options.naming == Naming::Keep {
+			nfa.names = self
+				.names
+				.iter()
+				.filter(|(index, _)| keep[**index])
+				.map(|(index, name)| (*index, name.clone()))
+				.collect();
+		}
+		Ok(nfa)
+	}
+
+	/// Returns the indices of the declared targets of the transitions from a state index.
+	fn declared_transitions(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+		self.state(index)
+			.into_iter()
+			.flat_map(|state| state.transitions.values())
+			.map(|(next, _)| *next)
+			.filter(move |next| self.states[*next].is_some())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::btreeset;
+
+	/// Accepts words over `a` & `b` with `a` as second to last input.
+	fn nfa() -> NFA<u8, char> {
+		let mut nfa = NFA::with_state(0, false);
+		nfa.add_state(1, false);
+		nfa.add_state(2, true);
+		nfa.add_state(3, false);
+		nfa.add_transition((0, 'a', 0)).unwrap();
+		nfa.add_transition((0, 'b', 0)).unwrap();
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		nfa.add_transition((1, 'a', 2)).unwrap();
+		nfa.add_transition((1, 'b', 2)).unwrap();
+		nfa
+	}
+
+	#[test]
+	fn to_dfa() {
+		let mut nfa = nfa();
+		nfa.set_name(&1, "Second").unwrap();
+		let mut dfa = nfa.to_dfa(&Conversion::new()).unwrap();
+		assert_eq!(4, dfa.stats().states, "Unreachable subsets kept");
+		assert!(dfa.run(&['b', 'a', 'b']), "Word rejected");
+		assert!(!dfa.run(&['a', 'b', 'b']), "Word accepted");
+		assert_eq!(
+			Some("{0, Second}"),
+			dfa.name(&btreeset![0, 1]),
+			"Incorrect combined name"
+		);
+		assert!(
+			matches!(
+				nfa.to_dfa(&Conversion::new().limit(3)),
+				Err(AutomatonError::LimitExceeded(3))
+			),
+			"Limit not enforced"
+		);
+
+		let all = nfa
+			.to_dfa(&Conversion::new().reachable_only(false).naming(Naming::Drop))
+			.unwrap();
+		assert_eq!(15, all.stats().states, "Subsets missing");
+		assert_eq!(None, all.name(&btreeset![0, 1]), "Name not dropped");
+	}
+
+	#[test]
+	fn renamed() {
+		let mut nfa = nfa();
+		nfa.set_name(&1, "Second").unwrap();
+		let mut numbered = nfa.to_dfa_numbered(&Conversion::new()).unwrap();
+		assert_eq!(4, numbered.stats().states, "Unreachable subsets kept");
+		assert_eq!(Some(&0), numbered.get_current(), "Initial state not first");
+		assert!(numbered.run(&['b', 'a', 'b']), "Word rejected");
+		assert!(!numbered.run(&['a', 'b', 'b']), "Word accepted");
+		numbered.reset();
+		numbered.step(&'a');
+		let next = *numbered.get_current().unwrap();
+		assert_eq!(Some("{0, Second}"), numbered.name(&next), "Name not kept");
+
+		let mut renamed = nfa
+			.to_dfa_with(&Conversion::new(), |set| {
+				set.iter().map(|id| id.to_string()).collect::<String>()
+			})
+			.unwrap();
+		assert!(renamed.has_state(&"01".to_string()), "State not renamed");
+		assert!(renamed.run(&['a', 'a']), "Word rejected");
+	}
+
+	#[test]
+	fn report() {
+		// accepts only `a`, with a dead state after `b`
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_state(2, false);
+		for transition in [(0, 'a', 1), (0, 'b', 2), (2, 'a', 2)] {
+			nfa.add_transition(transition).unwrap();
+		}
+		let (dfa, report) = nfa.to_dfa_checked(&Conversion::new()).unwrap();
+		assert_eq!(
+			ConversionReport {
+				states: 3,
+				explored: 3,
+				dead: 0,
+			},
+			report,
+			"Incorrect report"
+		);
+		assert!(dfa.has_state(&btreeset![2]), "Dead state trimmed");
+		assert!(
+			(report.unreachable_fraction() - 4.0 / 7.0).abs() < 1e-9,
+			"Incorrect unreachable fraction"
+		);
+
+		let (mut trimmed, report) = nfa
+			.to_dfa_checked(&Conversion::new().trim_dead(true))
+			.unwrap();
+		assert_eq!(1, report.dead, "Dead state not counted");
+		assert!(!trimmed.has_state(&btreeset![2]), "Dead state kept");
+		assert!(trimmed.run(&['a']), "Word rejected");
+		let numbered = nfa
+			.to_dfa_numbered(&Conversion::new().trim_dead(true))
+			.unwrap();
+		assert!(
+			numbered.has_state(&0) && numbered.has_state(&1),
+			"Numbering not contiguous"
+		);
+
+		nfa.set_initial(NFA::<u8, char>::new_state(2));
+		let numbered = nfa
+			.to_dfa_numbered(&Conversion::new().reachable_only(false))
+			.unwrap();
+		assert_eq!(Some(&0), numbered.get_initial(), "Initial state not first");
+	}
+
+	#[test]
+	fn to_nfa() {
+		let mut dfa = DFA::<u8, char>::with_state(0, false);
+		dfa.add_state(1, true);
+		dfa.add_state(2, true);
+		dfa.add_transition((0, 'a', 1)).unwrap();
+		dfa.set_name(&2, "Unreachable").unwrap();
+		let mut nfa = dfa.to_nfa(&Conversion::new()).unwrap();
+		assert!(nfa.run(&['a']), "Word rejected");
+		assert!(!nfa.has_state(&2), "Unreachable state kept");
+		let nfa = dfa
+			.to_nfa(&Conversion::new().reachable_only(false))
+			.unwrap();
+		assert_eq!(Some("Unreachable"), nfa.name(&2), "Name not kept");
+		assert!(
+			dfa.to_nfa(&Conversion::new().limit(1)).is_err(),
+			"Limit not enforced"
+		);
+	}
+
+	#[test]
+	fn payload_order() {
+		let mut nfa = NFA::<u8, char, &str>::with_state(0, false);
+		nfa.add_state(2, true);
+		nfa.add_state(1, true);
+		nfa.add_transition_with_payload((0, 'a', 2), "second")
+			.unwrap();
+		nfa.add_transition_with_payload((0, 'a', 1), "first")
+			.unwrap();
+		let dfa = nfa.to_dfa(&Conversion::new()).unwrap();
+		assert_eq!(
+			Some(&"first"),
+			dfa.payload(&btreeset![0], &'a'),
+			"Payload depends on declaration order"
+		);
+	}
+}
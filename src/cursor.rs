@@ -0,0 +1,422 @@
+use super::{DFA, NFA};
+use std::{collections::BTreeSet, fmt, hash::Hash, rc::Rc};
+
+/// States of a run of a DFA or an NFA.
+/// The states of an NFA are shared with checkpoints & replaced as a whole on every step.
+#[derive(Debug)]
+enum Run<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	Deterministic(&'a DFA<S, I, T>, Option<usize>),
+	Nondeterministic(&'a NFA<S, I, T>, Rc<BTreeSet<usize>>),
+}
+
+impl<'a, S, I, T> Clone for Run<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	fn clone(&self) -> Self {
+		match self {
+			Self::Deterministic(dfa, current) => Self::Deterministic(dfa, *current),
+			Self::Nondeterministic(nfa, current) => Self::Nondeterministic(nfa, Rc::clone(current)),
+		}
+	}
+}
+
+/// Location of a `Cursor` in its inputs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+	/// Number of inputs read, which is the offset in chars for a run on text.
+	pub offset: usize,
+
+	/// Offset in bytes of the UTF-8 encoding of the chars read for a run on text, otherwise the number of inputs read.
+	pub byte: usize,
+
+	/// Line & column of the next char, both starting from `1`, only tracked for a run on text.
+	///
+	/// Lines are ended by `\n` & columns count chars.
+	pub line: Option<(usize, usize)>,
+}
+
+impl Location {
+	/// Returns the location at the start of text, with line & column tracked.
+	fn text() -> Self {
+		Self {
+			line: Some((1, 1)),
+			..Self::default()
+		}
+	}
+}
+
+/// Advances a location over an input.
+type Track<I> = fn(&mut Location, &I);
+
+/// Advances a location over any input by one byte.
+fn count<I>(location: &mut Location, _: &I) {
+	location.offset += 1;
+	location.byte += 1;
+}
+
+/// Advances a location over a char of text.
+fn text(location: &mut Location, input: &char) {
+	location.offset += 1;
+	location.byte += input.len_utf8();
+	location.line = location.line.map(|(line, column)| {
+		if *input == '\n' {
+			(line + 1, 1)
+		} else {
+			(line, column + 1)
+		}
+	});
+}
+
+/// Resumable run of a DFA or an NFA, see `DFA::start_run` & `NFA::start_run`.
+///
+/// The cursor owns the states of its run & only borrows the automaton, whose own current state is left untouched.
+/// Cloning a cursor forks the run, e.g. to explore several continuations of the inputs read so far.
+/// Checkpoints allow speculatively reading inputs & rewinding the run afterwards.
+///
+/// The cursor tracks its location in the inputs, including byte offsets, lines & columns for runs on text,
+/// see `DFA::start_text_run` & `NFA::start_text_run`, e.g. to point at the input where a run failed.
+#[derive(Debug)]
+pub struct Cursor<'a, S, I, T = ()>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	run: Run<'a, S, I, T>,
+	location: Location,
+	track: Track<I>,
+}
+
+impl<'a, S, I, T> Clone for Cursor<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	fn clone(&self) -> Self {
+		Self {
+			run: self.run.clone(),
+			location: self.location,
+			track: self.track,
+		}
+	}
+}
+
+/// Saved position of a `Cursor`, see `Cursor::checkpoint`.
+#[derive(Debug)]
+pub struct Checkpoint<'a, S, I, T = ()>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	run: Run<'a, S, I, T>,
+	location: Location,
+}
+
+impl<'a, S, I, T> Clone for Checkpoint<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	fn clone(&self) -> Self {
+		Self {
+			run: self.run.clone(),
+			location: self.location,
+		}
+	}
+}
+
+impl<'a, S, I, T> Cursor<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Reads an input & returns whether the run can still continue.
+	///
+	/// If there is no transition for the input, the run goes into an invalid state it can not leave.
+	/// The location stays at the rejected input & inputs read afterwards are ignored.
+	pub fn push(&mut self, input: &I) -> bool {
+		if !self.is_valid() {
+			return false;
+		}
+		match &mut self.run {
+			Run::Deterministic(dfa, current) => {
+				*current =
+					current.and_then(|index| dfa.transition(index, input).map(|(next, _)| *next));
+			}
+			Run::Nondeterministic(nfa, current) => {
+				*current = Rc::new(nfa.epsilon_closure(&nfa.successors(current, input)));
+			}
+		}
+		let valid = self.is_valid();
+		if valid {
+			(self.track)(&mut self.location, input);
+		}
+		valid
+	}
+
+	/// Reads a sequence of inputs & returns whether the run can still continue.
+	pub fn extend<'b, V>(&mut self, inputs: V) -> bool
+	where
+		I: 'b,
+		V: IntoIterator<Item = &'b I>,
+	{
+		for input in inputs {
+			self.push(input);
+		}
+		self.is_valid()
+	}
+
+	/// Checks whether the automaton accepts the inputs read so far.
+	pub fn accepts_so_far(&self) -> bool {
+		match &self.run {
+			Run::Deterministic(dfa, current) => {
+				current.is_some_and(|index| dfa.state(index).is_some_and(|state| state.accepts))
+			}
+			Run::Nondeterministic(nfa, current) => current
+				.iter()
+				.any(|index| nfa.state(*index).is_some_and(|state| state.accepts)),
+		}
+	}
+
+	/// Checks whether the run is in a valid state, i.e. it can still continue.
+	pub fn is_valid(&self) -> bool {
+		match &self.run {
+			Run::Deterministic(_, current) => current.is_some(),
+			Run::Nondeterministic(_, current) => !current.is_empty(),
+		}
+	}
+
+	/// Saves the current states & location of the run.
+	///
+	/// Checkpoints take constant time, the current state of a DFA is copied & the current states of an NFA are shared.
+	pub fn checkpoint(&self) -> Checkpoint<'a, S, I, T> {
+		Checkpoint {
+			run: self.run.clone(),
+			location: self.location,
+		}
+	}
+
+	/// Restores the states & location of the run saved in a checkpoint, discarding the inputs read since.
+	/// Checkpoints may be restored any number of times & in any order, also by forks of the cursor.
+	pub fn rewind(&mut self, checkpoint: &Checkpoint<'a, S, I, T>) {
+		self.run = checkpoint.run.clone();
+		self.location = checkpoint.location;
+	}
+
+	/// Returns the number of inputs read so far, up to the first rejected input.
+	pub fn position(&self) -> usize {
+		self.location.offset
+	}
+
+	/// Returns the location of the next input.
+	/// Once the run is invalid, this is the location of the rejected input.
+	pub fn location(&self) -> Location {
+		self.location
+	}
+
+	/// Returns the current states of the run in order of declaration.
+	/// A run of a DFA has at most one current state.
+	pub fn states(&self) -> Vec<&'a S> {
+		match &self.run {
+			Run::Deterministic(dfa, current) => {
+				current.iter().map(|index| &dfa.ids[*index]).collect()
+			}
+			Run::Nondeterministic(nfa, current) => {
+				current.iter().map(|index| &nfa.ids[*index]).collect()
+			}
+		}
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Starts a run from the initial state, without changing the current state of the DFA.
+	/// The run is in an invalid state if there is no initial state.
+	pub fn start_run(&self) -> Cursor<'_, S, I, T> {
+		Cursor {
+			run: Run::Deterministic(self, self.initial),
+			location: Location::default(),
+			track: count,
+		}
+	}
+}
+
+impl<S, T> DFA<S, char, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+{
+	/// Starts a run on text like `start_run`, tracking byte offsets, lines & columns of the chars read.
+	pub fn start_text_run(&self) -> Cursor<'_, S, char, T> {
+		Cursor {
+			location: Location::text(),
+			track: text,
+			..self.start_run()
+		}
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Starts a run from the initial states, without changing the current states of the NFA.
+	/// Epsilon transitions are followed.
+	pub fn start_run(&self) -> Cursor<'_, S, I, T> {
+		Cursor {
+			run: Run::Nondeterministic(self, Rc::new(self.epsilon_closure(self.initial_indices()))),
+			location: Location::default(),
+			track: count,
+		}
+	}
+}
+
+impl<S, T> NFA<S, char, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+{
+	/// Starts a run on text like `start_run`, tracking byte offsets, lines & columns of the chars read.
+	pub fn start_text_run(&self) -> Cursor<'_, S, char, T> {
+		Cursor {
+			location: Location::text(),
+			track: text,
+			..self.start_run()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{hashmap, hashset};
+
+	#[test]
+	fn dfa() {
+		// accepts `ab` followed by any number of `c`
+		let mut dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1)),
+				1 => (false, hashmap!('b' => 2)),
+				2 => (true, hashmap!('c' => 2))
+			),
+		);
+		dfa.step(&'a');
+		let mut cursor = dfa.start_run();
+		assert!(cursor.extend(&['a', 'b']), "Run stopped");
+		assert!(cursor.accepts_so_far(), "Prefix rejected");
+		let mut fork = cursor.clone();
+		assert!(!fork.push(&'a'), "Fork continued without transition");
+		assert!(!fork.push(&'c'), "Fork left invalid state");
+		assert_eq!(2, fork.position(), "Position moved past rejected input");
+		assert!(cursor.push(&'c'), "Forked run changed");
+		assert_eq!(vec![&2], cursor.states(), "Incorrect state");
+		assert_eq!(Some(&1), dfa.get_current(), "Current state changed");
+	}
+
+	#[test]
+	fn nfa() {
+		// accepts words over `a` & `b` ending in `ab`
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![0, 1], 'b' => hashset![0])),
+				1 => (false, hashmap!('b' => hashset![2])),
+				2 => (true, hashmap!())
+			),
+		);
+		let mut cursor = nfa.start_run();
+		cursor.extend(&['b', 'a']);
+		assert_eq!(
+			hashset![&0, &1],
+			cursor.states().into_iter().collect(),
+			"Incorrect states"
+		);
+		assert!(!cursor.accepts_so_far(), "Prefix accepted");
+		let mut fork = cursor.clone();
+		fork.push(&'b');
+		assert!(fork.accepts_so_far(), "Fork rejected");
+		assert_eq!(2, cursor.position(), "Original cursor moved");
+		assert!(!cursor.accepts_so_far(), "Original cursor changed");
+	}
+
+	#[test]
+	fn checkpoint() {
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![0, 1])),
+				1 => (true, hashmap!('b' => hashset![1]))
+			),
+		);
+		let mut cursor = nfa.start_run();
+		cursor.push(&'a');
+		let checkpoint = cursor.checkpoint();
+		assert!(
+			!cursor.extend(&['b', 'a']),
+			"Run continued without transition"
+		);
+		cursor.rewind(&checkpoint);
+		assert_eq!(1, cursor.position(), "Position not rewound");
+		assert!(cursor.push(&'b'), "States not rewound");
+		assert!(cursor.accepts_so_far(), "Rewound run rejected");
+
+		let dfa = DFA::<u8, char>::from_map(0, hashmap!(0 => (true, hashmap!('a' => 0))));
+		let mut cursor = dfa.start_run();
+		let start = cursor.checkpoint();
+		cursor.extend(&['a', 'b']);
+		cursor.rewind(&start);
+		assert!(cursor.is_valid(), "Invalid state not rewound");
+		assert_eq!(0, cursor.position(), "Position not rewound");
+	}
+
+	#[test]
+	fn location() {
+		// accepts lines of `ä`
+		let dfa =
+			DFA::<u8, char>::from_map(0, hashmap!(0 => (true, hashmap!('ä' => 0, '\n' => 0))));
+		let mut cursor = dfa.start_text_run();
+		cursor.extend(&['ä', '\n']);
+		let checkpoint = cursor.checkpoint();
+		assert!(!cursor.extend(&['ä', 'ä', 'x', 'ä']), "Invalid input read");
+		assert_eq!(
+			Location {
+				offset: 4,
+				byte: 7,
+				line: Some((2, 3))
+			},
+			cursor.location(),
+			"Incorrect location of rejected input"
+		);
+		cursor.rewind(&checkpoint);
+		assert_eq!(
+			Location {
+				offset: 2,
+				byte: 3,
+				line: Some((2, 1))
+			},
+			cursor.location(),
+			"Location not rewound"
+		);
+		let mut cursor = dfa.start_run();
+		cursor.extend(&['ä', '\n']);
+		assert_eq!(
+			Location {
+				offset: 2,
+				byte: 2,
+				line: None
+			},
+			cursor.location(),
+			"Text tracked without text run"
+		);
+	}
+}
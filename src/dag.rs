@@ -0,0 +1,178 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{fmt, hash::Hash};
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Orders the states so that every transition leads to a later state, using Kahn's algorithm.
+	/// Returns `None` if the graph contains a cycle.
+	pub(crate) fn topological_order(&self) -> Option<Vec<usize>> {
+		let mut incoming = vec![0; self.len()];
+		for (_, next) in self.edges.iter().flatten() {
+			incoming[*next] += 1;
+		}
+		let mut order: Vec<_> = (0..self.len()).filter(|i| incoming[*i] == 0).collect();
+		let mut i = 0;
+		while i < order.len() {
+			for (_, next) in &self.edges[order[i]] {
+				incoming[*next] -= 1;
+				if incoming[*next] == 0 {
+					order.push(*next);
+				}
+			}
+			i += 1;
+		}
+		Some(order).filter(|order| order.len() == self.len())
+	}
+
+	/// Groups the states by the length of the longest path leading to them.
+	/// Returns `None` if the graph contains a cycle.
+	pub(crate) fn layers(&self) -> Option<Vec<Vec<usize>>> {
+		let order = self.topological_order()?;
+		let mut depth = vec![0; self.len()];
+		let mut layers: Vec<Vec<usize>> = Vec::new();
+		for state in order {
+			if depth[state] == layers.len() {
+				layers.push(Vec::new());
+			}
+			layers[depth[state]].push(state);
+			for (_, next) in &self.edges[state] {
+				depth[*next] = depth[*next].max(depth[state] + 1);
+			}
+		}
+		Some(layers)
+	}
+
+	/// Returns the labels of a list of states.
+	fn labeled(&self, states: Vec<usize>) -> Vec<S>
+	where
+		S: Clone,
+	{
+		states
+			.into_iter()
+			.map(|state| self.labels[state].clone())
+			.collect()
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Checks whether the transitions between the states form no cycles.
+	pub fn is_acyclic(&self) -> bool {
+		self.graph().topological_order().is_some()
+	}
+
+	/// Orders the states so that every transition leads to a later state.
+	/// Returns `None` if the automaton is not acyclic.
+	pub fn topological_order(&self) -> Option<Vec<S>> {
+		let graph = self.graph();
+		graph.topological_order().map(|order| graph.labeled(order))
+	}
+
+	/// Groups the states by the length of the longest path leading to them.
+	/// Returns `None` if the automaton is not acyclic.
+	pub fn layers(&self) -> Option<Vec<Vec<S>>> {
+		let graph = self.graph();
+		graph.layers().map(|layers| {
+			layers
+				.into_iter()
+				.map(|layer| graph.labeled(layer))
+				.collect()
+		})
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Checks whether the transitions between the states form no cycles.
+	pub fn is_acyclic(&self) -> bool {
+		self.graph().topological_order().is_some()
+	}
+
+	/// Orders the states so that every transition leads to a later state.
+	/// Returns `None` if the automaton is not acyclic.
+	pub fn topological_order(&self) -> Option<Vec<S>> {
+		let graph = self.graph();
+		graph.topological_order().map(|order| graph.labeled(order))
+	}
+
+	/// Groups the states by the length of the longest path leading to them.
+	/// Returns `None` if the automaton is not acyclic.
+	pub fn layers(&self) -> Option<Vec<Vec<S>>> {
+		let graph = self.graph();
+		graph.layers().map(|layers| {
+			layers
+				.into_iter()
+				.map(|layer| graph.labeled(layer))
+				.collect()
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	#[test]
+	fn dfa() {
+		let mut dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1, 'b' => 2)),
+				1 => (false, hashmap!('a' => 2)),
+				2 => (true, hashmap!())
+			),
+		);
+		assert!(dfa.is_acyclic(), "Incorrect acyclicity");
+		assert_eq!(
+			Some(vec![0, 1, 2]),
+			dfa.topological_order(),
+			"Incorrect order"
+		);
+		assert_eq!(
+			Some(vec![vec![0], vec![1], vec![2]]),
+			dfa.layers(),
+			"Incorrect layers"
+		);
+		dfa.add_transition((2, 'a', 2)).unwrap();
+		assert!(!dfa.is_acyclic(), "Self loop not detected");
+		assert_eq!(None, dfa.layers(), "Layers for cyclic automaton");
+	}
+
+	#[test]
+	fn nfa() {
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, false);
+		nfa.add_state(2, true);
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		nfa.add_transition((0, 'a', 2)).unwrap();
+		let layers = nfa.layers().unwrap();
+		assert_eq!(vec![0], layers[0], "Incorrect first layer");
+		let mut second = layers[1].clone();
+		second.sort();
+		assert_eq!(vec![1, 2], second, "Incorrect second layer");
+		nfa.add_transition((2, 'b', 0)).unwrap();
+		assert_eq!(None, nfa.topological_order(), "Order for cyclic automaton");
+	}
+
+	#[test]
+	fn epsilon() {
+		let nfa = crate::regex::compile("ab").unwrap();
+		assert!(nfa.is_acyclic(), "Cycle detected");
+		let order = nfa.topological_order().unwrap();
+		assert_eq!(0, order[0], "Initial state not first");
+		assert_eq!(Some(&3), order.last(), "Accepting state not last");
+		let nfa = crate::regex::compile("ab*").unwrap();
+		assert!(
+			!nfa.is_acyclic(),
+			"Cycle through epsilon transitions not detected"
+		);
+		assert_eq!(None, nfa.layers(), "Layers for cyclic automaton");
+	}
+}
@@ -0,0 +1,149 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{fmt, hash::Hash};
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Returns the reachable states without outgoing transitions.
+	fn deadlocks(&self) -> Vec<&'a S> {
+		self.reachable()
+			.into_iter()
+			.enumerate()
+			.filter(|(state, reachable)| *reachable && self.edges[*state].is_empty())
+			.map(|(state, _)| self.labels[state])
+			.collect()
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Returns the states reachable from the initial state without outgoing transitions.
+	///
+	/// Accepting states are included, so a composed system terminating successfully reports its final states as well.
+	pub fn deadlocks(&self) -> Vec<S> {
+		self.graph().deadlocks().into_iter().cloned().collect()
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Returns graphs of all moves & of only the epsilon transitions over the states of the NFA.
+	fn moves(&self) -> (Graph<'_, S, ()>, Graph<'_, S, ()>) {
+		let graph = self.transition_graph();
+		let indices = graph.indices();
+		let epsilon: Vec<Vec<_>> = graph
+			.labels
+			.iter()
+			.map(|id| {
+				self.index_of(id)
+					.and_then(|index| self.state(index))
+					.into_iter()
+					.flat_map(|state| &state.epsilon)
+					.filter_map(|next| indices.get(&self.ids[*next]))
+					.map(|next| (&(), *next))
+					.collect()
+			})
+			.collect();
+		let all = graph
+			.edges
+			.iter()
+			.zip(&epsilon)
+			.map(|(edges, epsilon)| {
+				edges
+					.iter()
+					.map(|(_, next)| (&(), *next))
+					.chain(epsilon.iter().cloned())
+					.collect()
+			})
+			.collect();
+		let graph = |edges| Graph {
+			labels: graph.labels.clone(),
+			accepts: graph.accepts.clone(),
+			edges,
+			initial: graph.initial.clone(),
+		};
+		(graph(all), graph(epsilon))
+	}
+
+	/// Returns the states reachable from the initial states without outgoing transitions, including epsilon transitions.
+	///
+	/// Accepting states are included, so a composed system terminating successfully reports its final states as well.
+	pub fn deadlocks(&self) -> Vec<S> {
+		self.moves().0.deadlocks().into_iter().cloned().collect()
+	}
+
+	/// Returns the reachable cycles of only epsilon transitions, e.g. of inputs hidden by `hide`.
+	///
+	/// Each livelock is a strongly connected component of the epsilon transitions,
+	/// in which the system can move internally forever without taking any input.
+	pub fn livelocks(&self) -> Vec<Vec<S>> {
+		let (all, epsilon) = self.moves();
+		epsilon
+			.components(&all.reachable())
+			.into_iter()
+			.filter(|component| match component.as_slice() {
+				[state] => epsilon.edges[*state].iter().any(|(_, next)| next == state),
+				_ => true,
+			})
+			.map(|component| {
+				component
+					.into_iter()
+					.map(|state| epsilon.labels[state].clone())
+					.collect()
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	#[test]
+	fn deadlocks() {
+		let dfa = DFA::<&str, char>::from_map(
+			"start",
+			hashmap!(
+				"start" => (false, hashmap!('a' => "stuck", 'b' => "end")),
+				"stuck" => (false, hashmap!()),
+				"end" => (true, hashmap!()),
+				"orphan" => (false, hashmap!())
+			),
+		);
+		let mut deadlocks = dfa.deadlocks();
+		deadlocks.sort();
+		assert_eq!(vec!["end", "stuck"], deadlocks, "Incorrect deadlocks");
+
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, false);
+		nfa.add_epsilon((0, 1)).unwrap();
+		assert_eq!(vec![1], nfa.deadlocks(), "Epsilon transition ignored");
+	}
+
+	#[test]
+	fn livelocks() {
+		// hidden `tau` loops between 1 & 2 and on the unreachable state 3
+		let mut nfa = NFA::<u8, &str>::with_state(0, false);
+		for state in 1..4 {
+			nfa.add_state(state, false);
+		}
+		for transition in [(0, "send", 1), (1, "tau", 2), (2, "tau", 1), (3, "tau", 3)] {
+			nfa.add_transition(transition).unwrap();
+		}
+		let hidden = nfa.hide(vec!["tau"]);
+		let mut livelocks = hidden.livelocks();
+		livelocks.iter_mut().for_each(|livelock| livelock.sort());
+		assert_eq!(vec![vec![1, 2]], livelocks, "Incorrect livelocks");
+		assert!(nfa.livelocks().is_empty(), "Visible cycle reported");
+		assert!(
+			hidden.deadlocks().is_empty(),
+			"Livelock reported as deadlock"
+		);
+	}
+}
@@ -0,0 +1,204 @@
+use super::DFA;
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Number of inputs processed between checks for the dead state in `DenseDFA::run`.
+const UNROLL: usize = 8;
+
+/// Compiled representation of a `DFA` as a dense transition table.
+///
+/// States & inputs are numbered, so a transition is a single lookup in a flat table.
+/// States are numbered in order of declaration & followed by a dead state, which loops to itself on all inputs.
+/// Inputs are numbered as symbols in order of their first appearance.
+/// Inputs without a symbol share an additional column of the table leading to the dead state.
+///
+/// This is meant for hot paths, e.g. custom matchers in downstream crates, which classify their inputs once
+/// & then only work with state & symbol indices.
+#[derive(Debug, Clone)]
+pub struct DenseDFA<I>
+where
+	I: Eq + Hash,
+{
+	symbols: HashMap<I, usize>,
+	accepts: Vec<bool>,
+	table: Vec<usize>,
+	initial: usize,
+}
+
+impl<I> DenseDFA<I>
+where
+	I: Eq + Hash,
+{
+	/// Returns the number of states, including the dead state.
+	pub fn states(&self) -> usize {
+		self.accepts.len()
+	}
+
+	/// Returns the number of symbols.
+	pub fn symbols(&self) -> usize {
+		self.symbols.len()
+	}
+
+	/// Returns the index of the initial state.
+	/// This is the dead state if the DFA has no initial state.
+	pub fn initial(&self) -> usize {
+		self.initial
+	}
+
+	/// Returns the index of the dead state.
+	pub fn dead(&self) -> usize {
+		self.accepts.len() - 1
+	}
+
+	/// Returns the symbol index of an input.
+	/// Returns `None` if no transition uses the input.
+	pub fn symbol(&self, input: &I) -> Option<usize> {
+		self.symbols.get(input).cloned()
+	}
+
+	/// Checks whether a state index is accepting.
+	///
+	/// Panics if the state index is out of bounds.
+	pub fn accepts(&self, state: usize) -> bool {
+		self.accepts[state]
+	}
+
+	/// Returns the index of the next state for a state & symbol index.
+	///
+	/// Symbol indices range up to the number of symbols, which is the index of unknown inputs leading to the dead state.
+	/// The indices are not validated, so they have to be obtained from this table.
+	/// Other indices result in an arbitrary state or a panic.
+	#[inline]
+	pub fn step_index(&self, state: usize, symbol: usize) -> usize {
+		self.table[state * (self.symbols.len() + 1) + symbol]
+	}
+
+	/// Returns the index of the next state for a state index & input.
+	/// Inputs without symbol lead to the dead state.
+	#[inline]
+	pub fn step(&self, state: usize, input: &I) -> usize {
+		let unknown = self.symbols.len();
+		self.step_index(state, self.symbol(input).unwrap_or(unknown))
+	}
+
+	/// Runs the table on a sequence of inputs, starting from the initial state.
+	///
+	/// The loop is unrolled into chunks of 8 inputs. As the dead state loops to itself,
+	/// it is only checked for once per chunk instead of after every input.
+	pub fn run<'a, V>(&self, inputs: V) -> bool
+	where
+		V: IntoIterator<Item = &'a I>,
+		I: 'a,
+	{
+		let dead = self.dead();
+		let mut state = self.initial;
+		let mut inputs = inputs.into_iter();
+		loop {
+			for _ in 0..UNROLL {
+				match inputs.next() {
+					Some(input) => state = self.step(state, input),
+					None => return self.accepts(state),
+				}
+			}
+			if state == dead {
+				return false;
+			}
+		}
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Compiles the DFA into a dense transition table.
+	///
+	/// Missing transitions & transitions to inexistent states lead to the dead state.
+	pub fn compile(&self) -> DenseDFA<I> {
+		let graph = self.graph();
+		let mut symbols = HashMap::new();
+		for (input, _) in graph.edges.iter().flatten() {
+			let len = symbols.len();
+			symbols.entry((*input).clone()).or_insert(len);
+		}
+		let dead = graph.len();
+		let width = symbols.len() + 1;
+		let mut table = vec![dead; (dead + 1) * width];
+		for (state, edges) in graph.edges.iter().enumerate() {
+			for (input, next) in edges {
+				table[state * width + symbols[*input]] = *next;
+			}
+		}
+		let mut accepts = graph.accepts.clone();
+		accepts.push(false);
+		DenseDFA {
+			symbols,
+			accepts,
+			table,
+			initial: graph.initial.first().cloned().unwrap_or(dead),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	/// Accepts words over `a` & `b` with an even number of `b`.
+	fn dfa() -> DFA<&'static str, char> {
+		DFA::from_map(
+			"even",
+			hashmap!(
+				"even" => (true, hashmap!('a' => "even", 'b' => "odd")),
+				"odd" => (false, hashmap!('a' => "odd", 'b' => "even"))
+			),
+		)
+	}
+
+	#[test]
+	fn compile() {
+		let mut dfa = dfa();
+		let dense = dfa.compile();
+		assert_eq!(3, dense.states(), "Incorrect number of states");
+		assert_eq!(2, dense.symbols(), "Incorrect number of symbols");
+		for word in [vec!['a', 'b', 'b'], vec!['b'], vec![], vec!['a', 'c']] {
+			assert_eq!(dfa.run(&word), dense.run(&word), "Incorrect result");
+		}
+	}
+
+	#[test]
+	fn step_index() {
+		let dense = dfa().compile();
+		let b = dense.symbol(&'b').unwrap();
+		let mut state = dense.initial();
+		for _ in 0..3 {
+			state = dense.step_index(state, b);
+		}
+		assert!(!dense.accepts(state), "Odd number of b accepted");
+		let dead = dense.dead();
+		assert_eq!(dead, dense.step(state, &'c'), "Unknown input not dead");
+		assert_eq!(dead, dense.step_index(dead, b), "Dead state left");
+		assert!(!dense.accepts(dead), "Dead state accepting");
+
+		let empty = DFA::<u8, char>::new().compile();
+		assert_eq!(empty.dead(), empty.initial(), "Missing initial not dead");
+		assert!(!empty.run(&[]), "Empty DFA accepting");
+	}
+
+	#[test]
+	fn run_chunks() {
+		let mut dfa = dfa();
+		let dense = dfa.compile();
+		for len in [7, 8, 9, 16, 17] {
+			let mut word = vec!['a'; len];
+			assert!(dense.run(&word), "Word of {} inputs rejected", len);
+			word[len - 1] = 'b';
+			assert_eq!(dfa.run(&word), dense.run(&word), "Incorrect result");
+			// the dead state is entered in the middle of a chunk
+			word[2] = 'c';
+			assert!(!dense.run(&word), "Word through dead state accepted");
+		}
+	}
+}
@@ -0,0 +1,301 @@
+use super::{equivalence::equivalent, graph::Graph, intern::Interner, AutomatonError, DFA, NFA};
+use std::{
+	collections::{BTreeSet, HashMap, HashSet},
+	fmt,
+	hash::Hash,
+};
+
+/// Maximum number of subsets explored by the convenience methods.
+pub(crate) const LIMIT: usize = 1 << 16;
+
+/// Reachable part of the subset construction of a graph.
+///
+/// The first subset is the set of initial states.
+/// Missing transitions lead to the implicit empty subset.
+pub(crate) struct Subsets<'a, I> {
+	pub sets: Interner,
+	pub table: Vec<HashMap<&'a I, usize>>,
+	pub accepts: Vec<bool>,
+}
+
+impl<'a, S, I> Graph<'a, S, I>
+where
+	I: Eq + Hash,
+{
+	/// Performs the subset construction for subsets reachable from the initial states.
+	/// Returns `None` if more than `limit` subsets are reachable.
+	pub fn subsets(&self, limit: usize) -> Option<Subsets<'a, I>> {
+		let mut initial = self.initial.clone();
+		initial.sort_unstable();
+		initial.dedup();
+		let mut sets = Interner::new();
+		sets.intern(&initial);
+		let mut table = Vec::new();
+		let mut i = 0;
+		while i < sets.len() {
+			let mut successors: HashMap<&I, Vec<usize>> = HashMap::new();
+			for state in sets.set(i) {
+				for (input, next) in &self.edges[*state] {
+					successors.entry(*input).or_default().push(*next);
+				}
+			}
+			let mut row = HashMap::with_capacity(successors.len());
+			for (input, mut set) in successors {
+				set.sort_unstable();
+				set.dedup();
+				let number = match sets.get(&set) {
+					Some(number) => number,
+					None => {
+						if sets.len() >= limit {
+							return None;
+						}
+						sets.intern(&set).0
+					}
+				};
+				row.insert(input, number);
+			}
+			table.push(row);
+			i += 1;
+		}
+		let accepts = sets
+			.iter()
+			.map(|set| set.iter().any(|i| self.accepts[*i]))
+			.collect();
+		Some(Subsets {
+			sets,
+			table,
+			accepts,
+		})
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Ord + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Converts the NFA into an equivalent DFA only containing the subsets of states reachable from the initial states.
+	/// Returns an `AutomatonError::LimitExceeded` error if more than `limit` subsets are reachable.
+	///
+	/// Payloads & priorities of transitions are not kept.
+	pub fn determinize(&self, limit: usize) -> Result<DFA<BTreeSet<S>, I>, AutomatonError<S>> {
+		let graph = self.graph();
+		let subsets = graph
+			.subsets(limit)
+			.ok_or(AutomatonError::LimitExceeded(limit))?;
+		let labels = labels(&graph, &subsets);
+		Ok(DFA::from_map(
+			labels[0].clone(),
+			subsets
+				.table
+				.iter()
+				.enumerate()
+				.map(|(i, row)| {
+					let transitions = row
+						.iter()
+						.map(|(input, next)| ((*input).clone(), labels[*next].clone()))
+						.collect();
+					(labels[i].clone(), (subsets.accepts[i], transitions))
+				})
+				.collect::<HashMap<_, _>>(),
+		))
+	}
+
+	/// Constructs a DFA accepting exactly the words rejected by the NFA.
+	/// Returns an `AutomatonError::LimitExceeded` error if the determinization exceeds 65536 states.
+	///
+	/// Words are formed over the inputs used in the transitions of the NFA.
+	/// The empty set of states is used as dead state.
+	pub fn complement(&self) -> Result<DFA<BTreeSet<S>, I>, AutomatonError<S>> {
+		let graph = self.graph();
+		let subsets = graph
+			.subsets(LIMIT)
+			.ok_or(AutomatonError::LimitExceeded(LIMIT))?;
+		let labels = labels(&graph, &subsets);
+		let alphabet: HashSet<&I> = graph
+			.edges
+			.iter()
+			.flatten()
+			.map(|(input, _)| *input)
+			.collect();
+		let dead = BTreeSet::new();
+		let mut states: HashMap<_, _> = subsets
+			.table
+			.iter()
+			.enumerate()
+			.map(|(i, row)| {
+				let transitions = alphabet
+					.iter()
+					.map(|input| {
+						let next = row.get(input).map_or(&dead, |next| &labels[*next]);
+						((*input).clone(), next.clone())
+					})
+					.collect();
+				(labels[i].clone(), (!subsets.accepts[i], transitions))
+			})
+			.collect();
+		states.entry(dead.clone()).or_insert_with(|| {
+			let transitions = alphabet
+				.iter()
+				.map(|input| ((*input).clone(), dead.clone()))
+				.collect();
+			(true, transitions)
+		});
+		Ok(DFA::from_map(labels[0].clone(), states))
+	}
+
+	/// Checks whether the NFA accepts the same language as another NFA.
+	/// Returns an `AutomatonError::LimitExceeded` error if the determinization of either exceeds 65536 states.
+	pub fn equivalent<R, U>(&self, other: &NFA<R, I, U>) -> Result<bool, AutomatonError<S>>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		let (graph, other) = (self.graph(), other.graph());
+		let a = graph
+			.subsets(LIMIT)
+			.ok_or(AutomatonError::LimitExceeded(LIMIT))?;
+		let b = other
+			.subsets(LIMIT)
+			.ok_or(AutomatonError::LimitExceeded(LIMIT))?;
+
+		// combine both into one table with a shared dead state at the end
+		let offset = a.table.len();
+		let mut table = a.table;
+		table.extend(b.table.into_iter().map(|row| {
+			row.into_iter()
+				.map(|(input, next)| (input, next + offset))
+				.collect()
+		}));
+		let mut accepts = a.accepts;
+		accepts.extend(b.accepts);
+		Ok(equivalent(&table, &accepts, 0, offset))
+	}
+
+	/// Checks whether every word accepted by the NFA is also accepted by another NFA.
+	/// Returns an `AutomatonError::LimitExceeded` error if the determinization of either exceeds 65536 states.
+	pub fn is_subset_of<R, U>(&self, other: &NFA<R, I, U>) -> Result<bool, AutomatonError<S>>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		let (graph, other) = (self.graph(), other.graph());
+		let a = graph
+			.subsets(LIMIT)
+			.ok_or(AutomatonError::LimitExceeded(LIMIT))?;
+		let b = other
+			.subsets(LIMIT)
+			.ok_or(AutomatonError::LimitExceeded(LIMIT))?;
+
+		// explore pairs of subsets, the other automaton may be in its dead state
+		let mut visited = HashSet::new();
+		visited.insert((0, Some(0)));
+		let mut stack = vec![(0, Some(0))];
+		while let Some((p, q)) = stack.pop() {
+			if a.accepts[p] && !q.is_some_and(|q| b.accepts[q]) {
+				return Ok(false);
+			}
+			for (input, next) in &a.table[p] {
+				let pair = (*next, q.and_then(|q| b.table[q].get(input).cloned()));
+				if visited.insert(pair) {
+					stack.push(pair);
+				}
+			}
+		}
+		Ok(true)
+	}
+}
+
+/// Returns the states of the automaton contained in each subset.
+fn labels<S, I>(graph: &Graph<S, I>, subsets: &Subsets<I>) -> Vec<BTreeSet<S>>
+where
+	S: Clone + Ord,
+{
+	subsets
+		.sets
+		.iter()
+		.map(|set| set.iter().map(|i| graph.labels[*i].clone()).collect())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{btreeset, hashmap, hashset};
+
+	/// Accepts words over 'a' & 'b' ending in 'a'.
+	fn ends_in_a() -> NFA<u8, char> {
+		NFA::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![0, 1], 'b' => hashset![0])),
+				1 => (true, hashmap!())
+			),
+		)
+	}
+
+	#[test]
+	fn determinize() {
+		let nfa = ends_in_a();
+		let mut dfa = nfa.determinize(16).unwrap();
+		assert!(dfa.has_state(&btreeset![0, 1]), "Reachable subset missing");
+		assert!(!dfa.has_state(&btreeset![1]), "Unreachable subset present");
+		assert!(dfa.run(&['b', 'a']), "Incorrect result after run");
+		assert!(
+			nfa.determinize(1).is_err(),
+			"Missing error on exceeded limit"
+		);
+	}
+
+	#[test]
+	fn complement() {
+		let mut complement = ends_in_a().complement().unwrap();
+		assert!(complement.run(&[]), "Empty word rejected");
+		assert!(complement.run(&['a', 'b']), "Rejected word not accepted");
+		assert!(!complement.run(&['b', 'a']), "Accepted word not rejected");
+	}
+
+	#[test]
+	fn compare() {
+		let nfa = ends_in_a();
+		let dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1, 'b' => 0)),
+				1 => (true, hashmap!('a' => 1, 'b' => 0))
+			),
+		);
+		let other: NFA<_, _> = dfa.into();
+		assert!(nfa.equivalent(&other).unwrap(), "Equivalent NFAs differ");
+
+		let single = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(0 => (false, hashmap!('a' => hashset![1])), 1 => (true, hashmap!())),
+		);
+		assert!(
+			!nfa.equivalent(&single).unwrap(),
+			"Different NFAs equivalent"
+		);
+		assert!(single.is_subset_of(&nfa).unwrap(), "Subset not detected");
+		assert!(!nfa.is_subset_of(&single).unwrap(), "Superset is subset");
+	}
+
+	#[test]
+	fn epsilon() {
+		let a = crate::regex::compile("a").unwrap();
+		let either = crate::regex::compile("a|b").unwrap();
+		assert!(a.is_subset_of(&either).unwrap(), "Subset not detected");
+		assert!(!either.is_subset_of(&a).unwrap(), "Superset is subset");
+		let any = crate::regex::compile("(a|b)*").unwrap();
+		assert!(
+			any.equivalent(&crate::regex::compile("(a*b*)*").unwrap())
+				.unwrap(),
+			"Equivalent NFAs differ"
+		);
+		let mut complement = crate::regex::compile("a*b").unwrap().complement().unwrap();
+		assert!(complement.run(&['a']), "Rejected word not in complement");
+		assert!(
+			!complement.run(&['a', 'a', 'b']),
+			"Accepted word in complement"
+		);
+	}
+}
@@ -1,26 +1,31 @@
-use super::{Automaton, AutomatonError, NFA};
-use serde::{Deserialize, Serialize};
+use super::{
+	graph::Graph,
+	nfa,
+	serialize::{self, double_option},
+	Automaton, AutomatonError, DanglingPolicy, NFA,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-	collections::{HashMap, HashSet},
+	collections::{BTreeMap, HashMap, HashSet},
 	fmt,
 	hash::Hash,
 };
 
-#[derive(Default, Debug, Deserialize, Serialize)]
-#[serde(default, deny_unknown_fields)]
-struct State<S, I>
+/// A declared state with transitions to indices into the state arena & their payloads.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct State<I, T = ()>
 where
 	I: Eq + Hash,
 {
-	accepts: bool,
-	transitions: HashMap<I, S>,
+	pub(crate) accepts: bool,
+	pub(crate) transitions: HashMap<I, (usize, T)>,
 }
 
-impl<S, I> State<S, I>
+impl<I, T> State<I, T>
 where
 	I: Eq + Hash,
 {
-	pub fn new(accepts: bool, transitions: HashMap<I, S>) -> Self {
+	pub fn new(accepts: bool, transitions: HashMap<I, (usize, T)>) -> Self {
 		Self {
 			accepts,
 			transitions,
@@ -28,19 +33,210 @@ where
 	}
 }
 
-/// A deterministic finite state automaton.
-#[derive(Default, Debug, Serialize, Deserialize)]
+/// Fields of the serialized representation of a state.
+const STATE_FIELDS: &[&str] = &["accepts", "transitions", "payloads", "name"];
+
+/// Serialized representation of a state.
+///
+/// Payloads are only present for transitions with a payload other than the default.
+/// The display name is only present if the state has one.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct StateRepr<S, I, T>
+where
+	I: Eq + Hash,
+{
+	accepts: bool,
+	transitions: HashMap<I, S>,
+	// field default allows omitting payloads in the sequence form
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	payloads: HashMap<I, T>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	name: Option<String>,
+}
+
+/// Fields of the serialized representation.
+const FIELDS: &[&str] = &["initial", "current", "states", "alphabet", "dangling"];
+
+/// Serialized representation of a DFA.
+///
+/// The current state is only present if it differs from the initial state.
+/// Older representations without an initial state use the current state as initial state.
+#[derive(Default, Deserialize)]
 #[serde(default, deny_unknown_fields)]
-pub struct DFA<S, I>
+struct Repr<S, I, T>
+where
+	S: Default + Eq + Hash,
+	I: Default + Eq + Hash,
+	T: Default,
+{
+	initial: Option<S>,
+	#[serde(deserialize_with = "double_option")]
+	current: Option<Option<S>>,
+	states: HashMap<S, StateRepr<S, I, T>>,
+	alphabet: Option<HashSet<I>>,
+	dangling: Option<DanglingPolicy>,
+}
+
+/// Borrowed serialized representation of a DFA.
+#[derive(Serialize)]
+struct ReprRef<'a, S, I, T>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+{
+	initial: Option<&'a S>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	current: Option<Option<&'a S>>,
+	states: HashMap<&'a S, StateRepr<&'a S, &'a I, &'a T>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	alphabet: Option<&'a HashSet<I>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	dangling: Option<DanglingPolicy>,
+}
+
+/// A deterministic finite state automaton.
+///
+/// States are stored in an arena & transitions refer to states by their index.
+/// States which are referenced by transitions without being declared occupy an undeclared slot in the arena.
+///
+/// The initial & current state always refer to existing states or are invalid.
+/// Transitions to inexistent states are treated like missing transitions, unless a `DanglingPolicy` is set.
+///
+/// Each transition carries a payload of type `T`, e.g. a cost or a label.
+/// Transitions added without a payload carry the default payload.
+#[derive(Default, Debug)]
+pub struct DFA<S, I, T = ()>
 where
 	S: Default + Clone + Eq + Hash + fmt::Debug,
 	I: Default + Eq + Hash,
 {
-	current: Option<S>,
-	states: HashMap<S, State<S, I>>,
+	pub(crate) ids: Vec<S>,
+	pub(crate) index: HashMap<S, usize>,
+	pub(crate) states: Vec<Option<State<I, T>>>,
+	pub(crate) initial: Option<usize>,
+	pub(crate) current: Option<usize>,
+	pub(crate) alphabet: Option<HashSet<I>>,
+	pub(crate) names: HashMap<usize, String>,
+	pub(crate) dangling: Option<DanglingPolicy>,
+}
+
+impl<'de, S, I, T> Deserialize<'de> for DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug + Deserialize<'de>,
+	I: Default + Eq + Hash + Deserialize<'de>,
+	T: Default + Deserialize<'de>,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let Repr {
+			initial,
+			current,
+			states,
+			alphabet,
+			dangling,
+		} = serialize::repr::<Repr<S, I, T>, _>(deserializer, FIELDS, STATE_FIELDS)?;
+		let (initial, current) = match (initial, current) {
+			(Some(initial), current) => (
+				Some(initial.clone()),
+				current.unwrap_or_else(|| Some(initial)),
+			),
+			(None, current) => {
+				let current = current.flatten();
+				(current.clone(), current)
+			}
+		};
+
+		// only keep references to existing states
+		let mut names = Vec::new();
+		let mut dfa = Self::from_states_map(states.into_iter().map(|(id, state)| {
+			let StateRepr {
+				accepts,
+				transitions,
+				mut payloads,
+				name,
+			} = state;
+			if let Some(name) = name {
+				names.push((id.clone(), name));
+			}
+			let transitions: Vec<_> = transitions
+				.into_iter()
+				.map(|(input, next)| {
+					let payload = payloads.remove(&input).unwrap_or_default();
+					(input, (next, payload))
+				})
+				.collect();
+			(id, (accepts, transitions))
+		}));
+		if let Some(initial) = initial {
+			dfa.set_initial(initial);
+		}
+		if let Some(current) = current {
+			dfa.set_current(current);
+		}
+		dfa.alphabet = alphabet;
+		for (id, name) in names {
+			let index = dfa.index[&id];
+			dfa.names.insert(index, name);
+		}
+		if let Some(policy) = dangling {
+			dfa.set_dangling_policy(policy).map_err(de::Error::custom)?;
+		}
+		Ok(dfa)
+	}
+}
+
+impl<S, I, T> Serialize for DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug + Serialize,
+	I: Default + Eq + Hash + Serialize,
+	T: Default + PartialEq + Serialize,
+{
+	fn serialize<R>(&self, serializer: R) -> Result<R::Ok, R::Error>
+	where
+		R: Serializer,
+	{
+		let default = T::default();
+		ReprRef {
+			initial: self.initial.map(|i| &self.ids[i]),
+			current: if self.current != self.initial {
+				Some(self.current.map(|i| &self.ids[i]))
+			} else {
+				None
+			},
+			states: self
+				.declared()
+				.map(|(i, state)| {
+					(
+						&self.ids[i],
+						StateRepr {
+							accepts: state.accepts,
+							transitions: state
+								.transitions
+								.iter()
+								.map(|(input, (next, _))| (input, &self.ids[*next]))
+								.collect(),
+							payloads: state
+								.transitions
+								.iter()
+								.filter(|(_, (_, payload))| *payload != default)
+								.map(|(input, (_, payload))| (input, payload))
+								.collect(),
+							name: self.names.get(&i).cloned(),
+						},
+					)
+				})
+				.collect(),
+			alphabet: self.alphabet.as_ref(),
+			dangling: self.dangling,
+		}
+		.serialize(serializer)
+	}
 }
 
-impl<S, I> DFA<S, I>
+impl<S, I, T> DFA<S, I, T>
 where
 	S: Default + Clone + Eq + Hash + fmt::Debug,
 	I: Default + Eq + Hash,
@@ -49,40 +245,158 @@ where
 	pub fn from_map<M>(initial: S, states: M) -> Self
 	where
 		M: Into<HashMap<S, (bool, HashMap<I, S>)>>,
+		T: Default,
 	{
-		let map = states.into();
-		Self {
-			current: if map.contains_key(&initial) {
-				Some(initial)
-			} else {
-				None
+		let mut dfa = Self::from_states_map(states.into().into_iter().map(
+			|(id, (accepts, transitions))| {
+				let transitions = transitions
+					.into_iter()
+					.map(|(input, next)| (input, (next, T::default())));
+				(id, (accepts, transitions))
 			},
-			states: map
+		));
+		dfa.set_initial(initial.clone());
+		dfa.set_current(initial);
+		dfa
+	}
+
+	/// Creates a new DFA without initial state from an iterator over states & their transitions with payloads.
+	pub(crate) fn from_states_map<M, E>(states: M) -> Self
+	where
+		M: IntoIterator<Item = (S, (bool, E))>,
+		E: IntoIterator<Item = (I, (S, T))>,
+	{
+		let mut dfa = Self {
+			ids: Vec::new(),
+			index: HashMap::new(),
+			states: Vec::new(),
+			initial: None,
+			current: None,
+			alphabet: None,
+			names: HashMap::new(),
+			dangling: None,
+		};
+		for (id, (accepts, transitions)) in states {
+			let transitions = transitions
 				.into_iter()
-				.map(|(state, (accepts, transitions))| (state, State::new(accepts, transitions)))
-				.collect(),
+				.map(|(input, (next, payload))| (input, (dfa.slot(next), payload)))
+				.collect();
+			let index = dfa.slot(id);
+			dfa.states[index] = Some(State::new(accepts, transitions));
 		}
+		dfa
 	}
 
-	/// Returns a reference to the requested state or an `AutomatonError::InexistentState` error otherwise.
-	fn get_state(&self, id: &S) -> Result<&State<S, I>, AutomatonError<S>> {
-		self.states
+	/// Returns the index of a state, allocating an undeclared slot if necessary.
+	pub(crate) fn slot(&mut self, id: S) -> usize {
+		if let Some(index) = self.index.get(&id) {
+			return *index;
+		}
+		self.ids.push(id.clone());
+		self.states.push(None);
+		self.index.insert(id, self.ids.len() - 1);
+		self.ids.len() - 1
+	}
+
+	/// Returns the index of a declared state.
+	pub(crate) fn index_of(&self, id: &S) -> Option<usize> {
+		self.index
 			.get(id)
-			.ok_or_else(|| AutomatonError::InexistentState(id.clone()))
+			.cloned()
+			.filter(|index| self.states[*index].is_some())
 	}
 
-	/// Returns a mutable reference to the requested state or an `AutomatonError::InexistentState` error otherwise.
-	fn get_state_mut(&mut self, id: &S) -> Result<&mut State<S, I>, AutomatonError<S>> {
+	/// Returns the declared state at an index.
+	pub(crate) fn state(&self, index: usize) -> Option<&State<I, T>> {
+		self.states[index].as_ref()
+	}
+
+	/// Returns the target index & payload of the transition from a state index with an input.
+	/// Transitions to undeclared states are treated like missing transitions.
+	pub(crate) fn transition(&self, index: usize, input: &I) -> Option<&(usize, T)> {
+		self.state(index)
+			.and_then(|state| state.transitions.get(input))
+			.filter(|(next, _)| self.states[*next].is_some())
+	}
+
+	/// Returns an iterator over the indices & declared states.
+	pub(crate) fn declared(&self) -> impl Iterator<Item = (usize, &State<I, T>)> {
 		self.states
-			.get_mut(id)
-			.ok_or_else(|| AutomatonError::InexistentState(id.clone()))
+			.iter()
+			.enumerate()
+			.filter_map(|(i, state)| state.as_ref().map(|state| (i, state)))
+	}
+
+	/// Returns a mutable reference to the requested state or an `AutomatonError::InexistentState` error otherwise.
+	fn get_state_mut<E>(&mut self, id: &S) -> Result<&mut State<I, T>, AutomatonError<S, E>> {
+		match self.index.get(id) {
+			Some(index) => self.states[*index].as_mut(),
+			None => None,
+		}
+		.ok_or_else(|| AutomatonError::InexistentState(id.clone(), Vec::new()))
+	}
+
+	/// Returns an index-based view of the transition graph.
+	pub(crate) fn graph(&self) -> Graph<'_, S, I> {
+		Graph::new(
+			self.ids.iter().zip(&self.states).map(|(id, state)| {
+				(
+					id,
+					state.as_ref().map(|state| {
+						(
+							state.accepts,
+							state
+								.transitions
+								.iter()
+								.map(|(input, (next, _))| (input, *next))
+								.collect(),
+						)
+					}),
+				)
+			}),
+			self.initial,
+		)
+	}
+
+	/// Adds a new transition carrying a payload to the automaton.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	/// The error suggests the states with similar names, if there are any.
+	/// Returns an `AutomatonError::UnknownInput` error if the input is not part of the declared alphabet.
+	pub fn add_transition_with_payload(
+		&mut self,
+		transition: (S, I, S),
+		payload: T,
+	) -> Result<(), AutomatonError<S, I>> {
+		let (prev, input, next) = transition;
+		if !self.knows(&input) {
+			return Err(AutomatonError::UnknownInput(input));
+		}
+		if self.index_of(&prev).is_none() {
+			return Err(self.inexistent(prev));
+		}
+		match self.index_of(&next) {
+			Some(next) => {
+				let State { transitions, .. } = self.get_state_mut(&prev)?;
+				transitions.insert(input, (next, payload));
+				Ok(())
+			}
+			None => Err(self.inexistent(next)),
+		}
+	}
+
+	/// Returns the payload of the transition from a state with an input.
+	pub fn payload(&self, id: &S, input: &I) -> Option<&T> {
+		self.index_of(id)
+			.and_then(|index| self.transition(index, input))
+			.map(|(_, payload)| payload)
 	}
 }
 
-impl<S, I> Automaton<S, I> for DFA<S, I>
+impl<S, I, T> Automaton<S, I> for DFA<S, I, T>
 where
 	S: Default + Clone + Eq + Hash + fmt::Debug,
 	I: Default + Eq + Hash,
+	T: Default,
 {
 	type State = S;
 	type Transition = (S, I, S);
@@ -92,82 +406,90 @@ where
 	}
 
 	fn has_state(&self, id: &S) -> bool {
-		self.states.contains_key(id)
+		self.index_of(id).is_some()
 	}
 
 	fn add_state(&mut self, id: S, accept: bool) {
-		self.states.insert(id, State::new(accept, HashMap::new()));
+		let index = self.slot(id);
+		self.states[index] = Some(State::new(accept, HashMap::new()));
 	}
 
-	fn add_transition(&mut self, transition: Self::Transition) -> Result<(), AutomatonError<S>> {
-		let (prev, input, next) = transition;
-		if !self.has_state(&next) {
-			Err(AutomatonError::InexistentState(next))
-		} else {
-			let State { transitions, .. } = self.get_state_mut(&prev)?;
-			transitions.insert(input, next);
-			Ok(())
-		}
+	fn add_transition(&mut self, transition: Self::Transition) -> Result<(), AutomatonError<S, I>> {
+		self.add_transition_with_payload(transition, T::default())
+	}
+
+	fn set_initial(&mut self, id: S) {
+		self.initial = self.index_of(&id);
+	}
+
+	fn get_initial(&self) -> Option<&S> {
+		self.initial.map(|index| &self.ids[index])
 	}
 
 	fn get_current(&self) -> Option<&S> {
-		self.current.as_ref()
+		self.current.map(|index| &self.ids[index])
 	}
 
 	fn set_current(&mut self, id: S) {
-		self.current = if self.has_state(&id) { Some(id) } else { None };
+		self.current = self.index_of(&id);
+	}
+
+	fn reset(&mut self) {
+		self.current = self.initial;
 	}
 
 	fn accepts(&self) -> bool {
-		match &self.current {
-			Some(current) => self.get_state(current).unwrap().accepts,
+		match self.current {
+			Some(current) => matches!(self.state(current), Some(state) if state.accepts),
 			None => false,
 		}
 	}
 
 	fn step(&mut self, input: &I) {
-		if let Some(current) = &self.current {
-			match self.get_state(current).unwrap().transitions.get(input) {
-				Some(next) if self.has_state(next) => self.current = Some(next.clone()),
-				_ => self.current = None,
-			}
+		if let Some(current) = self.current {
+			self.current = self.transition(current, input).map(|(next, _)| *next);
 		}
 	}
 }
 
-impl<S, I> Into<NFA<S, I>> for DFA<S, I>
+impl<S, I, T> From<DFA<S, I, T>> for NFA<S, I, T>
 where
 	S: Default + Clone + Eq + Hash + fmt::Debug,
 	I: Default + Eq + Hash,
 {
-	fn into(self) -> NFA<S, I> {
-		let mut set = HashSet::new();
-		if let Some(current) = self.current {
-			set.insert(current);
-		}
-		NFA::from_map(
-			set,
-			self.states
+	fn from(dfa: DFA<S, I, T>) -> Self {
+		let mut nfa = NFA::from_parts(
+			dfa.ids,
+			dfa.index,
+			dfa.states
 				.into_iter()
-				.map(|(id, state)| {
-					(
-						id,
-						(
-							state.accepts,
-							state
-								.transitions
-								.into_iter()
-								.map(|(input, state)| {
-									let mut set = HashSet::with_capacity(1);
-									set.insert(state);
-									(input, set)
-								})
-								.collect(),
-						),
+				.map(|state| {
+					state.map(
+						|State {
+						     accepts,
+						     transitions,
+						 }| {
+							nfa::State::new(
+								accepts,
+								transitions
+									.into_iter()
+									.map(|(input, (next, payload))| {
+										let mut map = BTreeMap::new();
+										map.insert(next, nfa::Edge::new(payload));
+										(input, map)
+									})
+									.collect(),
+							)
+						},
 					)
 				})
-				.collect::<HashMap<S, (bool, HashMap<I, HashSet<S>>)>>(),
-		)
+				.collect(),
+			dfa.initial.into_iter().collect(),
+			dfa.current.into_iter().collect(),
+		);
+		nfa.alphabet = dfa.alphabet;
+		nfa.names = dfa.names;
+		nfa
 	}
 }
 
@@ -228,6 +550,16 @@ mod tests {
 			!dfa.run(&"ba".chars().collect::<Vec<_>>()),
 			"Incorrect result on not-accepting run"
 		);
+
+		// check recovery
+		dfa.step(&'b');
+		assert_eq!(
+			None,
+			dfa.get_current(),
+			"Incorrect state after invalid step"
+		);
+		dfa.reset();
+		assert_eq!(Some(&0), dfa.get_current(), "Incorrect state after reset");
 	}
 
 	#[test]
@@ -241,6 +573,80 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn serialize() {
+		let yaml = r"{initial: 0, states: {0: {transitions: {a: 1}}, 1: {accepts: true}}}";
+		let mut dfa: DFA<u8, char> = serde_yaml::from_str(yaml).unwrap();
+		assert_eq!(Some(&0), dfa.get_initial(), "Incorrect initial state");
+		assert_eq!(Some(&0), dfa.get_current(), "Incorrect current state");
+		assert!(
+			!serde_yaml::to_string(&dfa).unwrap().contains("current"),
+			"Current state serialized while equal to initial state"
+		);
+
+		// current state is kept after a partial run
+		dfa.step(&'a');
+		let yaml = serde_yaml::to_string(&dfa).unwrap();
+		let dfa: DFA<u8, char> = serde_yaml::from_str(&yaml).unwrap();
+		assert_eq!(Some(&0), dfa.get_initial(), "Incorrect initial state");
+		assert_eq!(Some(&1), dfa.get_current(), "Incorrect current state");
+	}
+
+	#[test]
+	fn invalid() {
+		let yaml = r"{initial: 0, current: 2, states: {0: {transitions: {a: 1, b: 2}}, 1: {accepts: true}}}";
+		let mut dfa: DFA<u8, char> = serde_yaml::from_str(yaml).unwrap();
+		assert_eq!(None, dfa.get_current(), "Inexistent current state kept");
+		assert!(!dfa.accepts(), "Invalid state accepting");
+		dfa.set_current(0);
+		dfa.step(&'b');
+		assert_eq!(None, dfa.get_current(), "Stepped into inexistent state");
+	}
+
+	#[test]
+	fn arena() {
+		let mut dfa = DFA::<String, char>::from_map(
+			"start".to_string(),
+			hashmap!(
+				"start".to_string() => (false, hashmap!('a' => "end".to_string(), 'b' => "end".to_string()))
+			),
+		);
+		assert_eq!(2, dfa.ids.len(), "State labels duplicated in arena");
+		assert!(
+			!dfa.has_state(&"end".to_string()),
+			"Undeclared state exists"
+		);
+		assert!(!dfa.run(&['a']), "Transition to undeclared state taken");
+
+		// declaring the state enables the transitions to it
+		dfa.add_state("end".to_string(), true);
+		assert_eq!(2, dfa.ids.len(), "Undeclared slot not reused");
+		assert!(dfa.run(&['b']), "Incorrect result after run");
+	}
+
+	#[test]
+	fn payload() {
+		let mut dfa = DFA::<u8, char, u32>::with_state(0, false);
+		dfa.add_state(1, true);
+		dfa.add_transition_with_payload((0, 'a', 1), 5).unwrap();
+		dfa.add_transition((1, 'b', 1)).unwrap();
+		assert_eq!(Some(&5), dfa.payload(&0, &'a'), "Incorrect payload");
+		assert_eq!(Some(&0), dfa.payload(&1, &'b'), "Incorrect default payload");
+
+		// only non-default payloads are serialized
+		let yaml = serde_yaml::to_string(&dfa).unwrap();
+		assert_eq!(
+			1,
+			yaml.matches("payloads").count(),
+			"Default payload serialized"
+		);
+		let dfa: DFA<u8, char, u32> = serde_yaml::from_str(&yaml).unwrap();
+		assert_eq!(Some(&5), dfa.payload(&0, &'a'), "Payload not deserialized");
+
+		let nfa: NFA<_, _, _> = dfa.into();
+		assert_eq!(Some(&5), nfa.payload(&0, &'a', &1), "Payload not converted");
+	}
+
 	#[test]
 	fn convert() {
 		// construct a new DFA
@@ -0,0 +1,478 @@
+use super::{Automaton, AutomatonError, DFA};
+use serde::{Serialize, Serializer};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	fmt,
+	hash::Hash,
+};
+
+/// Guard deciding whether a transition may be taken for the current variables.
+type Guard<V> = Box<dyn Fn(&V) -> bool>;
+
+/// Update of the variables performed when a transition is taken.
+type Update<V> = Box<dyn Fn(&mut V)>;
+
+/// Named guard & update of a transition of an `EFSM` over variables of type `V`.
+///
+/// The names identify the guard & update in the serialized structure of the machine.
+pub struct Rule<V> {
+	guard: Option<(String, Guard<V>)>,
+	update: Option<(String, Update<V>)>,
+}
+
+impl<V> Rule<V> {
+	/// Creates a new rule without guard & update.
+	pub fn new() -> Self {
+		Self {
+			guard: None,
+			update: None,
+		}
+	}
+
+	/// Sets the guard deciding whether the transition may be taken.
+	pub fn with_guard<N, F>(mut self, name: N, guard: F) -> Self
+	where
+		N: Into<String>,
+		F: Fn(&V) -> bool + 'static,
+	{
+		self.guard = Some((name.into(), Box::new(guard)));
+		self
+	}
+
+	/// Sets the update of the variables performed when the transition is taken.
+	pub fn with_update<N, F>(mut self, name: N, update: F) -> Self
+	where
+		N: Into<String>,
+		F: Fn(&mut V) + 'static,
+	{
+		self.update = Some((name.into(), Box::new(update)));
+		self
+	}
+
+	/// Checks whether the guard allows the transition.
+	/// Returns `true` if there is no guard.
+	fn allows(&self, variables: &V) -> bool {
+		self.guard
+			.as_ref()
+			.is_none_or(|(_, guard)| guard(variables))
+	}
+
+	/// Performs the update, if there is one.
+	fn perform(&self, variables: &mut V) {
+		if let Some((_, update)) = &self.update {
+			update(variables);
+		}
+	}
+}
+
+impl<V> Default for Rule<V> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<V> fmt::Debug for Rule<V> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Rule")
+			.field("guard", &self.guard.as_ref().map(|(name, _)| name))
+			.field("update", &self.update.as_ref().map(|(name, _)| name))
+			.finish()
+	}
+}
+
+/// Transition of an `EFSM` between state indices.
+#[derive(Debug)]
+struct Transition<I, V> {
+	input: I,
+	next: usize,
+	rule: Rule<V>,
+}
+
+/// Serialized representation of a transition.
+#[derive(Serialize)]
+struct TransitionRepr<'a, S, I> {
+	input: &'a I,
+	next: &'a S,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	guard: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	update: Option<&'a str>,
+}
+
+/// Serialized representation of a state.
+#[derive(Serialize)]
+struct StateRepr<'a, S, I> {
+	accepts: bool,
+	transitions: Vec<TransitionRepr<'a, S, I>>,
+}
+
+/// Serialized representation of an EFSM.
+#[derive(Serialize)]
+struct Repr<'a, S, I>
+where
+	S: Eq + Hash,
+{
+	initial: Option<&'a S>,
+	states: HashMap<&'a S, StateRepr<'a, S, I>>,
+}
+
+/// An extended finite state machine with transitions guarded by & updating a store of variables of type `V`.
+///
+/// Each state has a list of transitions, which are tried in the order they were added.
+/// The first transition for an input whose guard allows it is taken & performs its update.
+///
+/// The structure of the machine serializes with the names of guards & updates in place of the functions.
+pub struct EFSM<S, I, V> {
+	ids: Vec<S>,
+	index: HashMap<S, usize>,
+	accepts: Vec<bool>,
+	transitions: Vec<Vec<Transition<I, V>>>,
+	initial: Option<usize>,
+	current: Option<usize>,
+	start: V,
+	variables: V,
+}
+
+impl<S, I, V> EFSM<S, I, V>
+where
+	S: Clone + Eq + Hash + fmt::Debug,
+	I: Eq,
+	V: Clone,
+{
+	/// Creates a new machine without states & with initial values of the variables.
+	pub fn new(variables: V) -> Self {
+		Self {
+			ids: Vec::new(),
+			index: HashMap::new(),
+			accepts: Vec::new(),
+			transitions: Vec::new(),
+			initial: None,
+			current: None,
+			start: variables.clone(),
+			variables,
+		}
+	}
+
+	/// Adds a new state or updates whether an existing state accepts.
+	pub fn add_state(&mut self, id: S, accepts: bool) {
+		match self.index.get(&id) {
+			Some(index) => self.accepts[*index] = accepts,
+			None => {
+				self.index.insert(id.clone(), self.ids.len());
+				self.ids.push(id);
+				self.accepts.push(accepts);
+				self.transitions.push(Vec::new());
+			}
+		}
+	}
+
+	/// Checks whether a state exists.
+	pub fn has_state(&self, id: &S) -> bool {
+		self.index.contains_key(id)
+	}
+
+	/// Sets the initial state & resets the machine.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn set_initial(&mut self, id: S) -> Result<(), AutomatonError<S>> {
+		self.initial = Some(self.index_of(id)?);
+		self.reset();
+		Ok(())
+	}
+
+	/// Adds a transition without guard & update.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	pub fn add_transition(&mut self, transition: (S, I, S)) -> Result<(), AutomatonError<S>> {
+		self.add_transition_with_rule(transition, Rule::new())
+	}
+
+	/// Adds a transition with a guard & update.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	pub fn add_transition_with_rule(
+		&mut self,
+		transition: (S, I, S),
+		rule: Rule<V>,
+	) -> Result<(), AutomatonError<S>> {
+		let (prev, input, next) = transition;
+		let prev = self.index_of(prev)?;
+		let next = self.index_of(next)?;
+		self.transitions[prev].push(Transition { input, next, rule });
+		Ok(())
+	}
+
+	/// Returns the current state.
+	/// Returns `None` if the machine is in an invalid state.
+	pub fn current(&self) -> Option<&S> {
+		self.current.map(|index| &self.ids[index])
+	}
+
+	/// Returns the current values of the variables.
+	pub fn variables(&self) -> &V {
+		&self.variables
+	}
+
+	/// Checks whether the current state accepts.
+	pub fn accepts(&self) -> bool {
+		self.current.is_some_and(|index| self.accepts[index])
+	}
+
+	/// Performs a single state transition for an input.
+	/// Returns whether a transition was taken.
+	///
+	/// If guards reject all transitions for the input, the current state & variables are kept.
+	/// If there is no transition for the input, the machine goes into an invalid state.
+	pub fn step(&mut self, input: &I) -> bool {
+		let current = match self.current {
+			Some(current) => current,
+			None => return false,
+		};
+		let mut candidates = self.transitions[current]
+			.iter()
+			.filter(|transition| transition.input == *input)
+			.peekable();
+		if candidates.peek().is_none() {
+			self.current = None;
+			return false;
+		}
+		match candidates.find(|transition| transition.rule.allows(&self.variables)) {
+			Some(transition) => {
+				transition.rule.perform(&mut self.variables);
+				self.current = Some(transition.next);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Resets the machine to its initial state & the variables to their initial values.
+	pub fn reset(&mut self) {
+		self.current = self.initial;
+		self.variables = self.start.clone();
+	}
+
+	/// Resets the machine & runs it on a sequence of inputs.
+	/// Returns whether it accepts afterwards.
+	pub fn run(&mut self, inputs: &[I]) -> bool {
+		self.reset();
+		for input in inputs {
+			self.step(input);
+		}
+		self.accepts()
+	}
+
+	/// Looks up the index of a state or returns an `AutomatonError::InexistentState` error.
+	fn index_of(&self, id: S) -> Result<usize, AutomatonError<S>> {
+		self.index
+			.get(&id)
+			.cloned()
+			.ok_or(AutomatonError::InexistentState(id, Vec::new()))
+	}
+}
+
+impl<S, I, V> EFSM<S, I, V>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	V: Default + Clone + Eq + Hash + fmt::Debug,
+{
+	/// Expands the machine into a DFA over pairs of states & values of the variables reachable from the initial state.
+	/// Returns an `AutomatonError::LimitExceeded` error if more than `limit` pairs are reachable.
+	///
+	/// A pair has a transition for each input with a transition taken from it,
+	/// so inputs rejected by all guards have no transition, unlike when stepping the machine.
+	#[allow(clippy::type_complexity)]
+	pub fn expand(&self, limit: usize) -> Result<DFA<(S, V), I>, AutomatonError<(S, V)>> {
+		let mut dfa = DFA::new();
+		let initial = match self.initial {
+			Some(initial) => (initial, self.start.clone()),
+			None => return Ok(dfa),
+		};
+		let id = |(index, variables): &(usize, V)| (self.ids[*index].clone(), variables.clone());
+		let mut known = HashSet::new();
+		known.insert(initial.clone());
+		dfa.add_state(id(&initial), self.accepts[initial.0]);
+		let mut queue = VecDeque::new();
+		queue.push_back(initial.clone());
+		while let Some((state, variables)) = queue.pop_front() {
+			let mut inputs: Vec<&I> = Vec::new();
+			for transition in &self.transitions[state] {
+				if !inputs.contains(&&transition.input) {
+					inputs.push(&transition.input);
+				}
+			}
+			for input in inputs {
+				let taken = self.transitions[state].iter().find(|transition| {
+					transition.input == *input && transition.rule.allows(&variables)
+				});
+				if let Some(transition) = taken {
+					let mut next_variables = variables.clone();
+					transition.rule.perform(&mut next_variables);
+					let next = (transition.next, next_variables);
+					if !known.contains(&next) {
+						if known.len() >= limit {
+							return Err(AutomatonError::LimitExceeded(limit));
+						}
+						known.insert(next.clone());
+						dfa.add_state(id(&next), self.accepts[next.0]);
+						queue.push_back(next.clone());
+					}
+					dfa.add_transition((id(&(state, variables.clone())), input.clone(), id(&next)))
+						.map_err(AutomatonError::without_input)?;
+				}
+			}
+		}
+		dfa.set_initial(id(&initial));
+		dfa.reset();
+		Ok(dfa)
+	}
+}
+
+impl<S, I, V> Serialize for EFSM<S, I, V>
+where
+	S: Eq + Hash + Serialize,
+	I: Serialize,
+{
+	fn serialize<R>(&self, serializer: R) -> Result<R::Ok, R::Error>
+	where
+		R: Serializer,
+	{
+		Repr {
+			initial: self.initial.map(|index| &self.ids[index]),
+			states: self
+				.ids
+				.iter()
+				.zip(&self.accepts)
+				.zip(&self.transitions)
+				.map(|((id, accepts), transitions)| {
+					let transitions = transitions
+						.iter()
+						.map(|transition| TransitionRepr {
+							input: &transition.input,
+							next: &self.ids[transition.next],
+							guard: transition
+								.rule
+								.guard
+								.as_ref()
+								.map(|(name, _)| name.as_str()),
+							update: transition
+								.rule
+								.update
+								.as_ref()
+								.map(|(name, _)| name.as_str()),
+						})
+						.collect();
+					(
+						id,
+						StateRepr {
+							accepts: *accepts,
+							transitions,
+						},
+					)
+				})
+				.collect(),
+		}
+		.serialize(serializer)
+	}
+}
+
+impl<S, I, V> fmt::Debug for EFSM<S, I, V>
+where
+	S: fmt::Debug,
+	I: fmt::Debug,
+	V: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("EFSM")
+			.field("ids", &self.ids)
+			.field("accepts", &self.accepts)
+			.field("transitions", &self.transitions)
+			.field("current", &self.current.map(|index| &self.ids[index]))
+			.field("variables", &self.variables)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Sender retrying up to 2 times before failing, counting sequence numbers of acknowledged messages.
+	#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+	struct Vars {
+		sequence: u8,
+		retries: u8,
+	}
+
+	fn sender() -> EFSM<&'static str, &'static str, Vars> {
+		let mut efsm = EFSM::new(Vars::default());
+		efsm.add_state("idle", true);
+		efsm.add_state("waiting", false);
+		efsm.add_state("failed", false);
+		efsm.set_initial("idle").unwrap();
+		efsm.add_transition(("idle", "send", "waiting")).unwrap();
+		efsm.add_transition_with_rule(
+			("waiting", "ack", "idle"),
+			Rule::new().with_update("next sequence", |vars: &mut Vars| {
+				vars.sequence = (vars.sequence + 1) % 2;
+				vars.retries = 0;
+			}),
+		)
+		.unwrap();
+		efsm.add_transition_with_rule(
+			("waiting", "timeout", "waiting"),
+			Rule::new()
+				.with_guard("retries left", |vars: &Vars| vars.retries < 2)
+				.with_update("retry", |vars: &mut Vars| vars.retries += 1),
+		)
+		.unwrap();
+		efsm.add_transition(("waiting", "timeout", "failed"))
+			.unwrap();
+		efsm
+	}
+
+	#[test]
+	fn simulate() {
+		let mut sender = sender();
+		assert!(
+			sender.run(&["send", "timeout", "ack"]),
+			"Retry not accepted"
+		);
+		assert_eq!(1, sender.variables().sequence, "Sequence not updated");
+		assert!(
+			!sender.run(&["send", "timeout", "timeout", "timeout"]),
+			"Retries not bounded"
+		);
+		assert_eq!(Some(&"failed"), sender.current(), "Fallback not taken");
+		assert!(!sender.step(&"ack"), "Missing transition taken");
+		assert_eq!(None, sender.current(), "Missing transition taken");
+	}
+
+	#[test]
+	fn expand() {
+		let mut dfa = sender().expand(100).unwrap();
+		// idle, failed & waiting with 0 to 2 retries for both sequence numbers
+		assert_eq!(10, dfa.stats().states, "Incorrect number of states");
+		assert!(
+			dfa.run(&["send", "ack", "send", "timeout", "ack"]),
+			"Word rejected"
+		);
+		assert!(
+			matches!(sender().expand(3), Err(AutomatonError::LimitExceeded(3))),
+			"Limit not enforced"
+		);
+	}
+
+	#[test]
+	fn serialize() {
+		let yaml = serde_yaml::to_string(&sender()).unwrap();
+		let value: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+		assert_eq!("idle", value["initial"], "Incorrect initial state");
+		let timeout = &value["states"]["waiting"]["transitions"][1];
+		assert_eq!("retries left", timeout["guard"], "Missing guard name");
+		assert_eq!("retry", timeout["update"], "Missing update name");
+		assert_eq!(
+			serde_yaml::Value::Null,
+			value["states"]["idle"]["transitions"][0]["guard"],
+			"Unexpected guard"
+		);
+	}
+}
@@ -0,0 +1,143 @@
+use super::{graph::Graph, DFA};
+use std::{fmt, hash::Hash};
+
+/// Maximum number of power iterations.
+const MAX_ITERATIONS: usize = 100_000;
+
+/// Relative tolerance for the convergence of power iterations.
+const TOLERANCE: f64 = 1e-12;
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Computes the spectral radius of the adjacency matrix restricted to the states selected by a mask.
+	pub fn spectral_radius(&self, mask: &[bool]) -> f64 {
+		let mut component_of = vec![None; self.len()];
+		let mut radius: f64 = 0.0;
+		for component in self.components(mask) {
+			for (i, state) in component.iter().enumerate() {
+				component_of[*state] = Some(i);
+			}
+			let edges: Vec<Vec<usize>> = component
+				.iter()
+				.map(|state| {
+					self.edges[*state]
+						.iter()
+						.filter_map(|(_, next)| {
+							if mask[*next] {
+								component_of[*next]
+							} else {
+								None
+							}
+						})
+						.collect()
+				})
+				.collect();
+			if edges.iter().any(|edges| !edges.is_empty()) {
+				radius = radius.max(perron(&edges));
+			}
+			for state in component {
+				component_of[state] = None;
+			}
+		}
+		radius
+	}
+}
+
+/// Computes the Perron eigenvalue of an irreducible adjacency matrix.
+///
+/// Power iteration is performed on the primitive matrix `A + I` to avoid oscillation on periodic graphs.
+fn perron(edges: &[Vec<usize>]) -> f64 {
+	let len = edges.len();
+	let mut vector = vec![1.0 / len as f64; len];
+	let mut eigenvalue = 0.0;
+	for _ in 0..MAX_ITERATIONS {
+		let mut next = vector.clone();
+		for (from, edges) in edges.iter().enumerate() {
+			for to in edges {
+				next[from] += vector[*to];
+			}
+		}
+		let norm: f64 = next.iter().sum();
+		for el in &mut next {
+			*el /= norm;
+		}
+		let converged = (norm - eigenvalue).abs() <= TOLERANCE * norm;
+		eigenvalue = norm;
+		vector = next;
+		if converged {
+			break;
+		}
+	}
+	eigenvalue - 1.0
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Computes the topological entropy of the accepted language in bits per input.
+	///
+	/// This is the binary logarithm of the spectral radius of the adjacency matrix of the trimmed DFA.
+	/// Finite languages have an entropy of zero.
+	pub fn entropy(&self) -> f64 {
+		let graph = self.graph();
+		let radius = graph.spectral_radius(&graph.useful());
+		if radius < 1.0 {
+			0.0
+		} else {
+			radius.log2()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::hashmap;
+
+	fn assert_close(expected: f64, actual: f64, msg: &str) {
+		assert!((expected - actual).abs() < 1e-9, "{}: {}", msg, actual);
+	}
+
+	#[test]
+	fn full_shift() {
+		let dfa = DFA::<u8, u8>::from_map(
+			0,
+			hashmap!(0 => (true, hashmap!(0 => 0, 1 => 0, 2 => 0, 3 => 0))),
+		);
+		assert_close(2.0, dfa.entropy(), "Incorrect entropy");
+	}
+
+	#[test]
+	fn golden_mean() {
+		// binary words without consecutive ones
+		let dfa = DFA::<u8, u8>::from_map(
+			0,
+			hashmap!(
+				0 => (true, hashmap!(0 => 0, 1 => 1)),
+				1 => (true, hashmap!(0 => 0))
+			),
+		);
+		let golden = (1.0 + 5f64.sqrt()) / 2.0;
+		assert_close(golden.log2(), dfa.entropy(), "Incorrect entropy");
+	}
+
+	#[test]
+	fn periodic() {
+		// (ab)* has a periodic adjacency matrix
+		let dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (true, hashmap!('a' => 1)),
+				1 => (false, hashmap!('b' => 0))
+			),
+		);
+		assert_close(0.0, dfa.entropy(), "Incorrect entropy");
+
+		let finite = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(0 => (false, hashmap!('a' => 1)), 1 => (true, hashmap!())),
+		);
+		assert_close(0.0, finite.entropy(), "Incorrect entropy");
+	}
+}
@@ -0,0 +1,165 @@
+use super::{nfa::State, AutomatonError, NFA};
+use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Adds an epsilon transition, which is taken without consuming an input.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	///
+	/// Stepping & acceptance follow epsilon transitions, as do the conversion into a DFA & the analyses of the transition graph.
+	/// Use `remove_epsilons` to obtain an equivalent NFA without epsilon transitions.
+	pub fn add_epsilon(&mut self, (prev, next): (S, S)) -> Result<(), AutomatonError<S>> {
+		let next = self.index_of(&next).ok_or_else(|| self.inexistent(next))?;
+		let index = self.index_of(&prev).ok_or_else(|| self.inexistent(prev))?;
+		if let Some(state) = self.states[index].as_mut() {
+			state.epsilon.insert(next);
+		}
+		Ok(())
+	}
+
+	/// Checks whether the NFA has any epsilon transitions.
+	pub fn has_epsilons(&self) -> bool {
+		self.declared().any(|(_, state)| !state.epsilon.is_empty())
+	}
+
+	/// Returns the indices of the declared states reachable from a set of states via epsilon transitions.
+	/// The declared states of the set are included.
+	pub(crate) fn epsilon_closure(&self, set: &BTreeSet<usize>) -> BTreeSet<usize> {
+		let mut closure: BTreeSet<_> = set
+			.iter()
+			.cloned()
+			.filter(|index| self.states[*index].is_some())
+			.collect();
+		let mut queue: Vec<_> = closure.iter().cloned().collect();
+		while let Some(index) = queue.pop() {
+			if let Some(state) = self.state(index) {
+				for next in &state.epsilon {
+					if self.states[*next].is_some() && closure.insert(*next) {
+						queue.push(*next);
+					}
+				}
+			}
+		}
+		closure
+	}
+
+	/// Returns an equivalent NFA without epsilon transitions.
+	///
+	/// A state accepts if any state in its epsilon closure accepts
+	/// & has the transitions of all states in its epsilon closure.
+	/// The initial & current states are replaced by their epsilon closures.
+	pub fn remove_epsilons(&self) -> Self
+	where
+		I: Clone,
+		T: Clone,
+	{
+		let states = self
+			.states
+			.iter()
+			.enumerate()
+			.map(|(index, state)| {
+				state.as_ref().map(|_| {
+					let closure = self.epsilon_closure(&Some(index).into_iter().collect());
+					let mut accepts = false;
+					let mut transitions = HashMap::new();
+					for state in closure.iter().filter_map(|index| self.state(*index)) {
+						accepts |= state.accepts;
+						for (input, next) in &state.transitions {
+							let targets = transitions
+								.entry(input.clone())
+								.or_insert_with(BTreeMap::new);
+							for (next, edge) in next {
+								targets.entry(*next).or_insert_with(|| edge.clone());
+							}
+						}
+					}
+					State::new(accepts, transitions)
+				})
+			})
+			.collect();
+		let mut nfa = Self::from_parts(
+			self.ids.clone(),
+			self.index.clone(),
+			states,
+			self.epsilon_closure(self.initial_indices()),
+			self.epsilon_closure(self.current_indices()),
+		);
+		nfa.alphabet = self.alphabet.clone();
+		nfa.names = self.names.clone();
+		nfa
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Automaton, DFA};
+	use maplit::{btreeset, hashset};
+
+	/// Accepts `a*b*`, switching from `a` to `b` via an epsilon transition.
+	fn nfa() -> NFA<u8, char> {
+		let mut nfa = NFA::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_transition((0, 'a', 0)).unwrap();
+		nfa.add_transition((1, 'b', 1)).unwrap();
+		nfa.add_epsilon((0, 1)).unwrap();
+		nfa
+	}
+
+	#[test]
+	fn step() {
+		let mut nfa = nfa();
+		assert!(nfa.accepts(), "Empty word rejected");
+		assert!(nfa.run(&['a', 'a', 'b']), "Word rejected");
+		assert!(!nfa.run(&['b', 'a']), "Word accepted");
+		nfa.step(&'a');
+		assert_eq!(
+			Some(&hashset![0, 1]),
+			nfa.get_current(),
+			"Epsilon closure not taken"
+		);
+		assert!(
+			nfa.add_epsilon((1, 2)).is_err(),
+			"Missing error on inexistent state"
+		);
+
+		let mut dfa: DFA<_, _> = nfa.into();
+		dfa.reset();
+		assert_eq!(
+			Some(&btreeset![0, 1]),
+			dfa.get_current(),
+			"Initial closure not converted"
+		);
+		assert!(dfa.run(&['a', 'b', 'b']), "Word rejected after conversion");
+	}
+
+	#[test]
+	fn remove() {
+		let mut nfa = nfa().remove_epsilons();
+		assert!(!nfa.has_epsilons(), "Epsilon transitions kept");
+		assert!(nfa.run(&['a', 'b']), "Word rejected");
+		assert!(nfa.run(&[]), "Empty word rejected");
+		assert!(!nfa.run(&['b', 'a']), "Word accepted");
+	}
+
+	#[test]
+	fn serialize() {
+		let nfa = nfa();
+		assert_eq!(
+			"-> 0\n\t'a' -> 0\n\tε -> 1\n1 (accepting)\n\t'b' -> 1\n",
+			nfa.to_string(),
+			"Incorrect display"
+		);
+		let yaml = serde_yaml::to_string(&nfa).unwrap();
+		let mut nfa: NFA<u8, char> = serde_yaml::from_str(&yaml).unwrap();
+		assert!(nfa.has_epsilons(), "Epsilon transitions not serialized");
+		assert!(nfa.run(&['a', 'b']), "Word rejected");
+	}
+}
@@ -0,0 +1,463 @@
+use super::{union_find::UnionFind, AutomatonError, DFA};
+use std::{
+	collections::{hash_map::Entry, HashMap, VecDeque},
+	fmt,
+	hash::Hash,
+};
+
+/// Checks whether two states of a deterministic transition table accept the same language.
+///
+/// Uses the Hopcroft-Karp algorithm with an implicit dead state at index `table.len()`.
+pub(crate) fn equivalent<I>(
+	table: &[HashMap<&I, usize>],
+	accepts: &[bool],
+	a: usize,
+	b: usize,
+) -> bool
+where
+	I: Eq + Hash,
+{
+	let dead = table.len();
+	let accepting = |state: usize| state != dead && accepts[state];
+	let next = |state: usize, input: &I| {
+		if state == dead {
+			dead
+		} else {
+			table[state].get(input).cloned().unwrap_or(dead)
+		}
+	};
+	let mut sets = UnionFind::new(dead + 1);
+	sets.union(a, b);
+	let mut stack = vec![(a, b)];
+	while let Some((p, q)) = stack.pop() {
+		if accepting(p) != accepting(q) {
+			return false;
+		}
+		let inputs: Vec<_> = [p, q]
+			.iter()
+			.filter(|state| **state != dead)
+			.flat_map(|state| table[*state].keys())
+			.collect();
+		for input in inputs {
+			let (p, q) = (next(p, input), next(q, input));
+			if sets.union(p, q) {
+				stack.push((p, q));
+			}
+		}
+	}
+	true
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Checks whether two states accept the same language.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	pub fn equivalent_states(&self, a: &S, b: &S) -> Result<bool, AutomatonError<S>> {
+		let graph = self.graph();
+		let indices = graph.indices();
+		let a = index(&indices, a)?;
+		let b = index(&indices, b)?;
+		Ok(equivalent(&graph.table(), &graph.accepts, a, b))
+	}
+
+	/// Returns an incremental equivalence query for the automaton.
+	pub fn equivalences(&mut self) -> Equivalences<'_, S, I, T> {
+		Equivalences {
+			sets: UnionFind::new(self.states.len() + 1),
+			reachable: None,
+			dfa: self,
+		}
+	}
+}
+
+/// Incremental equivalence queries on a `DFA`, which can be edited through it.
+///
+/// States proven equivalent are kept in a union-find across queries & across edits preserving the languages of all states,
+/// so a query only explores pairs of states not known to be equivalent yet.
+pub struct Equivalences<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	dfa: &'a mut DFA<S, I, T>,
+	sets: UnionFind,
+	reachable: Option<Vec<bool>>,
+}
+
+impl<'a, S, I, T> Equivalences<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Checks whether two states accept the same language.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	pub fn equivalent_states(&mut self, a: &S, b: &S) -> Result<bool, AutomatonError<S>> {
+		let a = self.index(a)?;
+		let b = self.index(b)?;
+		Ok(self.equivalent(a, b))
+	}
+
+	/// Checks whether adding a transition would change the accepted language.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	///
+	/// If the source state already has a transition for the input, the check considers it replaced.
+	pub fn would_change(&mut self, transition: &(S, I, S)) -> Result<bool, AutomatonError<S>> {
+		let (prev, input, next) = transition;
+		let prev = self.index(prev)?;
+		let next = self.index(next)?;
+		if !self.reachable(prev) {
+			return Ok(false);
+		}
+		let current = self.next(prev, input);
+		Ok(!self.equivalent(current, next))
+	}
+
+	/// Adds a transition to the automaton, replacing an existing transition for the input.
+	/// Returns whether the accepted language changed.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	/// Returns an `AutomatonError::UnknownInput` error if the input is not part of the declared alphabet.
+	///
+	/// The known equivalences are kept if the languages of all states stay the same.
+	pub fn add_transition(&mut self, transition: (S, I, S)) -> Result<bool, AutomatonError<S, I>>
+	where
+		T: Default,
+	{
+		let (prev, input, next) = transition;
+		let (prev_index, next_index) = (self.index(&prev)?, self.index(&next)?);
+		let current = self.next(prev_index, &input);
+		let preserved = self.equivalent(current, next_index);
+		let changed = !preserved && self.reachable(prev_index);
+		self.dfa
+			.add_transition_with_payload((prev, input, next), T::default())?;
+		if !preserved {
+			self.sets = UnionFind::new(self.dfa.states.len() + 1);
+		}
+		self.reachable = None;
+		Ok(changed)
+	}
+
+	/// Looks up the index of a declared state or returns an `AutomatonError::InexistentState` error.
+	fn index<E>(&self, id: &S) -> Result<usize, AutomatonError<S, E>> {
+		self.dfa
+			.index_of(id)
+			.ok_or_else(|| self.dfa.inexistent(id.clone()))
+	}
+
+	/// Checks whether a state is reachable from the initial state.
+	fn reachable(&mut self, state: usize) -> bool {
+		let dfa = &*self.dfa;
+		self.reachable
+			.get_or_insert_with(|| dfa.graph().reachable())[state]
+	}
+
+	/// Returns the next state index for an input, with the dead state at index `states.len()`.
+	fn next(&self, state: usize, input: &I) -> usize {
+		let dead = self.dfa.states.len();
+		if state == dead {
+			return dead;
+		}
+		self.dfa
+			.transition(state, input)
+			.map(|(next, _)| *next)
+			.unwrap_or(dead)
+	}
+
+	/// Checks whether two states accept the same language using the Hopcroft-Karp algorithm.
+	///
+	/// Pairs are merged tentatively on top of the known equivalences & only kept if the states are equivalent.
+	fn equivalent(&mut self, a: usize, b: usize) -> bool {
+		let dfa = &*self.dfa;
+		let dead = dfa.states.len();
+		let accepting =
+			|state: usize| state != dead && dfa.state(state).is_some_and(|state| state.accepts);
+		let sets = &mut self.sets;
+		let mut tentative = HashMap::new();
+		let mut merged = Vec::new();
+		let mut union = |p: usize, q: usize| {
+			let root = |tentative: &HashMap<usize, usize>, mut el: usize| {
+				while let Some(parent) = tentative.get(&el) {
+					el = *parent;
+				}
+				el
+			};
+			let p = root(&tentative, sets.find(p));
+			let q = root(&tentative, sets.find(q));
+			if p == q {
+				return false;
+			}
+			tentative.insert(p, q);
+			merged.push((p, q));
+			true
+		};
+		if !union(a, b) {
+			return true;
+		}
+		let mut stack = vec![(a, b)];
+		while let Some((p, q)) = stack.pop() {
+			if accepting(p) != accepting(q) {
+				return false;
+			}
+			let inputs: Vec<_> = [p, q]
+				.iter()
+				.filter_map(|state| dfa.states.get(*state).and_then(Option::as_ref))
+				.flat_map(|state| state.transitions.keys())
+				.collect();
+			for input in inputs {
+				let next = |state: usize| {
+					if state == dead {
+						return dead;
+					}
+					dfa.transition(state, input)
+						.map(|(next, _)| *next)
+						.unwrap_or(dead)
+				};
+				let (p, q) = (next(p), next(q));
+				if union(p, q) {
+					stack.push((p, q));
+				}
+			}
+		}
+		for (p, q) in merged {
+			self.sets.union(p, q);
+		}
+		true
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Checks whether this & another DFA accept the same language, starting from their initial states.
+	/// Returns a shortest word accepted by exactly one of them as error if the languages differ.
+	///
+	/// A DFA without initial state accepts no words.
+	pub fn equivalent<R, U>(&self, other: &DFA<R, I, U>) -> Result<(), Vec<I>>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		let (graph, other_graph) = (self.graph(), other.graph());
+		let offset = graph.labels.len();
+
+		// disjoint union of both transition tables with a shared dead state
+		let mut table = graph.table();
+		table.extend(other_graph.table().into_iter().map(|transitions| {
+			transitions
+				.into_iter()
+				.map(|(input, next)| (input, next + offset))
+				.collect::<HashMap<_, _>>()
+		}));
+		let accepts: Vec<_> = graph
+			.accepts
+			.iter()
+			.chain(&other_graph.accepts)
+			.cloned()
+			.collect();
+		let dead = table.len();
+		let a = graph.initial.first().cloned().unwrap_or(dead);
+		let b = other_graph
+			.initial
+			.first()
+			.map(|initial| initial + offset)
+			.unwrap_or(dead);
+		if equivalent(&table, &accepts, a, b) {
+			Ok(())
+		} else {
+			Err(distinguish(&table, &accepts, a, b))
+		}
+	}
+}
+
+/// Returns a shortest word distinguishing two states of a deterministic transition table with an implicit dead state.
+///
+/// Searches the pairs of states breadth-first, so the states have to be inequivalent.
+fn distinguish<I>(table: &[HashMap<&I, usize>], accepts: &[bool], a: usize, b: usize) -> Vec<I>
+where
+	I: Clone + Eq + Hash,
+{
+	let dead = table.len();
+	let accepting = |state: usize| state != dead && accepts[state];
+	let next = |state: usize, input: &I| {
+		if state == dead {
+			dead
+		} else {
+			table[state].get(input).cloned().unwrap_or(dead)
+		}
+	};
+	// previous pair & input of each visited pair
+	let mut visited = HashMap::new();
+	visited.insert((a, b), None::<((usize, usize), &I)>);
+	let mut queue = VecDeque::new();
+	queue.push_back((a, b));
+	while let Some((p, q)) = queue.pop_front() {
+		if accepting(p) != accepting(q) {
+			let mut word = Vec::new();
+			let mut pair = (p, q);
+			while let Some((prev, input)) = visited[&pair] {
+				word.push(input.clone());
+				pair = prev;
+			}
+			word.reverse();
+			return word;
+		}
+		let inputs: Vec<_> = [p, q]
+			.iter()
+			.filter(|state| **state != dead)
+			.flat_map(|state| table[*state].keys().cloned())
+			.collect();
+		for input in inputs {
+			let pair = (next(p, input), next(q, input));
+			if let Entry::Vacant(entry) = visited.entry(pair) {
+				entry.insert(Some(((p, q), input)));
+				queue.push_back(pair);
+			}
+		}
+	}
+	unreachable!("Equivalent states distinguished")
+}
+
+/// Looks up the index of a state or returns an `AutomatonError::InexistentState` error.
+fn index<S>(indices: &HashMap<&S, usize>, id: &S) -> Result<usize, AutomatonError<S>>
+where
+	S: Clone + Eq + Hash + fmt::Debug,
+{
+	indices
+		.get(id)
+		.cloned()
+		.ok_or_else(|| AutomatonError::InexistentState(id.clone(), Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	fn dfa() -> DFA<u8, char> {
+		// states 1 & 2 both accept any number of 'a'
+		DFA::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1, 'b' => 2)),
+				1 => (true, hashmap!('a' => 1)),
+				2 => (true, hashmap!('a' => 3)),
+				3 => (true, hashmap!('a' => 2)),
+				4 => (false, hashmap!('a' => 4)),
+				5 => (true, hashmap!())
+			),
+		)
+	}
+
+	#[test]
+	fn states() {
+		let dfa = dfa();
+		assert!(
+			dfa.equivalent_states(&1, &2).unwrap(),
+			"Equivalent states not detected"
+		);
+		assert!(
+			!dfa.equivalent_states(&1, &5).unwrap(),
+			"Inequivalent states detected as equivalent"
+		);
+		assert!(
+			dfa.equivalent_states(&1, &9).is_err(),
+			"Missing error on inexistent state"
+		);
+	}
+
+	#[test]
+	fn change() {
+		let mut dfa = dfa();
+		let mut dfa = dfa.equivalences();
+		assert!(
+			!dfa.would_change(&(0, 'b', 1)).unwrap(),
+			"Replacing with equivalent target changes language"
+		);
+		assert!(
+			!dfa.would_change(&(0, 'c', 4)).unwrap(),
+			"Adding transition to dead state changes language"
+		);
+		assert!(
+			!dfa.would_change(&(5, 'a', 1)).unwrap(),
+			"Adding transition from unreachable state changes language"
+		);
+		assert!(
+			dfa.would_change(&(0, 'c', 5)).unwrap(),
+			"Adding transition to accepting state does not change language"
+		);
+	}
+
+	#[test]
+	fn languages() {
+		// words of any number of `a`
+		let any = DFA::<u8, char>::from_map(0, hashmap!(0 => (true, hashmap!('a' => 0))));
+		assert_eq!(
+			Ok(()),
+			dfa().equivalent(&dfa()),
+			"Identical DFAs distinguished"
+		);
+		assert_eq!(
+			Ok(()),
+			dfa().minimize().equivalent(&dfa()),
+			"Minimal DFA distinguished"
+		);
+		assert_eq!(
+			Err(vec![]),
+			any.equivalent(&dfa()),
+			"Incorrect counterexample"
+		);
+		// words of at least one `a` or only `b`
+		let mut prefix = DFA::<&str, char>::with_state("start", false);
+		prefix.add_state("a", true);
+		prefix.add_state("b", true);
+		for transition in [("start", 'a', "a"), ("a", 'a', "a"), ("start", 'b', "b")] {
+			prefix.add_transition(transition).unwrap();
+		}
+		assert_eq!(
+			Err(vec!['b', 'a']),
+			prefix.equivalent(&dfa()),
+			"Counterexample not shortest"
+		);
+		assert_eq!(
+			Err(vec![]),
+			DFA::<u8, char>::new().equivalent(&any),
+			"Missing initial state not rejecting"
+		);
+	}
+
+	#[test]
+	fn edit() {
+		let mut dfa = dfa();
+		let mut equivalences = dfa.equivalences();
+		assert!(
+			equivalences.equivalent_states(&1, &2).unwrap(),
+			"Equivalent states not detected"
+		);
+		assert!(
+			!equivalences.add_transition((0, 'b', 1)).unwrap(),
+			"Replacing with equivalent target changes language"
+		);
+		assert!(
+			equivalences.equivalent_states(&2, &3).unwrap(),
+			"Equivalence lost after preserving edit"
+		);
+		assert!(
+			equivalences.add_transition((1, 'a', 5)).unwrap(),
+			"Replacing with inequivalent target keeps language"
+		);
+		assert!(
+			!equivalences.equivalent_states(&1, &2).unwrap(),
+			"Equivalence kept after changing edit"
+		);
+		assert!(
+			equivalences.add_transition((0, 'a', 9)).is_err(),
+			"Missing error on inexistent state"
+		);
+		assert!(dfa.run(&['b', 'a']), "Transition not added");
+		assert!(!dfa.run(&['b', 'a', 'a']), "Transition not replaced");
+	}
+}
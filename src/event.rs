@@ -0,0 +1,190 @@
+use super::DFA;
+use std::{fmt, hash::Hash};
+
+/// Trait for inputs which are looked up by a key instead of their full value.
+///
+/// This allows events carrying data to be used as inputs of an automaton with transitions for their keys.
+/// Use the `input_key!` macro to implement it for an event `enum`.
+pub trait InputKey {
+	/// Key used for looking up transitions.
+	type Key: Default + Eq + Hash;
+
+	/// Returns the key of the input.
+	fn key(&self) -> Self::Key;
+}
+
+/// Implements `InputKey` for types which are their own key.
+macro_rules! impl_input_key {
+	($($ty:ty),*) => {
+		$(
+			impl InputKey for $ty {
+				type Key = Self;
+
+				fn key(&self) -> Self::Key {
+					self.clone()
+				}
+			}
+		)*
+	};
+}
+
+impl_input_key!(
+	bool,
+	char,
+	u8,
+	u16,
+	u32,
+	u64,
+	u128,
+	usize,
+	i8,
+	i16,
+	i32,
+	i64,
+	i128,
+	isize,
+	String,
+	&'static str
+);
+
+/// Implements `InputKey` for an event type by mapping patterns to keys.
+///
+/// ```
+/// use finite::input_key;
+///
+/// #[derive(Default, PartialEq, Eq, Hash)]
+/// enum Kind {
+///     #[default]
+///     Click,
+///     Key,
+/// }
+///
+/// enum Event {
+///     Click { x: i32, y: i32 },
+///     Key(char),
+/// }
+///
+/// input_key!(Event => Kind {
+///     Event::Click { .. } => Kind::Click,
+///     Event::Key(_) => Kind::Key,
+/// });
+/// ```
+#[macro_export]
+macro_rules! input_key {
+	($event:ty => $key:ty { $($pattern:pat => $value:expr),* $(,)? }) => {
+		impl $crate::InputKey for $event {
+			type Key = $key;
+
+			fn key(&self) -> $key {
+				match self {
+					$($pattern => $value),*
+				}
+			}
+		}
+	};
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Performs a single state transition for the key of an event.
+	/// Returns whether a transition was taken.
+	///
+	/// The callback receives the previous state, the event, the next state & the payload of the transition.
+	/// If there is no transition for the key, the automaton goes into an invalid state without calling the callback.
+	pub fn step_event<E, F>(&mut self, event: &E, callback: F) -> bool
+	where
+		E: InputKey<Key = I>,
+		F: FnOnce(&S, &E, &S, &T),
+	{
+		let current = match self.current {
+			Some(current) => current,
+			None => return false,
+		};
+		let key = event.key();
+		let next = self.transition(current, &key);
+		match next {
+			Some((next, payload)) => {
+				callback(&self.ids[current], event, &self.ids[*next], payload);
+				self.current = Some(*next);
+				true
+			}
+			None => {
+				self.current = None;
+				false
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	#[derive(Default, Debug, PartialEq, Eq, Hash)]
+	enum Kind {
+		#[default]
+		Coin,
+		Push,
+	}
+
+	enum Event {
+		Coin(u32),
+		Push,
+	}
+
+	input_key!(Event => Kind {
+		Event::Coin(_) => Kind::Coin,
+		Event::Push => Kind::Push,
+	});
+
+	#[test]
+	fn event() {
+		let mut turnstile = DFA::<&str, Kind, u32>::with_state("locked", false);
+		turnstile.add_state("unlocked", true);
+		turnstile
+			.add_transition_with_payload(("locked", Kind::Coin, "unlocked"), 1)
+			.unwrap();
+		turnstile
+			.add_transition(("unlocked", Kind::Push, "locked"))
+			.unwrap();
+
+		let mut paid = 0;
+		let taken = turnstile.step_event(&Event::Coin(50), |prev, event, next, payload| {
+			assert_eq!(("locked", "unlocked"), (*prev, *next), "Incorrect states");
+			assert_eq!(1, *payload, "Incorrect transition payload");
+			if let Event::Coin(amount) = event {
+				paid += amount;
+			}
+		});
+		assert!(taken, "Transition not taken");
+		assert_eq!(50, paid, "Event payload not forwarded");
+		assert!(turnstile.accepts(), "Incorrect result after step");
+
+		assert!(
+			!turnstile.step_event(&Event::Coin(10), |_, _, _, _| {}),
+			"Missing transition taken"
+		);
+		assert_eq!(None, turnstile.get_current(), "Missing transition taken");
+		turnstile.set_current("unlocked");
+		assert!(
+			turnstile.step_event(&Event::Push, |_, _, _, _| {}),
+			"Transition not taken"
+		);
+	}
+
+	#[test]
+	fn primitive() {
+		let mut dfa = DFA::<u8, char>::with_state(0, false);
+		dfa.add_state(1, true);
+		dfa.add_transition((0, 'a', 1)).unwrap();
+		assert!(
+			dfa.step_event(&'a', |_, _, _, _| {}),
+			"Transition not taken"
+		);
+		assert!(dfa.accepts(), "Incorrect result after step");
+	}
+}
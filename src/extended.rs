@@ -0,0 +1,156 @@
+use super::{Automaton, AutomatonError, Behavior, InputKey, DFA};
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Predicate deciding whether a state accepts for the current variables.
+type Acceptance<V> = Box<dyn Fn(&V) -> bool>;
+
+/// DFA carrying typed variables of type `V`, which are updated by the actions of its transitions.
+///
+/// Transitions carry a `Behavior` with the variables as context, so guards & actions can read & update them.
+/// States can compute their acceptance from the variables through a predicate, e.g. accepting once a counter reaches a bound.
+/// States without a predicate accept like in the automaton.
+pub struct ExtendedDFA<S, E, V>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+{
+	automaton: DFA<S, E::Key, Behavior<V, E>>,
+	acceptance: HashMap<S, Acceptance<V>>,
+	initial: V,
+	variables: V,
+}
+
+impl<S, E, V> ExtendedDFA<S, E, V>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+	V: Clone,
+{
+	/// Creates a new extended DFA running an automaton with initial values of the variables.
+	pub fn new(automaton: DFA<S, E::Key, Behavior<V, E>>, variables: V) -> Self {
+		Self {
+			automaton,
+			acceptance: HashMap::new(),
+			initial: variables.clone(),
+			variables,
+		}
+	}
+
+	/// Returns the automaton run by the extended DFA.
+	pub fn automaton(&self) -> &DFA<S, E::Key, Behavior<V, E>> {
+		&self.automaton
+	}
+
+	/// Returns the current values of the variables.
+	pub fn variables(&self) -> &V {
+		&self.variables
+	}
+
+	/// Returns the current state.
+	/// Returns `None` if the extended DFA is in an invalid state.
+	pub fn current(&self) -> Option<&S> {
+		self.automaton.get_current()
+	}
+
+	/// Sets the predicate deciding whether a state accepts for the current variables, replacing a previous one.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn set_acceptance<F>(&mut self, id: S, predicate: F) -> Result<(), AutomatonError<S>>
+	where
+		F: Fn(&V) -> bool + 'static,
+	{
+		if !self.automaton.has_state(&id) {
+			return Err(AutomatonError::InexistentState(id, Vec::new()));
+		}
+		self.acceptance.insert(id, Box::new(predicate));
+		Ok(())
+	}
+
+	/// Checks whether the current state accepts for the current variables.
+	pub fn accepts(&self) -> bool {
+		match self.current() {
+			Some(id) => match self.acceptance.get(id) {
+				Some(predicate) => predicate(&self.variables),
+				None => self.automaton.accepts(),
+			},
+			None => false,
+		}
+	}
+
+	/// Performs a single state transition for an event, updating the variables by the action of the transition.
+	/// Returns whether a transition was taken, see `DFA::step_with`.
+	pub fn step(&mut self, event: &E) -> bool {
+		self.automaton.step_with(event, &mut self.variables)
+	}
+
+	/// Resets the automaton to its initial state & the variables to their initial values.
+	pub fn reset(&mut self) {
+		self.automaton.reset();
+		self.variables = self.initial.clone();
+	}
+
+	/// Resets the extended DFA & runs it on a sequence of events.
+	/// Returns whether it accepts afterwards.
+	pub fn run(&mut self, events: &[E]) -> bool {
+		self.reset();
+		for event in events {
+			self.step(event);
+		}
+		self.accepts()
+	}
+}
+
+impl<S, E, V> fmt::Debug for ExtendedDFA<S, E, V>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+	E::Key: fmt::Debug,
+	V: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ExtendedDFA")
+			.field("automaton", &self.automaton)
+			.field("acceptance", &self.acceptance.keys().collect::<Vec<_>>())
+			.field("variables", &self.variables)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Counts `+` & accepts once the count reaches 3, until `x` stops counting.
+	fn counter() -> ExtendedDFA<&'static str, char, u32> {
+		let mut dfa = DFA::with_state("counting", false);
+		dfa.add_state("stopped", false);
+		dfa.add_transition_with_payload(
+			("counting", '+', "counting"),
+			Behavior::new().with_action(|count: &mut u32, _: &char| *count += 1),
+		)
+		.unwrap();
+		dfa.add_transition(("counting", 'x', "stopped")).unwrap();
+		let mut counter = ExtendedDFA::new(dfa, 0);
+		counter
+			.set_acceptance("counting", |count| *count >= 3)
+			.unwrap();
+		counter
+	}
+
+	#[test]
+	fn acceptance() {
+		let mut counter = counter();
+		assert!(!counter.run(&['+', '+']), "Accepted below bound");
+		assert!(counter.run(&['+', '+', '+']), "Rejected at bound");
+		assert_eq!(&3, counter.variables(), "Variables not updated");
+		assert!(
+			!counter.run(&['+', '+', '+', 'x']),
+			"State without predicate accepting"
+		);
+		counter.reset();
+		assert_eq!(&0, counter.variables(), "Variables not reset");
+		assert!(
+			counter.set_acceptance("missing", |_| true).is_err(),
+			"Predicate for inexistent state"
+		);
+	}
+}
@@ -0,0 +1,124 @@
+use super::{Automaton, DFA};
+
+/// DFA with a fixed number of states & input symbols, backed by arrays.
+///
+/// States & symbols are indices, with `0` being the initial state.
+/// Construction, stepping & running are `const`, so small machines known at compile time need neither heap allocations nor startup work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstDFA<const STATES: usize, const SYMBOLS: usize> {
+	accepts: [bool; STATES],
+	transitions: [[Option<usize>; SYMBOLS]; STATES],
+}
+
+impl<const STATES: usize, const SYMBOLS: usize> ConstDFA<STATES, SYMBOLS> {
+	/// Creates a new DFA from the accepting states & the transitions of each state for each symbol.
+	///
+	/// Panics if a transition leads to an inexistent state, which fails compilation in `const` contexts.
+	pub const fn new(
+		accepts: [bool; STATES],
+		transitions: [[Option<usize>; SYMBOLS]; STATES],
+	) -> Self {
+		let mut state = 0;
+		while state < STATES {
+			let mut symbol = 0;
+			while symbol < SYMBOLS {
+				if let Some(next) = transitions[state][symbol] {
+					assert!(next < STATES, "Transition to inexistent state");
+				}
+				symbol += 1;
+			}
+			state += 1;
+		}
+		Self {
+			accepts,
+			transitions,
+		}
+	}
+
+	/// Checks whether a state is accepting.
+	/// Inexistent states are not accepting.
+	pub const fn accepts(&self, state: usize) -> bool {
+		state < STATES && self.accepts[state]
+	}
+
+	/// Returns the next state for a state & symbol.
+	/// Returns `None` if there is no transition or the state or symbol is inexistent.
+	pub const fn step(&self, state: usize, symbol: usize) -> Option<usize> {
+		if state < STATES && symbol < SYMBOLS {
+			self.transitions[state][symbol]
+		} else {
+			None
+		}
+	}
+
+	/// Runs the DFA on a sequence of symbols, starting from the initial state.
+	pub const fn run(&self, symbols: &[usize]) -> bool {
+		if STATES == 0 {
+			return false;
+		}
+		let mut state = 0;
+		let mut i = 0;
+		while i < symbols.len() {
+			state = match self.step(state, symbols[i]) {
+				Some(next) => next,
+				None => return false,
+			};
+			i += 1;
+		}
+		self.accepts[state]
+	}
+}
+
+/// Converts a fixed-size DFA into a DFA with state & symbol indices as IDs & inputs.
+impl<const STATES: usize, const SYMBOLS: usize> From<ConstDFA<STATES, SYMBOLS>>
+	for DFA<usize, usize>
+{
+	fn from(fixed: ConstDFA<STATES, SYMBOLS>) -> Self {
+		let mut dfa = DFA::new();
+		for (state, accepts) in fixed.accepts.iter().enumerate() {
+			dfa.add_state(state, *accepts);
+		}
+		for (state, transitions) in fixed.transitions.iter().enumerate() {
+			for (symbol, next) in transitions.iter().enumerate() {
+				if let Some(next) = next {
+					dfa.add_transition((state, symbol, *next))
+						.expect("Transition to inexistent state");
+				}
+			}
+		}
+		if STATES > 0 {
+			dfa.set_initial(0);
+			dfa.reset();
+		}
+		dfa
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Accepts words over `0` & `1` with an even number of `1`.
+	const EVEN: ConstDFA<2, 2> =
+		ConstDFA::new([true, false], [[Some(0), Some(1)], [Some(1), Some(0)]]);
+
+	#[test]
+	fn run() {
+		// evaluated at compile time
+		const { assert!(EVEN.run(&[1, 0, 1]), "Word rejected") };
+		assert!(!EVEN.run(&[1, 0]), "Word accepted");
+		assert!(!EVEN.run(&[2]), "Inexistent symbol accepted");
+		assert_eq!(None, EVEN.step(2, 0), "Step from inexistent state");
+		assert!(
+			!ConstDFA::<0, 1>::new([], []).run(&[]),
+			"Empty DFA accepting"
+		);
+	}
+
+	#[test]
+	fn convert() {
+		let mut dfa: DFA<_, _> = EVEN.into();
+		assert!(dfa.run(&[1, 1]), "Word rejected after conversion");
+		assert!(!dfa.run(&[0, 1]), "Word accepted after conversion");
+	}
+}
@@ -0,0 +1,96 @@
+use super::{product::Product, Automaton, DFA};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Fuses several tagged DFAs into a single DFA accepting the words accepted by any of them.
+	///
+	/// States are tuples holding the state of each DFA, with `None` for DFAs which can no longer accept.
+	/// Every accepting state is mapped to the tags of the DFAs accepting in it.
+	#[allow(clippy::type_complexity)]
+	pub fn fuse<K>(
+		machines: Vec<(DFA<S, I, T>, K)>,
+	) -> (DFA<Vec<Option<S>>, I>, HashMap<Vec<Option<S>>, BTreeSet<K>>)
+	where
+		K: Clone + Ord,
+	{
+		let graphs: Vec<_> = machines.iter().map(|(dfa, _)| dfa.graph()).collect();
+		let product = Product::new(&graphs, false);
+		let labels = product.labels(&graphs);
+		let mut tags = HashMap::new();
+		let mut dfa = DFA::from_states_map(product.table.iter().enumerate().map(|(i, row)| {
+			let accepting: BTreeSet<K> = product
+				.accepts(&graphs, i)
+				.zip(&machines)
+				.filter(|(accepts, _)| *accepts)
+				.map(|(_, (_, tag))| tag.clone())
+				.collect();
+			let accepts = !accepting.is_empty();
+			if accepts {
+				tags.insert(labels[i].clone(), accepting);
+			}
+			let transitions = row
+				.iter()
+				.map(|(input, next)| ((*input).clone(), (labels[*next].clone(), ())))
+				.collect::<HashMap<_, _>>();
+			(labels[i].clone(), (accepts, transitions))
+		}));
+		dfa.set_initial(labels[0].clone());
+		dfa.set_current(labels[0].clone());
+		(dfa, tags)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::{btreeset, hashmap};
+
+	/// Accepts words ending with an input.
+	fn ends_with(input: char) -> DFA<u8, char> {
+		let other = if input == 'a' { 'b' } else { 'a' };
+		DFA::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!(input => 1, other => 0)),
+				1 => (true, hashmap!(input => 1, other => 0))
+			),
+		)
+	}
+
+	#[test]
+	fn fuse() {
+		let any = DFA::from_map(0, hashmap!(0 => (true, hashmap!('a' => 0, 'b' => 0))));
+		let (mut dfa, tags) = DFA::fuse(vec![
+			(ends_with('a'), "a"),
+			(ends_with('b'), "b"),
+			(any, "any"),
+		]);
+		let mut matches = |inputs: &[char]| {
+			dfa.set_current(dfa.get_initial().unwrap().clone());
+			for input in inputs {
+				dfa.step(input);
+			}
+			dfa.get_current().and_then(|state| tags.get(state)).cloned()
+		};
+		assert_eq!(Some(btreeset!["any"]), matches(&[]), "Incorrect tags");
+		assert_eq!(
+			Some(btreeset!["a", "any"]),
+			matches(&['b', 'a']),
+			"Incorrect tags"
+		);
+		assert_eq!(
+			Some(btreeset!["b", "any"]),
+			matches(&['a', 'b']),
+			"Incorrect tags"
+		);
+		assert_eq!(None, matches(&['c']), "Tags for rejected word");
+	}
+}
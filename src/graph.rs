@@ -0,0 +1,202 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Index-based view of the transition graph of an automaton.
+///
+/// Declared states are numbered in order of the underlying arena.
+/// Transitions to inexistent states are omitted.
+pub(crate) struct Graph<'a, S, I> {
+	pub labels: Vec<&'a S>,
+	pub accepts: Vec<bool>,
+	pub edges: Vec<Vec<(&'a I, usize)>>,
+	pub initial: Vec<usize>,
+}
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Creates a new graph from the slots of a state arena & the indices of the initial states.
+	///
+	/// Undeclared slots are skipped together with all transitions to them.
+	pub fn new<V, T>(slots: V, initial: T) -> Self
+	where
+		V: IntoIterator<Item = (&'a S, Option<(bool, Vec<(&'a I, usize)>)>)>,
+		T: IntoIterator<Item = usize>,
+	{
+		let mut remap = Vec::new();
+		let mut labels = Vec::new();
+		let mut accepts = Vec::new();
+		let mut raw = Vec::new();
+		for (id, state) in slots {
+			match state {
+				Some((accept, transitions)) => {
+					remap.push(Some(labels.len()));
+					labels.push(id);
+					accepts.push(accept);
+					raw.push(transitions);
+				}
+				None => remap.push(None),
+			}
+		}
+		let edges = raw
+			.into_iter()
+			.map(|transitions| {
+				transitions
+					.into_iter()
+					.filter_map(|(input, next)| remap[next].map(|next| (input, next)))
+					.collect()
+			})
+			.collect();
+		let initial = initial.into_iter().filter_map(|i| remap[i]).collect();
+		Self {
+			labels,
+			accepts,
+			edges,
+			initial,
+		}
+	}
+}
+
+impl<'a, S, I> Graph<'a, S, I>
+where
+	S: Eq + Hash,
+{
+	/// Returns a map from state to index.
+	pub fn indices(&self) -> HashMap<&'a S, usize> {
+		self.labels
+			.iter()
+			.enumerate()
+			.map(|(i, id)| (*id, i))
+			.collect()
+	}
+}
+
+impl<'a, S, I> Graph<'a, S, I>
+where
+	I: Eq + Hash,
+{
+	/// Returns the transitions of each state as lookup table.
+	///
+	/// If a state has multiple transitions for one input, only one of them is kept.
+	pub fn table(&self) -> Vec<HashMap<&'a I, usize>> {
+		self.edges
+			.iter()
+			.map(|edges| edges.iter().cloned().collect())
+			.collect()
+	}
+}
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Returns the number of states.
+	pub fn len(&self) -> usize {
+		self.labels.len()
+	}
+
+	/// Returns the predecessors of each state together with the input of the transition.
+	pub fn reverse_edges(&self) -> Vec<Vec<(&'a I, usize)>> {
+		let mut reverse = vec![Vec::new(); self.len()];
+		for (from, edges) in self.edges.iter().enumerate() {
+			for (input, to) in edges {
+				reverse[*to].push((*input, from));
+			}
+		}
+		reverse
+	}
+
+	/// Marks all states reachable from the initial states.
+	pub fn reachable(&self) -> Vec<bool> {
+		search(&self.edges, self.initial.iter().cloned())
+	}
+
+	/// Marks all states which can reach an accepting state.
+	pub fn coreachable(&self) -> Vec<bool> {
+		let accepting = (0..self.len()).filter(|i| self.accepts[*i]);
+		search(&self.reverse_edges(), accepting)
+	}
+
+	/// Marks all states which are both reachable & coreachable.
+	pub fn useful(&self) -> Vec<bool> {
+		self.reachable()
+			.into_iter()
+			.zip(self.coreachable())
+			.map(|(reachable, coreachable)| reachable && coreachable)
+			.collect()
+	}
+}
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Computes the strongly connected components of the states selected by a mask.
+	///
+	/// Components are returned in reverse topological order using Tarjan's algorithm.
+	pub fn components(&self, mask: &[bool]) -> Vec<Vec<usize>> {
+		let len = self.len();
+		let mut index = vec![usize::MAX; len];
+		let mut low = vec![0; len];
+		let mut on_stack = vec![false; len];
+		let mut stack = Vec::new();
+		let mut components = Vec::new();
+		let mut counter = 0;
+		for root in (0..len).filter(|i| mask[*i]) {
+			if index[root] != usize::MAX {
+				continue;
+			}
+			let mut calls = vec![(root, 0)];
+			while let Some((state, edge)) = calls.pop() {
+				if edge == 0 {
+					index[state] = counter;
+					low[state] = counter;
+					counter += 1;
+					stack.push(state);
+					on_stack[state] = true;
+				} else {
+					let (_, child) = self.edges[state][edge - 1];
+					low[state] = low[state].min(low[child]);
+				}
+				let mut recurse = None;
+				for (i, (_, next)) in self.edges[state].iter().enumerate().skip(edge) {
+					if !mask[*next] {
+						continue;
+					}
+					if index[*next] == usize::MAX {
+						recurse = Some((i, *next));
+						break;
+					} else if on_stack[*next] {
+						low[state] = low[state].min(index[*next]);
+					}
+				}
+				match recurse {
+					Some((i, next)) => {
+						calls.push((state, i + 1));
+						calls.push((next, 0));
+					}
+					None if low[state] == index[state] => {
+						let mut component = Vec::new();
+						while let Some(el) = stack.pop() {
+							on_stack[el] = false;
+							component.push(el);
+							if el == state {
+								break;
+							}
+						}
+						components.push(component);
+					}
+					None => {}
+				}
+			}
+		}
+		components
+	}
+}
+
+/// Performs a depth-first search over the given adjacency lists.
+fn search<I, V>(edges: &[Vec<(I, usize)>], start: V) -> Vec<bool>
+where
+	V: IntoIterator<Item = usize>,
+{
+	let mut visited = vec![false; edges.len()];
+	let mut stack: Vec<usize> = start.into_iter().collect();
+	while let Some(state) = stack.pop() {
+		if !visited[state] {
+			visited[state] = true;
+			stack.extend(edges[state].iter().map(|(_, next)| *next));
+		}
+	}
+	visited
+}
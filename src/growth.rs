@@ -0,0 +1,207 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{collections::HashSet, fmt, hash::Hash};
+
+/// Asymptotic growth of the number of accepted words per length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Growth {
+	/// The language is finite.
+	Finite,
+
+	/// The number of accepted words of length `n` is bounded by a polynomial of the given degree in `n`.
+	Polynomial(usize),
+
+	/// The number of accepted words grows exponentially with the length.
+	Exponential,
+}
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Determines the growth of the number of accepting paths.
+	fn growth(&self) -> Growth {
+		let useful = self.useful();
+		let components = self.components(&useful);
+		let mut component_of = vec![0; self.len()];
+		for (i, component) in components.iter().enumerate() {
+			for state in component {
+				component_of[*state] = i;
+			}
+		}
+
+		// components are in reverse topological order, so successors are visited first
+		let mut cycles = vec![0; components.len()];
+		for (i, component) in components.iter().enumerate() {
+			let mut internal = 0;
+			let mut longest = 0;
+			for state in component {
+				for (_, next) in &self.edges[*state] {
+					if !useful[*next] {
+						continue;
+					}
+					let j = component_of[*next];
+					if i == j {
+						internal += 1;
+					} else {
+						longest = cycles[j].max(longest);
+					}
+				}
+			}
+			if internal > component.len() {
+				return Growth::Exponential;
+			}
+			cycles[i] = longest + if internal > 0 { 1 } else { 0 };
+		}
+		match cycles.into_iter().max() {
+			Some(cycles) if cycles > 0 => Growth::Polynomial(cycles - 1),
+			_ => Growth::Finite,
+		}
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Determines whether the number of accepted words grows polynomially or exponentially with the word length.
+	pub fn growth_rate(&self) -> Growth {
+		self.graph().growth()
+	}
+
+	/// Checks whether the DFA accepts finitely many words,
+	/// i.e. no cycle lies on a path from the initial state to an accepting state.
+	pub fn is_finite(&self) -> bool {
+		self.growth_rate() == Growth::Finite
+	}
+
+	/// Computes the fraction of words of each length up to `max_len` which are accepted.
+	///
+	/// Words are formed over the inputs used in the transitions of the DFA.
+	pub fn densities(&self, max_len: usize) -> Vec<f64> {
+		let graph = self.graph();
+		let alphabet = graph
+			.edges
+			.iter()
+			.flatten()
+			.map(|(input, _)| *input)
+			.collect::<HashSet<_>>()
+			.len() as f64;
+		let mut weights = vec![0.0; graph.len()];
+		for state in &graph.initial {
+			weights[*state] = 1.0;
+		}
+		let mut densities = Vec::with_capacity(max_len + 1);
+		for len in 0..=max_len {
+			if len > 0 {
+				let mut next = vec![0.0; graph.len()];
+				for (state, weight) in weights.iter().enumerate() {
+					for (_, to) in &graph.edges[state] {
+						next[*to] += weight / alphabet;
+					}
+				}
+				weights = next;
+			}
+			densities.push(
+				weights
+					.iter()
+					.enumerate()
+					.filter(|(state, _)| graph.accepts[*state])
+					.map(|(_, weight)| weight)
+					.sum(),
+			);
+		}
+		densities
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Clone,
+{
+	/// Checks whether the NFA accepts finitely many words,
+	/// i.e. no cycle lies on a path from an initial state to an accepting state.
+	///
+	/// Epsilon transitions are removed beforehand, so cycles of only epsilon transitions do not count.
+	pub fn is_finite(&self) -> bool {
+		if self.has_epsilons() {
+			return self.remove_epsilons().is_finite();
+		}
+		self.graph().growth() == Growth::Finite
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	#[test]
+	fn growth() {
+		// a*b*
+		let poly = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (true, hashmap!('a' => 0, 'b' => 1)),
+				1 => (true, hashmap!('b' => 1))
+			),
+		);
+		assert_eq!(
+			Growth::Polynomial(1),
+			poly.growth_rate(),
+			"Incorrect growth"
+		);
+
+		// (a|b)*
+		let exp = DFA::<u8, char>::from_map(0, hashmap!(0 => (true, hashmap!('a' => 0, 'b' => 0))));
+		assert_eq!(Growth::Exponential, exp.growth_rate(), "Incorrect growth");
+
+		// a|ab with a dead cycle
+		let finite = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1, 'c' => 2)),
+				1 => (true, hashmap!('b' => 3)),
+				2 => (false, hashmap!('c' => 2, 'd' => 2)),
+				3 => (true, hashmap!())
+			),
+		);
+		assert_eq!(Growth::Finite, finite.growth_rate(), "Incorrect growth");
+		assert!(finite.is_finite(), "Finite language not detected");
+		assert!(!poly.is_finite(), "Infinite language not detected");
+	}
+
+	#[test]
+	fn finite() {
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		nfa.add_epsilon((1, 0)).unwrap();
+		assert!(
+			!nfa.is_finite(),
+			"Cycle through epsilon transition not detected"
+		);
+		let mut epsilon = NFA::<u8, char>::with_state(0, false);
+		epsilon.add_state(1, true);
+		epsilon.add_epsilon((0, 1)).unwrap();
+		epsilon.add_epsilon((1, 0)).unwrap();
+		assert!(epsilon.is_finite(), "Epsilon cycle counted");
+	}
+
+	#[test]
+	fn densities() {
+		// words starting with 'a' over {a, b}
+		let dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1)),
+				1 => (true, hashmap!('a' => 1, 'b' => 1))
+			),
+		);
+		assert_eq!(
+			vec![0.0, 0.5, 0.5, 0.5],
+			dfa.densities(3),
+			"Incorrect densities"
+		);
+	}
+}
@@ -0,0 +1,232 @@
+use super::{dfa, nfa, AutomatonError, StateId, DFA, NFA};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+	T: Default,
+{
+	/// Adds a new state to the automaton & returns its handle.
+	pub fn insert_state(&mut self, id: S, accept: bool) -> StateId {
+		let index = self.slot(id);
+		self.states[index] = Some(dfa::State::new(accept, HashMap::new()));
+		StateId(index)
+	}
+
+	/// Returns the handle of a state.
+	pub fn state_id(&self, id: &S) -> Option<StateId> {
+		self.index_of(id).map(StateId)
+	}
+
+	/// Returns the state a handle refers to.
+	pub fn state_label(&self, state: StateId) -> Option<&S> {
+		self.resolve(state).map(|_| &self.ids[state.0])
+	}
+
+	/// Adds a new transition between two state handles.
+	/// Returns an `AutomatonError::InvalidStateId` error if one of the handles is invalid.
+	/// Returns an `AutomatonError::UnknownInput` error if the input is not part of the declared alphabet.
+	pub fn add_transition_by_id(
+		&mut self,
+		prev: StateId,
+		input: I,
+		next: StateId,
+	) -> Result<(), AutomatonError<S, I>> {
+		self.resolve(next)
+			.ok_or(AutomatonError::InvalidStateId(next))?;
+		if !self.knows(&input) {
+			return Err(AutomatonError::UnknownInput(input));
+		}
+		match self.states.get_mut(prev.0).and_then(Option::as_mut) {
+			Some(state) => {
+				state.transitions.insert(input, (next.0, T::default()));
+				Ok(())
+			}
+			None => Err(AutomatonError::InvalidStateId(prev)),
+		}
+	}
+
+	/// Returns the handle of the current state.
+	pub fn current_id(&self) -> Option<StateId> {
+		self.current.map(StateId)
+	}
+
+	/// Updates the current state from a handle.
+	/// If the handle is invalid, the automaton will go into an invalid state.
+	pub fn set_current_id(&mut self, state: StateId) {
+		self.current = self.resolve(state).map(|_| state.0);
+	}
+
+	/// Returns the handle of the state reached from a state with an input.
+	pub fn next_id(&self, state: StateId, input: &I) -> Option<StateId> {
+		self.resolve(state)
+			.and_then(|state| state.transitions.get(input))
+			.filter(|(next, _)| self.states[*next].is_some())
+			.map(|(next, _)| StateId(*next))
+	}
+
+	/// Checks whether the state of a handle is accepting.
+	pub fn accepts_id(&self, state: StateId) -> bool {
+		matches!(self.resolve(state), Some(state) if state.accepts)
+	}
+
+	/// Returns the declared state a handle refers to.
+	fn resolve(&self, state: StateId) -> Option<&dfa::State<I, T>> {
+		self.states.get(state.0).and_then(Option::as_ref)
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+	T: Default,
+{
+	/// Adds a new state to the automaton & returns its handle.
+	pub fn insert_state(&mut self, id: S, accept: bool) -> StateId {
+		let index = self.slot(id);
+		self.states[index] = Some(nfa::State::new(accept, HashMap::new()));
+		StateId(index)
+	}
+
+	/// Returns the handle of a state.
+	pub fn state_id(&self, id: &S) -> Option<StateId> {
+		self.index_of(id).map(StateId)
+	}
+
+	/// Returns the state a handle refers to.
+	pub fn state_label(&self, state: StateId) -> Option<&S> {
+		self.resolve(state).map(|_| &self.ids[state.0])
+	}
+
+	/// Adds a new transition between two state handles.
+	/// Returns an `AutomatonError::InvalidStateId` error if one of the handles is invalid.
+	/// Returns an `AutomatonError::UnknownInput` error if the input is not part of the declared alphabet.
+	pub fn add_transition_by_id(
+		&mut self,
+		prev: StateId,
+		input: I,
+		next: StateId,
+	) -> Result<(), AutomatonError<S, I>> {
+		self.resolve(next)
+			.ok_or(AutomatonError::InvalidStateId(next))?;
+		if !self.knows(&input) {
+			return Err(AutomatonError::UnknownInput(input));
+		}
+		match self.states.get_mut(prev.0).and_then(Option::as_mut) {
+			Some(state) => {
+				state
+					.transitions
+					.entry(input)
+					.or_default()
+					.insert(next.0, nfa::Edge::new(T::default()));
+				Ok(())
+			}
+			None => Err(AutomatonError::InvalidStateId(prev)),
+		}
+	}
+
+	/// Returns the handles of the current states.
+	pub fn current_ids(&self) -> Vec<StateId> {
+		self.current_indices()
+			.iter()
+			.cloned()
+			.map(StateId)
+			.collect()
+	}
+
+	/// Updates the current states from handles.
+	/// If any of the handles is invalid, the automaton will go into an invalid state.
+	pub fn set_current_ids<V>(&mut self, states: V)
+	where
+		V: IntoIterator<Item = StateId>,
+	{
+		let current = states
+			.into_iter()
+			.map(|state| self.resolve(state).map(|_| state.0))
+			.collect::<Option<BTreeSet<_>>>()
+			.unwrap_or_default();
+		self.set_current_indices(current);
+	}
+
+	/// Returns the handles of the states reached from a state with an input.
+	pub fn next_ids(&self, state: StateId, input: &I) -> Vec<StateId> {
+		self.resolve(state)
+			.and_then(|state| state.transitions.get(input))
+			.into_iter()
+			.flat_map(|next| next.keys())
+			.filter(|next| self.states[**next].is_some())
+			.map(|next| StateId(*next))
+			.collect()
+	}
+
+	/// Checks whether the state of a handle is accepting.
+	pub fn accepts_id(&self, state: StateId) -> bool {
+		matches!(self.resolve(state), Some(state) if state.accepts)
+	}
+
+	/// Returns the declared state a handle refers to.
+	fn resolve(&self, state: StateId) -> Option<&nfa::State<I, T>> {
+		self.states.get(state.0).and_then(Option::as_ref)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	#[test]
+	fn dfa() {
+		let mut dfa = DFA::<String, char>::new();
+		let start = dfa.insert_state("start".to_string(), false);
+		let end = dfa.insert_state("end".to_string(), true);
+		dfa.add_transition_by_id(start, 'a', end).unwrap();
+		dfa.add_transition_by_id(end, 'a', end).unwrap();
+		assert_eq!(
+			Some(start),
+			dfa.state_id(&"start".to_string()),
+			"Incorrect handle"
+		);
+		assert_eq!(
+			Some(&"end".to_string()),
+			dfa.state_label(end),
+			"Incorrect label"
+		);
+
+		dfa.set_current_id(start);
+		let next = dfa.next_id(start, &'a').unwrap();
+		assert!(dfa.accepts_id(next), "Incorrect result after step");
+		assert_eq!(None, dfa.next_id(start, &'b'), "Incorrect missing step");
+		assert!(
+			dfa.add_transition_by_id(start, 'b', StateId(5)).is_err(),
+			"Missing error on invalid handle"
+		);
+		dfa.set_current_id(StateId(5));
+		assert_eq!(None, dfa.current_id(), "Invalid handle set as current");
+	}
+
+	#[test]
+	fn nfa() {
+		let mut nfa = NFA::<String, char>::new();
+		let start = nfa.insert_state("start".to_string(), false);
+		let end = nfa.insert_state("end".to_string(), true);
+		nfa.add_transition_by_id(start, 'a', start).unwrap();
+		nfa.add_transition_by_id(start, 'a', end).unwrap();
+		assert_eq!(
+			vec![start, end],
+			nfa.next_ids(start, &'a'),
+			"Incorrect successors"
+		);
+
+		nfa.set_current_ids(vec![start]);
+		nfa.step(&'a');
+		assert_eq!(vec![start, end], nfa.current_ids(), "Incorrect step");
+		assert!(nfa.accepts(), "Incorrect result after step");
+	}
+}
@@ -0,0 +1,489 @@
+use super::DFA;
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Node<I>
+where
+	I: Ord,
+{
+	accepts: bool,
+	transitions: BTreeMap<I, usize>,
+}
+
+impl<I> Default for Node<I>
+where
+	I: Ord,
+{
+	fn default() -> Self {
+		Self {
+			accepts: false,
+			transitions: BTreeMap::new(),
+		}
+	}
+}
+
+/// A minimal acyclic DFA built incrementally from a set of words.
+///
+/// Equivalent states are shared through a register, so the automaton stays minimal after every insertion.
+/// Words can be inserted in any order.
+/// Use `SortedIncrementalDFA` to build the automaton faster from words in lexicographic order.
+#[derive(Debug)]
+pub struct IncrementalDFA<I>
+where
+	I: Ord + Clone + Hash,
+{
+	nodes: Vec<Option<Node<I>>>,
+	refs: Vec<usize>,
+	free: Vec<usize>,
+	register: HashMap<Node<I>, usize>,
+	root: usize,
+	words: usize,
+}
+
+impl<I> Default for IncrementalDFA<I>
+where
+	I: Ord + Clone + Hash,
+{
+	fn default() -> Self {
+		let mut dfa = Self {
+			nodes: Vec::new(),
+			refs: Vec::new(),
+			free: Vec::new(),
+			register: HashMap::new(),
+			root: 0,
+			words: 0,
+		};
+		dfa.root = dfa.intern(Node::default());
+		dfa.refs[dfa.root] += 1;
+		dfa
+	}
+}
+
+impl<I> IncrementalDFA<I>
+where
+	I: Ord + Clone + Hash,
+{
+	/// Creates a new empty automaton.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a new automaton accepting the given words.
+	pub fn from_words<V, W>(words: V) -> Self
+	where
+		V: IntoIterator<Item = W>,
+		W: AsRef<[I]>,
+	{
+		let mut dfa = Self::new();
+		for word in words {
+			dfa.insert(word.as_ref());
+		}
+		dfa
+	}
+
+	/// Creates a new automaton accepting the given words in lexicographic order.
+	/// Returns an `UnsortedError` for the first word preceding the word before it.
+	pub fn from_sorted_words<V, W>(words: V) -> Result<Self, UnsortedError<I>>
+	where
+		V: IntoIterator<Item = W>,
+		W: AsRef<[I]>,
+	{
+		let mut dfa = SortedIncrementalDFA::new();
+		for word in words {
+			dfa.insert(word.as_ref())?;
+		}
+		Ok(dfa.finish())
+	}
+
+	/// Returns the number of accepted words.
+	pub fn len(&self) -> usize {
+		self.words
+	}
+
+	/// Checks whether the automaton accepts no words.
+	pub fn is_empty(&self) -> bool {
+		self.words == 0
+	}
+
+	/// Returns the number of states of the minimal automaton.
+	pub fn state_count(&self) -> usize {
+		self.register.len()
+	}
+
+	/// Checks whether the automaton accepts a word.
+	pub fn contains(&self, word: &[I]) -> bool {
+		let mut current = self.root;
+		for input in word {
+			match self.node(current).transitions.get(input) {
+				Some(next) => current = *next,
+				None => return false,
+			}
+		}
+		self.node(current).accepts
+	}
+
+	/// Adds a word to the accepted language.
+	/// Returns `false` if the word was already accepted.
+	pub fn insert(&mut self, word: &[I]) -> bool {
+		if self.contains(word) {
+			return false;
+		}
+
+		// copy the nodes along the existing path of the word
+		let mut path = Vec::with_capacity(word.len() + 1);
+		let mut current = Some(self.root);
+		for input in word {
+			let node = current.map(|id| self.node(id).clone()).unwrap_or_default();
+			current = node.transitions.get(input).cloned();
+			path.push(node);
+		}
+		let mut last = current.map(|id| self.node(id).clone()).unwrap_or_default();
+		last.accepts = true;
+
+		// register the modified nodes bottom-up
+		let mut next = self.intern(last);
+		for (node, input) in path.into_iter().zip(word).rev() {
+			let mut node = node;
+			node.transitions.insert(input.clone(), next);
+			next = self.intern(node);
+		}
+		self.refs[next] += 1;
+		let old = self.root;
+		self.root = next;
+		self.release(old);
+		self.words += 1;
+		true
+	}
+
+	/// Converts the automaton into a `DFA` with numeric states.
+	pub fn to_dfa(&self) -> DFA<usize, I>
+	where
+		I: Default,
+	{
+		DFA::from_map(
+			self.root,
+			self.register
+				.iter()
+				.map(|(node, id)| {
+					(
+						*id,
+						(
+							node.accepts,
+							node.transitions
+								.iter()
+								.map(|(input, next)| (input.clone(), *next))
+								.collect(),
+						),
+					)
+				})
+				.collect::<HashMap<_, _>>(),
+		)
+	}
+
+	fn node(&self, id: usize) -> &Node<I> {
+		self.nodes[id].as_ref().expect("Released node in use")
+	}
+
+	/// Returns the id of the registered node equivalent to the passed node, registering it if necessary.
+	fn intern(&mut self, node: Node<I>) -> usize {
+		if let Some(id) = self.register.get(&node) {
+			return *id;
+		}
+		for next in node.transitions.values() {
+			self.refs[*next] += 1;
+		}
+		let id = match self.free.pop() {
+			Some(id) => {
+				self.nodes[id] = Some(node.clone());
+				id
+			}
+			None => {
+				self.nodes.push(Some(node.clone()));
+				self.refs.push(0);
+				self.nodes.len() - 1
+			}
+		};
+		self.register.insert(node, id);
+		id
+	}
+
+	/// Drops a reference to a node, releasing it & its children once unreferenced.
+	fn release(&mut self, id: usize) {
+		let mut stack = vec![id];
+		while let Some(id) = stack.pop() {
+			self.refs[id] -= 1;
+			if self.refs[id] == 0 {
+				let node = self.nodes[id].take().expect("Released node in use");
+				stack.extend(node.transitions.values());
+				self.register.remove(&node);
+				self.free.push(id);
+			}
+		}
+	}
+}
+
+/// Error for a word inserted after a lexicographically greater word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsortedError<I>(pub Vec<I>);
+
+impl<I> fmt::Display for UnsortedError<I>
+where
+	I: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Unsorted word {:?}", self.0)
+	}
+}
+
+/// A minimal acyclic DFA built incrementally from words in lexicographic order.
+///
+/// Only the path of the last inserted word is kept outside of the register.
+/// Once the next word diverges from it, the diverging suffix is frozen & merged with equivalent states.
+/// Unlike `IncrementalDFA`, no paths are copied on insertion.
+#[derive(Debug)]
+pub struct SortedIncrementalDFA<I>
+where
+	I: Ord + Clone + Hash,
+{
+	nodes: Vec<Option<Node<I>>>,
+	free: Vec<usize>,
+	register: HashMap<Node<I>, usize>,
+	last: Option<Vec<I>>,
+	words: usize,
+}
+
+impl<I> Default for SortedIncrementalDFA<I>
+where
+	I: Ord + Clone + Hash,
+{
+	fn default() -> Self {
+		Self {
+			nodes: vec![Some(Node::default())],
+			free: Vec::new(),
+			register: HashMap::new(),
+			last: None,
+			words: 0,
+		}
+	}
+}
+
+impl<I> SortedIncrementalDFA<I>
+where
+	I: Ord + Clone + Hash,
+{
+	/// Creates a new empty automaton.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the number of accepted words.
+	pub fn len(&self) -> usize {
+		self.words
+	}
+
+	/// Checks whether the automaton accepts no words.
+	pub fn is_empty(&self) -> bool {
+		self.words == 0
+	}
+
+	/// Checks whether the automaton accepts a word.
+	pub fn contains(&self, word: &[I]) -> bool {
+		let mut current = 0;
+		for input in word {
+			match self.node(current).transitions.get(input) {
+				Some(next) => current = *next,
+				None => return false,
+			}
+		}
+		self.node(current).accepts
+	}
+
+	/// Adds a word to the accepted language.
+	/// Returns `false` if the word equals the last inserted word.
+	/// Returns an `UnsortedError` without changing the automaton if the word precedes the last inserted word.
+	pub fn insert(&mut self, word: &[I]) -> Result<bool, UnsortedError<I>> {
+		let prefix = match self.last.take() {
+			Some(last) if word <= last.as_slice() => {
+				let inserted = word == last.as_slice();
+				self.last = Some(last);
+				return if inserted {
+					Ok(false)
+				} else {
+					Err(UnsortedError(word.to_vec()))
+				};
+			}
+			Some(last) => {
+				let prefix = last.iter().zip(word).take_while(|(a, b)| a == b).count();
+				self.freeze(&last, prefix);
+				prefix
+			}
+			None => 0,
+		};
+
+		// append the remaining suffix to the unfrozen path
+		let mut current = 0;
+		for input in &word[..prefix] {
+			current = self.node(current).transitions[input];
+		}
+		for input in &word[prefix..] {
+			let next = self.alloc(Node::default());
+			self.node_mut(current)
+				.transitions
+				.insert(input.clone(), next);
+			current = next;
+		}
+		self.node_mut(current).accepts = true;
+		self.last = Some(word.to_vec());
+		self.words += 1;
+		Ok(true)
+	}
+
+	/// Freezes the remaining path & converts the automaton into an `IncrementalDFA` accepting further words in any order.
+	pub fn finish(mut self) -> IncrementalDFA<I> {
+		if let Some(last) = self.last.take() {
+			self.freeze(&last, 0);
+		}
+
+		// the root accepts the longest words, so it is never equivalent to a registered node
+		self.register.insert(self.node(0).clone(), 0);
+		let mut refs = vec![0; self.nodes.len()];
+		refs[0] += 1;
+		for node in self.nodes.iter().flatten() {
+			for next in node.transitions.values() {
+				refs[*next] += 1;
+			}
+		}
+		IncrementalDFA {
+			nodes: self.nodes,
+			refs,
+			free: self.free,
+			register: self.register,
+			root: 0,
+			words: self.words,
+		}
+	}
+
+	fn node(&self, id: usize) -> &Node<I> {
+		self.nodes[id].as_ref().expect("Released node in use")
+	}
+
+	fn node_mut(&mut self, id: usize) -> &mut Node<I> {
+		self.nodes[id].as_mut().expect("Released node in use")
+	}
+
+	fn alloc(&mut self, node: Node<I>) -> usize {
+		match self.free.pop() {
+			Some(id) => {
+				self.nodes[id] = Some(node);
+				id
+			}
+			None => {
+				self.nodes.push(Some(node));
+				self.nodes.len() - 1
+			}
+		}
+	}
+
+	/// Registers the nodes along the path of the word below the given depth bottom-up,
+	/// replacing nodes by their registered equivalents.
+	fn freeze(&mut self, word: &[I], depth: usize) {
+		let mut path = vec![0];
+		for input in word {
+			let next = self.node(path[path.len() - 1]).transitions[input];
+			path.push(next);
+		}
+		for index in (depth + 1..path.len()).rev() {
+			let id = path[index];
+			let node = self.node(id).clone();
+			match self.register.get(&node) {
+				Some(equivalent) => {
+					let equivalent = *equivalent;
+					self.nodes[id] = None;
+					self.free.push(id);
+					self.node_mut(path[index - 1])
+						.transitions
+						.insert(word[index - 1].clone(), equivalent);
+				}
+				None => {
+					self.register.insert(node, id);
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	#[test]
+	fn minimal() {
+		let mut dfa = IncrementalDFA::new();
+		for word in &["tap", "taps", "top", "tops"] {
+			assert!(dfa.insert(word.as_bytes()), "Word not inserted");
+		}
+		assert!(!dfa.insert(b"tap"), "Duplicate word inserted");
+		assert_eq!(4, dfa.len(), "Incorrect word count");
+		assert_eq!(5, dfa.state_count(), "Automaton not minimal");
+		assert!(dfa.contains(b"tops"), "Inserted word missing");
+		assert!(!dfa.contains(b"to"), "Prefix incorrectly accepted");
+	}
+
+	#[test]
+	fn unsorted() {
+		let sorted = IncrementalDFA::from_words(["ab", "abc", "b", "bc", "cab"]);
+		let unsorted = IncrementalDFA::from_words(["cab", "bc", "ab", "b", "abc"]);
+		assert_eq!(
+			sorted.state_count(),
+			unsorted.state_count(),
+			"Insertion order affects automaton size"
+		);
+		let mut dfa = unsorted.to_dfa();
+		for word in &["ab", "abc", "b", "bc", "cab"] {
+			assert!(dfa.run(word.as_bytes()), "Word not accepted");
+		}
+		assert!(!dfa.run(b"ca"), "Prefix incorrectly accepted");
+	}
+
+	#[test]
+	fn sorted() {
+		let words = ["ab", "abc", "b", "bc", "cab", "cb"];
+		let mut sorted = SortedIncrementalDFA::new();
+		for word in &words {
+			assert_eq!(
+				Ok(true),
+				sorted.insert(word.as_bytes()),
+				"Word not inserted"
+			);
+			assert!(sorted.contains(word.as_bytes()), "Inserted word missing");
+		}
+		assert_eq!(Ok(false), sorted.insert(b"cb"), "Duplicate word inserted");
+		assert_eq!(
+			Err(UnsortedError(b"ca".to_vec())),
+			sorted.insert(b"ca"),
+			"Unsorted word inserted"
+		);
+		assert!(!sorted.contains(b"ca"), "Unsorted word accepted");
+		assert_eq!(6, sorted.len(), "Incorrect word count");
+
+		let mut dfa = sorted.finish();
+		let unsorted = IncrementalDFA::from_words(words.iter().rev());
+		assert_eq!(
+			unsorted.state_count(),
+			dfa.state_count(),
+			"Sorted construction not minimal"
+		);
+		assert!(dfa.insert(b"a"), "Word not inserted after finishing");
+		assert!(dfa.contains(b"a"), "Inserted word missing");
+		assert!(dfa.contains(b"cab"), "Sorted word missing");
+		assert!(
+			IncrementalDFA::from_sorted_words(["b", "a"]).is_err(),
+			"Missing error for unsorted words"
+		);
+	}
+}
@@ -0,0 +1,76 @@
+use std::{collections::HashMap, rc::Rc};
+
+/// Hash-consing table of subsets of state indices, numbered in order of interning.
+///
+/// Each distinct subset is stored once as a sorted slice shared by the table & its numbering,
+/// so repeated subsets share one allocation & interned subsets are compared by their number.
+#[derive(Debug, Default)]
+pub(crate) struct Interner {
+	numbers: HashMap<Rc<[usize]>, usize>,
+	sets: Vec<Rc<[usize]>>,
+}
+
+impl Interner {
+	/// Creates a new empty table.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the number of interned subsets.
+	pub fn len(&self) -> usize {
+		self.sets.len()
+	}
+
+	/// Returns the number of an interned subset, given as sorted slice of state indices.
+	pub fn get(&self, set: &[usize]) -> Option<usize> {
+		self.numbers.get(set).cloned()
+	}
+
+	/// Interns a subset, given as sorted slice of state indices.
+	/// Returns its number & whether it was newly interned.
+	///
+	/// The subset is only copied if it was not interned before.
+	pub fn intern(&mut self, set: &[usize]) -> (usize, bool) {
+		if let Some(number) = self.get(set) {
+			return (number, false);
+		}
+		let number = self.sets.len();
+		let set: Rc<[usize]> = Rc::from(set);
+		self.numbers.insert(Rc::clone(&set), number);
+		self.sets.push(set);
+		(number, true)
+	}
+
+	/// Returns the interned subset with a number.
+	pub fn set(&self, number: usize) -> &[usize] {
+		&self.sets[number]
+	}
+
+	/// Returns an iterator over the interned subsets in order of their numbers.
+	pub fn iter(&self) -> impl Iterator<Item = &[usize]> {
+		self.sets.iter().map(|set| set.as_ref())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn intern() {
+		let mut sets = Interner::new();
+		assert_eq!((0, true), sets.intern(&[0, 2]), "Subset not interned");
+		assert_eq!((1, true), sets.intern(&[1]), "Subset not interned");
+		assert_eq!((0, false), sets.intern(&[0, 2]), "Repeated subset interned");
+		assert_eq!(2, sets.len(), "Incorrect number of subsets");
+		assert_eq!(None, sets.get(&[0, 1]), "Unknown subset found");
+		assert_eq!(&[1], sets.set(1), "Incorrect subset");
+		assert!(
+			Rc::ptr_eq(
+				&sets.sets[0],
+				sets.numbers.keys().find(|set| set.len() == 2).unwrap()
+			),
+			"Repeated subset not shared"
+		);
+	}
+}
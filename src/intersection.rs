@@ -0,0 +1,335 @@
+use super::DFA;
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Product of two transition tables for pairs of optional states reachable from the pair of initial states.
+///
+/// A pair has a transition for an input if either state has one & the pair of targets is kept by the filter.
+/// Returns the pairs & the transitions of each pair.
+#[allow(clippy::type_complexity)]
+fn product<'a, I, F>(
+	tables: (&[HashMap<&'a I, usize>], &[HashMap<&'a I, usize>]),
+	initial: (Option<usize>, Option<usize>),
+	keep: F,
+) -> (
+	Vec<(Option<usize>, Option<usize>)>,
+	Vec<Vec<(&'a I, usize)>>,
+)
+where
+	I: Eq + Hash,
+	F: Fn(Option<usize>, Option<usize>) -> bool,
+{
+	let mut numbers = HashMap::new();
+	numbers.insert(initial, 0);
+	let mut pairs = vec![initial];
+	let mut rows = Vec::new();
+	let mut i = 0;
+	while i < pairs.len() {
+		let (state, other_state) = pairs[i];
+		let mut inputs: Vec<&'a I> = Vec::new();
+		if let Some(state) = state {
+			inputs.extend(tables.0[state].keys().cloned());
+		}
+		if let Some(other_state) = other_state {
+			inputs.extend(
+				tables.1[other_state]
+					.keys()
+					.cloned()
+					.filter(|input| state.is_none_or(|state| !tables.0[state].contains_key(input))),
+			);
+		}
+		let mut row = Vec::new();
+		for input in inputs {
+			let next = (
+				state.and_then(|state| tables.0[state].get(input).cloned()),
+				other_state.and_then(|state| tables.1[state].get(input).cloned()),
+			);
+			if keep(next.0, next.1) {
+				let number = *numbers.entry(next).or_insert_with(|| {
+					pairs.push(next);
+					pairs.len() - 1
+				});
+				row.push((input, number));
+			}
+		}
+		rows.push(row);
+		i += 1;
+	}
+	(pairs, rows)
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns a DFA accepting the intersection of the languages of this & another DFA.
+	///
+	/// States are pairs of a state of each DFA & only pairs reachable from the pair of initial states are built.
+	/// A pair has a transition for an input if both of its states have one.
+	/// If either DFA has no initial state, the result has no states.
+	pub fn intersection<R, U>(&self, other: &DFA<R, I, U>) -> DFA<(S, R), I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		let (graph, other_graph) = (self.graph(), other.graph());
+		let initial = match (graph.initial.first(), other_graph.initial.first()) {
+			(Some(initial), Some(other_initial)) => (Some(*initial), Some(*other_initial)),
+			_ => return DFA::default(),
+		};
+		let (pairs, rows) = product(
+			(&graph.table(), &other_graph.table()),
+			initial,
+			|state, other_state| state.is_some() && other_state.is_some(),
+		);
+		let labels: Vec<_> = pairs
+			.iter()
+			.map(|pair| match pair {
+				(Some(state), Some(other_state)) => (
+					graph.labels[*state].clone(),
+					other_graph.labels[*other_state].clone(),
+				),
+				_ => unreachable!("Intersection of missing states"),
+			})
+			.collect();
+		let accepts = pairs.iter().map(|(state, other_state)| {
+			graph.accepts[state.unwrap()] && other_graph.accepts[other_state.unwrap()]
+		});
+		build(labels, accepts, rows)
+	}
+
+	/// Returns a DFA accepting the union of the languages of this & another DFA.
+	///
+	/// States are pairs of an optional state of each DFA, with `None` once a DFA has no transition for an input.
+	/// Only pairs reachable from the pair of initial states are built.
+	/// A pair has a transition for an input if either of its states has one & it accepts if either state accepts.
+	pub fn union<R, U>(&self, other: &DFA<R, I, U>) -> DFA<(Option<S>, Option<R>), I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		self.either(other, |accepts, other_accepts| accepts || other_accepts)
+	}
+
+	/// Returns a DFA accepting the words accepted by this DFA but not by another DFA.
+	///
+	/// States are pairs of a state of this DFA & an optional state of the other DFA, with `None` once it has no transition for an input.
+	/// Only pairs reachable from the pair of initial states are built.
+	/// A pair has a transition for an input if the state of this DFA has one.
+	/// If this DFA has no initial state, the result has no states.
+	pub fn difference<R, U>(&self, other: &DFA<R, I, U>) -> DFA<(S, Option<R>), I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		let (graph, other_graph) = (self.graph(), other.graph());
+		let initial = match graph.initial.first() {
+			Some(initial) => (Some(*initial), other_graph.initial.first().cloned()),
+			None => return DFA::default(),
+		};
+		let (pairs, rows) = product(
+			(&graph.table(), &other_graph.table()),
+			initial,
+			|state, _| state.is_some(),
+		);
+		let labels: Vec<_> = pairs
+			.iter()
+			.map(|(state, other_state)| {
+				(
+					graph.labels[state.expect("Difference of missing state")].clone(),
+					other_state.map(|state| other_graph.labels[state].clone()),
+				)
+			})
+			.collect();
+		let accepts = pairs.iter().map(|(state, other_state)| {
+			state.is_some_and(|state| graph.accepts[state])
+				&& !other_state.is_some_and(|state| other_graph.accepts[state])
+		});
+		build(labels, accepts, rows)
+	}
+
+	/// Returns a DFA accepting the words accepted by exactly one of this & another DFA.
+	///
+	/// States are pairs of optional states like in `union`, accepting if exactly one of their states accepts.
+	pub fn symmetric_difference<R, U>(&self, other: &DFA<R, I, U>) -> DFA<(Option<S>, Option<R>), I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		self.either(other, |accepts, other_accepts| accepts != other_accepts)
+	}
+
+	/// Returns a DFA over pairs of optional states built like in `union`, accepting depending on the acceptance of both states.
+	/// Missing states are not accepting.
+	fn either<R, U, F>(&self, other: &DFA<R, I, U>, accepts: F) -> DFA<(Option<S>, Option<R>), I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+		F: Fn(bool, bool) -> bool,
+	{
+		let (graph, other_graph) = (self.graph(), other.graph());
+		let initial = (
+			graph.initial.first().cloned(),
+			other_graph.initial.first().cloned(),
+		);
+		if initial == (None, None) {
+			return DFA::default();
+		}
+		let (pairs, rows) = product(
+			(&graph.table(), &other_graph.table()),
+			initial,
+			|state, other_state| state.is_some() || other_state.is_some(),
+		);
+		let labels: Vec<_> = pairs
+			.iter()
+			.map(|(state, other_state)| {
+				(
+					state.map(|state| graph.labels[state].clone()),
+					other_state.map(|state| other_graph.labels[state].clone()),
+				)
+			})
+			.collect();
+		let states = pairs.iter().map(|(state, other_state)| {
+			accepts(
+				state.is_some_and(|state| graph.accepts[state]),
+				other_state.is_some_and(|state| other_graph.accepts[state]),
+			)
+		});
+		build(labels, states, rows)
+	}
+}
+
+/// Builds a DFA from the labels, acceptance & transitions of numbered states with the first state as initial state.
+pub(crate) fn build<S, I, A>(labels: Vec<S>, accepts: A, rows: Vec<Vec<(&I, usize)>>) -> DFA<S, I>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	A: Iterator<Item = bool>,
+{
+	let mut dfa = DFA::from_states_map(labels.iter().zip(accepts).zip(rows).map(
+		|((label, accepts), row)| {
+			let transitions: Vec<_> = row
+				.into_iter()
+				.map(|(input, next)| (input.clone(), (labels[next].clone(), ())))
+				.collect();
+			(label.clone(), (accepts, transitions))
+		},
+	));
+	dfa.initial = dfa.index_of(&labels[0]);
+	dfa.current = dfa.initial;
+	dfa
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	#[test]
+	fn intersection() {
+		// words with an even number of `a` & ending with `b`
+		let even = DFA::<bool, char>::from_map(
+			true,
+			hashmap!(
+				true => (true, hashmap!('a' => false, 'b' => true)),
+				false => (false, hashmap!('a' => true, 'b' => false))
+			),
+		);
+		let end = DFA::<&str, char>::from_map(
+			"other",
+			hashmap!(
+				"other" => (false, hashmap!('a' => "other", 'b' => "end")),
+				"end" => (true, hashmap!('a' => "other", 'b' => "end"))
+			),
+		);
+		let mut both = even.intersection(&end);
+		assert_eq!(4, both.stats().states, "Incorrect number of states");
+		assert_eq!(
+			Some(&(true, "other")),
+			both.get_current(),
+			"Incorrect initial pair"
+		);
+		assert!(both.run(&['a', 'a', 'b']), "Word rejected");
+		assert!(!both.run(&['a', 'b']), "Odd word accepted");
+		assert!(!both.run(&['a', 'a']), "Word not ending with b accepted");
+	}
+
+	#[test]
+	fn partial() {
+		// transitions missing in either DFA are missing in the intersection
+		let a = DFA::<u8, char>::from_map(0, hashmap!(0 => (true, hashmap!('a' => 0))));
+		let ab = DFA::<u8, char>::from_map(0, hashmap!(0 => (true, hashmap!('a' => 0, 'b' => 0))));
+		let mut both = a.intersection(&ab);
+		assert!(both.run(&['a', 'a']), "Word rejected");
+		assert!(!both.run(&['b']), "Word accepted");
+		let mut none = a.intersection(&DFA::<u8, char>::new());
+		assert!(!none.run(&[]), "Empty word accepted without initial state");
+	}
+
+	#[test]
+	fn union() {
+		// words of only `a` or only `b`
+		let a = DFA::<u8, char>::from_map(0, hashmap!(0 => (true, hashmap!('a' => 0))));
+		let b = DFA::<&str, char>::from_map("b", hashmap!("b" => (true, hashmap!('b' => "b"))));
+		let mut either = a.union(&b);
+		assert_eq!(
+			Some(&(Some(0), Some("b"))),
+			either.get_current(),
+			"Incorrect initial pair"
+		);
+		assert!(either.run(&['a', 'a']), "Word of first DFA rejected");
+		assert!(either.run(&['b']), "Word of second DFA rejected");
+		assert!(either.run(&[]), "Empty word rejected");
+		assert!(!either.run(&['a', 'b']), "Mixed word accepted");
+		assert!(
+			either.has_state(&(None, Some("b"))),
+			"Missing state without first DFA"
+		);
+		let mut single = a.union(&DFA::<u8, char>::new());
+		assert!(
+			single.run(&['a']),
+			"Word rejected without second initial state"
+		);
+	}
+
+	#[test]
+	fn difference() {
+		// words of only `a` except the empty word
+		let a = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(0 => (true, hashmap!('a' => 0, 'b' => 1)), 1 => (false, hashmap!())),
+		);
+		let mut epsilon = DFA::<u8, char>::with_state(0, true);
+		epsilon.add_transition((0, 'b', 0)).unwrap();
+		let mut nonempty = a.difference(&epsilon);
+		assert!(nonempty.run(&['a', 'a']), "Word rejected");
+		assert!(!nonempty.run(&[]), "Word of second DFA accepted");
+		assert!(!nonempty.run(&['b']), "Word of neither DFA accepted");
+		assert!(
+			nonempty.has_state(&(0, None)),
+			"Missing state without second DFA"
+		);
+		let mut all = a.difference(&DFA::<u8, char>::new());
+		assert!(all.run(&[]), "Word rejected without second initial state");
+	}
+
+	#[test]
+	fn symmetric_difference() {
+		// words with an even number of `a` or ending with `b`, but not both
+		let even = DFA::<bool, char>::from_map(
+			true,
+			hashmap!(
+				true => (true, hashmap!('a' => false, 'b' => true)),
+				false => (false, hashmap!('a' => true, 'b' => false))
+			),
+		);
+		let end = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 0, 'b' => 1)),
+				1 => (true, hashmap!('a' => 0, 'b' => 1))
+			),
+		);
+		let mut either = even.symmetric_difference(&end);
+		assert!(either.run(&[]), "Word of first DFA rejected");
+		assert!(either.run(&['a', 'b']), "Word of second DFA rejected");
+		assert!(!either.run(&['a', 'a', 'b']), "Word of both DFAs accepted");
+		assert!(!either.run(&['a']), "Word of neither DFA accepted");
+	}
+}
@@ -0,0 +1,622 @@
+//! Reading & writing of finite automata in the XML format of [JFLAP](https://www.jflap.org/) (`.jff` files).
+//!
+//! Read automata use the numeric JFLAP IDs as state IDs & the JFLAP names as display names.
+//! Inputs are read as strings & empty transitions, JFLAP's λ transitions, as epsilon transitions.
+//!
+//! Written automata number their states in order & use the display names as JFLAP names.
+//! Inputs are written using their `Display` representation.
+//! Since JFLAP files store the positions of the states, written states are placed on a grid.
+
+use super::{Automaton, AutomatonError, DFA, NFA};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt,
+	fmt::Write,
+	hash::Hash,
+};
+
+/// Error for a malformed or unsupported JFLAP file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JflapError {
+	/// Malformed XML at a byte offset.
+	Syntax(usize),
+	/// Automaton type other than a finite automaton.
+	UnsupportedType(String),
+	/// Missing or malformed element.
+	Invalid(String),
+	/// Transition referencing an undeclared state.
+	InexistentState(u32),
+	/// State with an empty transition or multiple transitions for an input, when reading a DFA.
+	Nondeterministic(u32),
+}
+
+impl fmt::Display for JflapError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Syntax(offset) => write!(f, "Malformed XML at byte {}", offset),
+			Self::UnsupportedType(kind) => write!(f, "Unsupported JFLAP type \"{}\"", kind),
+			Self::Invalid(element) => write!(f, "Missing or malformed <{}>", element),
+			Self::InexistentState(id) => write!(f, "Inexistent State \"{}\"", id),
+			Self::Nondeterministic(id) => write!(f, "Nondeterministic State \"{}\"", id),
+		}
+	}
+}
+
+impl<I> From<AutomatonError<u32, I>> for JflapError {
+	fn from(err: AutomatonError<u32, I>) -> Self {
+		match err {
+			AutomatonError::InexistentState(id, _) => Self::InexistentState(id),
+			_ => Self::Invalid("transition".into()),
+		}
+	}
+}
+
+/// Element of an XML document.
+#[derive(Debug, Default)]
+struct Element {
+	name: String,
+	attributes: HashMap<String, String>,
+	children: Vec<Element>,
+	text: String,
+}
+
+impl Element {
+	/// Returns the children with a name.
+	fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+		self.children.iter().filter(move |child| child.name == name)
+	}
+
+	/// Returns the first child with a name.
+	fn child(&self, name: &str) -> Option<&Element> {
+		self.children.iter().find(|child| child.name == name)
+	}
+
+	/// Returns the trimmed text of the first child with a name.
+	fn child_text(&self, name: &str) -> Option<&str> {
+		self.child(name).map(|child| child.text.trim())
+	}
+}
+
+/// Parser for the subset of XML used by JFLAP.
+///
+/// Processing instructions, comments & doctype declarations are skipped, CDATA sections are read as text.
+struct Parser<'a> {
+	xml: &'a str,
+	offset: usize,
+}
+
+impl<'a> Parser<'a> {
+	/// Parses the root element of an XML document.
+	fn parse(xml: &'a str) -> Result<Element, JflapError> {
+		let mut parser = Self { xml, offset: 0 };
+		parser.misc()?;
+		let root = parser.element()?;
+		parser.misc()?;
+		if parser.offset < xml.len() {
+			return Err(parser.error());
+		}
+		Ok(root)
+	}
+
+	fn rest(&self) -> &'a str {
+		&self.xml[self.offset..]
+	}
+
+	fn error(&self) -> JflapError {
+		JflapError::Syntax(self.offset)
+	}
+
+	/// Skips whitespace.
+	fn whitespace(&mut self) {
+		let rest = self.rest();
+		self.offset += rest.len() - rest.trim_start().len();
+	}
+
+	/// Consumes a prefix if present.
+	fn eat(&mut self, prefix: &str) -> bool {
+		let found = self.rest().starts_with(prefix);
+		if found {
+			self.offset += prefix.len();
+		}
+		found
+	}
+
+	/// Consumes everything up to & including a delimiter, returning the part before it.
+	fn until(&mut self, delimiter: &str) -> Result<&'a str, JflapError> {
+		let rest = self.rest();
+		let end = rest.find(delimiter).ok_or_else(|| self.error())?;
+		self.offset += end + delimiter.len();
+		Ok(&rest[..end])
+	}
+
+	/// Skips whitespace, processing instructions, comments & doctype declarations.
+	fn misc(&mut self) -> Result<(), JflapError> {
+		loop {
+			self.whitespace();
+			if self.eat("<?") {
+				self.until("?>")?;
+			} else if self.eat("<!--") {
+				self.until("-->")?;
+			} else if self.eat("<!DOCTYPE") {
+				self.until(">")?;
+			} else {
+				return Ok(());
+			}
+		}
+	}
+
+	/// Consumes a tag or attribute name.
+	fn name(&mut self) -> Result<String, JflapError> {
+		let rest = self.rest();
+		let end = rest
+			.find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+			.unwrap_or(rest.len());
+		if end == 0 {
+			return Err(self.error());
+		}
+		self.offset += end;
+		Ok(rest[..end].into())
+	}
+
+	/// Parses an element starting at its opening tag.
+	fn element(&mut self) -> Result<Element, JflapError> {
+		if !self.eat("<") {
+			return Err(self.error());
+		}
+		let mut element = Element {
+			name: self.name()?,
+			..Element::default()
+		};
+		loop {
+			self.whitespace();
+			if self.eat("/>") {
+				return Ok(element);
+			} else if self.eat(">") {
+				break;
+			}
+			let name = self.name()?;
+			self.whitespace();
+			if !self.eat("=") {
+				return Err(self.error());
+			}
+			self.whitespace();
+			let quote = if self.eat("\"") {
+				"\""
+			} else if self.eat("'") {
+				"'"
+			} else {
+				return Err(self.error());
+			};
+			let start = self.offset;
+			let value = self.until(quote)?;
+			let value = unescape(value).ok_or(JflapError::Syntax(start))?;
+			element.attributes.insert(name, value);
+		}
+		loop {
+			if self.eat("</") {
+				if self.name()? != element.name {
+					return Err(self.error());
+				}
+				self.whitespace();
+				if !self.eat(">") {
+					return Err(self.error());
+				}
+				return Ok(element);
+			} else if self.eat("<!--") {
+				self.until("-->")?;
+			} else if self.eat("<![CDATA[") {
+				let text = self.until("]]>")?;
+				element.text.push_str(text);
+			} else if self.rest().starts_with('<') {
+				let child = self.element()?;
+				element.children.push(child);
+			} else if self.rest().is_empty() {
+				return Err(self.error());
+			} else {
+				let start = self.offset;
+				let end = self.rest().find('<').unwrap_or(self.rest().len());
+				let text = &self.rest()[..end];
+				self.offset += end;
+				element
+					.text
+					.push_str(&unescape(text).ok_or(JflapError::Syntax(start))?);
+			}
+		}
+	}
+}
+
+/// Replaces the entity & character references in XML text.
+/// Returns `None` for an unknown or unterminated reference.
+fn unescape(text: &str) -> Option<String> {
+	let mut result = String::new();
+	let mut rest = text;
+	while let Some(start) = rest.find('&') {
+		result.push_str(&rest[..start]);
+		let end = rest[start..].find(';')? + start;
+		let reference = &rest[start + 1..end];
+		let c = match reference {
+			"lt" => '<',
+			"gt" => '>',
+			"amp" => '&',
+			"quot" => '"',
+			"apos" => '\'',
+			_ => {
+				let code = match reference.strip_prefix("#x") {
+					Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+					None => reference.strip_prefix('#')?.parse().ok()?,
+				};
+				char::from_u32(code)?
+			}
+		};
+		result.push(c);
+		rest = &rest[end + 1..];
+	}
+	result.push_str(rest);
+	Some(result)
+}
+
+/// Escapes text for XML content & quoted attributes.
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+/// State of a JFLAP automaton.
+#[derive(Debug)]
+struct Node {
+	id: u32,
+	name: Option<String>,
+	initial: bool,
+	accepts: bool,
+}
+
+/// States & transitions of a JFLAP automaton, with `None` as input of empty transitions.
+#[derive(Debug, Default)]
+struct Machine {
+	states: Vec<Node>,
+	transitions: Vec<(u32, Option<String>, u32)>,
+}
+
+impl Machine {
+	/// Reads a JFLAP finite automaton.
+	///
+	/// Both the current format with an `<automaton>` element & the older format with states directly in the structure are read.
+	fn parse(xml: &str) -> Result<Self, JflapError> {
+		let root = Parser::parse(xml)?;
+		if root.name != "structure" {
+			return Err(JflapError::Invalid("structure".into()));
+		}
+		match root.child_text("type") {
+			Some("fa") => {}
+			Some(kind) => return Err(JflapError::UnsupportedType(kind.into())),
+			None => return Err(JflapError::Invalid("type".into())),
+		}
+		let automaton = root.child("automaton").unwrap_or(&root);
+		let mut machine = Self::default();
+		for state in automaton.children("state") {
+			let id = state
+				.attributes
+				.get("id")
+				.and_then(|id| id.trim().parse().ok())
+				.ok_or_else(|| JflapError::Invalid("state".into()))?;
+			machine.states.push(Node {
+				id,
+				name: state.attributes.get("name").cloned(),
+				initial: state.child("initial").is_some(),
+				accepts: state.child("final").is_some(),
+			});
+		}
+		for transition in automaton.children("transition") {
+			let state = |name| {
+				transition
+					.child_text(name)
+					.and_then(|id| id.parse().ok())
+					.ok_or_else(|| JflapError::Invalid(name.into()))
+			};
+			let input = transition
+				.child("read")
+				.map(|read| read.text.clone())
+				.filter(|read| !read.is_empty());
+			machine
+				.transitions
+				.push((state("from")?, input, state("to")?));
+		}
+		Ok(machine)
+	}
+
+	/// Builds the machine of an automaton from its states & its transitions between their indices.
+	fn new<'a, I, V, E>(states: V, transitions: E) -> Self
+	where
+		I: fmt::Display + 'a,
+		V: IntoIterator<Item = (String, bool, bool)>,
+		E: IntoIterator<Item = (usize, Option<&'a I>, usize)>,
+	{
+		let states = states
+			.into_iter()
+			.enumerate()
+			.map(|(id, (name, initial, accepts))| Node {
+				id: id as u32,
+				name: Some(name),
+				initial,
+				accepts,
+			})
+			.collect();
+		let mut transitions: Vec<_> = transitions
+			.into_iter()
+			.map(|(from, input, to)| (from as u32, input.map(|input| input.to_string()), to as u32))
+			.collect();
+		transitions.sort();
+		Self {
+			states,
+			transitions,
+		}
+	}
+
+	/// Replaces multiple initial states by a new initial state with empty transitions to them.
+	fn single_initial(&mut self) {
+		let initial: Vec<_> = self
+			.states
+			.iter()
+			.filter(|state| state.initial)
+			.map(|state| state.id)
+			.collect();
+		if initial.len() > 1 {
+			let id = self.states.len() as u32;
+			for state in &mut self.states {
+				state.initial = false;
+			}
+			self.states.push(Node {
+				id,
+				name: Some("start".into()),
+				initial: true,
+				accepts: false,
+			});
+			self.transitions
+				.extend(initial.into_iter().map(|next| (id, None, next)));
+		}
+	}
+
+	/// Writes the machine as JFLAP file.
+	fn xml(&self) -> String {
+		let mut xml = String::from(
+			"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+			<structure>\n\t<type>fa</type>\n\t<automaton>\n",
+		);
+		for (i, state) in self.states.iter().enumerate() {
+			write!(xml, "\t\t<state id=\"{}\"", state.id).unwrap();
+			if let Some(name) = &state.name {
+				write!(xml, " name=\"{}\"", escape(name)).unwrap();
+			}
+			writeln!(
+				xml,
+				">\n\t\t\t<x>{}.0</x>\n\t\t\t<y>{}.0</y>",
+				100 + 150 * (i % 5),
+				100 + 150 * (i / 5)
+			)
+			.unwrap();
+			if state.initial {
+				xml.push_str("\t\t\t<initial/>\n");
+			}
+			if state.accepts {
+				xml.push_str("\t\t\t<final/>\n");
+			}
+			xml.push_str("\t\t</state>\n");
+		}
+		for (from, input, to) in &self.transitions {
+			write!(
+				xml,
+				"\t\t<transition>\n\t\t\t<from>{}</from>\n\t\t\t<to>{}</to>\n",
+				from, to
+			)
+			.unwrap();
+			match input {
+				Some(input) => writeln!(xml, "\t\t\t<read>{}</read>", escape(input)).unwrap(),
+				None => xml.push_str("\t\t\t<read/>\n"),
+			}
+			xml.push_str("\t\t</transition>\n");
+		}
+		xml.push_str("\t</automaton>\n</structure>\n");
+		xml
+	}
+}
+
+/// Reads a DFA from a JFLAP file.
+/// Returns a `JflapError::Nondeterministic` error if a state has an empty transition or multiple transitions for an input.
+pub fn read_dfa(xml: &str) -> Result<DFA<u32, String>, JflapError> {
+	let machine = Machine::parse(xml)?;
+	let mut dfa = DFA::new();
+	for state in &machine.states {
+		dfa.add_state(state.id, state.accepts);
+		if let Some(name) = &state.name {
+			dfa.set_name(&state.id, name.as_str())?;
+		}
+	}
+	let mut inputs = HashSet::new();
+	for (from, input, to) in machine.transitions {
+		match input {
+			Some(input) if inputs.insert((from, input.clone())) => {
+				dfa.add_transition((from, input, to))?
+			}
+			_ => return Err(JflapError::Nondeterministic(from)),
+		}
+	}
+	if let Some(state) = machine.states.iter().find(|state| state.initial) {
+		dfa.set_initial(state.id);
+		dfa.reset();
+	}
+	Ok(dfa)
+}
+
+/// Reads an NFA from a JFLAP file.
+pub fn read_nfa(xml: &str) -> Result<NFA<u32, String>, JflapError> {
+	let machine = Machine::parse(xml)?;
+	let mut nfa = NFA::new();
+	for state in &machine.states {
+		nfa.add_state(state.id, state.accepts);
+		if let Some(name) = &state.name {
+			nfa.set_name(&state.id, name.as_str())?;
+		}
+	}
+	for (from, input, to) in machine.transitions {
+		match input {
+			Some(input) => nfa.add_transition((from, input, to))?,
+			None => nfa.add_epsilon((from, to))?,
+		}
+	}
+	nfa.set_initial(
+		machine
+			.states
+			.iter()
+			.filter(|state| state.initial)
+			.map(|state| state.id)
+			.collect(),
+	);
+	nfa.reset();
+	Ok(nfa)
+}
+
+/// Writes a DFA as JFLAP file.
+pub fn write_dfa<S, I, T>(dfa: &DFA<S, I, T>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Display,
+{
+	let graph = dfa.graph();
+	let states = graph.labels.iter().enumerate().map(|(i, id)| {
+		(
+			dfa.display_name(id),
+			graph.initial.contains(&i),
+			graph.accepts[i],
+		)
+	});
+	let transitions = graph.edges.iter().enumerate().flat_map(|(from, edges)| {
+		edges
+			.iter()
+			.map(move |(input, to)| (from, Some(*input), *to))
+	});
+	Machine::new(states, transitions).xml()
+}
+
+/// Writes an NFA as JFLAP file, with epsilon transitions as empty transitions.
+///
+/// Since JFLAP allows a single initial state, multiple initial states are reached by empty transitions from a new initial state.
+pub fn write_nfa<S, I, T>(nfa: &NFA<S, I, T>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Display,
+{
+	let graph = nfa.graph();
+	let indices = graph.indices();
+	let states = graph.labels.iter().enumerate().map(|(i, id)| {
+		(
+			nfa.display_name(id),
+			graph.initial.contains(&i),
+			graph.accepts[i],
+		)
+	});
+	let transitions = graph.edges.iter().enumerate().flat_map(|(from, edges)| {
+		edges
+			.iter()
+			.map(move |(input, to)| (from, Some(*input), *to))
+	});
+	let epsilon = graph.labels.iter().enumerate().flat_map(|(from, id)| {
+		nfa.index_of(id)
+			.and_then(|index| nfa.state(index))
+			.into_iter()
+			.flat_map(|state| &state.epsilon)
+			.filter_map(|next| indices.get(&nfa.ids[*next]))
+			.map(move |to| (from, None, *to))
+	});
+	let mut machine = Machine::new(states, transitions.chain(epsilon));
+	machine.single_initial();
+	machine.xml()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// JFLAP file of a DFA accepting words over `a` & `b` ending with `b`, as saved by JFLAP.
+	const ENDS_WITH_B: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?><!--Created with JFLAP 7.1.--><structure>\n\
+		<type>fa</type>\n\
+		<automaton>\n\
+			<!--The list of states.-->\n\
+			<state id=\"0\" name=\"q0\">\n<x>82.0</x>\n<y>115.0</y>\n<initial/>\n</state>\n\
+			<state id=\"1\" name=\"q&lt;1&gt;\">\n<x>226.0</x>\n<y>118.0</y>\n<final/>\n</state>\n\
+			<!--The list of transitions.-->\n\
+			<transition>\n<from>0</from>\n<to>0</to>\n<read>a</read>\n</transition>\n\
+			<transition>\n<from>0</from>\n<to>1</to>\n<read>b</read>\n</transition>\n\
+			<transition>\n<from>1</from>\n<to>0</to>\n<read>a</read>\n</transition>\n\
+			<transition>\n<from>1</from>\n<to>1</to>\n<read>b</read>\n</transition>\n\
+		</automaton>\n\
+	</structure>";
+
+	#[test]
+	fn read() {
+		let mut dfa = read_dfa(ENDS_WITH_B).unwrap();
+		let word = |word: &str| -> Vec<String> { word.chars().map(String::from).collect() };
+		assert!(dfa.run(&word("aab")), "Word rejected");
+		assert!(!dfa.run(&word("aba")), "Word accepted");
+		assert_eq!(Some("q<1>"), dfa.name(&1), "Name not unescaped");
+
+		let nfa = ENDS_WITH_B.replace("<read>a</read>", "<read/>");
+		assert_eq!(
+			Err(JflapError::Nondeterministic(0)),
+			read_dfa(&nfa).map(|_| ()),
+			"Empty transition in DFA"
+		);
+		let mut nfa = read_nfa(&nfa).unwrap();
+		assert!(nfa.has_epsilons(), "Missing epsilon transitions");
+		assert!(nfa.run(&word("b")), "Word rejected");
+
+		assert_eq!(
+			Err(JflapError::UnsupportedType("pda".into())),
+			read_nfa(&ENDS_WITH_B.replace(">fa<", ">pda<")).map(|_| ()),
+			"Pushdown automaton read"
+		);
+		assert_eq!(
+			Err(JflapError::InexistentState(2)),
+			read_nfa(&ENDS_WITH_B.replace("<to>1</to>", "<to>2</to>")).map(|_| ()),
+			"Transition to inexistent state read"
+		);
+		assert!(
+			matches!(
+				read_nfa(&ENDS_WITH_B.replace("</automaton>", "")),
+				Err(JflapError::Syntax(_))
+			),
+			"Malformed XML read"
+		);
+	}
+
+	#[test]
+	fn roundtrip() {
+		let dfa = read_dfa(ENDS_WITH_B).unwrap();
+		let xml = write_dfa(&dfa);
+		assert!(
+			xml.contains("<state id=\"1\" name=\"q&lt;1&gt;\">"),
+			"Name not escaped"
+		);
+		let mut read = read_dfa(&xml).unwrap();
+		assert!(
+			read.run(&["b".to_string()]) && !read.run(&["a".to_string()]),
+			"Language changed"
+		);
+
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		nfa.set_initial(vec![0, 1].into_iter().collect());
+		let xml = write_nfa(&nfa);
+		assert!(
+			xml.contains("<state id=\"2\" name=\"start\">\n\t\t\t<x>400.0</x>\n\t\t\t<y>100.0</y>\n\t\t\t<initial/>"),
+			"Missing new initial state"
+		);
+		let mut read = read_nfa(&xml).unwrap();
+		assert!(
+			read.run(&Vec::<String>::new()) && read.run(&["a".to_string()]),
+			"Language changed"
+		);
+	}
+}
@@ -1,7 +1,116 @@
+mod alphabet;
+mod antichain;
 mod automaton;
+mod behavior;
+#[cfg(feature = "bitset")]
+mod bitset;
+mod boolean;
+mod buchi;
+pub mod canonical;
+mod check;
+mod class;
+pub mod codegen;
+mod complete;
+mod compose;
+mod concat;
+mod conformance;
+mod convert;
+mod cursor;
+mod dag;
+mod deadlock;
+mod dense;
+mod determinize;
 mod dfa;
+mod efsm;
+mod entropy;
+mod epsilon;
+mod equivalence;
+mod event;
+mod extended;
+mod fixed;
+mod fuse;
+mod graph;
+mod growth;
+mod handle;
+mod incremental;
+mod intern;
+mod intersection;
+#[cfg(feature = "jflap")]
+pub mod jflap;
+mod literal;
+mod machine;
+mod matrix;
+mod minimize;
+mod names;
 mod nfa;
+pub mod numeric;
+mod parallel;
+pub mod partition;
+mod path;
+mod persistent;
+mod priority;
+mod probabilistic;
+mod product;
+pub mod query;
+mod recording;
+pub mod regex;
+mod relabel;
+mod runner;
+mod runs;
+#[cfg(feature = "rand")]
+mod sample;
+mod sanitize;
+mod serialize;
+mod shift;
+pub mod shrink;
+mod simplify;
+mod stats;
+mod suggest;
+mod synthesis;
+mod tags;
+mod tick;
+mod trim;
+mod union_find;
+mod unused;
+pub mod visualize;
+mod weighted;
+mod workflow;
 
-pub use automaton::{Automaton, AutomatonError};
+pub use alphabet::{Alphabet, Exact, Ranges};
+pub use automaton::{Automaton, AutomatonError, StateId};
+pub use behavior::Behavior;
+#[cfg(feature = "bitset")]
+pub use bitset::{BitSet, BitSimulation};
+pub use boolean::BooleanCombination;
+pub use buchi::Buchi;
+pub use class::{CharClass, CharClasses, OverlapError, ParseClassError};
+pub use concat::Concat;
+pub use conformance::{ConformanceReport, ConformanceSummary, Divergence};
+pub use convert::{Conversion, ConversionReport, Naming};
+pub use cursor::{Checkpoint, Cursor, Location};
+pub use dense::DenseDFA;
 pub use dfa::DFA;
+pub use efsm::{Rule, EFSM};
+pub use equivalence::Equivalences;
+pub use event::InputKey;
+pub use extended::ExtendedDFA;
+pub use fixed::ConstDFA;
+pub use growth::Growth;
+pub use incremental::{IncrementalDFA, SortedIncrementalDFA, UnsortedError};
+pub use machine::{Failure, Machine, MachineError};
+pub use matrix::TransitionMatrices;
 pub use nfa::NFA;
+pub use persistent::PersistentDFA;
+pub use probabilistic::{ProbabilisticAutomaton, ProbabilityError};
+#[cfg(feature = "time")]
+pub use recording::Dwell;
+pub use recording::{Record, Recorder, Trace};
+pub use runner::MultiRunner;
+pub use sanitize::DanglingPolicy;
+pub use serialize::Lenient;
+pub use simplify::{Pass, PassReport, Simplification, SimplificationReport};
+pub use stats::Stats;
+pub use synthesis::Synthesis;
+pub use tags::TagPolicy;
+pub use weighted::{Count, Probability, Semiring, Tropical, WeightedAutomaton};
+pub use workflow::{Snapshot, WorkflowStore};
@@ -0,0 +1,173 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{collections::BTreeSet, fmt, hash::Hash};
+
+impl<'a, S, I> Graph<'a, S, I>
+where
+	I: Eq,
+{
+	/// Computes the longest sequence of inputs every accepted word starts with.
+	fn required_prefix(&self) -> Vec<&'a I> {
+		let useful = self.useful();
+		let current = self
+			.initial
+			.iter()
+			.cloned()
+			.filter(|i| useful[*i])
+			.collect();
+		walk(current, &self.edges, &useful, |set| {
+			set.iter().any(|i| self.accepts[*i])
+		})
+	}
+
+	/// Computes the longest sequence of inputs every accepted word ends with.
+	fn required_suffix(&self) -> Vec<&'a I> {
+		let useful = self.useful();
+		let current = (0..self.len())
+			.filter(|i| useful[*i] && self.accepts[*i])
+			.collect();
+		let mut suffix = walk(current, &self.reverse_edges(), &useful, |set| {
+			self.initial.iter().any(|i| set.contains(i))
+		});
+		suffix.reverse();
+		suffix
+	}
+}
+
+/// Follows the edges from a set of states as long as all useful edges share the same input.
+fn walk<'a, I, F>(
+	mut current: BTreeSet<usize>,
+	edges: &[Vec<(&'a I, usize)>],
+	useful: &[bool],
+	stop: F,
+) -> Vec<&'a I>
+where
+	I: Eq,
+	F: Fn(&BTreeSet<usize>) -> bool,
+{
+	let mut literal = Vec::new();
+	while !current.is_empty() && !stop(&current) {
+		let mut input = None;
+		let mut next = BTreeSet::new();
+		for (el, to) in current.iter().flat_map(|i| &edges[*i]) {
+			if useful[*to] {
+				match input {
+					Some(input) if input != *el => return literal,
+					_ => input = Some(*el),
+				}
+				next.insert(*to);
+			}
+		}
+		match input {
+			Some(input) => literal.push(input),
+			None => break,
+		}
+		current = next;
+	}
+	literal
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the longest sequence of inputs all accepted words start with.
+	///
+	/// This can be used to pre-filter inputs before running the automaton.
+	pub fn required_prefix(&self) -> Vec<I> {
+		self.graph()
+			.required_prefix()
+			.into_iter()
+			.cloned()
+			.collect()
+	}
+
+	/// Returns the longest sequence of inputs all accepted words end with.
+	pub fn required_suffix(&self) -> Vec<I> {
+		self.graph()
+			.required_suffix()
+			.into_iter()
+			.cloned()
+			.collect()
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the longest sequence of inputs all accepted words start with.
+	///
+	/// This can be used to pre-filter inputs before running the automaton.
+	pub fn required_prefix(&self) -> Vec<I> {
+		self.graph()
+			.required_prefix()
+			.into_iter()
+			.cloned()
+			.collect()
+	}
+
+	/// Returns the longest sequence of inputs all accepted words end with.
+	pub fn required_suffix(&self) -> Vec<I> {
+		self.graph()
+			.required_suffix()
+			.into_iter()
+			.cloned()
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{hashmap, hashset};
+
+	#[test]
+	fn dfa() {
+		// accepts "ab" followed by any number of "c" or "d" and a final "xy"
+		let dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1)),
+				1 => (false, hashmap!('b' => 2, 'z' => 5)),
+				2 => (false, hashmap!('c' => 2, 'd' => 2, 'x' => 3)),
+				3 => (false, hashmap!('y' => 4)),
+				4 => (true, hashmap!()),
+				5 => (false, hashmap!())
+			),
+		);
+		assert_eq!(vec!['a', 'b'], dfa.required_prefix(), "Incorrect prefix");
+		assert_eq!(vec!['x', 'y'], dfa.required_suffix(), "Incorrect suffix");
+	}
+
+	#[test]
+	fn nfa() {
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![1, 2])),
+				1 => (false, hashmap!('b' => hashset![3])),
+				2 => (false, hashmap!('b' => hashset![3], 'c' => hashset![2])),
+				3 => (true, hashmap!('b' => hashset![3]))
+			),
+		);
+		assert_eq!(vec!['a'], nfa.required_prefix(), "Incorrect prefix");
+		assert_eq!(vec!['b'], nfa.required_suffix(), "Incorrect suffix");
+	}
+
+	#[test]
+	fn empty() {
+		let dfa = DFA::<u8, char>::with_state(0, true);
+		assert!(dfa.required_prefix().is_empty(), "Incorrect prefix");
+		assert!(dfa.required_suffix().is_empty(), "Incorrect suffix");
+	}
+
+	#[test]
+	fn epsilon() {
+		let nfa = crate::regex::compile("abc*d").unwrap();
+		assert_eq!(vec!['a', 'b'], nfa.required_prefix(), "Incorrect prefix");
+		assert_eq!(vec!['d'], nfa.required_suffix(), "Incorrect suffix");
+	}
+}
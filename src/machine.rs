@@ -0,0 +1,908 @@
+use super::{AutomatonError, Behavior, InputKey, DFA};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	fmt,
+	hash::Hash,
+	panic::{self, AssertUnwindSafe},
+};
+
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+/// Default number of completion transitions taken in a row before reporting a loop.
+const COMPLETION_LIMIT: usize = 1000;
+
+/// Hook run when entering or exiting a state.
+type Hook<C> = Box<dyn Fn(&mut C)>;
+
+/// Condition deciding whether a completion transition fires.
+type Condition<C> = Box<dyn Fn(&C) -> bool>;
+
+/// Hook capturing the cause of a failure.
+type FailureHook<S, C> = Box<dyn Fn(&mut C, &Failure<S>)>;
+
+/// Cause of a failure while handling an event, with the state it occurred in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure<S> {
+	/// There was no transition for the event.
+	MissingTransition(S),
+
+	/// The guard of the transition rejected it.
+	GuardRejected(S),
+
+	/// The action of the transition panicked.
+	ActionPanicked(S),
+}
+
+/// Enum representing an error while running a machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MachineError<S> {
+	/// Completion transitions exceeded the completion limit, stopping in the state with its display name.
+	CompletionLoop(S, String),
+}
+
+impl<S> fmt::Display for MachineError<S>
+where
+	S: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::CompletionLoop(_, name) => write!(f, "Completion loop at State \"{}\"", name),
+		}
+	}
+}
+
+/// Runtime for a DFA with transition behaviors & state entry/exit hooks, following UML statechart semantics.
+///
+/// Taking an external transition runs the exit hook of the source state, then the action of the transition, then the entry hook of the target state.
+/// This also applies to external self-transitions, which leave & re-enter their state.
+/// Internal self-transitions only run their action.
+///
+/// Events can also be posted to an internal queue & processed with run-to-completion semantics.
+/// Events deferred in the current state are kept until a transition is taken.
+///
+/// Completion transitions fire without an event as soon as their state is entered & their guard holds.
+/// Taking more completion transitions in a row than the completion limit counts as a loop.
+/// Choice states always leave through one of their completion transitions, falling back to an else branch.
+///
+/// Final states mark the machine as done, independently of whether the automaton accepts.
+/// A done machine ignores further events.
+/// Final substates of composite states instead raise a completion event for their parent.
+///
+/// Failures can move the machine into a designated error state instead of an invalid state.
+///
+/// With the `metrics` feature, the machine emits the following metrics labelled with state IDs:
+/// - `finite_transitions_total`: counter of transitions taken per pair of states.
+/// - `finite_state_duration_seconds`: histogram of the time spent in a state.
+/// - `finite_invalid_events_total`: counter of events without a transition per state.
+pub struct Machine<S, E, C>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+{
+	automaton: DFA<S, E::Key, Behavior<C, E>>,
+	entry: HashMap<S, Hook<C>>,
+	exit: HashMap<S, Hook<C>>,
+	completions: HashMap<S, Vec<(S, Condition<C>)>>,
+	completion_limit: usize,
+	choices: HashMap<S, S>,
+	deferrals: HashMap<S, HashSet<E::Key>>,
+	finals: HashSet<S>,
+	parents: HashMap<S, S>,
+	done: Option<Hook<C>>,
+	error: Option<S>,
+	failure: Option<FailureHook<S, C>>,
+	queue: VecDeque<E>,
+	deferred: VecDeque<E>,
+	#[cfg(feature = "metrics")]
+	entered: Option<Instant>,
+}
+
+impl<S, E, C> Machine<S, E, C>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+{
+	/// Creates a new machine running an automaton.
+	pub fn new(automaton: DFA<S, E::Key, Behavior<C, E>>) -> Self {
+		Self {
+			automaton,
+			entry: HashMap::new(),
+			exit: HashMap::new(),
+			completions: HashMap::new(),
+			completion_limit: COMPLETION_LIMIT,
+			choices: HashMap::new(),
+			deferrals: HashMap::new(),
+			finals: HashSet::new(),
+			parents: HashMap::new(),
+			done: None,
+			error: None,
+			failure: None,
+			queue: VecDeque::new(),
+			deferred: VecDeque::new(),
+			#[cfg(feature = "metrics")]
+			entered: None,
+		}
+	}
+
+	/// Returns the automaton run by the machine.
+	pub fn automaton(&self) -> &DFA<S, E::Key, Behavior<C, E>> {
+		&self.automaton
+	}
+
+	/// Returns the current state.
+	/// Returns `None` if the machine is in an invalid state.
+	pub fn current(&self) -> Option<&S> {
+		self.automaton
+			.current
+			.map(|index| &self.automaton.ids[index])
+	}
+
+	/// Updates the current state without running any hooks.
+	/// If the state is inexistent, the machine goes into an invalid state.
+	///
+	/// With the `metrics` feature, no duration is recorded when leaving the state, as it is unknown when it was entered.
+	pub fn set_current(&mut self, id: S) {
+		self.automaton.current = self.automaton.index_of(&id);
+		#[cfg(feature = "metrics")]
+		{
+			self.entered = None;
+		}
+	}
+
+	/// Updates the current state like `set_current` together with the time it was entered.
+	#[cfg(feature = "metrics")]
+	pub(crate) fn resume(&mut self, id: S, entered: Option<Instant>) {
+		self.automaton.current = self.automaton.index_of(&id);
+		self.entered = entered;
+	}
+
+	/// Returns the time the current state was entered, if it is known.
+	#[cfg(feature = "metrics")]
+	pub(crate) fn entered(&self) -> Option<Instant> {
+		self.entered
+	}
+
+	/// Sets the hook run when entering a state, replacing a previous one.
+	pub fn on_entry<F>(&mut self, id: S, hook: F)
+	where
+		F: Fn(&mut C) + 'static,
+	{
+		self.entry.insert(id, Box::new(hook));
+	}
+
+	/// Sets the hook run when exiting a state, replacing a previous one.
+	pub fn on_exit<F>(&mut self, id: S, hook: F)
+	where
+		F: Fn(&mut C) + 'static,
+	{
+		self.exit.insert(id, Box::new(hook));
+	}
+
+	/// Adds a completion transition firing without an event when its guard holds after entering the source state.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	///
+	/// Completion transitions of a state are tried in the order they were added.
+	pub fn add_completion<F>(
+		&mut self,
+		transition: (S, S),
+		guard: F,
+	) -> Result<(), AutomatonError<S>>
+	where
+		F: Fn(&C) -> bool + 'static,
+	{
+		let (prev, next) = transition;
+		for id in [&prev, &next] {
+			if self.automaton.index_of(id).is_none() {
+				return Err(AutomatonError::InexistentState(id.clone(), Vec::new()));
+			}
+		}
+		self.completions
+			.entry(prev)
+			.or_default()
+			.push((next, Box::new(guard)));
+		Ok(())
+	}
+
+	/// Sets the number of completion transitions taken in a row before reporting a loop, defaults to 1000.
+	///
+	/// Guards may depend on context changed by hooks, so revisiting a state does not imply a loop.
+	pub fn set_completion_limit(&mut self, limit: usize) {
+		self.completion_limit = limit;
+	}
+
+	/// Turns a state into a choice with an else branch taken if none of its completion transitions fire.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	///
+	/// The branches of the choice are its completion transitions, evaluated when the choice is entered.
+	pub fn add_choice(&mut self, id: S, otherwise: S) -> Result<(), AutomatonError<S>> {
+		for id in [&id, &otherwise] {
+			if self.automaton.index_of(id).is_none() {
+				return Err(AutomatonError::InexistentState(id.clone(), Vec::new()));
+			}
+		}
+		self.choices.insert(id, otherwise);
+		Ok(())
+	}
+
+	/// Marks a state as final.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn set_final(&mut self, id: S) -> Result<(), AutomatonError<S>> {
+		if self.automaton.index_of(&id).is_none() {
+			return Err(AutomatonError::InexistentState(id, Vec::new()));
+		}
+		self.finals.insert(id);
+		Ok(())
+	}
+
+	/// Checks whether a state is final.
+	pub fn is_final(&self, id: &S) -> bool {
+		self.finals.contains(id)
+	}
+
+	/// Makes a state a substate of a composite state.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	///
+	/// Reaching a final substate raises a completion event for the composite state, firing its completion transitions.
+	/// If none fire & the composite state is final itself, the event bubbles up to its own parent.
+	/// Composite states only take part in completion, substates do not inherit their hooks or transitions.
+	pub fn set_parent(&mut self, id: S, parent: S) -> Result<(), AutomatonError<S>> {
+		for id in [&id, &parent] {
+			if self.automaton.index_of(id).is_none() {
+				return Err(AutomatonError::InexistentState(id.clone(), Vec::new()));
+			}
+		}
+		self.parents.insert(id, parent);
+		Ok(())
+	}
+
+	/// Returns the composite state containing a state.
+	pub fn parent(&self, id: &S) -> Option<&S> {
+		self.parents.get(id)
+	}
+
+	/// Checks whether the machine reached a final state outside of any composite state.
+	pub fn is_done(&self) -> bool {
+		self.current()
+			.is_some_and(|id| self.is_final(id) && self.parent(id).is_none())
+	}
+
+	/// Sets the hook run when the machine is done, replacing a previous one.
+	///
+	/// This acts as completion event for code embedding the machine.
+	pub fn on_done<F>(&mut self, hook: F)
+	where
+		F: Fn(&mut C) + 'static,
+	{
+		self.done = Some(Box::new(hook));
+	}
+
+	/// Designates the error state the machine moves into on failures.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	///
+	/// With an error state, missing & rejected transitions lead into it and panics of actions are caught.
+	/// Moving into the error state leaves the current state like an external transition.
+	pub fn set_error_state(&mut self, id: S) -> Result<(), AutomatonError<S>> {
+		if self.automaton.index_of(&id).is_none() {
+			return Err(AutomatonError::InexistentState(id, Vec::new()));
+		}
+		self.error = Some(id);
+		Ok(())
+	}
+
+	/// Sets the hook capturing the cause of failures, replacing a previous one.
+	///
+	/// The hook runs on every failure, even without an error state.
+	pub fn on_failure<F>(&mut self, hook: F)
+	where
+		F: Fn(&mut C, &Failure<S>) + 'static,
+	{
+		self.failure = Some(Box::new(hook));
+	}
+
+	/// Marks events with a key as deferred while in a state.
+	pub fn defer(&mut self, id: S, key: E::Key) {
+		self.deferrals.entry(id).or_default().insert(key);
+	}
+
+	/// Posts an event to the queue of the machine.
+	pub fn post(&mut self, event: E) {
+		self.queue.push_back(event);
+	}
+
+	/// Returns the number of queued & deferred events.
+	pub fn pending(&self) -> usize {
+		self.queue.len() + self.deferred.len()
+	}
+
+	/// Processes the queued events in order, handling each to completion before the next.
+	/// Returns the number of transitions taken.
+	///
+	/// Events deferred in the current state are set aside.
+	/// Taking a transition puts them back in front of the queue, in their original order.
+	///
+	/// Returns a `MachineError::CompletionLoop` error if completion transitions loop.
+	pub fn process(&mut self, ctx: &mut C) -> Result<usize, MachineError<S>> {
+		let mut taken = 0;
+		while let Some(event) = self.queue.pop_front() {
+			if self.is_deferred(&event) {
+				self.deferred.push_back(event);
+			} else if self.handle(&event, ctx)? {
+				taken += 1;
+				while let Some(event) = self.deferred.pop_back() {
+					self.queue.push_front(event);
+				}
+			}
+		}
+		Ok(taken)
+	}
+
+	/// Checks whether an event is deferred in the current state.
+	fn is_deferred(&self, event: &E) -> bool {
+		self.current()
+			.and_then(|id| self.deferrals.get(id))
+			.is_some_and(|keys| keys.contains(&event.key()))
+	}
+
+	/// Resets the machine to the initial state of the automaton & runs its entry hook, followed by completion transitions.
+	/// Returns a `MachineError::CompletionLoop` error if completion transitions loop.
+	pub fn start(&mut self, ctx: &mut C) -> Result<(), MachineError<S>> {
+		self.automaton.current = self.automaton.initial;
+		if let Some(initial) = self.automaton.initial {
+			Self::run(&self.entry, &self.automaton.ids[initial], ctx);
+		}
+		self.record_entry();
+		self.complete(ctx)
+	}
+
+	/// Handles an event, taking the transition for its key if the guard allows it.
+	/// Returns whether a transition was taken.
+	///
+	/// If the machine is done, the current state is kept without running any hooks.
+	/// Without an error state, the same applies if the guard rejects the transition
+	/// & the machine goes into an invalid state if there is no transition for the event.
+	///
+	/// Completion transitions are taken after entering the target state.
+	/// Returns a `MachineError::CompletionLoop` error if they loop.
+	pub fn handle(&mut self, event: &E, ctx: &mut C) -> Result<bool, MachineError<S>> {
+		let current = match self.automaton.current {
+			Some(current) if !self.is_done() => current,
+			_ => return Ok(false),
+		};
+		let (next, behavior) = match self.automaton.transition(current, &event.key()) {
+			Some((next, behavior)) => (*next, behavior),
+			None => {
+				self.record_invalid(current);
+				let id = self.automaton.ids[current].clone();
+				self.fail(current, Failure::MissingTransition(id), false, ctx)?;
+				return Ok(false);
+			}
+		};
+		if !behavior.allows(ctx, event) {
+			let id = self.automaton.ids[current].clone();
+			self.fail(current, Failure::GuardRejected(id), false, ctx)?;
+			return Ok(false);
+		}
+		let external = next != current || !behavior.is_internal();
+		if external {
+			Self::run(&self.exit, &self.automaton.ids[current], ctx);
+		}
+		if self.error.is_some() {
+			if panic::catch_unwind(AssertUnwindSafe(|| behavior.perform(ctx, event))).is_err() {
+				let id = self.automaton.ids[current].clone();
+				self.fail(current, Failure::ActionPanicked(id), external, ctx)?;
+				return Ok(false);
+			}
+		} else {
+			behavior.perform(ctx, event);
+		}
+		if external {
+			Self::run(&self.entry, &self.automaton.ids[next], ctx);
+		}
+		self.record_transition(current, next, external);
+		self.automaton.current = Some(next);
+		self.complete(ctx)?;
+		Ok(true)
+	}
+
+	/// Handles a failure in a state, moving into the error state if there is one.
+	///
+	/// Without an error state, missing transitions lead to an invalid state & other failures keep the current state.
+	/// The exit hook of the current state is skipped if it already ran before the failure.
+	fn fail(
+		&mut self,
+		current: usize,
+		failure: Failure<S>,
+		exited: bool,
+		ctx: &mut C,
+	) -> Result<(), MachineError<S>> {
+		if let Some(hook) = &self.failure {
+			hook(ctx, &failure);
+		}
+		match self
+			.error
+			.as_ref()
+			.and_then(|id| self.automaton.index_of(id))
+		{
+			Some(error) => {
+				if !exited {
+					Self::run(&self.exit, &self.automaton.ids[current], ctx);
+				}
+				Self::run(&self.entry, &self.automaton.ids[error], ctx);
+				self.record_transition(current, error, true);
+				self.automaton.current = Some(error);
+				self.complete(ctx)
+			}
+			None => {
+				if let Failure::MissingTransition(_) = failure {
+					self.automaton.current = None;
+				}
+				Ok(())
+			}
+		}
+	}
+
+	/// Takes completion transitions until no guard holds & runs the done hook if a final state was reached.
+	/// Returns a `MachineError::CompletionLoop` error if more transitions than the completion limit would be taken.
+	fn complete(&mut self, ctx: &mut C) -> Result<(), MachineError<S>> {
+		let mut taken = 0;
+		while let Some(current) = self.automaton.current {
+			let next = match self.completion(current, ctx) {
+				Some(next) => next,
+				None => break,
+			};
+			if taken == self.completion_limit {
+				let id = &self.automaton.ids[current];
+				return Err(MachineError::CompletionLoop(
+					id.clone(),
+					self.automaton.display_name(id),
+				));
+			}
+			taken += 1;
+			Self::run(&self.exit, &self.automaton.ids[current], ctx);
+			Self::run(&self.entry, &self.automaton.ids[next], ctx);
+			self.record_transition(current, next, true);
+			self.automaton.current = Some(next);
+		}
+		if let (true, Some(done)) = (self.is_done(), &self.done) {
+			done(ctx);
+		}
+		Ok(())
+	}
+
+	/// Returns the target of the completion transition firing in a state.
+	///
+	/// Final states pass the completion event up to their composite states until a transition fires.
+	fn completion(&self, current: usize, ctx: &C) -> Option<usize> {
+		let mut id = &self.automaton.ids[current];
+		let mut next = self.fired(id, ctx).or_else(|| self.choices.get(id));
+		// bounded by the number of parents in case they form a cycle
+		for _ in 0..self.parents.len() {
+			if next.is_some() || !self.is_final(id) {
+				break;
+			}
+			id = self.parent(id)?;
+			next = self.fired(id, ctx);
+		}
+		next.and_then(|next| self.automaton.index_of(next))
+	}
+
+	/// Returns the target of the first completion transition of a state with a holding guard.
+	fn fired(&self, id: &S, ctx: &C) -> Option<&S> {
+		self.completions
+			.get(id)
+			.and_then(|completions| completions.iter().find(|(_, guard)| guard(ctx)))
+			.map(|(next, _)| next)
+	}
+
+	/// Records entering the current state.
+	#[cfg(feature = "metrics")]
+	fn record_entry(&mut self) {
+		self.entered = Some(Instant::now());
+	}
+
+	/// Records a transition between two states, leaving the previous one if external.
+	#[cfg(feature = "metrics")]
+	fn record_transition(&mut self, prev: usize, next: usize, external: bool) {
+		let prev = format!("{:?}", self.automaton.ids[prev]);
+		if external {
+			if let Some(entered) = self.entered.replace(Instant::now()) {
+				metrics::histogram!("finite_state_duration_seconds", "state" => prev.clone())
+					.record(entered.elapsed());
+			}
+		}
+		let next = format!("{:?}", self.automaton.ids[next]);
+		metrics::counter!("finite_transitions_total", "from" => prev, "to" => next).increment(1);
+	}
+
+	/// Records an event without a transition in a state.
+	#[cfg(feature = "metrics")]
+	fn record_invalid(&self, state: usize) {
+		let state = format!("{:?}", self.automaton.ids[state]);
+		metrics::counter!("finite_invalid_events_total", "state" => state).increment(1);
+	}
+
+	#[cfg(not(feature = "metrics"))]
+	fn record_entry(&mut self) {}
+
+	#[cfg(not(feature = "metrics"))]
+	fn record_transition(&mut self, _: usize, _: usize, _: bool) {}
+
+	#[cfg(not(feature = "metrics"))]
+	fn record_invalid(&self, _: usize) {}
+
+	/// Runs the hook for a state, if there is one.
+	fn run(hooks: &HashMap<S, Hook<C>>, id: &S, ctx: &mut C) {
+		if let Some(hook) = hooks.get(id) {
+			hook(ctx);
+		}
+	}
+}
+
+impl<S, E, C> fmt::Debug for Machine<S, E, C>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+	E::Key: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Machine")
+			.field("automaton", &self.automaton)
+			.field("entry", &self.entry.keys().collect::<Vec<_>>())
+			.field("exit", &self.exit.keys().collect::<Vec<_>>())
+			.field("deferrals", &self.deferrals)
+			.field("choices", &self.choices)
+			.field("finals", &self.finals)
+			.field("parents", &self.parents)
+			.field("error", &self.error)
+			.field("pending", &self.pending())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	/// Returns a hook logging a text.
+	fn log(text: &'static str) -> impl Fn(&mut Vec<&'static str>) {
+		move |log| log.push(text)
+	}
+
+	/// Returns a behavior logging a text in its action.
+	fn logged(text: &'static str) -> Behavior<Vec<&'static str>, char> {
+		Behavior::new().with_action(move |log: &mut Vec<_>, _: &char| log.push(text))
+	}
+
+	/// Machine logging its hooks & actions.
+	fn logging() -> Machine<&'static str, char, Vec<&'static str>> {
+		let mut dfa = DFA::with_state("idle", false);
+		dfa.add_state("busy", true);
+		dfa.add_transition_with_payload(("idle", 'a', "busy"), logged("action"))
+			.unwrap();
+		dfa.add_transition_with_payload(("busy", 's', "busy"), logged("self"))
+			.unwrap();
+		dfa.add_transition_with_payload(("busy", 'i', "busy"), logged("internal").internal())
+			.unwrap();
+		let mut machine = Machine::new(dfa);
+		machine.on_entry("idle", log("enter idle"));
+		machine.on_exit("idle", log("exit idle"));
+		machine.on_entry("busy", log("enter busy"));
+		machine.on_exit("busy", log("exit busy"));
+		machine
+	}
+
+	#[test]
+	fn order() {
+		let mut machine = logging();
+		let mut log = Vec::new();
+		machine.start(&mut log).unwrap();
+		assert!(
+			machine.handle(&'a', &mut log).unwrap(),
+			"Transition not taken"
+		);
+		assert_eq!(
+			vec!["enter idle", "exit idle", "action", "enter busy"],
+			log,
+			"Incorrect order of hooks & action"
+		);
+	}
+
+	#[test]
+	fn self_transitions() {
+		let mut machine = logging();
+		let mut log = Vec::new();
+		machine.start(&mut log).unwrap();
+		machine.handle(&'a', &mut log).unwrap();
+		log.clear();
+		machine.handle(&'s', &mut log).unwrap();
+		assert_eq!(
+			vec!["exit busy", "self", "enter busy"],
+			log,
+			"External self-transition did not re-enter"
+		);
+		log.clear();
+		machine.handle(&'i', &mut log).unwrap();
+		assert_eq!(vec!["internal"], log, "Internal self-transition ran hooks");
+		assert_eq!(Some(&"busy"), machine.current(), "Incorrect state");
+	}
+
+	#[test]
+	fn deferred() {
+		let mut dfa = DFA::<&str, char, Behavior<Vec<char>, char>>::with_state("loading", false);
+		dfa.add_state("ready", true);
+		let record =
+			|| Behavior::new().with_action(|log: &mut Vec<char>, event: &char| log.push(*event));
+		dfa.add_transition_with_payload(("loading", 'l', "ready"), record())
+			.unwrap();
+		dfa.add_transition_with_payload(("ready", 'x', "ready"), record())
+			.unwrap();
+		dfa.add_transition_with_payload(("ready", 'y', "ready"), record())
+			.unwrap();
+		let mut machine = Machine::new(dfa);
+		machine.defer("loading", 'x');
+		machine.defer("loading", 'y');
+
+		let mut log = Vec::new();
+		machine.post('x');
+		machine.post('y');
+		assert_eq!(
+			0,
+			machine.process(&mut log).unwrap(),
+			"Deferred event handled"
+		);
+		assert_eq!(2, machine.pending(), "Deferred events dropped");
+		assert_eq!(Some(&"loading"), machine.current(), "Incorrect state");
+
+		machine.post('l');
+		assert_eq!(
+			3,
+			machine.process(&mut log).unwrap(),
+			"Deferred events not recalled"
+		);
+		assert_eq!(vec!['l', 'x', 'y'], log, "Incorrect processing order");
+		assert_eq!(0, machine.pending(), "Events left in queue");
+	}
+
+	#[test]
+	fn completion() {
+		let mut dfa = DFA::<&str, char, Behavior<u32, char>>::with_state("input", false);
+		dfa.add_state("decide", false);
+		dfa.add_state("small", true);
+		dfa.add_state("large", true);
+		dfa.add_transition_with_payload(
+			("input", 'n', "decide"),
+			Behavior::new().with_action(|value: &mut u32, _: &char| *value += 5),
+		)
+		.unwrap();
+		let mut machine = Machine::new(dfa);
+		machine
+			.add_completion(("decide", "large"), |value| *value > 10)
+			.unwrap();
+		machine
+			.add_completion(("decide", "small"), |_| true)
+			.unwrap();
+
+		let mut value = 0;
+		machine.handle(&'n', &mut value).unwrap();
+		assert_eq!(Some(&"small"), machine.current(), "Incorrect decision");
+		value = 10;
+		machine.start(&mut value).unwrap();
+		machine.handle(&'n', &mut value).unwrap();
+		assert_eq!(Some(&"large"), machine.current(), "Incorrect decision");
+	}
+
+	#[test]
+	fn completion_loop() {
+		let mut dfa = DFA::<u8, char, Behavior<(), char>>::with_state(0, false);
+		dfa.add_state(1, false);
+		dfa.set_name(&0, "Start").unwrap();
+		let mut machine = Machine::new(dfa);
+		machine.add_completion((0, 1), |_| true).unwrap();
+		machine.add_completion((1, 0), |_| true).unwrap();
+		assert!(
+			machine.add_completion((1, 2), |_| true).is_err(),
+			"Missing error on inexistent state"
+		);
+		let error = machine.start(&mut ()).unwrap_err();
+		assert_eq!(
+			MachineError::CompletionLoop(0, "Start".into()),
+			error,
+			"Missing error on completion loop"
+		);
+		assert_eq!(
+			"Completion loop at State \"Start\"",
+			error.to_string(),
+			"Display name not used"
+		);
+	}
+
+	#[test]
+	fn completion_chain() {
+		let mut dfa = DFA::<u8, char, Behavior<u32, char>>::with_state(0, false);
+		dfa.add_state(1, false);
+		let mut machine = Machine::new(dfa);
+		machine.on_entry(0, |count| *count += 1);
+		machine.add_completion((0, 1), |count| *count < 3).unwrap();
+		machine.add_completion((1, 0), |_| true).unwrap();
+
+		let mut count = 0;
+		machine.start(&mut count).unwrap();
+		assert_eq!(3, count, "Guarded chain stopped early");
+		assert_eq!(Some(&0), machine.current(), "Incorrect state");
+
+		machine.set_completion_limit(2);
+		assert!(
+			matches!(
+				machine.start(&mut 0),
+				Err(MachineError::CompletionLoop(0, _))
+			),
+			"Missing error on exceeded completion limit"
+		);
+	}
+
+	#[test]
+	fn done() {
+		let mut dfa = DFA::<&str, char, Behavior<u32, char>>::with_state("open", true);
+		dfa.add_state("closed", false);
+		dfa.add_transition(("open", 'c', "closed")).unwrap();
+		dfa.add_transition(("closed", 'o', "open")).unwrap();
+		let mut machine = Machine::new(dfa);
+		machine.set_final("closed").unwrap();
+		machine.on_done(|count| *count += 1);
+
+		let mut count = 0;
+		assert!(!machine.is_done(), "Accepting state treated as final");
+		machine.handle(&'c', &mut count).unwrap();
+		assert!(machine.is_done(), "Final state not detected");
+		assert!(
+			!machine.automaton().accepts(),
+			"Final state treated as accepting"
+		);
+		assert_eq!(1, count, "Done hook not run");
+		assert!(
+			!machine.handle(&'o', &mut count).unwrap(),
+			"Transition taken after termination"
+		);
+	}
+
+	#[test]
+	fn bubbling() {
+		let mut dfa = DFA::<&str, char, Behavior<u32, char>>::with_state("idle", false);
+		for id in ["fetch", "parse", "job", "batch", "report"] {
+			dfa.add_state(id, false);
+		}
+		dfa.add_transition(("idle", 's', "fetch")).unwrap();
+		dfa.add_transition(("fetch", 'f', "parse")).unwrap();
+		let mut machine = Machine::new(dfa);
+		machine.set_parent("fetch", "job").unwrap();
+		machine.set_parent("parse", "job").unwrap();
+		machine.set_parent("job", "batch").unwrap();
+		machine.set_final("parse").unwrap();
+		machine.set_final("report").unwrap();
+		machine.on_done(|count| *count += 1);
+		assert!(
+			machine.set_parent("idle", "none").is_err(),
+			"Missing error on inexistent state"
+		);
+
+		let mut count = 0;
+		machine.handle(&'s', &mut count).unwrap();
+		machine.handle(&'f', &mut count).unwrap();
+		assert_eq!(Some(&"parse"), machine.current(), "Completion not final");
+		assert!(!machine.is_done(), "Final substate treated as done");
+
+		machine
+			.add_completion(("batch", "report"), |_| true)
+			.unwrap();
+		machine.set_current("fetch");
+		machine.handle(&'f', &mut count).unwrap();
+		assert_eq!(
+			Some(&"parse"),
+			machine.current(),
+			"Completion bubbled past non-final composite"
+		);
+
+		machine.set_final("job").unwrap();
+		machine.set_current("fetch");
+		machine.handle(&'f', &mut count).unwrap();
+		assert_eq!(Some(&"report"), machine.current(), "Completion not bubbled");
+		assert!(machine.is_done(), "Final state not detected");
+		assert_eq!(1, count, "Done hook not run exactly once");
+	}
+
+	#[test]
+	fn choice() {
+		let mut dfa = DFA::<&str, char, Behavior<i32, char>>::with_state("idle", false);
+		for id in ["sign", "negative", "positive", "zero"] {
+			dfa.add_state(id, true);
+		}
+		dfa.add_transition(("idle", 's', "sign")).unwrap();
+		let mut machine = Machine::new(dfa);
+		machine
+			.add_completion(("sign", "negative"), |value| *value < 0)
+			.unwrap();
+		machine
+			.add_completion(("sign", "positive"), |value| *value > 0)
+			.unwrap();
+		machine.add_choice("sign", "zero").unwrap();
+
+		for (mut value, expected) in [(-3, "negative"), (0, "zero"), (7, "positive")] {
+			machine.start(&mut value).unwrap();
+			machine.handle(&'s', &mut value).unwrap();
+			assert_eq!(Some(&expected), machine.current(), "Incorrect branch");
+		}
+	}
+
+	#[test]
+	fn failure() {
+		let mut dfa =
+			DFA::<&str, char, Behavior<Vec<Failure<&str>>, char>>::with_state("idle", false);
+		dfa.add_state("busy", true);
+		dfa.add_state("error", false);
+		dfa.add_transition_with_payload(
+			("idle", 'g', "busy"),
+			Behavior::new().with_guard(|_, _| false),
+		)
+		.unwrap();
+		dfa.add_transition_with_payload(
+			("idle", 'p', "busy"),
+			Behavior::new().with_action(|_, _| panic!("action failed")),
+		)
+		.unwrap();
+		dfa.add_transition(("error", 'r', "idle")).unwrap();
+		let mut machine = Machine::new(dfa);
+		machine.on_failure(|causes, failure| causes.push(failure.clone()));
+
+		let mut causes = Vec::new();
+		machine.handle(&'g', &mut causes).unwrap();
+		assert_eq!(Some(&"idle"), machine.current(), "Rejection changed state");
+		machine.handle(&'x', &mut causes).unwrap();
+		assert_eq!(None, machine.current(), "Missing transition taken");
+
+		machine.set_error_state("error").unwrap();
+		for input in ['g', 'x', 'p'] {
+			machine.set_current("idle");
+			machine.handle(&input, &mut causes).unwrap();
+			assert_eq!(Some(&"error"), machine.current(), "Error state not entered");
+		}
+		assert_eq!(
+			vec![
+				Failure::GuardRejected("idle"),
+				Failure::MissingTransition("idle"),
+				Failure::GuardRejected("idle"),
+				Failure::MissingTransition("idle"),
+				Failure::ActionPanicked("idle"),
+			],
+			causes,
+			"Incorrect failure causes"
+		);
+	}
+
+	#[test]
+	fn failure_hooks() {
+		let mut dfa = DFA::with_state("idle", false);
+		dfa.add_state("error", false);
+		let panicking =
+			|| Behavior::new().with_action(|_: &mut Vec<_>, _: &char| panic!("action failed"));
+		dfa.add_transition_with_payload(("idle", 'e', "idle"), panicking())
+			.unwrap();
+		dfa.add_transition_with_payload(("idle", 'i', "idle"), panicking().internal())
+			.unwrap();
+		let mut machine = Machine::new(dfa);
+		machine.on_exit("idle", log("exit idle"));
+		machine.on_entry("error", log("enter error"));
+		machine.set_error_state("error").unwrap();
+		for input in ['e', 'i'] {
+			let mut log = Vec::new();
+			machine.set_current("idle");
+			machine.handle(&input, &mut log).unwrap();
+			assert_eq!(
+				vec!["exit idle", "enter error"],
+				log,
+				"Exit hook not run exactly once"
+			);
+		}
+	}
+}
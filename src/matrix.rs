@@ -0,0 +1,288 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Adjacency matrices of the transitions of an automaton, one per input.
+///
+/// States are numbered by their position in `states`, which follows the order the states were declared in.
+/// An entry at row `i` & column `j` is set if there is a transition from state `i` to state `j`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionMatrices<S, I>
+where
+	I: Eq + Hash,
+{
+	/// States in the order of the rows & columns.
+	pub states: Vec<S>,
+
+	/// Indices of the initial states.
+	pub initial: Vec<usize>,
+
+	/// Whether each state is accepting.
+	pub accepts: Vec<bool>,
+
+	/// Adjacency matrix for each input.
+	pub matrices: HashMap<I, Vec<Vec<bool>>>,
+}
+
+impl<S, I> TransitionMatrices<S, I>
+where
+	I: Eq + Hash,
+{
+	/// Returns the transfer matrix, counting the inputs leading from one state to another.
+	pub fn transfer(&self) -> Vec<Vec<u64>> {
+		let len = self.states.len();
+		let mut transfer = vec![vec![0; len]; len];
+		for matrix in self.matrices.values() {
+			for (row, entries) in transfer.iter_mut().zip(matrix) {
+				for (count, entry) in row.iter_mut().zip(entries) {
+					*count += *entry as u64;
+				}
+			}
+		}
+		transfer
+	}
+
+	/// Converts the matrices into `ndarray` arrays.
+	#[cfg(feature = "ndarray")]
+	pub fn to_ndarray(&self) -> HashMap<&I, ndarray::Array2<bool>> {
+		let len = self.states.len();
+		self.matrices
+			.iter()
+			.map(|(input, matrix)| {
+				(
+					input,
+					ndarray::Array2::from_shape_fn((len, len), |(i, j)| matrix[i][j]),
+				)
+			})
+			.collect()
+	}
+}
+
+/// Multiplies two square matrices.
+/// Returns `None` if an entry overflows.
+fn multiply(a: &[Vec<u128>], b: &[Vec<u128>]) -> Option<Vec<Vec<u128>>> {
+	a.iter()
+		.map(|row| {
+			(0..b.len())
+				.map(|j| {
+					row.iter().zip(b).try_fold(0u128, |sum, (x, column)| {
+						sum.checked_add(x.checked_mul(column[j])?)
+					})
+				})
+				.collect()
+		})
+		.collect()
+}
+
+/// Raises a square matrix to a power using repeated squaring.
+/// Returns `None` if an entry of the result or of a power of the matrix needed for it overflows.
+fn power(mut matrix: Vec<Vec<u128>>, mut exponent: usize) -> Option<Vec<Vec<u128>>> {
+	let len = matrix.len();
+	let mut result: Vec<Vec<u128>> = (0..len)
+		.map(|i| (0..len).map(|j| (i == j) as u128).collect())
+		.collect();
+	while exponent > 0 {
+		if exponent % 2 == 1 {
+			result = multiply(&result, &matrix)?;
+		}
+		exponent /= 2;
+		if exponent > 0 {
+			matrix = multiply(&matrix, &matrix)?;
+		}
+	}
+	Some(result)
+}
+
+impl<'a, S, I> Graph<'a, S, I>
+where
+	S: Clone,
+	I: Clone + Eq + Hash,
+{
+	/// Builds the adjacency matrices of the graph.
+	fn matrices(&self) -> TransitionMatrices<S, I> {
+		let len = self.len();
+		let mut matrices = HashMap::new();
+		for (from, edges) in self.edges.iter().enumerate() {
+			for (input, to) in edges {
+				matrices
+					.entry((*input).clone())
+					.or_insert_with(|| vec![vec![false; len]; len])[from][*to] = true;
+			}
+		}
+		TransitionMatrices {
+			states: self.labels.iter().map(|id| (*id).clone()).collect(),
+			initial: self.initial.clone(),
+			accepts: self.accepts.clone(),
+			matrices,
+		}
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the adjacency matrices of the transitions for each input.
+	pub fn to_transition_matrices(&self) -> TransitionMatrices<S, I> {
+		self.graph().matrices()
+	}
+
+	/// Counts the accepted words with a length of at most `n`.
+	///
+	/// The count is computed by raising the transfer matrix to the power of `n + 1` using repeated squaring.
+	/// The transfer matrix is extended by an accumulating state, collecting the accepted words of all lengths.
+	/// Returns `None` if the count or one of the intermediate counts between states overflows a `u128`,
+	/// which may happen for counts slightly below `u128::MAX` already.
+	/// Also returns `None` for `n == usize::MAX`, as the power would overflow a `usize`.
+	pub fn count_words_up_to(&self, n: usize) -> Option<u128> {
+		let matrices = self.to_transition_matrices();
+		let len = matrices.states.len();
+		let mut extended: Vec<Vec<u128>> = matrices
+			.transfer()
+			.into_iter()
+			.zip(&matrices.accepts)
+			.map(|(row, accepts)| {
+				row.into_iter()
+					.map(u128::from)
+					.chain(Some(*accepts as u128))
+					.collect()
+			})
+			.collect();
+		extended.push((0..=len).map(|j| (j == len) as u128).collect());
+		let power = power(extended, n.checked_add(1)?)?;
+		matrices
+			.initial
+			.iter()
+			.try_fold(0u128, |sum, initial| sum.checked_add(power[*initial][len]))
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the adjacency matrices of the transitions for each input.
+	pub fn to_transition_matrices(&self) -> TransitionMatrices<S, I> {
+		self.graph().matrices()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	#[test]
+	fn dfa() {
+		let mut dfa = DFA::<u8, char>::with_state(0, false);
+		dfa.add_state(1, true);
+		dfa.add_transition((0, 'a', 1)).unwrap();
+		dfa.add_transition((0, 'b', 1)).unwrap();
+		dfa.add_transition((1, 'a', 0)).unwrap();
+		let matrices = dfa.to_transition_matrices();
+		assert_eq!(vec![0, 1], matrices.states, "Incorrect numbering");
+		assert_eq!(vec![0], matrices.initial, "Incorrect initial states");
+		assert_eq!(
+			hashmap!(
+				'a' => vec![vec![false, true], vec![true, false]],
+				'b' => vec![vec![false, true], vec![false, false]]
+			),
+			matrices.matrices,
+			"Incorrect matrices"
+		);
+		assert_eq!(
+			vec![vec![0, 2], vec![1, 0]],
+			matrices.transfer(),
+			"Incorrect transfer matrix"
+		);
+	}
+
+	#[test]
+	fn nfa() {
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_transition((0, 'a', 0)).unwrap();
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		let matrices = nfa.to_transition_matrices();
+		assert_eq!(
+			vec![vec![true, true], vec![false, false]],
+			matrices.matrices[&'a'],
+			"Incorrect matrix"
+		);
+		assert_eq!(vec![false, true], matrices.accepts, "Incorrect accepts");
+	}
+
+	#[test]
+	fn count() {
+		let mut dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (true, hashmap!('a' => 1, 'b' => 0)),
+				1 => (false, hashmap!('b' => 0))
+			),
+		);
+		assert_eq!(Some(1), dfa.count_words_up_to(0), "Incorrect count");
+		assert_eq!(
+			Some(1 + 1 + 2 + 3 + 5),
+			dfa.count_words_up_to(4),
+			"Incorrect count"
+		);
+		// the counts up to `n` sum to the fibonacci number `F(n + 3)` minus one
+		let (mut previous, mut fibonacci) = (0u128, 1u128);
+		for _ in 1..153 {
+			(previous, fibonacci) = (fibonacci, previous + fibonacci);
+		}
+		assert_eq!(
+			Some(fibonacci - 1),
+			dfa.count_words_up_to(150),
+			"Inexact count"
+		);
+		assert_eq!(None, dfa.count_words_up_to(1100), "Overflow not detected");
+		assert_eq!(
+			None,
+			dfa.count_words_up_to(usize::MAX),
+			"Overflow not detected"
+		);
+		dfa.set_initial(2);
+		assert_eq!(
+			Some(0),
+			dfa.count_words_up_to(3),
+			"Count without initial state"
+		);
+	}
+
+	#[test]
+	fn epsilon() {
+		let matrices = crate::regex::compile("ab*")
+			.unwrap()
+			.to_transition_matrices();
+		let len = matrices.states.len();
+		let accepts = |word: &[char]| {
+			let mut current: Vec<bool> = (0..len).map(|i| matrices.initial.contains(&i)).collect();
+			for input in word {
+				let matrix = &matrices.matrices[input];
+				current = (0..len)
+					.map(|j| (0..len).any(|i| current[i] && matrix[i][j]))
+					.collect();
+			}
+			current
+				.iter()
+				.zip(&matrices.accepts)
+				.any(|(current, accepts)| *current && *accepts)
+		};
+		assert!(accepts(&['a', 'b', 'b']), "Word rejected");
+		assert!(!accepts(&['b']), "Word accepted");
+	}
+
+	#[cfg(feature = "ndarray")]
+	#[test]
+	fn ndarray() {
+		let mut dfa = DFA::<u8, char>::with_state(0, true);
+		dfa.add_transition((0, 'a', 0)).unwrap();
+		let matrices = dfa.to_transition_matrices();
+		let arrays = matrices.to_ndarray();
+		assert_eq!(ndarray::arr2(&[[true]]), arrays[&'a'], "Incorrect array");
+	}
+}
@@ -0,0 +1,204 @@
+use super::{partition::Partition, DFA};
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Partitions the states of a deterministic transition table into classes of equivalent states.
+///
+/// Uses Hopcroft's algorithm with an implicit dead state at index `table.len()`, which loops to itself on all inputs.
+/// Returns the class of each state, including the dead state.
+fn partition(table: &[Vec<usize>], accepts: &[bool], inputs: usize) -> Vec<usize> {
+	let dead = table.len();
+	let next = |state: usize, input: usize| {
+		if state == dead {
+			dead
+		} else {
+			table[state][input]
+		}
+	};
+
+	// predecessors of each state for each input
+	let mut predecessors = vec![vec![Vec::new(); dead + 1]; inputs];
+	for state in 0..=dead {
+		for (input, predecessors) in predecessors.iter_mut().enumerate() {
+			predecessors[next(state, input)].push(state);
+		}
+	}
+
+	let keys: Vec<_> = (0..=dead)
+		.map(|state| state != dead && accepts[state])
+		.collect();
+	let mut partition = Partition::by_key(&keys);
+	let mut queued = vec![true; partition.len()];
+	let mut queue: Vec<_> = (0..partition.len()).collect();
+
+	while let Some(splitter) = queue.pop() {
+		queued[splitter] = false;
+		let members = partition.members(splitter).to_vec();
+		for predecessors in &predecessors {
+			let marked = members.iter().flat_map(|state| &predecessors[*state]);
+			for (old, new) in partition.split(marked.cloned()) {
+				// the smaller half suffices as splitter unless the old class is queued anyway
+				if queued[old] || partition.members(new).len() < partition.members(old).len() {
+					queued.push(true);
+					queue.push(new);
+				} else {
+					queued.push(false);
+					queued[old] = true;
+					queue.push(old);
+				}
+			}
+		}
+	}
+	partition.assignment().to_vec()
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Clone,
+{
+	/// Returns an equivalent DFA with the minimal number of states using Hopcroft's algorithm.
+	///
+	/// States unreachable from the initial state & states which can not reach an accepting state are dropped.
+	/// Equivalent states are merged into the first of them in order of declaration,
+	/// keeping its ID, display name & transition payloads.
+	/// The minimal DFA is in its initial state.
+	pub fn minimize(&self) -> Self {
+		let graph = self.graph();
+		let reachable = graph.reachable();
+		let states: Vec<_> = (0..graph.len()).filter(|i| reachable[*i]).collect();
+		let numbers: HashMap<_, _> = states.iter().enumerate().map(|(i, s)| (*s, i)).collect();
+		let mut inputs = HashMap::new();
+		for state in &states {
+			for (input, _) in &graph.edges[*state] {
+				let len = inputs.len();
+				inputs.entry(*input).or_insert(len);
+			}
+		}
+		let dead = states.len();
+		let table: Vec<_> = states
+			.iter()
+			.map(|state| {
+				let mut row = vec![dead; inputs.len()];
+				for (input, next) in &graph.edges[*state] {
+					row[inputs[input]] = numbers[next];
+				}
+				row
+			})
+			.collect();
+		let accepts: Vec<_> = states.iter().map(|state| graph.accepts[*state]).collect();
+		let class = partition(&table, &accepts, inputs.len());
+
+		// first state of each class in order of declaration represents it
+		let mut representatives = HashMap::new();
+		for (i, state) in states.iter().enumerate() {
+			if class[i] != class[dead] {
+				representatives.entry(class[i]).or_insert(*state);
+			}
+		}
+		let id = |i: usize| graph.labels[representatives[&class[i]]].clone();
+		let mut minimal = Self::from_states_map(
+			states
+				.iter()
+				.enumerate()
+				.filter(|(i, state)| representatives.get(&class[*i]) == Some(*state))
+				.map(|(i, state)| {
+					let index = self.index[graph.labels[*state]];
+					let transitions: Vec<_> = self.states[index]
+						.iter()
+						.flat_map(|state| &state.transitions)
+						.filter_map(|(input, (_, payload))| {
+							// transitions to undeclared states are missing from the graph
+							let next = table[i][*inputs.get(input)?];
+							(class[next] != class[dead])
+								.then(|| (input.clone(), (id(next), payload.clone())))
+						})
+						.collect();
+					(id(i), (accepts[i], transitions))
+				}),
+		);
+		let initial = graph.initial.first().map(|initial| numbers[initial]);
+		if let Some(initial) = initial.filter(|initial| class[*initial] != class[dead]) {
+			minimal.initial = minimal.index_of(&id(initial));
+			minimal.current = minimal.initial;
+		}
+		for (index, name) in &self.names {
+			if let Some(index) = minimal.index_of(&self.ids[*index]) {
+				minimal.names.insert(index, name.clone());
+			}
+		}
+		minimal.alphabet = self.alphabet.clone();
+		minimal.dangling = self.dangling;
+		minimal
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Automaton, Simplification};
+	use maplit::hashmap;
+
+	#[test]
+	fn minimize() {
+		// accepts words over `a` & `b` ending with `b`, with redundant states
+		let mut dfa = DFA::<u8, char>::with_state(0, false);
+		for (state, accepts) in [(1, false), (2, true), (3, true), (4, true), (5, false)] {
+			dfa.add_state(state, accepts);
+		}
+		for (prev, a, b) in [
+			(0, 1, 2),
+			(1, 1, 3),
+			(2, 1, 3),
+			(3, 0, 2),
+			(4, 0, 4),
+			(5, 5, 5),
+		] {
+			dfa.add_transition((prev, 'a', a)).unwrap();
+			dfa.add_transition((prev, 'b', b)).unwrap();
+		}
+		let mut minimal = dfa.minimize();
+		assert_eq!(2, minimal.stats().states, "Incorrect number of states");
+		assert!(minimal.has_state(&0), "Representative missing");
+		assert!(minimal.has_state(&2), "Representative missing");
+		assert!(!minimal.has_state(&4), "Unreachable state kept");
+		assert!(minimal.run(&['a', 'a', 'b']), "Word rejected");
+		assert!(!minimal.run(&['b', 'a']), "Word accepted");
+	}
+
+	#[test]
+	fn dead() {
+		// the trap state 2 is dropped
+		let mut dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1, 'b' => 2)),
+				1 => (true, hashmap!('a' => 1, 'b' => 2)),
+				2 => (false, hashmap!('a' => 2, 'b' => 2))
+			),
+		);
+		dfa.set_name(&1, "Done").unwrap();
+		let mut minimal = dfa.minimize();
+		assert!(!minimal.has_state(&2), "Dead state kept");
+		assert_eq!(Some("Done"), minimal.name(&1), "Name not kept");
+		assert!(minimal.run(&['a', 'a']), "Word rejected");
+		assert!(!minimal.run(&['a', 'b']), "Word accepted");
+
+		let empty = DFA::<u8, char>::from_map(0, hashmap!(0 => (false, hashmap!('a' => 0))));
+		let minimal = empty.minimize();
+		assert_eq!(0, minimal.stats().states, "Rejecting states kept");
+		assert_eq!(None, minimal.get_current(), "Initial state kept");
+	}
+
+	#[test]
+	fn dangling() {
+		let yaml = r"{initial: 0, states: {0: {accepts: true, transitions: {a: 0, b: 9}}}}";
+		let dfa: DFA<u8, char> = serde_yaml::from_str(yaml).unwrap();
+		let mut minimal = dfa.minimize();
+		assert_eq!(1, minimal.stats().transitions, "Dangling transition kept");
+		assert!(minimal.run(&['a', 'a']), "Word rejected");
+		assert!(!minimal.run(&['b']), "Word accepted");
+		let (mut simplified, _) = dfa.simplify(&Simplification::new());
+		assert!(simplified.run(&['a']), "Word rejected after simplification");
+	}
+}
@@ -0,0 +1,204 @@
+use super::{AutomatonError, DFA, NFA};
+use std::{fmt, hash::Hash};
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Sets a human-readable display name for a state, independent of its ID.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn set_name<N>(&mut self, id: &S, name: N) -> Result<(), AutomatonError<S>>
+	where
+		N: Into<String>,
+	{
+		let index = self
+			.index_of(id)
+			.ok_or_else(|| AutomatonError::InexistentState(id.clone(), Vec::new()))?;
+		self.names.insert(index, name.into());
+		Ok(())
+	}
+
+	/// Returns the display name of a state, if it has one.
+	pub fn name(&self, id: &S) -> Option<&str> {
+		self.index_of(id)
+			.and_then(|index| self.names.get(&index))
+			.map(String::as_str)
+	}
+
+	/// Returns the display name of a state, falling back to the debug representation of its ID.
+	pub fn display_name(&self, id: &S) -> String {
+		match self.name(id) {
+			Some(name) => name.into(),
+			None => format!("{:?}", id),
+		}
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Sets a human-readable display name for a state, independent of its ID.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn set_name<N>(&mut self, id: &S, name: N) -> Result<(), AutomatonError<S>>
+	where
+		N: Into<String>,
+	{
+		let index = self
+			.index_of(id)
+			.ok_or_else(|| AutomatonError::InexistentState(id.clone(), Vec::new()))?;
+		self.names.insert(index, name.into());
+		Ok(())
+	}
+
+	/// Returns the display name of a state, if it has one.
+	pub fn name(&self, id: &S) -> Option<&str> {
+		self.index_of(id)
+			.and_then(|index| self.names.get(&index))
+			.map(String::as_str)
+	}
+
+	/// Returns the display name of a state, falling back to the debug representation of its ID.
+	pub fn display_name(&self, id: &S) -> String {
+		match self.name(id) {
+			Some(name) => name.into(),
+			None => format!("{:?}", id),
+		}
+	}
+}
+
+/// Writes the lines of a state & its transitions, sorted for a stable output.
+fn write_state(
+	f: &mut fmt::Formatter,
+	name: &str,
+	initial: bool,
+	accepts: bool,
+	mut transitions: Vec<(String, String)>,
+) -> fmt::Result {
+	let marker = if initial { "-> " } else { "" };
+	let accepting = if accepts { " (accepting)" } else { "" };
+	writeln!(f, "{}{}{}", marker, name, accepting)?;
+	transitions.sort();
+	for (input, next) in transitions {
+		writeln!(f, "\t{} -> {}", input, next)?;
+	}
+	Ok(())
+}
+
+/// Lists the states with their display names & transitions.
+impl<S, I, T> fmt::Display for DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (index, state) in self.declared() {
+			let transitions = state
+				.transitions
+				.iter()
+				.filter(|(_, (next, _))| self.states[*next].is_some())
+				.map(|(input, (next, _))| {
+					(format!("{:?}", input), self.display_name(&self.ids[*next]))
+				})
+				.collect();
+			write_state(
+				f,
+				&self.display_name(&self.ids[index]),
+				self.initial == Some(index),
+				state.accepts,
+				transitions,
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Lists the states with their display names & transitions.
+impl<S, I, T> fmt::Display for NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let initial = self.initial_indices();
+		for (index, state) in self.declared() {
+			let transitions = state
+				.transitions
+				.iter()
+				.flat_map(|(input, next)| next.keys().map(move |next| (input, *next)))
+				.filter(|(_, next)| self.states[*next].is_some())
+				.map(|(input, next)| (format!("{:?}", input), self.display_name(&self.ids[next])))
+				.chain(
+					state
+						.epsilon
+						.iter()
+						.filter(|next| self.states[**next].is_some())
+						.map(|next| ("ε".into(), self.display_name(&self.ids[*next]))),
+				)
+				.collect();
+			write_state(
+				f,
+				&self.display_name(&self.ids[index]),
+				initial.contains(&index),
+				state.accepts,
+				transitions,
+			)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use std::collections::BTreeSet;
+
+	#[test]
+	fn dfa() {
+		let mut dfa = DFA::<BTreeSet<u8>, char>::with_state([0].into(), false);
+		dfa.add_state([1, 2].into(), true);
+		dfa.add_transition(([0].into(), 'a', [1, 2].into()))
+			.unwrap();
+		dfa.set_name(&[1, 2].into(), "Done").unwrap();
+		assert!(
+			dfa.set_name(&[3].into(), "Missing").is_err(),
+			"Missing error on inexistent state"
+		);
+		assert_eq!("{0}", dfa.display_name(&[0].into()), "Incorrect fallback");
+		assert_eq!(
+			"-> {0}\n\t'a' -> Done\nDone (accepting)\n",
+			dfa.to_string(),
+			"Incorrect display"
+		);
+
+		let yaml = serde_yaml::to_string(&dfa).unwrap();
+		let dfa: DFA<BTreeSet<u8>, char> = serde_yaml::from_str(&yaml).unwrap();
+		assert_eq!(
+			Some("Done"),
+			dfa.name(&[1, 2].into()),
+			"Name not serialized"
+		);
+		let nfa: NFA<_, _> = dfa.into();
+		assert_eq!(Some("Done"), nfa.name(&[1, 2].into()), "Name not converted");
+	}
+
+	#[test]
+	fn error() {
+		let mut nfa = NFA::<u8, char>::with_state(0, true);
+		nfa.set_name(&0, "Start").unwrap();
+		let error = nfa.add_transition((0, 'a', 1)).unwrap_err();
+		assert_eq!(
+			"Inexistent State \"1\"",
+			error.describe(|id| nfa.display_name(id)),
+			"Incorrect description"
+		);
+		assert_eq!(
+			"-> Start (accepting)\n",
+			nfa.to_string(),
+			"Incorrect display"
+		);
+	}
+}
@@ -1,47 +1,300 @@
-use super::{Automaton, AutomatonError, DFA};
-use serde::{Deserialize, Serialize};
+use super::{graph::Graph, serialize, Automaton, AutomatonError, Conversion, Naming, DFA};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-	collections::{BTreeSet, HashMap, HashSet},
+	collections::{BTreeMap, BTreeSet, HashMap, HashSet},
 	fmt,
 	hash::Hash,
+	sync::OnceLock,
 };
 
-#[derive(Default, Debug, Serialize, Deserialize)]
-#[serde(default, deny_unknown_fields)]
-struct State<S, I>
+/// A declared state with transitions to indices into the state arena.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct State<I, T = ()>
 where
-	S: Eq + Hash,
 	I: Eq + Hash,
 {
-	accepts: bool,
-	transitions: HashMap<I, HashSet<S>>,
+	pub(crate) accepts: bool,
+	pub(crate) transitions: HashMap<I, BTreeMap<usize, Edge<T>>>,
+	pub(crate) epsilon: BTreeSet<usize>,
 }
 
-impl<S, I> State<S, I>
+impl<I, T> State<I, T>
 where
-	S: Eq + Hash,
 	I: Eq + Hash,
 {
-	pub fn new(accepts: bool, transitions: HashMap<I, HashSet<S>>) -> Self {
+	pub fn new(accepts: bool, transitions: HashMap<I, BTreeMap<usize, Edge<T>>>) -> Self {
 		Self {
 			accepts,
 			transitions,
+			epsilon: BTreeSet::new(),
 		}
 	}
 }
 
-#[derive(Default, Debug, Serialize, Deserialize)]
+/// Payload & priority of a transition.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct Edge<T> {
+	pub(crate) payload: T,
+	pub(crate) priority: u32,
+}
+
+impl<T> Edge<T> {
+	pub fn new(payload: T) -> Self {
+		Self {
+			payload,
+			priority: 0,
+		}
+	}
+}
+
+/// Fields of the serialized representation of a state.
+const STATE_FIELDS: &[&str] = &[
+	"accepts",
+	"transitions",
+	"payloads",
+	"priorities",
+	"epsilon",
+	"name",
+];
+
+/// Serialized representation of a state.
+///
+/// Payloads & priorities are only present for transitions with a payload or priority other than the default.
+/// The display name is only present if the state has one.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct StateRepr<S, I, T>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+{
+	accepts: bool,
+	transitions: HashMap<I, HashSet<S>>,
+	// field defaults allow omitting payloads & priorities in the sequence form
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	payloads: HashMap<I, HashMap<S, T>>,
+	#[serde(default, skip_serializing_if = "HashMap::is_empty")]
+	priorities: HashMap<I, HashMap<S, u32>>,
+	#[serde(default, skip_serializing_if = "HashSet::is_empty")]
+	epsilon: HashSet<S>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	name: Option<String>,
+}
+
+/// Fields of the serialized representation.
+const FIELDS: &[&str] = &["initial", "current", "states", "alphabet"];
+
+/// Serialized representation of an NFA.
+///
+/// The current states are only present if they differ from the initial states.
+/// Older representations without initial states use the current states as initial states.
+#[derive(Default, Deserialize)]
 #[serde(default, deny_unknown_fields)]
-pub struct NFA<S, I>
+struct Repr<S, I, T>
+where
+	S: Default + Eq + Hash,
+	I: Default + Eq + Hash,
+	T: Default,
+{
+	initial: Option<HashSet<S>>,
+	current: Option<HashSet<S>>,
+	states: HashMap<S, StateRepr<S, I, T>>,
+	alphabet: Option<HashSet<I>>,
+}
+
+/// Borrowed serialized representation of an NFA.
+#[derive(Serialize)]
+struct ReprRef<'a, S, I, T>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+{
+	initial: HashSet<&'a S>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	current: Option<HashSet<&'a S>>,
+	states: HashMap<&'a S, StateRepr<&'a S, &'a I, &'a T>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	alphabet: Option<&'a HashSet<I>>,
+}
+
+/// A nondeterministic finite state automaton.
+///
+/// States are stored in an arena & transitions refer to states by their index.
+/// States which are referenced by transitions without being declared occupy an undeclared slot in the arena.
+///
+/// The initial & current states always refer to existing states or are invalid.
+/// Transitions to inexistent states are ignored.
+///
+/// Each transition carries a payload of type `T`, e.g. a cost or a label.
+/// Transitions added without a payload carry the default payload.
+#[derive(Default, Debug)]
+pub struct NFA<S, I, T = ()>
 where
 	S: Default + Clone + Eq + Hash + fmt::Debug,
 	I: Default + Eq + Hash,
 {
-	current: HashSet<S>,
-	states: HashMap<S, State<S, I>>,
+	pub(crate) ids: Vec<S>,
+	pub(crate) index: HashMap<S, usize>,
+	pub(crate) states: Vec<Option<State<I, T>>>,
+	initial: BTreeSet<usize>,
+	current: BTreeSet<usize>,
+	initial_ids: OnceLock<HashSet<S>>,
+	current_ids: OnceLock<HashSet<S>>,
+	pub(crate) alphabet: Option<HashSet<I>>,
+	pub(crate) names: HashMap<usize, String>,
+}
+
+impl<'de, S, I, T> Deserialize<'de> for NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug + Deserialize<'de>,
+	I: Default + Eq + Hash + Deserialize<'de>,
+	T: Default + Deserialize<'de>,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let Repr {
+			initial,
+			current,
+			states,
+			alphabet,
+		} = serialize::repr::<Repr<S, I, T>, _>(deserializer, FIELDS, STATE_FIELDS)?;
+		let (initial, current) = match (initial, current) {
+			(Some(initial), Some(current)) => (initial, current),
+			(Some(initial), None) => (initial.clone(), initial),
+			(None, Some(current)) => (current.clone(), current),
+			(None, None) => (HashSet::new(), HashSet::new()),
+		};
+
+		// only keep references to existing states
+		let mut names = Vec::new();
+		let mut epsilons = Vec::new();
+		let mut nfa = Self::from_states_map(states.into_iter().map(|(id, state)| {
+			let StateRepr {
+				accepts,
+				transitions,
+				mut payloads,
+				mut priorities,
+				epsilon,
+				name,
+			} = state;
+			if let Some(name) = name {
+				names.push((id.clone(), name));
+			}
+			if !epsilon.is_empty() {
+				epsilons.push((id.clone(), epsilon));
+			}
+			let transitions: Vec<_> = transitions
+				.into_iter()
+				.map(|(input, next)| {
+					let mut payloads = payloads.remove(&input).unwrap_or_default();
+					let mut priorities = priorities.remove(&input).unwrap_or_default();
+					let next: Vec<_> = next
+						.into_iter()
+						.map(|next| {
+							let edge = Edge {
+								payload: payloads.remove(&next).unwrap_or_default(),
+								priority: priorities.remove(&next).unwrap_or_default(),
+							};
+							(next, edge)
+						})
+						.collect();
+					(input, next)
+				})
+				.collect();
+			(id, (accepts, transitions))
+		}));
+		nfa.set_initial(initial);
+		nfa.set_current(current);
+		nfa.alphabet = alphabet;
+		for (id, name) in names {
+			let index = nfa.index[&id];
+			nfa.names.insert(index, name);
+		}
+		for (id, epsilon) in epsilons {
+			let index = nfa.index[&id];
+			let epsilon = epsilon.into_iter().map(|next| nfa.slot(next)).collect();
+			if let Some(state) = nfa.states[index].as_mut() {
+				state.epsilon = epsilon;
+			}
+		}
+		Ok(nfa)
+	}
+}
+
+impl<S, I, T> Serialize for NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug + Serialize,
+	I: Default + Eq + Hash + Serialize,
+	T: Default + PartialEq + Serialize,
+{
+	fn serialize<R>(&self, serializer: R) -> Result<R::Ok, R::Error>
+	where
+		R: Serializer,
+	{
+		let default = T::default();
+		let ids = |set: &BTreeSet<usize>| set.iter().map(|i| &self.ids[*i]).collect();
+		ReprRef {
+			initial: ids(&self.initial),
+			current: if self.current != self.initial {
+				Some(ids(&self.current))
+			} else {
+				None
+			},
+			states: self
+				.declared()
+				.map(|(i, state)| {
+					(
+						&self.ids[i],
+						StateRepr {
+							accepts: state.accepts,
+							transitions: state
+								.transitions
+								.iter()
+								.map(|(input, next)| {
+									(input, next.keys().map(|i| &self.ids[*i]).collect())
+								})
+								.collect(),
+							payloads: state
+								.transitions
+								.iter()
+								.map(|(input, next)| {
+									let payloads: HashMap<_, _> = next
+										.iter()
+										.filter(|(_, edge)| edge.payload != default)
+										.map(|(i, edge)| (&self.ids[*i], &edge.payload))
+										.collect();
+									(input, payloads)
+								})
+								.filter(|(_, payloads)| !payloads.is_empty())
+								.collect(),
+							priorities: state
+								.transitions
+								.iter()
+								.map(|(input, next)| {
+									let priorities: HashMap<_, _> = next
+										.iter()
+										.filter(|(_, edge)| edge.priority != 0)
+										.map(|(i, edge)| (&self.ids[*i], edge.priority))
+										.collect();
+									(input, priorities)
+								})
+								.filter(|(_, priorities)| !priorities.is_empty())
+								.collect(),
+							epsilon: state.epsilon.iter().map(|i| &self.ids[*i]).collect(),
+							name: self.names.get(&i).cloned(),
+						},
+					)
+				})
+				.collect(),
+			alphabet: self.alphabet.as_ref(),
+		}
+		.serialize(serializer)
+	}
 }
 
-impl<S, I> NFA<S, I>
+impl<S, I, T> NFA<S, I, T>
 where
 	S: Default + Clone + Eq + Hash + fmt::Debug,
 	I: Default + Eq + Hash,
@@ -50,36 +303,274 @@ where
 	pub fn from_map<M>(initial: HashSet<S>, states: M) -> Self
 	where
 		M: Into<HashMap<S, (bool, HashMap<I, HashSet<S>>)>>,
+		T: Default,
 	{
-		let map = states.into();
-		Self {
-			current: initial,
-			states: map
+		let mut nfa = Self::from_states_map(states.into().into_iter().map(
+			|(id, (accepts, transitions))| {
+				let transitions = transitions.into_iter().map(|(input, next)| {
+					let next = next.into_iter().map(|next| (next, Edge::new(T::default())));
+					(input, next)
+				});
+				(id, (accepts, transitions))
+			},
+		));
+		nfa.set_initial(initial.clone());
+		nfa.set_current(initial);
+		nfa
+	}
+
+	/// Creates a new NFA without initial states from an iterator over states & their transitions.
+	fn from_states_map<M, E, N>(states: M) -> Self
+	where
+		M: IntoIterator<Item = (S, (bool, E))>,
+		E: IntoIterator<Item = (I, N)>,
+		N: IntoIterator<Item = (S, Edge<T>)>,
+	{
+		let mut nfa = Self::from_parts(
+			Vec::new(),
+			HashMap::new(),
+			Vec::new(),
+			BTreeSet::new(),
+			BTreeSet::new(),
+		);
+		for (id, (accepts, transitions)) in states {
+			let transitions = transitions
 				.into_iter()
-				.map(|(state, (accepts, transitions))| (state, State::new(accepts, transitions)))
-				.collect(),
+				.map(|(input, next)| {
+					let next = next
+						.into_iter()
+						.map(|(next, edge)| (nfa.slot(next), edge))
+						.collect();
+					(input, next)
+				})
+				.collect();
+			let index = nfa.slot(id);
+			nfa.states[index] = Some(State::new(accepts, transitions));
 		}
+		nfa
 	}
 
-	/// Returns a reference to the requested state or an `AutomatonError::InexistentState` error otherwise.
-	fn get_state(&self, id: &S) -> Result<&State<S, I>, AutomatonError<S>> {
-		self.states
+	/// Creates a new NFA from the parts of a state arena.
+	pub(crate) fn from_parts(
+		ids: Vec<S>,
+		index: HashMap<S, usize>,
+		states: Vec<Option<State<I, T>>>,
+		initial: BTreeSet<usize>,
+		current: BTreeSet<usize>,
+	) -> Self {
+		Self {
+			ids,
+			index,
+			states,
+			initial,
+			current,
+			initial_ids: OnceLock::new(),
+			current_ids: OnceLock::new(),
+			alphabet: None,
+			names: HashMap::new(),
+		}
+	}
+
+	/// Returns the index of a state, allocating an undeclared slot if necessary.
+	pub(crate) fn slot(&mut self, id: S) -> usize {
+		if let Some(index) = self.index.get(&id) {
+			return *index;
+		}
+		self.ids.push(id.clone());
+		self.states.push(None);
+		self.index.insert(id, self.ids.len() - 1);
+		self.ids.len() - 1
+	}
+
+	/// Returns the index of a declared state.
+	pub(crate) fn index_of(&self, id: &S) -> Option<usize> {
+		self.index
 			.get(id)
-			.ok_or_else(|| AutomatonError::InexistentState(id.clone()))
+			.cloned()
+			.filter(|index| self.states[*index].is_some())
 	}
 
-	/// Returns a mutable reference to the requested state or an `AutomatonError::InexistentState` error otherwise.
-	fn get_state_mut(&mut self, id: &S) -> Result<&mut State<S, I>, AutomatonError<S>> {
+	/// Returns the declared state at an index.
+	pub(crate) fn state(&self, index: usize) -> Option<&State<I, T>> {
+		self.states[index].as_ref()
+	}
+
+	/// Returns an iterator over the indices & declared states.
+	pub(crate) fn declared(&self) -> impl Iterator<Item = (usize, &State<I, T>)> {
 		self.states
-			.get_mut(id)
-			.ok_or_else(|| AutomatonError::InexistentState(id.clone()))
+			.iter()
+			.enumerate()
+			.filter_map(|(i, state)| state.as_ref().map(|state| (i, state)))
+	}
+
+	/// Returns the indices of the initial states.
+	pub(crate) fn initial_indices(&self) -> &BTreeSet<usize> {
+		&self.initial
+	}
+
+	/// Updates the initial states from their indices.
+	pub(crate) fn set_initial_indices(&mut self, initial: BTreeSet<usize>) {
+		self.initial = initial;
+		self.initial_ids = OnceLock::new();
+	}
+
+	/// Returns the indices of the current states.
+	pub(crate) fn current_indices(&self) -> &BTreeSet<usize> {
+		&self.current
+	}
+
+	/// Updates the current states from their indices.
+	pub(crate) fn set_current_indices(&mut self, current: BTreeSet<usize>) {
+		self.current = current;
+		self.current_ids = OnceLock::new();
+	}
+
+	/// Returns the indices of the declared states reachable from a set of state indices with an input.
+	pub(crate) fn successors(&self, current: &BTreeSet<usize>, input: &I) -> BTreeSet<usize> {
+		let mut next = BTreeSet::new();
+		for el in current {
+			if let Some(states) = self
+				.state(*el)
+				.and_then(|state| state.transitions.get(input))
+			{
+				next.extend(states.keys().filter(|el| self.states[**el].is_some()));
+			}
+		}
+		next
+	}
+
+	/// Returns the indices of a set of states if all of them are declared.
+	fn indices_of(&self, ids: &HashSet<S>) -> Option<BTreeSet<usize>> {
+		ids.iter().map(|id| self.index_of(id)).collect()
+	}
+
+	/// Returns a mutable reference to the requested state or an `AutomatonError::InexistentState` error otherwise.
+	fn get_state_mut<E>(&mut self, id: &S) -> Result<&mut State<I, T>, AutomatonError<S, E>> {
+		match self.index.get(id) {
+			Some(index) => self.states[*index].as_mut(),
+			None => None,
+		}
+		.ok_or_else(|| AutomatonError::InexistentState(id.clone(), Vec::new()))
+	}
+
+	/// Returns an index-based view of the transition graph with epsilon transitions resolved.
+	///
+	/// Each state has the transitions of all states in its epsilon closure to the epsilon closures of their targets
+	/// & accepts if any state in its epsilon closure accepts. The initial states are replaced by their epsilon closure.
+	/// This preserves the accepted language as well as which states are reachable & coreachable.
+	pub(crate) fn graph(&self) -> Graph<'_, S, I> {
+		let mut graph = self.transition_graph();
+		if !self.has_epsilons() {
+			return graph;
+		}
+		let closures = self.graph_closures(&graph);
+		graph.initial = closed(&closures, &graph.initial);
+		graph.accepts = closures
+			.iter()
+			.map(|closure| closure.iter().any(|state| graph.accepts[*state]))
+			.collect();
+		graph.edges = closures
+			.iter()
+			.map(|closure| {
+				let mut edges = Vec::new();
+				let mut seen = HashSet::new();
+				for (input, next) in closure.iter().flat_map(|state| &graph.edges[*state]) {
+					for next in &closures[*next] {
+						if seen.insert((*input, *next)) {
+							edges.push((*input, *next));
+						}
+					}
+				}
+				edges
+			})
+			.collect();
+		graph
+	}
+
+	/// Returns the epsilon closure of each state of a transition graph of the NFA in graph indices.
+	pub(crate) fn graph_closures(&self, graph: &Graph<'_, S, I>) -> Vec<Vec<usize>> {
+		let indices = graph.indices();
+		graph
+			.labels
+			.iter()
+			.map(|id| {
+				self.epsilon_closure(&Some(self.index[*id]).into_iter().collect())
+					.into_iter()
+					.filter_map(|index| indices.get(&self.ids[index]).cloned())
+					.collect()
+			})
+			.collect()
+	}
+
+	/// Returns an index-based view of the labeled transitions, ignoring epsilon transitions.
+	pub(crate) fn transition_graph(&self) -> Graph<'_, S, I> {
+		Graph::new(
+			self.ids.iter().zip(&self.states).map(|(id, state)| {
+				(
+					id,
+					state.as_ref().map(|state| {
+						(
+							state.accepts,
+							state
+								.transitions
+								.iter()
+								.flat_map(|(input, next)| {
+									next.keys().map(move |next| (input, *next))
+								})
+								.collect(),
+						)
+					}),
+				)
+			}),
+			self.initial.iter().cloned(),
+		)
+	}
+
+	/// Adds a new transition carrying a payload to the automaton.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	/// The error suggests the states with similar names, if there are any.
+	/// Returns an `AutomatonError::UnknownInput` error if the input is not part of the declared alphabet.
+	pub fn add_transition_with_payload(
+		&mut self,
+		transition: (S, I, S),
+		payload: T,
+	) -> Result<(), AutomatonError<S, I>> {
+		let (prev, input, next) = transition;
+		if !self.knows(&input) {
+			return Err(AutomatonError::UnknownInput(input));
+		}
+		if self.index_of(&prev).is_none() {
+			return Err(self.inexistent(prev));
+		}
+		match self.index_of(&next) {
+			Some(next) => {
+				let State { transitions, .. } = self.get_state_mut(&prev)?;
+				transitions
+					.entry(input)
+					.or_default()
+					.insert(next, Edge::new(payload));
+				Ok(())
+			}
+			None => Err(self.inexistent(next)),
+		}
+	}
+
+	/// Returns the payload of the transition between two states with an input.
+	pub fn payload(&self, prev: &S, input: &I, next: &S) -> Option<&T> {
+		let next = self.index_of(next)?;
+		self.index_of(prev)
+			.and_then(|index| self.state(index))
+			.and_then(|state| state.transitions.get(input))
+			.and_then(|transitions| transitions.get(&next))
+			.map(|edge| &edge.payload)
 	}
 }
 
-impl<S, I> Automaton<S, I> for NFA<S, I>
+impl<S, I, T> Automaton<S, I> for NFA<S, I, T>
 where
 	S: Default + Clone + Eq + Hash + fmt::Debug,
 	I: Default + Eq + Hash,
+	T: Default,
 {
 	type State = HashSet<S>;
 	type Transition = (S, I, S);
@@ -91,95 +582,112 @@ where
 	}
 
 	fn has_state(&self, id: &S) -> bool {
-		self.states.contains_key(id)
+		self.index_of(id).is_some()
 	}
 
 	fn add_state(&mut self, id: S, accept: bool) {
-		self.states.insert(id, State::new(accept, HashMap::new()));
+		let index = self.slot(id);
+		self.states[index] = Some(State::new(accept, HashMap::new()));
 	}
 
-	fn add_transition(&mut self, transition: Self::Transition) -> Result<(), AutomatonError<S>> {
-		let (prev, input, next) = transition;
-		if !self.has_state(&next) {
-			Err(AutomatonError::InexistentState(next))
+	fn add_transition(&mut self, transition: Self::Transition) -> Result<(), AutomatonError<S, I>> {
+		self.add_transition_with_payload(transition, T::default())
+	}
+
+	fn set_initial(&mut self, state: Self::State) {
+		let initial = self.indices_of(&state).unwrap_or_default();
+		self.set_initial_indices(initial);
+	}
+
+	fn get_initial(&self) -> Option<&Self::State> {
+		if !self.initial.is_empty() {
+			Some(
+				self.initial_ids
+					.get_or_init(|| self.initial.iter().map(|i| self.ids[*i].clone()).collect()),
+			)
 		} else {
-			let State { transitions, .. } = self.get_state_mut(&prev)?;
-			if let Some(set) = transitions.get_mut(&input) {
-				set.insert(next);
-			} else {
-				transitions.insert(input, Self::new_state(next));
-			}
-			Ok(())
+			None
 		}
 	}
 
 	fn get_current(&self) -> Option<&Self::State> {
 		if !self.current.is_empty() {
-			Some(&self.current)
+			Some(
+				self.current_ids
+					.get_or_init(|| self.current.iter().map(|i| self.ids[*i].clone()).collect()),
+			)
 		} else {
 			None
 		}
 	}
 
 	fn set_current(&mut self, state: Self::State) {
-		if state.iter().all(|el| self.has_state(el)) {
-			self.current = state;
-		} else {
-			self.current = HashSet::new();
-		}
+		let current = self.indices_of(&state).unwrap_or_default();
+		self.set_current_indices(current);
 	}
 
 	fn accepts(&self) -> bool {
-		self.current
+		self.epsilon_closure(&self.current)
 			.iter()
-			.any(|el| self.get_state(el).unwrap().accepts)
+			.any(|el| matches!(self.state(*el), Some(state) if state.accepts))
+	}
+
+	fn reset(&mut self) {
+		self.set_current_indices(self.initial.clone());
 	}
 
 	fn step(&mut self, input: &I) {
-		let mut new = HashSet::with_capacity(self.current.len());
-		for el in &self.current {
-			if let Some(states) = self.get_state(el).unwrap().transitions.get(input) {
-				new = new.union(&states).cloned().collect();
+		let next = self.successors(&self.epsilon_closure(&self.current), input);
+		self.set_current_indices(self.epsilon_closure(&next));
+	}
+
+	/// Runs the automaton on a sequence of inputs, starting from the full set of current states.
+	/// The current states are not changed.
+	fn run<'a, V>(&mut self, inputs: V) -> bool
+	where
+		V: IntoIterator<Item = &'a I>,
+		I: 'a,
+	{
+		let mut current = self.epsilon_closure(&self.current);
+		for input in inputs {
+			if current.is_empty() {
+				break;
 			}
+			current = self.epsilon_closure(&self.successors(&current, input));
 		}
-		new.shrink_to_fit();
-		self.current = new;
+		current
+			.iter()
+			.any(|el| matches!(self.state(*el), Some(state) if state.accepts))
 	}
 }
 
-impl<S, I> Into<DFA<BTreeSet<S>, I>> for NFA<S, I>
+/// Converts an NFA into an equivalent DFA using the powerset construction.
+///
+/// Only the subsets reachable from the initial & current states are built, without limit.
+/// See `NFA::to_dfa` for a configurable conversion.
+impl<S, I, T> From<NFA<S, I, T>> for DFA<BTreeSet<S>, I, T>
 where
 	S: Default + Clone + Eq + Ord + Hash + fmt::Debug,
 	I: Default + Clone + Eq + Hash,
+	T: Default + Clone,
 {
-	fn into(self) -> DFA<BTreeSet<S>, I> {
-		let size = 1 << self.states.len();
-		let mut states = HashMap::with_capacity(size - 1);
-		for i in 1..size {
-			let iter = self
-				.states
-				.iter()
-				.enumerate()
-				.filter(|(j, _)| i & (1 << j) != 0)
-				.map(|(_, el)| el);
-			let state_set = iter.clone().map(|(id, _)| id.clone()).collect();
-			let accepts = iter.clone().any(|(_, State { accepts, .. })| *accepts);
-			let mut transition_map: HashMap<I, BTreeSet<S>> = HashMap::new();
-			for (_, State { transitions, .. }) in iter {
-				for (input, next) in transitions {
-					if let Some(states) = transition_map.get_mut(input) {
-						states.append(&mut next.iter().cloned().collect());
-					} else {
-						transition_map.insert(input.clone(), next.iter().cloned().collect());
-					}
-				}
-			}
-			states.insert(state_set, (accepts, transition_map));
-		}
-		DFA::from_map(self.current.into_iter().collect(), states)
+	fn from(nfa: NFA<S, I, T>) -> Self {
+		let options = Conversion::new().limit(usize::MAX).naming(Naming::Drop);
+		nfa.to_dfa(&options)
+			.expect("Too many states for powerset construction")
 	}
 }
 
+/// Returns the union of the epsilon closures of a set of states in graph indices, in ascending order.
+pub(crate) fn closed(closures: &[Vec<usize>], states: &[usize]) -> Vec<usize> {
+	let closed: BTreeSet<usize> = states
+		.iter()
+		.flat_map(|state| &closures[*state])
+		.cloned()
+		.collect();
+	closed.into_iter().collect()
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -239,6 +747,40 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn reset() {
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_state(2, true);
+		nfa.set_initial(hashset![0, 1]);
+		nfa.set_current(hashset![0, 1]);
+		nfa.add_transition((0, 'a', 2)).unwrap();
+		nfa.add_transition((1, 'b', 2)).unwrap();
+
+		assert!(nfa.run(&['a']), "Incorrect result after run");
+		assert!(nfa.run(&['b']), "Start set not restored after run");
+		assert_eq!(
+			Some(&hashset![0, 1]),
+			nfa.get_current(),
+			"Incorrect state after run"
+		);
+
+		nfa.step(&'c');
+		assert_eq!(
+			None,
+			nfa.get_current(),
+			"Incorrect state after invalid step"
+		);
+		assert!(!nfa.run(&[]), "Invalid state accepting");
+		nfa.reset();
+		assert_eq!(
+			Some(&hashset![0, 1]),
+			nfa.get_current(),
+			"Initial states not restored after reset"
+		);
+		assert!(nfa.accepts(), "Incorrect acceptance after reset");
+	}
+
 	#[test]
 	fn deserialize() {
 		let yaml = r"{states: {0: {accepts: false, transitions: {a: [0, 1], b: [1]}}, 1: {accepts: true}}, current: [0]}";
@@ -250,6 +792,71 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn serialize() {
+		let yaml = r"{initial: [0], states: {0: {transitions: {a: [0, 1]}}, 1: {accepts: true}}}";
+		let mut nfa: NFA<u8, char> = serde_yaml::from_str(yaml).unwrap();
+		assert_eq!(
+			Some(&hashset![0]),
+			nfa.get_initial(),
+			"Incorrect initial states"
+		);
+
+		// current states are kept after a partial run
+		nfa.step(&'a');
+		let yaml = serde_yaml::to_string(&nfa).unwrap();
+		let nfa: NFA<u8, char> = serde_yaml::from_str(&yaml).unwrap();
+		assert_eq!(
+			Some(&hashset![0]),
+			nfa.get_initial(),
+			"Incorrect initial states"
+		);
+		assert_eq!(
+			Some(&hashset![0, 1]),
+			nfa.get_current(),
+			"Incorrect current states"
+		);
+	}
+
+	#[test]
+	fn invalid() {
+		let yaml = r"{initial: [0], states: {0: {transitions: {a: [0, 2]}}, 1: {accepts: true}}}";
+		let mut nfa: NFA<u8, char> = serde_yaml::from_str(yaml).unwrap();
+		nfa.step(&'a');
+		nfa.step(&'a');
+		assert_eq!(
+			Some(&hashset![0]),
+			nfa.get_current(),
+			"Stepped into inexistent state"
+		);
+
+		let nfa = NFA::<u8, char>::from_map(hashset![0, 3], hashmap!(0 => (true, hashmap!())));
+		assert_eq!(None, nfa.get_initial(), "Inexistent initial state kept");
+	}
+
+	#[test]
+	fn payload() {
+		let yaml = r"{initial: [0], states: {0: {transitions: {a: [1], b: [0]}, payloads: {a: {1: x}}}, 1: {accepts: true}}}";
+		let nfa: NFA<u8, char, String> = serde_yaml::from_str(yaml).unwrap();
+		assert_eq!(
+			Some(&"x".to_string()),
+			nfa.payload(&0, &'a', &1),
+			"Incorrect payload"
+		);
+		assert_eq!(
+			Some(&String::new()),
+			nfa.payload(&0, &'b', &0),
+			"Incorrect default payload"
+		);
+
+		let dfa: DFA<_, _, _> = nfa.into();
+		assert_eq!(
+			Some(&"x".to_string()),
+			dfa.payload(&btreeset![0], &'a'),
+			"Payload not converted"
+		);
+	}
+
 	#[test]
 	fn convert() {
 		let nfa = NFA::from_map(
@@ -268,8 +875,27 @@ mod tests {
 		let mut dfa: DFA<_, _> = nfa.into();
 		assert!(
 			dfa.has_state(&btreeset![0, 1]),
-			"Converted DFA is missing state {0, 1}"
+			"Converted DFA is missing state {}",
+			"{0, 1}"
 		);
 		assert!(dfa.run(&['a', 'b', 'b']), "Incorrect result after run");
+		assert!(
+			!dfa.has_state(&btreeset![1]),
+			"Converted DFA has unreachable state {}",
+			"{1}"
+		);
+	}
+
+	#[test]
+	fn convert_large() {
+		// a chain of 64 states only has 64 reachable subsets
+		let mut nfa = NFA::<u32, char>::with_state(0, false);
+		for i in 1..64 {
+			nfa.add_state(i, i == 63);
+			nfa.add_transition((i - 1, 'a', i)).unwrap();
+		}
+		let mut dfa: DFA<_, _> = nfa.into();
+		assert_eq!(64, dfa.stats().states, "Incorrect number of states");
+		assert!(dfa.run(&['a'; 63]), "Incorrect result after run");
 	}
 }
@@ -0,0 +1,169 @@
+//! DFAs recognizing encodings of natural numbers satisfying constraints.
+//!
+//! Numbers are written in a base with the most significant digit first & inputs are the digits from `0` to `base - 1`.
+//! Leading zeros are allowed & the empty word is treated as `0`.
+//! Constraints are combined using `DFA::intersection` or a `BooleanCombination`,
+//! e.g. the numbers below `100` congruent to `1` modulo `3`.
+
+use super::{canonical::from_fn, DFA};
+use std::cmp::Ordering;
+
+/// Returns the digits of a number in a base, with the most significant digit first.
+/// The number `0` is written as a single digit.
+///
+/// Panics if `base` is less than `2`.
+pub fn encode(number: u64, base: u32) -> Vec<u32> {
+	let mut digits = significant_digits(number, base);
+	if digits.is_empty() {
+		digits.push(0);
+	}
+	digits
+}
+
+/// Returns the number written by digits in a base.
+/// Returns `None` if a digit is out of range or the number overflows.
+pub fn decode(digits: &[u32], base: u32) -> Option<u64> {
+	digits.iter().try_fold(0u64, |number, digit| {
+		if *digit >= base {
+			return None;
+		}
+		number.checked_mul(base as u64)?.checked_add(*digit as u64)
+	})
+}
+
+/// Returns the digits of a number without leading zeros, which are none for `0`.
+fn significant_digits(mut number: u64, base: u32) -> Vec<u32> {
+	assert!(base >= 2, "Base less than 2");
+	let mut digits = Vec::new();
+	while number > 0 {
+		digits.push((number % base as u64) as u32);
+		number /= base as u64;
+	}
+	digits.reverse();
+	digits
+}
+
+/// Returns a DFA accepting the numbers congruent to `remainder` modulo `modulus`.
+/// The states are the remainders of the number read so far.
+///
+/// Panics if `modulus` is `0` or `base` is less than `2`.
+pub fn congruent(modulus: usize, remainder: usize, base: u32) -> DFA<usize, u32> {
+	assert!(modulus > 0, "Congruence modulo zero");
+	assert!(base >= 2, "Base less than 2");
+	from_fn(
+		0..base,
+		modulus,
+		|state| state == remainder % modulus,
+		|state, digit| (state * base as usize + *digit as usize) % modulus,
+	)
+}
+
+/// Returns a DFA accepting the numbers less than a bound.
+///
+/// The states track the number of significant digits read so far & how they compare to the leading digits of the bound,
+/// so the DFA only needs a few states per digit of the bound.
+///
+/// Panics if `base` is less than `2`.
+pub fn less_than(bound: u64, base: u32) -> DFA<usize, u32> {
+	let bound = significant_digits(bound, base);
+	let len = bound.len();
+	// state `3 * digits + ordering` with the ordering of the digits against the bound, `len + 1` digits are dead
+	let ordering = |state: usize| match state % 3 {
+		0 => Ordering::Equal,
+		1 => Ordering::Less,
+		_ => Ordering::Greater,
+	};
+	let state = |digits: usize, ordering: Ordering| {
+		3 * digits
+			+ match ordering {
+				Ordering::Equal => 0,
+				Ordering::Less => 1,
+				Ordering::Greater => 2,
+			}
+	};
+	let dead = state(len + 1, Ordering::Equal);
+	from_fn(
+		0..base,
+		dead + 1,
+		|current| {
+			let digits = current / 3;
+			digits < len || (digits == len && ordering(current) == Ordering::Less)
+		},
+		|current, digit| {
+			let digits = current / 3;
+			if current == dead || digits == len {
+				dead
+			} else if digits == 0 && *digit == 0 {
+				current
+			} else {
+				let ordering = ordering(current).then(digit.cmp(&bound[digits]));
+				state(digits + 1, ordering)
+			}
+		},
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Automaton, BooleanCombination};
+
+	#[test]
+	fn encoding() {
+		assert_eq!(vec![1, 0, 1], encode(5, 2), "Incorrect binary digits");
+		assert_eq!(vec![0], encode(0, 10), "Incorrect digits of zero");
+		assert_eq!(Some(123), decode(&[0, 1, 2, 3], 10), "Incorrect number");
+		assert_eq!(None, decode(&[2], 2), "Digit out of range decoded");
+		assert_eq!(Some(0), decode(&[], 10), "Incorrect empty number");
+	}
+
+	#[test]
+	fn constraints() {
+		for base in [2, 10] {
+			let mut congruent = congruent(3, 1, base);
+			let mut less = less_than(20, base);
+			let mut zero = less_than(0, base);
+			for number in 0..100 {
+				let digits = encode(number, base);
+				assert_eq!(
+					number % 3 == 1,
+					congruent.run(&digits),
+					"Incorrect congruence for {}",
+					number
+				);
+				assert_eq!(
+					number < 20,
+					less.run(&digits),
+					"Incorrect comparison for {}",
+					number
+				);
+				let mut padded = vec![0, 0];
+				padded.extend(digits);
+				assert_eq!(
+					number < 20,
+					less.run(&padded),
+					"Incorrect comparison with leading zeros for {}",
+					number
+				);
+				assert!(!zero.run(&encode(number, base)), "Number below zero");
+			}
+		}
+	}
+
+	#[test]
+	fn combination() {
+		let congruent = congruent(3, 1, 10);
+		let less = less_than(20, 10);
+		let mut both = BooleanCombination::new()
+			.and(&congruent)
+			.and(&less)
+			.compile();
+		let accepted: Vec<_> = (0..100)
+			.filter(|number| both.run(&encode(*number, 10)))
+			.collect();
+		assert_eq!(vec![1, 4, 7, 10, 13, 16, 19], accepted, "Incorrect numbers");
+		let mut intersection = congruent.intersection(&less);
+		assert!(intersection.run(&encode(13, 10)), "Number rejected");
+		assert!(!intersection.run(&encode(22, 10)), "Number accepted");
+	}
+}
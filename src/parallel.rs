@@ -0,0 +1,244 @@
+use super::{graph::Graph, intersection::build, Automaton, AutomatonError, DFA, NFA};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt,
+	hash::Hash,
+};
+
+/// Pairs of states & their transitions in a synchronized product.
+type Product<'a, I> = (Vec<(usize, usize)>, Vec<Vec<(&'a I, usize)>>);
+
+/// Returns the declared alphabet or the inputs used in the transitions of a graph.
+fn alphabet<'a, S, I>(graph: &Graph<'a, S, I>, declared: Option<&'a HashSet<I>>) -> HashSet<&'a I>
+where
+	I: Eq + Hash,
+{
+	match declared {
+		Some(alphabet) => alphabet.iter().collect(),
+		None => graph
+			.edges
+			.iter()
+			.flatten()
+			.map(|(input, _)| *input)
+			.collect(),
+	}
+}
+
+/// Synchronized product of two graphs over pairs of states reachable from the pairs of initial states.
+///
+/// Both states move together on shared inputs & each state moves on its own on the other inputs.
+fn synchronize<'a, S, R, I>(
+	graph: &Graph<'a, S, I>,
+	other: &Graph<'a, R, I>,
+	shared: &HashSet<&'a I>,
+) -> Product<'a, I>
+where
+	I: Eq + Hash,
+{
+	let mut numbers = HashMap::new();
+	let mut pairs = Vec::new();
+	for state in &graph.initial {
+		for other_state in &other.initial {
+			numbers.insert((*state, *other_state), pairs.len());
+			pairs.push((*state, *other_state));
+		}
+	}
+	let mut rows = Vec::new();
+	let mut i = 0;
+	while i < pairs.len() {
+		let (state, other_state) = pairs[i];
+		let mut next = Vec::new();
+		for (input, target) in &graph.edges[state] {
+			if shared.contains(input) {
+				for (other_input, other_target) in &other.edges[other_state] {
+					if other_input == input {
+						next.push((*input, (*target, *other_target)));
+					}
+				}
+			} else {
+				next.push((*input, (*target, other_state)));
+			}
+		}
+		for (input, other_target) in &other.edges[other_state] {
+			if !shared.contains(input) {
+				next.push((*input, (state, *other_target)));
+			}
+		}
+		let row = next
+			.into_iter()
+			.map(|(input, pair)| {
+				let number = *numbers.entry(pair).or_insert_with(|| {
+					pairs.push(pair);
+					pairs.len() - 1
+				});
+				(input, number)
+			})
+			.collect();
+		rows.push(row);
+		i += 1;
+	}
+	(pairs, rows)
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the parallel composition of this & another DFA, synchronizing on shared inputs.
+	///
+	/// The alphabet of a DFA is its declared alphabet or the inputs used in its transitions.
+	/// On inputs in both alphabets, both DFAs take a transition together & the pair has no transition if either has none.
+	/// On inputs of a single alphabet, only that DFA takes a transition while the other keeps its state.
+	/// A pair accepts if both states accept.
+	/// Only pairs reachable from the pair of initial states are built & the result has no states if either DFA has no initial state.
+	pub fn parallel<R, U>(&self, other: &DFA<R, I, U>) -> DFA<(S, R), I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		let (graph, other_graph) = (self.graph(), other.graph());
+		if graph.initial.is_empty() || other_graph.initial.is_empty() {
+			return DFA::default();
+		}
+		let shared = alphabet(&graph, self.alphabet.as_ref())
+			.intersection(&alphabet(&other_graph, other.alphabet.as_ref()))
+			.cloned()
+			.collect();
+		let (pairs, rows) = synchronize(&graph, &other_graph, &shared);
+		let labels: Vec<_> = pairs
+			.iter()
+			.map(|(state, other_state)| {
+				(
+					graph.labels[*state].clone(),
+					other_graph.labels[*other_state].clone(),
+				)
+			})
+			.collect();
+		let accepts = pairs
+			.iter()
+			.map(|(state, other_state)| graph.accepts[*state] && other_graph.accepts[*other_state]);
+		build(labels, accepts, rows)
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Clone,
+{
+	/// Returns the parallel composition of this & another NFA, synchronizing on shared inputs like `DFA::parallel`.
+	///
+	/// Epsilon transitions are removed beforehand.
+	/// The initial states are all pairs of initial states.
+	pub fn parallel<R, U>(&self, other: &NFA<R, I, U>) -> NFA<(S, R), I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+		U: Clone,
+	{
+		let (first, second) = (self.remove_epsilons(), other.remove_epsilons());
+		let (graph, other_graph) = (first.graph(), second.graph());
+		let shared = alphabet(&graph, first.alphabet.as_ref())
+			.intersection(&alphabet(&other_graph, second.alphabet.as_ref()))
+			.cloned()
+			.collect();
+		let (pairs, rows) = synchronize(&graph, &other_graph, &shared);
+		let label = |(state, other_state): (usize, usize)| {
+			(
+				graph.labels[state].clone(),
+				other_graph.labels[other_state].clone(),
+			)
+		};
+		let mut nfa = NFA::new();
+		for pair in &pairs {
+			nfa.add_state(
+				label(*pair),
+				graph.accepts[pair.0] && other_graph.accepts[pair.1],
+			);
+		}
+		for (pair, row) in pairs.iter().zip(rows) {
+			for (input, next) in row {
+				nfa.add_transition((label(*pair), input.clone(), label(pairs[next])))
+					.map_err(AutomatonError::without_input)
+					.expect("Transition between inexistent pairs");
+			}
+		}
+		let initial = graph.initial.len() * other_graph.initial.len();
+		nfa.set_initial(pairs[..initial].iter().map(|pair| label(*pair)).collect());
+		nfa.reset();
+		nfa
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::hashmap;
+
+	/// Producer alternating between `produce` & `put`.
+	fn producer() -> DFA<&'static str, &'static str> {
+		DFA::from_map(
+			"idle",
+			hashmap!(
+				"idle" => (true, hashmap!("produce" => "full")),
+				"full" => (false, hashmap!("put" => "idle"))
+			),
+		)
+	}
+
+	/// Consumer alternating between `put` & `consume`.
+	fn consumer() -> DFA<u8, &'static str> {
+		DFA::from_map(
+			0,
+			hashmap!(
+				0 => (true, hashmap!("put" => 1)),
+				1 => (false, hashmap!("consume" => 0))
+			),
+		)
+	}
+
+	#[test]
+	fn synchronize() {
+		let mut system = producer().parallel(&consumer());
+		assert_eq!(
+			Some(&("idle", 0)),
+			system.get_current(),
+			"Incorrect initial pair"
+		);
+		assert!(
+			system.run(&["produce", "put", "produce", "consume", "put", "consume"]),
+			"Interleaving rejected"
+		);
+		assert!(
+			!system.run(&["put"]),
+			"Unsynchronized shared input accepted"
+		);
+		assert!(
+			!system.run(&["produce", "produce"]),
+			"Private input without transition accepted"
+		);
+		assert_eq!(4, system.stats().states, "Incorrect number of pairs");
+	}
+
+	#[test]
+	fn declared() {
+		// declaring `consume` makes the producer block it
+		let mut producer = producer();
+		producer.declare_alphabet(vec!["produce", "put", "consume"]);
+		let mut system = producer.parallel(&consumer());
+		assert!(
+			!system.run(&["produce", "put", "consume"]),
+			"Blocked input accepted"
+		);
+
+		let mut nfa = NFA::<u8, &str>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_transition((0, "produce", 1)).unwrap();
+		nfa.add_transition((0, "produce", 0)).unwrap();
+		let mut both = nfa.parallel(&consumer().to_nfa(&Default::default()).unwrap());
+		assert!(
+			both.run(&["produce", "produce"]),
+			"Nondeterministic interleaving rejected"
+		);
+	}
+}
@@ -0,0 +1,266 @@
+//! Partition refinement over elements numbered from 0, the engine behind the minimization of DFAs.
+//!
+//! A `Partition` starts from an initial partition, e.g. by acceptance of states, & is refined by splitting its classes
+//! until it is stable, which yields the coarsest equivalence with the desired property.
+//! Classes are split against sets of elements by `split`, against keys of the elements by `split_by`
+//! or against a labeled transition relation by `refine`, which computes bisimilarity.
+//!
+//! Classes are numbered in order of creation & splitting keeps the old number for the elements left behind,
+//! so the numbering only depends on the order of the operations.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Partition of elements numbered from 0 into disjoint classes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+	class: Vec<usize>,
+	classes: Vec<Vec<usize>>,
+	marked: Vec<bool>,
+}
+
+impl Partition {
+	/// Creates a partition of a number of elements into a single class.
+	/// The partition has no classes if there are no elements.
+	pub fn new(len: usize) -> Self {
+		Self {
+			class: vec![0; len],
+			classes: if len > 0 {
+				vec![(0..len).collect()]
+			} else {
+				Vec::new()
+			},
+			marked: vec![false; len],
+		}
+	}
+
+	/// Creates a partition grouping the elements with equal keys.
+	/// Classes are numbered in order of their first element.
+	pub fn by_key<K>(keys: &[K]) -> Self
+	where
+		K: Eq + Hash,
+	{
+		let mut numbers = HashMap::new();
+		let mut classes: Vec<Vec<usize>> = Vec::new();
+		let class = keys
+			.iter()
+			.enumerate()
+			.map(|(el, key)| {
+				let class = *numbers.entry(key).or_insert_with(|| {
+					classes.push(Vec::new());
+					classes.len() - 1
+				});
+				classes[class].push(el);
+				class
+			})
+			.collect();
+		Self {
+			class,
+			classes,
+			marked: vec![false; keys.len()],
+		}
+	}
+
+	/// Returns the number of classes.
+	pub fn len(&self) -> usize {
+		self.classes.len()
+	}
+
+	/// Checks whether there are no classes.
+	pub fn is_empty(&self) -> bool {
+		self.classes.is_empty()
+	}
+
+	/// Returns the class of an element.
+	pub fn class_of(&self, el: usize) -> usize {
+		self.class[el]
+	}
+
+	/// Returns the class of each element.
+	pub fn assignment(&self) -> &[usize] {
+		&self.class
+	}
+
+	/// Returns the elements of a class in an arbitrary order.
+	pub fn members(&self, class: usize) -> &[usize] {
+		&self.classes[class]
+	}
+
+	/// Returns the elements of each class.
+	pub fn classes(&self) -> &[Vec<usize>] {
+		&self.classes
+	}
+
+	/// Splits every class with marked elements into its unmarked & its marked elements.
+	/// Duplicate elements count once.
+	///
+	/// The marked elements of a class form a new class, unless they are all of its elements.
+	/// Returns the pairs of split & new classes, in order of the first marked element of the split class.
+	pub fn split<V>(&mut self, marked: V) -> Vec<(usize, usize)>
+	where
+		V: IntoIterator<Item = usize>,
+	{
+		let mut touched = Vec::new();
+		let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+		for el in marked {
+			if !self.marked[el] {
+				self.marked[el] = true;
+				let class = self.class[el];
+				groups
+					.entry(class)
+					.or_insert_with(|| {
+						touched.push(class);
+						Vec::new()
+					})
+					.push(el);
+			}
+		}
+		let mut splits = Vec::new();
+		for old in touched {
+			let split = groups.remove(&old).unwrap_or_default();
+			for el in &split {
+				self.marked[*el] = false;
+			}
+			if split.len() == self.classes[old].len() {
+				continue;
+			}
+			let new = self.classes.len();
+			for el in &split {
+				self.class[*el] = new;
+			}
+			let class = &self.class;
+			self.classes[old].retain(|el| class[*el] == old);
+			self.classes.push(split);
+			splits.push((old, new));
+		}
+		splits
+	}
+
+	/// Splits every class into groups of elements with equal keys.
+	/// The group of the first element in a class keeps the class, the other groups form new classes.
+	/// Returns whether any class was split.
+	pub fn split_by<K, F>(&mut self, mut key: F) -> bool
+	where
+		K: Eq + Hash,
+		F: FnMut(usize) -> K,
+	{
+		let mut changed = false;
+		for class in 0..self.classes.len() {
+			let mut groups: Vec<Vec<usize>> = Vec::new();
+			let mut numbers = HashMap::new();
+			for el in &self.classes[class] {
+				let group = *numbers.entry(key(*el)).or_insert_with(|| {
+					groups.push(Vec::new());
+					groups.len() - 1
+				});
+				groups[group].push(*el);
+			}
+			if groups.len() > 1 {
+				changed = true;
+				for group in groups.drain(1..) {
+					let new = self.classes.len();
+					for el in &group {
+						self.class[*el] = new;
+					}
+					self.classes.push(group);
+				}
+				self.classes[class] = groups.pop().unwrap_or_default();
+			}
+		}
+		changed
+	}
+
+	/// Refines the partition until it is stable for a labeled transition relation, given as edges of each element.
+	///
+	/// In a stable partition, the elements of a class have edges with the same labels into the same classes.
+	/// Starting from the partition by acceptance, this computes bisimilarity of the states of an NFA
+	/// or equivalence of the states of a DFA.
+	/// Each class is used as splitter after its last change, which takes `O(m * n)` time for `m` edges & `n` elements.
+	pub fn refine<L>(&mut self, edges: &[Vec<(L, usize)>])
+	where
+		L: Eq + Hash,
+	{
+		// labels numbered in order of appearance
+		let mut labels = HashMap::new();
+		let mut predecessors = vec![Vec::new(); self.class.len()];
+		for (from, edges) in edges.iter().enumerate() {
+			for (label, to) in edges {
+				let len = labels.len();
+				let label = *labels.entry(label).or_insert(len);
+				predecessors[*to].push((label, from));
+			}
+		}
+		let mut queued = vec![true; self.len()];
+		let mut queue: Vec<usize> = (0..self.len()).rev().collect();
+		while let Some(splitter) = queue.pop() {
+			queued[splitter] = false;
+			let mut marked: Vec<(usize, usize)> = self.classes[splitter]
+				.iter()
+				.flat_map(|el| predecessors[*el].iter().cloned())
+				.collect();
+			marked.sort_unstable();
+			for group in marked.chunk_by(|(first, _), (second, _)| first == second) {
+				for (old, new) in self.split(group.iter().map(|(_, from)| *from)) {
+					queued.resize(self.len(), false);
+					for class in [old, new] {
+						if !queued[class] {
+							queued[class] = true;
+							queue.push(class);
+						}
+					}
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn split() {
+		let mut partition = Partition::by_key(&[true, false, true, true, false]);
+		assert_eq!(
+			&[0, 1, 0, 0, 1],
+			partition.assignment(),
+			"Incorrect classes"
+		);
+		assert_eq!(
+			vec![(0, 2)],
+			partition.split(vec![3, 2, 3, 1, 4]),
+			"Incorrect splits"
+		);
+		assert_eq!(&[0, 1, 2, 2, 1], partition.assignment(), "Incorrect split");
+		assert!(partition.split_by(|el| el % 2), "Split by key not reported");
+		assert_eq!(
+			&[0, 1, 4, 2, 3],
+			partition.assignment(),
+			"Incorrect split by key"
+		);
+		assert!(Partition::new(0).is_empty(), "Classes without elements");
+	}
+
+	#[test]
+	fn refine() {
+		// `a.(b + c)` from 0 & `a.b + a.c` from 4 accept the same words, but are not bisimilar
+		let edges = vec![
+			vec![('a', 1)],
+			vec![('b', 2), ('c', 3)],
+			vec![],
+			vec![],
+			vec![('a', 5), ('a', 6)],
+			vec![('b', 7)],
+			vec![('c', 8)],
+			vec![],
+			vec![],
+		];
+		let accepts = [false, false, true, true, false, false, false, true, true];
+		let mut partition = Partition::by_key(&accepts);
+		partition.refine(&edges);
+		let class = partition.assignment();
+		assert_eq!(class[2], class[8], "Accepting sinks distinguished");
+		assert_ne!(class[0], class[4], "Non-bisimilar states merged");
+		assert_ne!(class[5], class[6], "Different continuations merged");
+		assert_eq!(6, partition.len(), "Incorrect number of classes");
+	}
+}
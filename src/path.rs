@@ -0,0 +1,221 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{fmt, hash::Hash};
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Searches for the shortest sequence of inputs leading from one of the given states to a target state.
+	fn shortest_path<V, F>(&self, from: V, to: F) -> Option<Vec<&'a I>>
+	where
+		V: IntoIterator<Item = usize>,
+		F: Fn(usize) -> bool,
+	{
+		let mut parents: Vec<Option<Option<(usize, &'a I)>>> = vec![None; self.len()];
+		let mut queue = Vec::new();
+		for state in from {
+			if parents[state].is_none() {
+				parents[state] = Some(None);
+				queue.push(state);
+			}
+		}
+		let mut i = 0;
+		while i < queue.len() {
+			let state = queue[i];
+			if to(state) {
+				let mut path = Vec::new();
+				let mut current = state;
+				while let Some(Some((parent, input))) = parents[current] {
+					path.push(input);
+					current = parent;
+				}
+				path.reverse();
+				return Some(path);
+			}
+			for (input, next) in &self.edges[state] {
+				if parents[*next].is_none() {
+					parents[*next] = Some(Some((state, *input)));
+					queue.push(*next);
+				}
+			}
+			i += 1;
+		}
+		None
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the shortest sequence of inputs leading from the initial state to a state.
+	/// Returns `None` if the state is inexistent or unreachable.
+	pub fn path_to(&self, id: &S) -> Option<Vec<I>> {
+		let graph = self.graph();
+		let to = *graph.indices().get(id)?;
+		graph
+			.shortest_path(graph.initial.clone(), |state| state == to)
+			.map(|path| path.into_iter().cloned().collect())
+	}
+
+	/// Returns the shortest sequence of inputs leading from one state to another.
+	/// Returns `None` if one of the states is inexistent or the second state is unreachable from the first.
+	pub fn path_between(&self, from: &S, to: &S) -> Option<Vec<I>> {
+		let graph = self.graph();
+		let indices = graph.indices();
+		let (from, to) = (*indices.get(from)?, *indices.get(to)?);
+		graph
+			.shortest_path(Some(from), |state| state == to)
+			.map(|path| path.into_iter().cloned().collect())
+	}
+
+	/// Returns the shortest word accepted by the DFA.
+	/// Returns `None` if the DFA accepts no words.
+	pub fn shortest_word(&self) -> Option<Vec<I>> {
+		let graph = self.graph();
+		graph
+			.shortest_path(graph.initial.clone(), |state| graph.accepts[state])
+			.map(|path| path.into_iter().cloned().collect())
+	}
+
+	/// Checks whether the DFA accepts no words, i.e. no accepting state is reachable from the initial state.
+	/// See `shortest_word` for a witness if it accepts words.
+	pub fn is_empty(&self) -> bool {
+		self.shortest_word().is_none()
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the shortest sequence of inputs leading from one of the initial states to a state, following epsilon transitions.
+	/// Returns `None` if the state is inexistent or unreachable.
+	pub fn path_to(&self, id: &S) -> Option<Vec<I>> {
+		let graph = self.graph();
+		let to = *graph.indices().get(id)?;
+		graph
+			.shortest_path(graph.initial.clone(), |state| state == to)
+			.map(|path| path.into_iter().cloned().collect())
+	}
+
+	/// Returns the shortest sequence of inputs leading from one state to another, following epsilon transitions.
+	/// Returns `None` if one of the states is inexistent or the second state is unreachable from the first.
+	pub fn path_between(&self, from: &S, to: &S) -> Option<Vec<I>> {
+		let graph = self.graph();
+		let indices = graph.indices();
+		let to = *indices.get(to)?;
+		let from = self.epsilon_closure(&Some(*self.index.get(from)?).into_iter().collect());
+		graph
+			.shortest_path(
+				from.into_iter()
+					.filter_map(|index| indices.get(&self.ids[index]).cloned()),
+				|state| state == to,
+			)
+			.map(|path| path.into_iter().cloned().collect())
+	}
+
+	/// Returns the shortest word accepted by the NFA, following epsilon transitions.
+	/// Returns `None` if the NFA accepts no words.
+	pub fn shortest_word(&self) -> Option<Vec<I>> {
+		let graph = self.graph();
+		graph
+			.shortest_path(graph.initial.clone(), |state| graph.accepts[state])
+			.map(|path| path.into_iter().cloned().collect())
+	}
+
+	/// Checks whether the NFA accepts no words, i.e. no accepting state is reachable from the initial states.
+	/// See `shortest_word` for a witness if it accepts words.
+	pub fn is_empty(&self) -> bool {
+		self.shortest_word().is_none()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{hashmap, hashset};
+
+	#[test]
+	fn dfa() {
+		let dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1, 'b' => 0)),
+				1 => (false, hashmap!('a' => 2, 'b' => 0)),
+				2 => (true, hashmap!()),
+				3 => (true, hashmap!('a' => 0))
+			),
+		);
+		assert_eq!(
+			Some(vec![]),
+			dfa.path_to(&0),
+			"Incorrect path to initial state"
+		);
+		assert_eq!(Some(vec!['a', 'a']), dfa.path_to(&2), "Incorrect path");
+		assert_eq!(None, dfa.path_to(&3), "Path to unreachable state");
+		assert_eq!(None, dfa.path_to(&7), "Path to inexistent state");
+		assert_eq!(
+			Some(vec!['a', 'a', 'a']),
+			dfa.path_between(&3, &2),
+			"Incorrect path between states"
+		);
+		assert_eq!(None, dfa.path_between(&2, &0), "Path from dead end");
+	}
+
+	#[test]
+	fn nfa() {
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0, 1],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![0, 2])),
+				1 => (false, hashmap!('b' => hashset![2])),
+				2 => (true, hashmap!('c' => hashset![3])),
+				3 => (false, hashmap!())
+			),
+		);
+		let path = nfa.path_to(&2).unwrap();
+		assert_eq!(1, path.len(), "Path to state not shortest");
+		assert_eq!(Some(vec!['c']), nfa.path_between(&2, &3), "Incorrect path");
+		assert_eq!(None, nfa.path_between(&3, &0), "Path from dead end");
+	}
+
+	#[test]
+	fn empty() {
+		let mut dfa = DFA::<u8, char>::with_state(0, false);
+		dfa.add_state(1, false);
+		dfa.add_state(2, true);
+		dfa.add_transition((0, 'a', 1)).unwrap();
+		dfa.add_transition((1, 'b', 0)).unwrap();
+		assert!(dfa.is_empty(), "Unreachable accepting state found");
+		dfa.add_transition((1, 'a', 2)).unwrap();
+		assert_eq!(
+			Some(vec!['a', 'a']),
+			dfa.shortest_word(),
+			"Incorrect witness"
+		);
+		assert!(DFA::<u8, char>::new().is_empty(), "Word without states");
+
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, true);
+		assert!(nfa.is_empty(), "Unreachable accepting state found");
+		nfa.add_epsilon((0, 1)).unwrap();
+		assert_eq!(
+			Some(vec![]),
+			nfa.shortest_word(),
+			"Epsilon transition not followed"
+		);
+	}
+
+	#[test]
+	fn epsilon() {
+		let nfa = crate::regex::compile("ab*").unwrap();
+		assert_eq!(Some(vec!['a']), nfa.path_to(&5), "Incorrect path");
+		assert_eq!(
+			Some(vec![]),
+			nfa.path_between(&1, &5),
+			"Epsilon transitions not followed"
+		);
+		assert_eq!(Some(vec!['b']), nfa.path_between(&4, &3), "Incorrect path");
+	}
+}
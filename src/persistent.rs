@@ -0,0 +1,486 @@
+use super::{AutomatonError, DFA};
+use std::{
+	collections::hash_map::DefaultHasher,
+	fmt,
+	hash::{Hash, Hasher},
+	sync::Arc,
+};
+
+/// Number of hash bits used per level of a trie.
+const BITS: u32 = 4;
+
+/// Number of children of a branch.
+const WIDTH: usize = 1 << BITS;
+
+/// Node of a hash trie, with the entries of a single hash in a leaf.
+#[derive(Debug, Clone)]
+enum Node<K, V> {
+	Branch(Vec<Option<Arc<Node<K, V>>>>),
+	Leaf(u64, Vec<(K, V)>),
+}
+
+/// Returns the hash of a key.
+fn hash<K>(key: &K) -> u64
+where
+	K: Hash,
+{
+	let mut hasher = DefaultHasher::new();
+	key.hash(&mut hasher);
+	hasher.finish()
+}
+
+/// Returns the child of a branch holding a hash at a shift.
+fn slot(hash: u64, shift: u32) -> usize {
+	((hash >> shift) as usize) & (WIDTH - 1)
+}
+
+/// Persistent hash map, sharing all nodes not on the path to a changed entry between versions.
+#[derive(Debug)]
+struct Map<K, V> {
+	root: Option<Arc<Node<K, V>>>,
+	len: usize,
+}
+
+impl<K, V> Clone for Map<K, V> {
+	fn clone(&self) -> Self {
+		Self {
+			root: self.root.clone(),
+			len: self.len,
+		}
+	}
+}
+
+impl<K, V> Default for Map<K, V> {
+	fn default() -> Self {
+		Self { root: None, len: 0 }
+	}
+}
+
+impl<K, V> Map<K, V>
+where
+	K: Clone + Eq + Hash,
+	V: Clone,
+{
+	/// Returns the value of a key.
+	fn get(&self, key: &K) -> Option<&V> {
+		let hash = hash(key);
+		let mut node = self.root.as_deref()?;
+		let mut shift = 0;
+		loop {
+			match node {
+				Node::Branch(children) => {
+					node = children[slot(hash, shift)].as_deref()?;
+					shift += BITS;
+				}
+				Node::Leaf(_, entries) => {
+					return entries
+						.iter()
+						.find(|(other, _)| other == key)
+						.map(|(_, value)| value)
+				}
+			}
+		}
+	}
+
+	/// Returns a new version of the map with a key set to a value.
+	fn insert(&self, key: K, value: V) -> Self {
+		let (root, added) = insert(self.root.as_ref(), hash(&key), 0, key, value);
+		Self {
+			root: Some(root),
+			len: self.len + added as usize,
+		}
+	}
+
+	/// Returns the entries of the map in an arbitrary order.
+	fn entries(&self) -> Vec<(&K, &V)> {
+		let mut entries = Vec::with_capacity(self.len);
+		let mut stack: Vec<&Node<K, V>> = self.root.as_deref().into_iter().collect();
+		while let Some(node) = stack.pop() {
+			match node {
+				Node::Branch(children) => stack.extend(children.iter().flatten().map(Arc::as_ref)),
+				Node::Leaf(_, leaf) => entries.extend(leaf.iter().map(|(key, value)| (key, value))),
+			}
+		}
+		entries
+	}
+}
+
+/// Inserts an entry below a node, copying the nodes on the path to it.
+/// Returns the new node & whether the key was added.
+fn insert<K, V>(
+	node: Option<&Arc<Node<K, V>>>,
+	hash: u64,
+	shift: u32,
+	key: K,
+	value: V,
+) -> (Arc<Node<K, V>>, bool)
+where
+	K: Clone + Eq,
+	V: Clone,
+{
+	let node = match node {
+		Some(node) => node,
+		None => return (Arc::new(Node::Leaf(hash, vec![(key, value)])), true),
+	};
+	match node.as_ref() {
+		Node::Branch(children) => {
+			let index = slot(hash, shift);
+			let (child, added) = insert(children[index].as_ref(), hash, shift + BITS, key, value);
+			let mut children = children.clone();
+			children[index] = Some(child);
+			(Arc::new(Node::Branch(children)), added)
+		}
+		Node::Leaf(other, entries) if *other == hash => {
+			let mut entries = entries.clone();
+			let added = match entries.iter_mut().find(|(other, _)| *other == key) {
+				Some(entry) => {
+					entry.1 = value;
+					false
+				}
+				None => {
+					entries.push((key, value));
+					true
+				}
+			};
+			(Arc::new(Node::Leaf(hash, entries)), added)
+		}
+		Node::Leaf(other, _) => {
+			// different hashes differ at some later level, so the leaf moves into a new branch
+			let mut children = vec![None; WIDTH];
+			children[slot(*other, shift)] = Some(node.clone());
+			let branch = Arc::new(Node::Branch(children));
+			insert(Some(&branch), hash, shift, key, value)
+		}
+	}
+}
+
+/// State of a persistent DFA with the targets & payloads of its transitions.
+#[derive(Debug)]
+struct State<S, I, T> {
+	accepts: bool,
+	transitions: Map<I, (S, T)>,
+}
+
+impl<S, I, T> Clone for State<S, I, T> {
+	fn clone(&self) -> Self {
+		Self {
+			accepts: self.accepts,
+			transitions: self.transitions.clone(),
+		}
+	}
+}
+
+/// Immutable DFA, where every change returns a new version sharing most of its structure with the old one.
+///
+/// States & transitions are stored in persistent hash tries, so a change only copies the nodes on the path to it
+/// & keeping many speculative versions of a large DFA is cheap.
+/// Cloning a persistent DFA is constant time.
+/// Unlike `DFA`, there is no declared alphabet & no display names.
+pub struct PersistentDFA<S, I, T = ()>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Clone + Eq + Hash,
+	T: Clone,
+{
+	states: Map<S, State<S, I, T>>,
+	initial: Option<S>,
+	current: Option<S>,
+}
+
+impl<S, I, T> Clone for PersistentDFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Clone + Eq + Hash,
+	T: Clone,
+{
+	fn clone(&self) -> Self {
+		Self {
+			states: self.states.clone(),
+			initial: self.initial.clone(),
+			current: self.current.clone(),
+		}
+	}
+}
+
+impl<S, I, T> Default for PersistentDFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Clone + Eq + Hash,
+	T: Clone,
+{
+	fn default() -> Self {
+		Self {
+			states: Map::default(),
+			initial: None,
+			current: None,
+		}
+	}
+}
+
+impl<S, I, T> PersistentDFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Clone + Eq + Hash,
+	T: Clone,
+{
+	/// Creates a new persistent DFA without states.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a new persistent DFA with a single state, which is the initial & current state.
+	pub fn with_state(id: S, accepts: bool) -> Self {
+		Self::new()
+			.add_state(id.clone(), accepts)
+			.set_initial(id)
+			.reset()
+	}
+
+	/// Returns the number of states.
+	pub fn len(&self) -> usize {
+		self.states.len
+	}
+
+	/// Checks whether there are no states.
+	pub fn is_empty(&self) -> bool {
+		self.states.len == 0
+	}
+
+	/// Checks whether a state exists.
+	pub fn has_state(&self, id: &S) -> bool {
+		self.states.get(id).is_some()
+	}
+
+	/// Returns a new version with an added state.
+	/// An existing state with the same ID is replaced, dropping its transitions.
+	pub fn add_state(&self, id: S, accepts: bool) -> Self {
+		Self {
+			states: self.states.insert(
+				id,
+				State {
+					accepts,
+					transitions: Map::default(),
+				},
+			),
+			..self.clone()
+		}
+	}
+
+	/// Returns a new version with an added transition carrying a payload, replacing a transition for the same input.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	pub fn add_transition_with_payload(
+		&self,
+		transition: (S, I, S),
+		payload: T,
+	) -> Result<Self, AutomatonError<S>> {
+		let (prev, input, next) = transition;
+		let state = match self.states.get(&prev) {
+			Some(state) => state,
+			None => return Err(AutomatonError::InexistentState(prev, Vec::new())),
+		};
+		if !self.has_state(&next) {
+			return Err(AutomatonError::InexistentState(next, Vec::new()));
+		}
+		let state = State {
+			accepts: state.accepts,
+			transitions: state.transitions.insert(input, (next, payload)),
+		};
+		Ok(Self {
+			states: self.states.insert(prev, state),
+			..self.clone()
+		})
+	}
+
+	/// Returns a new version with an added transition.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	pub fn add_transition(&self, transition: (S, I, S)) -> Result<Self, AutomatonError<S>>
+	where
+		T: Default,
+	{
+		self.add_transition_with_payload(transition, T::default())
+	}
+
+	/// Returns a new version with a different initial state.
+	/// The initial state is unset if the state is inexistent.
+	pub fn set_initial(&self, id: S) -> Self {
+		Self {
+			initial: Some(id).filter(|id| self.has_state(id)),
+			..self.clone()
+		}
+	}
+
+	/// Returns the initial state.
+	pub fn get_initial(&self) -> Option<&S> {
+		self.initial.as_ref()
+	}
+
+	/// Returns the current state.
+	/// Returns `None` if the DFA is in an invalid state.
+	pub fn get_current(&self) -> Option<&S> {
+		self.current.as_ref()
+	}
+
+	/// Returns a new version with the current state reset to the initial state.
+	pub fn reset(&self) -> Self {
+		Self {
+			current: self.initial.clone(),
+			..self.clone()
+		}
+	}
+
+	/// Returns the target of the transition from a state with an input.
+	pub fn next(&self, id: &S, input: &I) -> Option<&S> {
+		self.states
+			.get(id)
+			.and_then(|state| state.transitions.get(input))
+			.map(|(next, _)| next)
+	}
+
+	/// Checks whether the current state is accepting.
+	pub fn accepts(&self) -> bool {
+		self.current
+			.as_ref()
+			.and_then(|id| self.states.get(id))
+			.is_some_and(|state| state.accepts)
+	}
+
+	/// Returns a new version after a single state transition.
+	pub fn step(&self, input: &I) -> Self {
+		Self {
+			current: self
+				.current
+				.as_ref()
+				.and_then(|id| self.next(id, input))
+				.cloned(),
+			..self.clone()
+		}
+	}
+
+	/// Runs the DFA on a sequence of inputs from the current state & checks whether it accepts afterwards.
+	pub fn run<'a, V>(&self, inputs: V) -> bool
+	where
+		V: IntoIterator<Item = &'a I>,
+		I: 'a,
+	{
+		let mut current = self.current.as_ref();
+		for input in inputs {
+			current = current.and_then(|id| self.next(id, input));
+		}
+		current
+			.and_then(|id| self.states.get(id))
+			.is_some_and(|state| state.accepts)
+	}
+
+	/// Converts the persistent DFA into a `DFA`.
+	pub fn to_dfa(&self) -> DFA<S, I, T>
+	where
+		I: Default,
+	{
+		let mut dfa = DFA::from_states_map(self.states.entries().into_iter().map(|(id, state)| {
+			let transitions = state
+				.transitions
+				.entries()
+				.into_iter()
+				.map(|(input, (next, payload))| (input.clone(), (next.clone(), payload.clone())));
+			(id.clone(), (state.accepts, transitions))
+		}));
+		dfa.initial = self.initial.as_ref().and_then(|id| dfa.index_of(id));
+		dfa.current = self.current.as_ref().and_then(|id| dfa.index_of(id));
+		dfa
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Clone,
+{
+	/// Converts the DFA into a `PersistentDFA` with the same states, transitions, initial & current state.
+	pub fn to_persistent(&self) -> PersistentDFA<S, I, T> {
+		let mut persistent = PersistentDFA::new();
+		for (index, state) in self.declared() {
+			persistent = persistent.add_state(self.ids[index].clone(), state.accepts);
+		}
+		for (index, state) in self.declared() {
+			for (input, (next, payload)) in &state.transitions {
+				if self.states[*next].is_some() {
+					persistent = persistent
+						.add_transition_with_payload(
+							(
+								self.ids[index].clone(),
+								input.clone(),
+								self.ids[*next].clone(),
+							),
+							payload.clone(),
+						)
+						.expect("Transition between inexistent states");
+				}
+			}
+		}
+		PersistentDFA {
+			initial: self.initial.map(|index| self.ids[index].clone()),
+			current: self.current.map(|index| self.ids[index].clone()),
+			..persistent
+		}
+	}
+}
+
+impl<S, I, T> fmt::Debug for PersistentDFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Clone + Eq + Hash + fmt::Debug,
+	T: Clone,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PersistentDFA")
+			.field("initial", &self.initial)
+			.field("current", &self.current)
+			.field("states", &self.states.len)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	#[test]
+	fn versions() {
+		let base = PersistentDFA::<u32, char>::with_state(0, false);
+		let mut chain = base.clone();
+		for state in 1..1000 {
+			chain = chain
+				.add_state(state, state == 999)
+				.add_transition((state - 1, 'a', state))
+				.unwrap();
+		}
+		let shortcut = chain.add_transition((0, 'b', 999)).unwrap();
+		assert_eq!(1000, chain.len(), "Incorrect number of states");
+		assert!(shortcut.run(&['b']), "New transition missing");
+		assert!(!chain.run(&['b']), "Old version changed");
+		assert_eq!(1, base.len(), "Base changed");
+		assert!(chain.run(vec!['a'; 999].iter()), "Chain rejected");
+		assert_eq!(Some(&1), chain.step(&'a').get_current(), "Incorrect step");
+		assert_eq!(Some(&0), chain.get_current(), "Stepping changed version");
+		assert!(
+			chain.add_transition((0, 'c', 1000)).is_err(),
+			"Transition to inexistent state"
+		);
+	}
+
+	#[test]
+	fn convert() {
+		let mut dfa = DFA::<u8, char>::with_state(0, false);
+		dfa.add_state(1, true);
+		dfa.add_transition((0, 'a', 1)).unwrap();
+		dfa.add_transition((1, 'a', 0)).unwrap();
+		let persistent = dfa.to_persistent().add_state(2, true);
+		let persistent = persistent.add_transition((1, 'b', 2)).unwrap();
+		let mut back = persistent.to_dfa();
+		assert!(back.run(&['a', 'b']), "Added transition missing");
+		assert!(!dfa.run(&['a', 'b']), "Original DFA changed");
+		assert_eq!(Some(&0), back.get_current(), "Current state lost");
+	}
+}
@@ -0,0 +1,219 @@
+use super::{nfa::Edge, AutomatonError, NFA};
+use std::{cmp::Reverse, fmt, hash::Hash};
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Ord + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Updates the priority of a transition, adding the transition if necessary.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	/// Returns an `AutomatonError::UnknownInput` error if the input is not part of the declared alphabet.
+	///
+	/// Alternatives are ordered by ascending priority, so smaller numbers take precedence.
+	/// Transitions have a priority of 0 by default.
+	pub fn set_priority(
+		&mut self,
+		transition: (S, I, S),
+		priority: u32,
+	) -> Result<(), AutomatonError<S, I>>
+	where
+		T: Default,
+	{
+		let (prev, input, next) = transition;
+		if !self.knows(&input) {
+			return Err(AutomatonError::UnknownInput(input));
+		}
+		let next = self
+			.index_of(&next)
+			.ok_or(AutomatonError::InexistentState(next, Vec::new()))?;
+		let prev = self
+			.index_of(&prev)
+			.ok_or(AutomatonError::InexistentState(prev, Vec::new()))?;
+		if let Some(state) = self.states[prev].as_mut() {
+			let edge = state
+				.transitions
+				.entry(input)
+				.or_default()
+				.entry(next)
+				.or_insert_with(|| Edge::new(T::default()));
+			edge.priority = priority;
+		}
+		Ok(())
+	}
+
+	/// Returns the priority of the transition between two states with an input.
+	pub fn priority(&self, prev: &S, input: &I, next: &S) -> Option<u32> {
+		let next = self.index_of(next)?;
+		self.index_of(prev)
+			.and_then(|index| self.state(index))
+			.and_then(|state| state.transitions.get(input))
+			.and_then(|transitions| transitions.get(&next))
+			.map(|edge| edge.priority)
+	}
+
+	/// Returns the states reached from a state with an input, ordered by preference.
+	///
+	/// Alternatives with equal priority are ordered by their states.
+	pub fn alternatives(&self, id: &S, input: &I) -> Vec<&S> {
+		match self.index_of(id) {
+			Some(index) => self
+				.ordered(index, input)
+				.into_iter()
+				.map(|index| &self.ids[index])
+				.collect(),
+			None => Vec::new(),
+		}
+	}
+
+	/// Runs the automaton on a sequence of inputs starting from the current states, resolving ambiguity by priority.
+	/// Returns the accepting state reached by the preferred alternative.
+	///
+	/// Current states are ordered by their IDs & every state is only followed by its most preferred path.
+	/// Epsilon transitions are followed before & after every input, see `closure`.
+	/// This does not change the current states.
+	pub fn run_leftmost<'a, V>(&self, inputs: V) -> Option<&S>
+	where
+		V: IntoIterator<Item = &'a I>,
+		I: 'a,
+	{
+		let mut current: Vec<_> = self.current_indices().iter().cloned().collect();
+		current.sort_by_key(|index| &self.ids[*index]);
+		let mut threads = self.closure(current);
+		for input in inputs {
+			let next: Vec<_> = threads
+				.into_iter()
+				.flat_map(|thread| self.ordered(thread, input))
+				.collect();
+			threads = self.closure(next);
+		}
+		threads
+			.into_iter()
+			.find(|index| matches!(self.state(*index), Some(state) if state.accepts))
+			.map(|index| &self.ids[index])
+	}
+
+	/// Returns the epsilon closure of states ordered by preference, without duplicates.
+	///
+	/// Each state is directly followed by the states reachable from it by epsilon transitions in depth-first order,
+	/// visiting the targets of a state ordered by their IDs.
+	fn closure<V>(&self, threads: V) -> Vec<usize>
+	where
+		V: IntoIterator<Item = usize>,
+	{
+		let mut seen = vec![false; self.states.len()];
+		let mut closure = Vec::new();
+		for thread in threads {
+			let mut stack = vec![thread];
+			while let Some(index) = stack.pop() {
+				if let Some(state) = self.state(index).filter(|_| !seen[index]) {
+					seen[index] = true;
+					closure.push(index);
+					let mut targets: Vec<_> = state.epsilon.iter().cloned().collect();
+					targets.sort_by_key(|target| Reverse(&self.ids[*target]));
+					stack.extend(targets);
+				}
+			}
+		}
+		closure
+	}
+
+	/// Returns the indices of the existing states reached from a state with an input, ordered by preference.
+	fn ordered(&self, index: usize, input: &I) -> Vec<usize> {
+		let mut next: Vec<_> = self
+			.state(index)
+			.and_then(|state| state.transitions.get(input))
+			.into_iter()
+			.flatten()
+			.filter(|(next, _)| self.states[**next].is_some())
+			.map(|(next, edge)| (edge.priority, *next))
+			.collect();
+		next.sort_by_key(|(priority, next)| (*priority, &self.ids[*next]));
+		next.into_iter().map(|(_, next)| next).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	/// Two alternatives for "ab" ending in different accepting states.
+	fn nfa() -> NFA<u8, char> {
+		let mut nfa = NFA::with_state(0, false);
+		nfa.add_state(1, false);
+		nfa.add_state(2, false);
+		nfa.add_state(3, true);
+		nfa.add_state(4, true);
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		nfa.add_transition((0, 'a', 2)).unwrap();
+		nfa.add_transition((1, 'b', 3)).unwrap();
+		nfa.add_transition((2, 'b', 4)).unwrap();
+		nfa
+	}
+
+	#[test]
+	fn leftmost() {
+		let mut nfa = nfa();
+		assert_eq!(
+			vec![&1, &2],
+			nfa.alternatives(&0, &'a'),
+			"Incorrect order by state"
+		);
+		assert_eq!(Some(&3), nfa.run_leftmost(&['a', 'b']), "Incorrect winner");
+
+		nfa.set_priority((0, 'a', 1), 1).unwrap();
+		assert_eq!(Some(1), nfa.priority(&0, &'a', &1), "Priority not set");
+		assert_eq!(
+			vec![&2, &1],
+			nfa.alternatives(&0, &'a'),
+			"Incorrect order by priority"
+		);
+		assert_eq!(Some(&4), nfa.run_leftmost(&['a', 'b']), "Incorrect winner");
+		assert_eq!(None, nfa.run_leftmost(&['b']), "Incorrect rejection");
+	}
+
+	#[test]
+	fn serialize() {
+		let mut nfa = nfa();
+		nfa.set_priority((0, 'a', 1), 2).unwrap();
+		assert!(
+			nfa.set_priority((0, 'a', 5), 1).is_err(),
+			"Missing error on inexistent state"
+		);
+		let yaml = serde_yaml::to_string(&nfa).unwrap();
+		let nfa: NFA<u8, char> = serde_yaml::from_str(&yaml).unwrap();
+		assert_eq!(Some(2), nfa.priority(&0, &'a', &1), "Priority not kept");
+		assert_eq!(
+			Some(0),
+			nfa.priority(&0, &'a', &2),
+			"Default priority changed"
+		);
+	}
+
+	#[test]
+	fn epsilon() {
+		let mut nfa = crate::regex::compile("ab|a(b)c").unwrap();
+		assert!(nfa.run(&['a', 'b']), "Word rejected");
+		assert!(
+			nfa.run_leftmost(&['a', 'b']).is_some(),
+			"Word rejected by leftmost run"
+		);
+		assert_eq!(None, nfa.run_leftmost(&['a']), "Incorrect rejection");
+	}
+
+	#[test]
+	fn declaration_order() {
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(2, true);
+		nfa.add_state(1, true);
+		nfa.add_transition((0, 'a', 2)).unwrap();
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		assert_eq!(
+			vec![&1, &2],
+			nfa.alternatives(&0, &'a'),
+			"Order depends on declaration"
+		);
+		assert_eq!(Some(&1), nfa.run_leftmost(&['a']), "Incorrect winner");
+	}
+}
@@ -0,0 +1,239 @@
+use super::{AutomatonError, Probability, Semiring, WeightedAutomaton};
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Tolerance of the sum of the probabilities of the transitions for a state & an input.
+const TOLERANCE: f64 = 1e-9;
+
+/// Enum representing an error in the probabilities of a `ProbabilisticAutomaton`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbabilityError<S, I> {
+	/// There is no initial state.
+	MissingInitial,
+
+	/// The probability of a transition is not between `0` & `1`.
+	OutOfRange(S, I, S, f64),
+
+	/// The probabilities of the transitions for a state & an input do not sum to `1`.
+	Unnormalized(S, I, f64),
+}
+
+impl<S, I> fmt::Display for ProbabilityError<S, I>
+where
+	S: fmt::Debug,
+	I: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingInitial => write!(f, "Missing initial State"),
+			Self::OutOfRange(prev, input, next, probability) => write!(
+				f,
+				"Probability {} of Transition {:?} -> {:?} for {:?} out of range",
+				probability, prev, next, input
+			),
+			Self::Unnormalized(id, input, sum) => write!(
+				f,
+				"Probabilities of State {:?} for {:?} sum to {}",
+				id, input, sum
+			),
+		}
+	}
+}
+
+/// A probabilistic automaton, where each transition is taken with a probability.
+///
+/// The transitions of a state for an input form a probability distribution over the next states.
+/// Inputs without transitions from a state lead to rejection, so probabilities only have to sum to `1`
+/// for the inputs with transitions, see `validate`.
+/// Runs start in the single initial state & the acceptance probability of a word
+/// is the probability of ending in an accepting state after reading it.
+#[derive(Debug, Clone)]
+pub struct ProbabilisticAutomaton<S, I>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+{
+	weighted: WeightedAutomaton<S, I, Probability>,
+	initial: Option<usize>,
+}
+
+impl<S, I> Default for ProbabilisticAutomaton<S, I>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+{
+	fn default() -> Self {
+		Self {
+			weighted: WeightedAutomaton::default(),
+			initial: None,
+		}
+	}
+}
+
+impl<S, I> ProbabilisticAutomaton<S, I>
+where
+	S: Clone + Eq + Hash + fmt::Debug,
+	I: Clone + Eq + Hash,
+{
+	/// Creates a new probabilistic automaton without states.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a new state or updates whether an existing state accepts.
+	pub fn add_state(&mut self, id: S, accepts: bool) {
+		let weight = if accepts {
+			Probability::one()
+		} else {
+			Probability::zero()
+		};
+		self.weighted.add_state(id, weight);
+	}
+
+	/// Checks whether a state exists.
+	pub fn has_state(&self, id: &S) -> bool {
+		self.weighted.has_state(id)
+	}
+
+	/// Sets the initial state.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn set_initial(&mut self, id: S) -> Result<(), AutomatonError<S>> {
+		let index = match self.weighted.index.get(&id) {
+			Some(index) => *index,
+			None => return Err(AutomatonError::InexistentState(id, Vec::new())),
+		};
+		if let Some(previous) = self.initial.replace(index) {
+			self.weighted.initial[previous] = Probability::zero();
+		}
+		self.weighted.initial[index] = Probability::one();
+		Ok(())
+	}
+
+	/// Returns the initial state.
+	pub fn get_initial(&self) -> Option<&S> {
+		self.initial.map(|index| &self.weighted.ids[index])
+	}
+
+	/// Adds a transition taken with a probability.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	///
+	/// Probabilities are not checked until `validate` is called.
+	pub fn add_transition(
+		&mut self,
+		transition: (S, I, S),
+		probability: f64,
+	) -> Result<(), AutomatonError<S>> {
+		self.weighted
+			.add_transition(transition, Probability(probability))
+	}
+
+	/// Checks whether the automaton has an initial state, every probability is between `0` & `1`
+	/// & the probabilities of the transitions of every state for every input with transitions sum to `1`.
+	/// Returns the first error found, checking states in order of their addition.
+	pub fn validate(&self) -> Result<(), ProbabilityError<S, I>> {
+		if self.initial.is_none() {
+			return Err(ProbabilityError::MissingInitial);
+		}
+		for (id, transitions) in self.weighted.ids.iter().zip(&self.weighted.transitions) {
+			for (input, transitions) in transitions {
+				let mut sum = 0.0;
+				for transition in transitions {
+					let probability = transition.weight.0;
+					if !(0.0..=1.0).contains(&probability) {
+						return Err(ProbabilityError::OutOfRange(
+							id.clone(),
+							input.clone(),
+							self.weighted.ids[transition.next].clone(),
+							probability,
+						));
+					}
+					sum += probability;
+				}
+				if (sum - 1.0).abs() > TOLERANCE {
+					return Err(ProbabilityError::Unnormalized(
+						id.clone(),
+						input.clone(),
+						sum,
+					));
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Returns the probability of being in each state after reading a word.
+	pub fn distribution(&self, word: &[I]) -> HashMap<&S, f64> {
+		self.weighted
+			.forward(word)
+			.into_iter()
+			.map(|(id, probability)| (id, probability.0))
+			.collect()
+	}
+
+	/// Returns the probability of ending in an accepting state after reading a word.
+	pub fn acceptance_probability(&self, word: &[I]) -> f64 {
+		self.weighted.weight(word).0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Coin flipped on `f`, which lands on heads with a probability of `0.75` & stays on heads once it did.
+	fn coin() -> ProbabilisticAutomaton<&'static str, char> {
+		let mut coin = ProbabilisticAutomaton::new();
+		coin.add_state("tails", false);
+		coin.add_state("heads", true);
+		coin.set_initial("tails").unwrap();
+		coin.add_transition(("tails", 'f', "heads"), 0.75).unwrap();
+		coin.add_transition(("tails", 'f', "tails"), 0.25).unwrap();
+		coin.add_transition(("heads", 'f', "heads"), 1.0).unwrap();
+		coin
+	}
+
+	#[test]
+	fn acceptance_probability() {
+		let coin = coin();
+		assert_eq!(Ok(()), coin.validate(), "Valid automaton rejected");
+		assert_eq!(0.0, coin.acceptance_probability(&[]), "Empty word accepted");
+		assert_eq!(
+			0.9375,
+			coin.acceptance_probability(&['f', 'f']),
+			"Incorrect probability"
+		);
+		assert_eq!(
+			0.0625,
+			coin.distribution(&['f', 'f'])[&"tails"],
+			"Incorrect distribution"
+		);
+		assert_eq!(
+			0.0,
+			coin.acceptance_probability(&['x']),
+			"Word without transitions accepted"
+		);
+	}
+
+	#[test]
+	fn validate() {
+		let mut coin = coin();
+		coin.add_transition(("heads", 'g', "tails"), 0.5).unwrap();
+		assert_eq!(
+			Err(ProbabilityError::Unnormalized("heads", 'g', 0.5)),
+			coin.validate(),
+			"Unnormalized probabilities accepted"
+		);
+		coin.add_transition(("heads", 'g', "heads"), 0.5).unwrap();
+		coin.add_transition(("tails", 'g', "heads"), 1.5).unwrap();
+		coin.add_transition(("tails", 'g', "tails"), -0.5).unwrap();
+		assert_eq!(
+			Err(ProbabilityError::OutOfRange("tails", 'g', "heads", 1.5)),
+			coin.validate(),
+			"Probability out of range accepted"
+		);
+		assert_eq!(
+			Err(ProbabilityError::MissingInitial),
+			ProbabilisticAutomaton::<u8, char>::new().validate(),
+			"Missing initial state accepted"
+		);
+	}
+}
@@ -0,0 +1,101 @@
+use super::graph::Graph;
+use std::{
+	collections::{HashMap, HashSet},
+	hash::Hash,
+};
+
+/// Reachable part of the product construction of several graphs.
+///
+/// Each tuple holds one state per graph, with `None` for graphs which can no longer accept.
+/// The first tuple consists of the initial states.
+pub(crate) struct Product<'a, I> {
+	pub tuples: Vec<Vec<Option<usize>>>,
+	pub table: Vec<HashMap<&'a I, usize>>,
+}
+
+impl<'a, I> Product<'a, I>
+where
+	I: Eq + Hash,
+{
+	/// Performs the product construction for tuples reachable from the initial states.
+	///
+	/// States from which a graph can not reach an accepting state are treated as dead, so tuples only differing in them are shared.
+	/// If `complete` is set, every tuple has transitions for all inputs used by any graph, leading to the all-dead tuple if necessary.
+	/// Otherwise the all-dead tuple is omitted.
+	pub fn new<S>(graphs: &[Graph<'a, S, I>], complete: bool) -> Self {
+		let tables: Vec<_> = graphs.iter().map(|graph| graph.table()).collect();
+		let alive: Vec<_> = graphs.iter().map(|graph| graph.coreachable()).collect();
+		let alive = |graph: usize, state: usize| Some(state).filter(|state| alive[graph][*state]);
+		let alphabet: HashSet<&I> = graphs
+			.iter()
+			.flat_map(|graph| graph.edges.iter().flatten().map(|(input, _)| *input))
+			.collect();
+
+		let initial: Vec<_> = graphs
+			.iter()
+			.enumerate()
+			.map(|(i, graph)| graph.initial.first().and_then(|state| alive(i, *state)))
+			.collect();
+		let mut numbers = HashMap::new();
+		numbers.insert(initial.clone(), 0);
+		let mut tuples = vec![initial];
+		let mut table = Vec::new();
+		let mut i = 0;
+		while i < tuples.len() {
+			let mut row = HashMap::new();
+			for input in &alphabet {
+				let next: Vec<_> = tuples[i]
+					.iter()
+					.enumerate()
+					.map(|(graph, state)| {
+						state
+							.and_then(|state| tables[graph][state].get(input))
+							.and_then(|next| alive(graph, *next))
+					})
+					.collect();
+				if !complete && next.iter().all(Option::is_none) {
+					continue;
+				}
+				let number = *numbers.entry(next.clone()).or_insert_with(|| {
+					tuples.push(next);
+					tuples.len() - 1
+				});
+				row.insert(*input, number);
+			}
+			table.push(row);
+			i += 1;
+		}
+		Self { tuples, table }
+	}
+}
+
+impl<'a, I> Product<'a, I> {
+	/// Returns the tuples with the labels of the states in the graphs.
+	pub fn labels<S>(&self, graphs: &[Graph<'a, S, I>]) -> Vec<Vec<Option<S>>>
+	where
+		S: Clone,
+	{
+		self.tuples
+			.iter()
+			.map(|tuple| {
+				tuple
+					.iter()
+					.zip(graphs)
+					.map(|(state, graph)| state.map(|state| graph.labels[state].clone()))
+					.collect()
+			})
+			.collect()
+	}
+
+	/// Returns whether each graph accepts in a tuple.
+	pub fn accepts<'b, S>(
+		&'b self,
+		graphs: &'b [Graph<'a, S, I>],
+		tuple: usize,
+	) -> impl Iterator<Item = bool> + 'b {
+		self.tuples[tuple]
+			.iter()
+			.zip(graphs)
+			.map(|(state, graph)| state.is_some_and(|state| graph.accepts[state]))
+	}
+}
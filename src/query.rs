@@ -0,0 +1,201 @@
+//! Declarative patterns over sequences of events, compiled into automata.
+//!
+//! A pattern describes the sequences of events it matches, e.g. "eventually `A`, then no `B` until `C`" is written as
+//! `Pattern::eventually(Pattern::event(A)).then(Pattern::until(vec![B], Pattern::event(C)))`.
+//! Patterns refer to all events of an alphabet with `Pattern::any` & `Pattern::except`,
+//! which are expanded when compiling the pattern over an alphabet.
+
+use super::{regex::Regex, AutomatonError, Conversion, DFA};
+use std::{collections::HashSet, hash::Hash};
+
+/// Pattern over sequences of events of type `I`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern<I> {
+	/// Matches a single event.
+	Event(I),
+
+	/// Matches any single event of the alphabet.
+	Any,
+
+	/// Matches any single event of the alphabet except the listed ones.
+	Except(Vec<I>),
+
+	/// Matches a sequence matching the first pattern followed by a sequence matching the second.
+	Then(Box<Pattern<I>>, Box<Pattern<I>>),
+
+	/// Matches sequences matching either pattern.
+	Or(Box<Pattern<I>>, Box<Pattern<I>>),
+
+	/// Matches any number of repetitions of sequences matching the inner pattern, including none.
+	Repeat(Box<Pattern<I>>),
+}
+
+impl<I> Pattern<I> {
+	/// Creates a pattern matching a single event.
+	pub fn event(event: I) -> Self {
+		Self::Event(event)
+	}
+
+	/// Creates a pattern matching any single event.
+	pub fn any() -> Self {
+		Self::Any
+	}
+
+	/// Creates a pattern matching any single event except the listed ones.
+	pub fn except<V>(events: V) -> Self
+	where
+		V: IntoIterator<Item = I>,
+	{
+		Self::Except(events.into_iter().collect())
+	}
+
+	/// Creates a pattern matching any sequence of events followed by a sequence matching the pattern.
+	pub fn eventually(pattern: Self) -> Self {
+		Self::any().repeat().then(pattern)
+	}
+
+	/// Creates a pattern matching a sequence without the excluded events followed by a sequence matching the pattern.
+	pub fn until<V>(excluded: V, pattern: Self) -> Self
+	where
+		V: IntoIterator<Item = I>,
+	{
+		Self::except(excluded).repeat().then(pattern)
+	}
+
+	/// Returns a pattern matching this pattern followed by another.
+	pub fn then(self, next: Self) -> Self {
+		Self::Then(Box::new(self), Box::new(next))
+	}
+
+	/// Returns a pattern matching this or another pattern.
+	pub fn or(self, other: Self) -> Self {
+		Self::Or(Box::new(self), Box::new(other))
+	}
+
+	/// Returns a pattern matching any number of repetitions of this pattern.
+	pub fn repeat(self) -> Self {
+		Self::Repeat(Box::new(self))
+	}
+}
+
+impl<I> Pattern<I>
+where
+	I: Default + Clone + Eq + Hash,
+{
+	/// Converts the pattern into a regular expression, expanding `Any` & `Except` over the alphabet.
+	/// Alternatives are ordered like the alphabet.
+	pub fn to_regex(&self, alphabet: &[I]) -> Regex<I> {
+		let events = |excluded: &[I]| {
+			alphabet
+				.iter()
+				.filter(|event| !excluded.contains(event))
+				.fold(Regex::Empty, |regex, event| {
+					regex.or(Regex::Symbol(event.clone()))
+				})
+		};
+		match self {
+			Self::Event(event) => Regex::Symbol(event.clone()),
+			Self::Any => events(&[]),
+			Self::Except(excluded) => events(excluded),
+			Self::Then(first, next) => first.to_regex(alphabet).concat(next.to_regex(alphabet)),
+			Self::Or(first, other) => first.to_regex(alphabet).or(other.to_regex(alphabet)),
+			Self::Repeat(inner) => inner.to_regex(alphabet).star(),
+		}
+	}
+
+	/// Compiles the pattern into a minimal DFA accepting exactly the sequences matching it.
+	/// Returns an `AutomatonError::LimitExceeded` error if the determinization exceeds the default limit of `Conversion`.
+	///
+	/// The alphabet is declared on the DFA, extended by the events named in the pattern.
+	/// States keep their numbers from the determinization, in order of exploration.
+	pub fn compile<V>(&self, alphabet: V) -> Result<DFA<usize, I>, AutomatonError<u32>>
+	where
+		V: IntoIterator<Item = I>,
+	{
+		let mut events: Vec<I> = Vec::new();
+		for event in alphabet.into_iter().chain(self.events()) {
+			if !events.contains(&event) {
+				events.push(event);
+			}
+		}
+		let mut dfa = self
+			.to_regex(&events)
+			.to_nfa()
+			.to_dfa_numbered(&Conversion::new())?
+			.minimize();
+		dfa.declare_alphabet(events);
+		Ok(dfa)
+	}
+
+	/// Compiles the pattern into a monitor, which accepts once a prefix of the sequence matched the pattern.
+	///
+	/// Unlike `compile`, the monitor keeps accepting for all further events of the alphabet.
+	pub fn monitor<V>(&self, alphabet: V) -> Result<DFA<usize, I>, AutomatonError<u32>>
+	where
+		V: IntoIterator<Item = I>,
+	{
+		self.clone().then(Self::any().repeat()).compile(alphabet)
+	}
+
+	/// Returns the events named in the pattern.
+	fn events(&self) -> HashSet<I> {
+		match self {
+			Self::Event(event) => Some(event.clone()).into_iter().collect(),
+			Self::Any => HashSet::new(),
+			Self::Except(excluded) => excluded.iter().cloned().collect(),
+			Self::Then(first, other) | Self::Or(first, other) => {
+				let mut events = first.events();
+				events.extend(other.events());
+				events
+			}
+			Self::Repeat(inner) => inner.events(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	/// Eventually `a`, then no `b` until `c`.
+	fn pattern() -> Pattern<char> {
+		Pattern::eventually(Pattern::event('a'))
+			.then(Pattern::until(vec!['b'], Pattern::event('c')))
+	}
+
+	#[test]
+	fn compile() {
+		let mut dfa = pattern().compile(vec!['a', 'b', 'c', 'd']).unwrap();
+		assert!(dfa.run(&['d', 'a', 'd', 'c']), "Matching sequence rejected");
+		assert!(dfa.run(&['b', 'a', 'a', 'c']), "Matching sequence rejected");
+		assert!(!dfa.run(&['a', 'b', 'c']), "Excluded event accepted");
+		assert!(!dfa.run(&['a', 'c', 'd']), "Trailing event accepted");
+		assert!(dfa.try_step(&'e').is_err(), "Alphabet not declared");
+	}
+
+	#[test]
+	fn monitor() {
+		let mut monitor = pattern().monitor(vec!['d']).unwrap();
+		assert!(
+			monitor.run(&['a', 'c', 'd', 'b']),
+			"Monitor not accepting after match"
+		);
+		assert!(!monitor.run(&['c', 'a', 'b', 'c']), "Monitor accepting");
+		assert_eq!(
+			Some(&['a', 'b', 'c', 'd'].iter().cloned().collect()),
+			monitor.alphabet(),
+			"Events not added to alphabet"
+		);
+	}
+
+	#[test]
+	fn to_regex() {
+		let pattern = Pattern::except(vec!['b']).repeat().or(Pattern::event('b'));
+		assert_eq!(
+			"(a|c)*|b",
+			pattern.to_regex(&['a', 'b', 'c']).to_string(),
+			"Incorrect expression"
+		);
+	}
+}
@@ -0,0 +1,486 @@
+use super::{Automaton, InputKey, Machine, MachineError};
+#[cfg(feature = "serde_json")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde_json")]
+use std::io::{self, BufRead, Write};
+#[cfg(feature = "time")]
+use std::{collections::HashMap, time::SystemTime};
+use std::{
+	fmt,
+	hash::Hash,
+	time::{Duration, Instant},
+};
+
+/// Input recorded with its time relative to the start of the recording.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Record<I> {
+	/// Time since the start of the recording.
+	pub time: Duration,
+
+	/// Recorded input.
+	pub input: I,
+}
+
+#[cfg(feature = "time")]
+impl<I> Record<I> {
+	/// Returns the wall-clock time of the record for a recording started at a point in time,
+	/// see `Recorder::started_at`.
+	pub fn timestamp(&self, start: SystemTime) -> SystemTime {
+		start + self.time
+	}
+}
+
+/// Statistics of the time spent in a state, see `Trace::time_in_state`.
+///
+/// A visit lasts from entering the state until leaving it for another state, self-loops do not end a visit.
+#[cfg(feature = "time")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Dwell {
+	/// Number of visits.
+	pub visits: usize,
+
+	/// Total time of all visits.
+	pub total: Duration,
+
+	/// Shortest visit.
+	pub min: Duration,
+
+	/// Longest visit.
+	pub max: Duration,
+}
+
+#[cfg(feature = "time")]
+impl Dwell {
+	/// Returns the mean time of a visit.
+	/// Returns zero if there were no visits.
+	pub fn mean(&self) -> Duration {
+		if self.visits == 0 {
+			Duration::ZERO
+		} else {
+			self.total / self.visits as u32
+		}
+	}
+
+	/// Adds a visit.
+	fn add(&mut self, time: Duration) {
+		self.min = if self.visits == 0 {
+			time
+		} else {
+			self.min.min(time)
+		};
+		self.max = self.max.max(time);
+		self.total += time;
+		self.visits += 1;
+	}
+}
+
+/// Sequence of recorded inputs, e.g. for reproducing a run.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Trace<I> {
+	/// Records in the order the inputs were fed.
+	pub records: Vec<Record<I>>,
+}
+
+impl<I> Default for Trace<I> {
+	fn default() -> Self {
+		Self {
+			records: Vec::new(),
+		}
+	}
+}
+
+impl<I> Trace<I> {
+	/// Creates a new empty trace.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the recorded inputs in order.
+	pub fn inputs(&self) -> impl Iterator<Item = &I> {
+		self.records.iter().map(|record| &record.input)
+	}
+
+	/// Re-drives an automaton with the recorded inputs, starting from its current state.
+	pub fn replay<S, A>(&self, automaton: &mut A)
+	where
+		S: Clone + PartialEq + fmt::Debug,
+		A: Automaton<S, I>,
+	{
+		for input in self.inputs() {
+			automaton.step(input);
+		}
+	}
+
+	/// Re-drives a machine with the recorded events, starting from its current state.
+	/// Returns the number of transitions taken.
+	///
+	/// Returns the first error a handled event results in.
+	pub fn replay_machine<S, C>(
+		&self,
+		machine: &mut Machine<S, I, C>,
+		ctx: &mut C,
+	) -> Result<usize, MachineError<S>>
+	where
+		S: Default + Clone + Eq + Hash + fmt::Debug,
+		I: InputKey,
+	{
+		let mut taken = 0;
+		for event in self.inputs() {
+			if machine.handle(event, ctx)? {
+				taken += 1;
+			}
+		}
+		Ok(taken)
+	}
+}
+
+#[cfg(feature = "time")]
+impl<I> Trace<I> {
+	/// Returns the time of the last record relative to the start of the recording.
+	/// Returns zero if the trace is empty.
+	pub fn duration(&self) -> Duration {
+		self.records
+			.last()
+			.map_or(Duration::ZERO, |record| record.time)
+	}
+
+	/// Re-drives an automaton with the recorded inputs like `replay` & returns the time spent in each state.
+	///
+	/// The current state of the automaton is entered at the start of the recording & the last state is left at `end`,
+	/// e.g. the `duration` of the trace or the time a recording was stopped.
+	/// Time in an invalid state is not counted. States have to be hashable, e.g. the states of a DFA.
+	pub fn time_in_state<S, A>(&self, automaton: &mut A, end: Duration) -> HashMap<A::State, Dwell>
+	where
+		S: Clone + PartialEq + fmt::Debug,
+		A: Automaton<S, I>,
+		A::State: Eq + Hash,
+	{
+		let mut dwells: HashMap<_, Dwell> = HashMap::new();
+		let mut leave = |stay: Option<(A::State, Duration)>, time: Duration| {
+			if let Some((state, since)) = stay {
+				dwells
+					.entry(state)
+					.or_default()
+					.add(time.saturating_sub(since));
+			}
+		};
+		let mut stay = automaton
+			.get_current()
+			.cloned()
+			.map(|state| (state, Duration::ZERO));
+		for record in &self.records {
+			automaton.step(&record.input);
+			let next = automaton.get_current();
+			if next != stay.as_ref().map(|(state, _)| state) {
+				let next = next.cloned().map(|state| (state, record.time));
+				leave(std::mem::replace(&mut stay, next), record.time);
+			}
+		}
+		leave(stay, end);
+		dwells
+	}
+}
+
+#[cfg(feature = "serde_json")]
+impl<I> Trace<I> {
+	/// Reads a trace in JSON lines format, with one record per line.
+	/// Empty lines are skipped.
+	pub fn read_json_lines<R>(reader: R) -> io::Result<Self>
+	where
+		R: BufRead,
+		I: DeserializeOwned,
+	{
+		let mut trace = Self::new();
+		for line in reader.lines() {
+			let line = line?;
+			if !line.trim().is_empty() {
+				trace.records.push(serde_json::from_str(&line)?);
+			}
+		}
+		Ok(trace)
+	}
+
+	/// Writes the trace in JSON lines format, with one record per line.
+	pub fn write_json_lines<W>(&self, mut writer: W) -> io::Result<()>
+	where
+		W: Write,
+		I: Serialize,
+	{
+		for record in &self.records {
+			serde_json::to_writer(&mut writer, record)?;
+			writer.write_all(b"\n")?;
+		}
+		Ok(())
+	}
+}
+
+/// Wrapper recording every input fed to an automaton or machine.
+///
+/// Recording starts when the recorder is created.
+#[derive(Debug)]
+pub struct Recorder<A, I> {
+	inner: A,
+	trace: Trace<I>,
+	start: Instant,
+	#[cfg(feature = "time")]
+	started: SystemTime,
+}
+
+impl<A, I> Recorder<A, I>
+where
+	I: Clone,
+{
+	/// Creates a new recorder for an automaton or machine.
+	pub fn new(inner: A) -> Self {
+		Self {
+			inner,
+			trace: Trace::new(),
+			start: Instant::now(),
+			#[cfg(feature = "time")]
+			started: SystemTime::now(),
+		}
+	}
+
+	/// Returns a reference to the recorded automaton or machine.
+	pub fn inner(&self) -> &A {
+		&self.inner
+	}
+
+	/// Returns the trace recorded so far.
+	pub fn trace(&self) -> &Trace<I> {
+		&self.trace
+	}
+
+	/// Stops recording, returning the automaton or machine & the recorded trace.
+	pub fn into_parts(self) -> (A, Trace<I>) {
+		(self.inner, self.trace)
+	}
+
+	/// Records an input at the current time.
+	fn record(&mut self, input: &I) {
+		self.record_at(input, self.start.elapsed());
+	}
+
+	/// Records an input at a time relative to the start of the recording.
+	fn record_at(&mut self, input: &I, time: Duration) {
+		self.trace.records.push(Record {
+			time,
+			input: input.clone(),
+		});
+	}
+
+	/// Records an input & performs a single state transition of the automaton.
+	pub fn step<S>(&mut self, input: &I)
+	where
+		S: Clone + PartialEq + fmt::Debug,
+		A: Automaton<S, I>,
+	{
+		self.record(input);
+		self.inner.step(input);
+	}
+}
+
+#[cfg(feature = "time")]
+impl<A, I> Recorder<A, I>
+where
+	I: Clone,
+{
+	/// Returns the wall-clock time the recording started at.
+	pub fn started_at(&self) -> SystemTime {
+		self.started
+	}
+
+	/// Records an input with its own timestamp relative to the start of the recording,
+	/// e.g. of an event read from a log, & performs a single state transition of the automaton.
+	///
+	/// Timestamps should not decrease over the recording.
+	pub fn step_at<S>(&mut self, input: &I, time: Duration)
+	where
+		S: Clone + PartialEq + fmt::Debug,
+		A: Automaton<S, I>,
+	{
+		self.record_at(input, time);
+		self.inner.step(input);
+	}
+}
+
+impl<S, E, C> Recorder<Machine<S, E, C>, E>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey + Clone,
+{
+	/// Records an event & lets the machine handle it.
+	pub fn handle(&mut self, event: &E, ctx: &mut C) -> Result<bool, MachineError<S>> {
+		self.record(event);
+		self.inner.handle(event, ctx)
+	}
+
+	/// Records an event with its own timestamp relative to the start of the recording & lets the machine handle it.
+	///
+	/// Timestamps should not decrease over the recording.
+	#[cfg(feature = "time")]
+	pub fn handle_at(
+		&mut self,
+		event: &E,
+		time: Duration,
+		ctx: &mut C,
+	) -> Result<bool, MachineError<S>> {
+		self.record_at(event, time);
+		self.inner.handle(event, ctx)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Behavior, DFA};
+
+	#[test]
+	fn automaton() {
+		let mut dfa = DFA::<u8, char>::with_state(0, false);
+		dfa.add_state(1, true);
+		dfa.add_transition((0, 'a', 1)).unwrap();
+		dfa.add_transition((1, 'b', 0)).unwrap();
+		let mut recorder = Recorder::new(dfa);
+		for input in &['a', 'b', 'a'] {
+			recorder.step(input);
+		}
+		let (dfa, trace) = recorder.into_parts();
+		assert!(dfa.accepts(), "Incorrect state after recording");
+		assert_eq!(
+			vec![&'a', &'b', &'a'],
+			trace.inputs().collect::<Vec<_>>(),
+			"Incorrect inputs"
+		);
+		assert!(
+			trace
+				.records
+				.windows(2)
+				.all(|pair| pair[0].time <= pair[1].time),
+			"Unordered times"
+		);
+
+		let yaml = serde_yaml::to_string(&trace).unwrap();
+		let trace: Trace<char> = serde_yaml::from_str(&yaml).unwrap();
+		let mut fresh = DFA::<u8, char>::with_state(0, false);
+		fresh.add_state(1, true);
+		fresh.add_transition((0, 'a', 1)).unwrap();
+		fresh.add_transition((1, 'b', 0)).unwrap();
+		trace.replay(&mut fresh);
+		assert_eq!(dfa.get_current(), fresh.get_current(), "Incorrect replay");
+	}
+
+	#[test]
+	fn machine() {
+		let counter = || {
+			let mut dfa = DFA::with_state("idle", false);
+			dfa.add_transition_with_payload(
+				("idle", 'i', "idle"),
+				Behavior::new().with_action(|count: &mut u32, _: &char| *count += 1),
+			)
+			.unwrap();
+			Machine::new(dfa)
+		};
+		let mut count = 0;
+		let mut recorder = Recorder::new(counter());
+		assert!(
+			recorder.handle(&'i', &mut count).unwrap(),
+			"Transition not taken"
+		);
+		assert!(
+			!recorder.handle(&'x', &mut count).unwrap(),
+			"Invalid transition taken"
+		);
+		let (_, trace) = recorder.into_parts();
+
+		let mut replayed = 0;
+		assert_eq!(
+			1,
+			trace.replay_machine(&mut counter(), &mut replayed).unwrap(),
+			"Incorrect transitions"
+		);
+		assert_eq!(count, replayed, "Incorrect replay");
+	}
+
+	#[cfg(feature = "time")]
+	#[test]
+	fn time_in_state() {
+		// `s` starts & `f` finishes a job, `p` polls without changing the state
+		let mut dfa = DFA::<&str, char>::with_state("idle", false);
+		dfa.add_state("busy", true);
+		dfa.add_transition(("idle", 's', "busy")).unwrap();
+		dfa.add_transition(("busy", 'p', "busy")).unwrap();
+		dfa.add_transition(("busy", 'f', "idle")).unwrap();
+		let mut recorder = Recorder::new(dfa);
+		for (input, millis) in [('s', 10), ('p', 15), ('f', 40), ('s', 50), ('f', 60)] {
+			recorder.step_at(&input, Duration::from_millis(millis));
+		}
+		let started = recorder.started_at();
+		let (mut dfa, trace) = recorder.into_parts();
+		assert_eq!(
+			started + Duration::from_millis(40),
+			trace.records[2].timestamp(started),
+			"Incorrect timestamp"
+		);
+		assert_eq!(
+			Duration::from_millis(60),
+			trace.duration(),
+			"Incorrect duration"
+		);
+
+		dfa.reset();
+		let dwells = trace.time_in_state(&mut dfa, Duration::from_millis(100));
+		assert_eq!(
+			Dwell {
+				visits: 2,
+				total: Duration::from_millis(40),
+				min: Duration::from_millis(10),
+				max: Duration::from_millis(30),
+			},
+			dwells["busy"],
+			"Incorrect time in state"
+		);
+		assert_eq!(
+			Duration::from_millis(60),
+			dwells["idle"].total,
+			"Incorrect time in state"
+		);
+		assert_eq!(
+			Duration::from_millis(20),
+			dwells["idle"].mean(),
+			"Incorrect mean time"
+		);
+	}
+
+	#[cfg(feature = "serde_json")]
+	#[test]
+	fn json_lines() {
+		let trace = Trace {
+			records: vec![
+				Record {
+					time: Duration::from_millis(1),
+					input: 'a',
+				},
+				Record {
+					time: Duration::from_millis(2),
+					input: 'b',
+				},
+			],
+		};
+		let mut buffer = Vec::new();
+		trace.write_json_lines(&mut buffer).unwrap();
+		assert_eq!(
+			2,
+			buffer.iter().filter(|c| **c == b'\n').count(),
+			"Incorrect lines"
+		);
+		buffer.extend(b"\n");
+		let read = Trace::read_json_lines(buffer.as_slice()).unwrap();
+		assert_eq!(trace, read, "Incorrect trace");
+		assert!(
+			Trace::<char>::read_json_lines(&b"{}\n"[..]).is_err(),
+			"Missing error on invalid record"
+		);
+	}
+}
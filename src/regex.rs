@@ -0,0 +1,683 @@
+//! Compilation of regular expressions into an `NFA`.
+//!
+//! The supported syntax consists of literal chars, concatenation, alternation `a|b`, repetition `a*`, `a+` & `a?`,
+//! grouping with parentheses & char classes like `[a-z_]`.
+//! The chars `()|*+?[\` have to be escaped with a backslash to match them literally.
+//! Negated char classes are not supported.
+//!
+//! The NFA is built using the Thompson construction & uses epsilon transitions.
+//! States are numbered in order of creation, starting with `0`.
+//!
+//! Automata are converted back into a `Regex` expression tree using state elimination.
+//! The displayed expression of an automaton over chars can be compiled again.
+
+use super::{Automaton, AutomatonError, DFA, NFA};
+use std::{fmt, hash::Hash};
+
+/// Chars which have to be escaped to be matched literally.
+const META: &[char] = &['(', ')', '|', '*', '+', '?', '[', '\\'];
+
+/// Expression tree of a regular expression over inputs of type `I`.
+///
+/// The constructor methods simplify the expression, e.g. by dropping empty words from concatenations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Regex<I> {
+	/// Matches nothing.
+	Empty,
+
+	/// Matches only the empty word.
+	Epsilon,
+
+	/// Matches a single input.
+	Symbol(I),
+
+	/// Matches the concatenation of words matching the parts.
+	Concat(Vec<Regex<I>>),
+
+	/// Matches words matching any of the alternatives.
+	Alternation(Vec<Regex<I>>),
+
+	/// Matches any number of repetitions of words matching the inner expression.
+	Star(Box<Regex<I>>),
+}
+
+impl<I> Regex<I>
+where
+	I: PartialEq,
+{
+	/// Returns the concatenation of this & another expression.
+	pub fn concat(self, other: Self) -> Self {
+		match (self, other) {
+			(Self::Empty, _) | (_, Self::Empty) => Self::Empty,
+			(Self::Epsilon, other) | (other, Self::Epsilon) => other,
+			(Self::Concat(mut parts), Self::Concat(others)) => {
+				parts.extend(others);
+				Self::Concat(parts)
+			}
+			(Self::Concat(mut parts), other) => {
+				parts.push(other);
+				Self::Concat(parts)
+			}
+			(first, Self::Concat(mut parts)) => {
+				parts.insert(0, first);
+				Self::Concat(parts)
+			}
+			(first, other) => Self::Concat(vec![first, other]),
+		}
+	}
+
+	/// Returns the alternation of this & another expression.
+	pub fn or(self, other: Self) -> Self {
+		let alternatives = |regex| match regex {
+			Self::Empty => Vec::new(),
+			Self::Alternation(alternatives) => alternatives,
+			regex => vec![regex],
+		};
+		let mut result = alternatives(self);
+		for alternative in alternatives(other) {
+			if !result.contains(&alternative) {
+				result.push(alternative);
+			}
+		}
+		match result.len() {
+			0 => Self::Empty,
+			1 => result.remove(0),
+			_ => Self::Alternation(result),
+		}
+	}
+
+	/// Returns the repetition of this expression.
+	pub fn star(self) -> Self {
+		match self {
+			Self::Empty | Self::Epsilon => Self::Epsilon,
+			Self::Star(inner) => Self::Star(inner),
+			regex => Self::Star(Box::new(regex)),
+		}
+	}
+}
+
+impl<I> Regex<I>
+where
+	I: fmt::Display,
+{
+	/// Writes the expression, wrapping it in parentheses if it binds weaker than the level.
+	/// Levels are `0` for alternatives, `1` for parts of concatenations & `2` for repeated expressions.
+	fn write(&self, f: &mut fmt::Formatter, level: u8) -> fmt::Result {
+		match self {
+			Self::Empty => write!(f, "∅"),
+			Self::Epsilon => write!(f, "()"),
+			Self::Symbol(input) => {
+				let input = input.to_string();
+				if input.len() == 1 && input.starts_with(META) {
+					write!(f, "\\")?;
+				}
+				write!(f, "{}", input)
+			}
+			Self::Concat(parts) => {
+				if level > 1 {
+					write!(f, "(")?;
+				}
+				for part in parts {
+					part.write(f, 2)?;
+				}
+				if level > 1 {
+					write!(f, ")")?;
+				}
+				Ok(())
+			}
+			Self::Alternation(alternatives) => {
+				if level > 0 {
+					write!(f, "(")?;
+				}
+				for (i, alternative) in alternatives.iter().enumerate() {
+					if i > 0 {
+						write!(f, "|")?;
+					}
+					alternative.write(f, 1)?;
+				}
+				if level > 0 {
+					write!(f, ")")?;
+				}
+				Ok(())
+			}
+			Self::Star(inner) => {
+				inner.write(f, 2)?;
+				write!(f, "*")
+			}
+		}
+	}
+}
+
+/// Writes the expression in the syntax accepted by `compile`.
+/// The empty language has no such syntax & is written as `∅`.
+impl<I> fmt::Display for Regex<I>
+where
+	I: fmt::Display,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.write(f, 0)
+	}
+}
+
+/// Adds an alternative to an entry of the elimination table.
+fn add<I>(entry: &mut Regex<I>, regex: Regex<I>)
+where
+	I: PartialEq,
+{
+	let old = std::mem::replace(entry, Regex::Empty);
+	*entry = old.or(regex);
+}
+
+/// Converts the useful states of a transition graph into an expression using state elimination.
+fn eliminate<I>(
+	accepts: &[bool],
+	edges: &[Vec<(&I, usize)>],
+	initial: &[usize],
+	useful: &[bool],
+) -> Regex<I>
+where
+	I: Clone + PartialEq,
+{
+	let len = accepts.len();
+	let (start, end) = (len, len + 1);
+	let mut table = vec![vec![Regex::Empty; len + 2]; len + 2];
+	for state in (0..len).filter(|state| useful[*state]) {
+		if initial.contains(&state) {
+			add(&mut table[start][state], Regex::Epsilon);
+		}
+		if accepts[state] {
+			add(&mut table[state][end], Regex::Epsilon);
+		}
+		for (input, next) in edges[state].iter().filter(|(_, next)| useful[*next]) {
+			add(&mut table[state][*next], Regex::Symbol((*input).clone()));
+		}
+	}
+	for state in (0..len).filter(|state| useful[*state]) {
+		let repeat = std::mem::replace(&mut table[state][state], Regex::Empty).star();
+		let incoming: Vec<_> = (0..len + 2)
+			.map(|prev| {
+				(
+					prev,
+					std::mem::replace(&mut table[prev][state], Regex::Empty),
+				)
+			})
+			.filter(|(_, regex)| *regex != Regex::Empty)
+			.collect();
+		let outgoing: Vec<_> = (0..len + 2)
+			.map(|next| {
+				(
+					next,
+					std::mem::replace(&mut table[state][next], Regex::Empty),
+				)
+			})
+			.filter(|(_, regex)| *regex != Regex::Empty)
+			.collect();
+		for (prev, before) in &incoming {
+			for (next, after) in &outgoing {
+				let path = before.clone().concat(repeat.clone()).concat(after.clone());
+				add(&mut table[*prev][*next], path);
+			}
+		}
+	}
+	std::mem::replace(&mut table[start][end], Regex::Empty)
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Converts the DFA into an equivalent regular expression using state elimination.
+	pub fn to_regex(&self) -> Regex<I> {
+		let graph = self.graph();
+		eliminate(
+			&graph.accepts,
+			&graph.edges,
+			&graph.initial,
+			&graph.useful(),
+		)
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Clone,
+{
+	/// Converts the NFA into an equivalent regular expression using state elimination.
+	/// Epsilon transitions are removed beforehand.
+	pub fn to_regex(&self) -> Regex<I> {
+		let nfa = self.remove_epsilons();
+		let graph = nfa.graph();
+		eliminate(
+			&graph.accepts,
+			&graph.edges,
+			&graph.initial,
+			&graph.useful(),
+		)
+	}
+}
+
+/// Error for a malformed regular expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexError {
+	/// Unexpected char at a position.
+	Unexpected(usize, char),
+
+	/// Unexpected end of the pattern.
+	UnexpectedEnd,
+
+	/// Char class range with the start after the end.
+	InvalidRange(char, char),
+
+	/// Negated char class at a position.
+	NegatedClass(usize),
+}
+
+impl fmt::Display for RegexError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Unexpected(position, c) => {
+				write!(f, "Unexpected '{}' at position {}", c, position)
+			}
+			Self::UnexpectedEnd => write!(f, "Unexpected end of pattern"),
+			Self::InvalidRange(start, end) => write!(f, "Invalid range \"{}-{}\"", start, end),
+			Self::NegatedClass(position) => {
+				write!(f, "Unsupported negated class at position {}", position)
+			}
+		}
+	}
+}
+
+/// Compiles a regular expression into an NFA accepting exactly the words matching it.
+pub fn compile(pattern: &str) -> Result<NFA<u32, char>, RegexError> {
+	let mut parser = Parser {
+		chars: pattern.chars().collect(),
+		position: 0,
+		builder: Builder::new(),
+	};
+	let fragment = parser.alternation()?;
+	if let Some(c) = parser.peek() {
+		return Err(RegexError::Unexpected(parser.position, c));
+	}
+	Ok(parser.builder.finish(fragment))
+}
+
+impl<I> Regex<I>
+where
+	I: Default + Clone + Eq + Hash,
+{
+	/// Converts the expression into an NFA accepting exactly the words matching it using the Thompson construction.
+	/// States are numbered in order of creation, starting with `0`.
+	pub fn to_nfa(&self) -> NFA<u32, I> {
+		let mut builder = Builder::new();
+		let fragment = builder.build(self);
+		builder.finish(fragment)
+	}
+}
+
+/// Part of the NFA with a single entry & exit state.
+#[derive(Debug, Clone, Copy)]
+struct Fragment {
+	start: u32,
+	end: u32,
+}
+
+/// Builder for NFAs using the Thompson construction.
+struct Builder<I>
+where
+	I: Default + Eq + Hash,
+{
+	nfa: NFA<u32, I>,
+	states: u32,
+}
+
+impl<I> Builder<I>
+where
+	I: Default + Clone + Eq + Hash,
+{
+	fn new() -> Self {
+		Self {
+			nfa: NFA::new(),
+			states: 0,
+		}
+	}
+
+	/// Returns the NFA with the fragment as initial & accepting states.
+	fn finish(mut self, fragment: Fragment) -> NFA<u32, I> {
+		self.nfa.add_state(fragment.end, true);
+		self.nfa
+			.set_initial(Some(fragment.start).into_iter().collect());
+		self.nfa.reset();
+		self.nfa
+	}
+
+	fn state(&mut self) -> u32 {
+		let state = self.states;
+		self.states += 1;
+		self.nfa.add_state(state, false);
+		state
+	}
+
+	fn epsilon(&mut self, prev: u32, next: u32) {
+		self.nfa
+			.add_epsilon((prev, next))
+			.expect("Epsilon transition between inexistent states");
+	}
+
+	/// Returns a fragment matching only the empty word.
+	fn empty(&mut self) -> Fragment {
+		let state = self.state();
+		Fragment {
+			start: state,
+			end: state,
+		}
+	}
+
+	/// Returns a fragment matching any single input of a list, which matches nothing if it is empty.
+	fn inputs<V>(&mut self, inputs: V) -> Fragment
+	where
+		V: IntoIterator<Item = I>,
+	{
+		let start = self.state();
+		let end = self.state();
+		for input in inputs {
+			self.nfa
+				.add_transition((start, input, end))
+				.map_err(AutomatonError::without_input)
+				.expect("Transition between inexistent states");
+		}
+		Fragment { start, end }
+	}
+
+	/// Returns a fragment matching a fragment followed by another.
+	fn concat(&mut self, prev: Fragment, next: Fragment) -> Fragment {
+		self.epsilon(prev.end, next.start);
+		Fragment {
+			start: prev.start,
+			end: next.end,
+		}
+	}
+
+	/// Returns a fragment matching any of the fragments.
+	fn alternation(&mut self, alternatives: &[Fragment]) -> Fragment {
+		let start = self.state();
+		let end = self.state();
+		for alternative in alternatives {
+			self.epsilon(start, alternative.start);
+			self.epsilon(alternative.end, end);
+		}
+		Fragment { start, end }
+	}
+
+	/// Returns a fragment matching repetitions of a fragment with an operator `*`, `+` or `?`.
+	fn repeat(&mut self, fragment: Fragment, op: char) -> Fragment {
+		let start = self.state();
+		let end = self.state();
+		self.epsilon(start, fragment.start);
+		self.epsilon(fragment.end, end);
+		if op != '+' {
+			self.epsilon(start, end);
+		}
+		if op != '?' {
+			self.epsilon(fragment.end, fragment.start);
+		}
+		Fragment { start, end }
+	}
+
+	/// Returns a fragment for an expression.
+	fn build(&mut self, regex: &Regex<I>) -> Fragment {
+		match regex {
+			Regex::Empty => self.inputs(None),
+			Regex::Epsilon => self.empty(),
+			Regex::Symbol(input) => self.inputs(Some(input.clone())),
+			Regex::Concat(parts) => {
+				let mut fragment = self.empty();
+				for part in parts {
+					let next = self.build(part);
+					fragment = self.concat(fragment, next);
+				}
+				fragment
+			}
+			Regex::Alternation(alternatives) => {
+				let alternatives: Vec<_> = alternatives
+					.iter()
+					.map(|alternative| self.build(alternative))
+					.collect();
+				self.alternation(&alternatives)
+			}
+			Regex::Star(inner) => {
+				let inner = self.build(inner);
+				self.repeat(inner, '*')
+			}
+		}
+	}
+}
+
+/// Recursive descent parser building the NFA while parsing.
+struct Parser {
+	chars: Vec<char>,
+	position: usize,
+	builder: Builder<char>,
+}
+
+impl Parser {
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.position).cloned()
+	}
+
+	fn next(&mut self) -> Result<char, RegexError> {
+		let c = self.peek().ok_or(RegexError::UnexpectedEnd)?;
+		self.position += 1;
+		Ok(c)
+	}
+
+	/// Parses alternatives separated by `|`.
+	fn alternation(&mut self) -> Result<Fragment, RegexError> {
+		let mut alternatives = vec![self.concatenation()?];
+		while self.peek() == Some('|') {
+			self.position += 1;
+			alternatives.push(self.concatenation()?);
+		}
+		Ok(match alternatives.as_slice() {
+			[single] => *single,
+			alternatives => self.builder.alternation(alternatives),
+		})
+	}
+
+	/// Parses a possibly empty sequence of repetitions.
+	fn concatenation(&mut self) -> Result<Fragment, RegexError> {
+		let mut fragment: Option<Fragment> = None;
+		while !matches!(self.peek(), None | Some('|') | Some(')')) {
+			let next = self.repetition()?;
+			fragment = Some(match fragment {
+				Some(prev) => self.builder.concat(prev, next),
+				None => next,
+			});
+		}
+		Ok(match fragment {
+			Some(fragment) => fragment,
+			None => self.builder.empty(),
+		})
+	}
+
+	/// Parses an atom followed by any number of `*`, `+` & `?`.
+	fn repetition(&mut self) -> Result<Fragment, RegexError> {
+		let mut fragment = self.atom()?;
+		while let Some(op @ ('*' | '+' | '?')) = self.peek() {
+			self.position += 1;
+			fragment = self.builder.repeat(fragment, op);
+		}
+		Ok(fragment)
+	}
+
+	/// Parses a group, char class or single char.
+	fn atom(&mut self) -> Result<Fragment, RegexError> {
+		let position = self.position;
+		let chars = match self.next()? {
+			'(' => {
+				let fragment = self.alternation()?;
+				return match self.next()? {
+					')' => Ok(fragment),
+					c => Err(RegexError::Unexpected(self.position - 1, c)),
+				};
+			}
+			'[' => self.class()?,
+			'\\' => vec![self.next()?],
+			c @ ('*' | '+' | '?' | ')' | '|') => return Err(RegexError::Unexpected(position, c)),
+			c => vec![c],
+		};
+		Ok(self.builder.inputs(chars))
+	}
+
+	/// Parses the chars of a class after the opening `[`.
+	fn class(&mut self) -> Result<Vec<char>, RegexError> {
+		if self.peek() == Some('^') {
+			return Err(RegexError::NegatedClass(self.position - 1));
+		}
+		let mut chars = Vec::new();
+		loop {
+			// a leading `]` is matched literally
+			let start = match self.next()? {
+				']' if !chars.is_empty() => return Ok(chars),
+				'\\' => self.next()?,
+				c => c,
+			};
+			if self.peek() == Some('-') && self.chars.get(self.position + 1) != Some(&']') {
+				self.position += 1;
+				let end = match self.next()? {
+					'\\' => self.next()?,
+					c => c,
+				};
+				if start > end {
+					return Err(RegexError::InvalidRange(start, end));
+				}
+				chars.extend(start..=end);
+			} else {
+				chars.push(start);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::DFA;
+
+	fn matches(pattern: &str, text: &str) -> bool {
+		let word: Vec<_> = text.chars().collect();
+		compile(pattern).unwrap().run(&word)
+	}
+
+	#[test]
+	fn syntax() {
+		assert!(matches("ab|cd", "cd"), "Alternative rejected");
+		assert!(!matches("ab|cd", "ad"), "Mixed alternatives accepted");
+		assert!(matches("a(b|c)*d", "abcbd"), "Repetition rejected");
+		assert!(matches("a(b|c)*d", "ad"), "Empty repetition rejected");
+		assert!(!matches("ab+", "a"), "Empty repetition accepted");
+		assert!(matches("colou?r", "color"), "Optional rejected");
+		assert!(matches("[a-z_][a-z0-9_]*", "snake_case2"), "Class rejected");
+		assert!(!matches("[a-z_][a-z0-9_]*", "2fast"), "Class accepted");
+		assert!(matches("[]-]\\*", "]*"), "Escapes rejected");
+		assert!(matches("(|a)", ""), "Empty alternative rejected");
+		assert!(matches("", ""), "Empty pattern rejected");
+	}
+
+	#[test]
+	fn error() {
+		assert_eq!(
+			Some(RegexError::Unexpected(1, ')')),
+			compile("a)b").err(),
+			"Missing error on unbalanced parenthesis"
+		);
+		assert_eq!(
+			Some(RegexError::UnexpectedEnd),
+			compile("(ab").err(),
+			"Missing error on unclosed group"
+		);
+		assert_eq!(
+			Some(RegexError::Unexpected(0, '*')),
+			compile("*a").err(),
+			"Missing error on repetition without atom"
+		);
+		assert_eq!(
+			Some(RegexError::InvalidRange('z', 'a')),
+			compile("[z-a]").err(),
+			"Missing error on reversed range"
+		);
+		assert_eq!(
+			Some(RegexError::NegatedClass(0)),
+			compile("[^a]").err(),
+			"Missing error on negated class"
+		);
+		assert_eq!(
+			"Unexpected end of pattern",
+			RegexError::UnexpectedEnd.to_string(),
+			"Incorrect message"
+		);
+	}
+
+	#[test]
+	fn convert() {
+		let nfa = compile("(a|b)*abb").unwrap();
+		let mut dfa: DFA<_, _> = nfa.into();
+		let minimal = dfa.minimize();
+		assert_eq!(4, minimal.stats().states, "DFA not minimal");
+		assert!(dfa.run(&['b', 'a', 'b', 'b']), "Word rejected");
+		assert!(!dfa.run(&['a', 'b', 'a']), "Word accepted");
+	}
+
+	/// Returns all words over `a`, `b` & `*` up to a length.
+	fn words(len: usize) -> Vec<Vec<char>> {
+		let mut words = vec![Vec::new()];
+		let mut last = vec![Vec::new()];
+		for _ in 0..len {
+			last = last
+				.iter()
+				.flat_map(|word: &Vec<char>| {
+					vec!['a', 'b', '*'].into_iter().map(move |c| {
+						let mut word = word.clone();
+						word.push(c);
+						word
+					})
+				})
+				.collect();
+			words.extend(last.iter().cloned());
+		}
+		words
+	}
+
+	#[test]
+	fn to_regex() {
+		for pattern in ["(a|b)*abb", "a\\*|b+", "(ab?)*", "()"] {
+			let mut nfa = compile(pattern).unwrap();
+			let regex = nfa.to_regex().to_string();
+			let mut dfa: DFA<_, _> = compile(&regex).unwrap().into();
+			for word in words(5) {
+				assert_eq!(
+					nfa.run(&word),
+					dfa.run(&word),
+					"Incorrect regex {} for {}",
+					regex,
+					pattern
+				);
+			}
+		}
+		let empty = DFA::<u8, char>::with_state(0, false);
+		assert_eq!(
+			"∅",
+			empty.to_regex().to_string(),
+			"Incorrect empty language"
+		);
+		let epsilon = DFA::<u8, char>::with_state(0, true);
+		assert_eq!(Regex::Epsilon, epsilon.to_regex(), "Incorrect empty word");
+		assert_eq!(
+			"a*",
+			Regex::Symbol('a').star().star().to_string(),
+			"Repetition not simplified"
+		);
+	}
+}
@@ -0,0 +1,150 @@
+use super::{nfa::State, NFA};
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	fmt,
+	hash::Hash,
+};
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Clone,
+{
+	/// Returns a copy of the NFA with copies of its states built from their transitions.
+	fn relabel<F>(&self, mut relabel: F) -> Self
+	where
+		F: FnMut(&State<I, T>) -> State<I, T>,
+	{
+		let states = self
+			.states
+			.iter()
+			.map(|state| state.as_ref().map(&mut relabel))
+			.collect();
+		let mut nfa = Self::from_parts(
+			self.ids.clone(),
+			self.index.clone(),
+			states,
+			self.initial_indices().clone(),
+			self.current_indices().clone(),
+		);
+		nfa.names = self.names.clone();
+		nfa
+	}
+
+	/// Returns an NFA with the transitions on the given inputs hidden.
+	///
+	/// Hidden transitions are replaced by epsilon transitions, dropping their payloads & priorities.
+	/// The hidden inputs are removed from the declared alphabet.
+	pub fn hide<V>(&self, inputs: V) -> Self
+	where
+		V: IntoIterator<Item = I>,
+	{
+		let hidden: HashSet<I> = inputs.into_iter().collect();
+		let mut nfa = self.relabel(|state| {
+			let mut hidden_state = State::new(state.accepts, HashMap::new());
+			hidden_state.epsilon = state.epsilon.clone();
+			for (input, next) in &state.transitions {
+				if hidden.contains(input) {
+					hidden_state.epsilon.extend(next.keys());
+				} else {
+					hidden_state.transitions.insert(input.clone(), next.clone());
+				}
+			}
+			hidden_state
+		});
+		nfa.alphabet = self.alphabet.as_ref().map(|alphabet| {
+			alphabet
+				.iter()
+				.filter(|input| !hidden.contains(input))
+				.cloned()
+				.collect()
+		});
+		nfa
+	}
+
+	/// Returns an NFA with the inputs renamed by a map.
+	///
+	/// Inputs missing from the map keep their name.
+	/// Transitions renamed to the same input & state are merged, keeping the edge with the highest priority.
+	/// The declared alphabet is renamed as well.
+	pub fn rename(&self, map: &HashMap<I, I>) -> Self {
+		let rename = |input: &I| map.get(input).unwrap_or(input).clone();
+		let mut nfa = self.relabel(|state| {
+			let mut renamed_state = State::new(state.accepts, HashMap::new());
+			renamed_state.epsilon = state.epsilon.clone();
+			for (input, next) in &state.transitions {
+				let targets = renamed_state
+					.transitions
+					.entry(rename(input))
+					.or_insert_with(BTreeMap::new);
+				for (next, edge) in next {
+					let target = targets.entry(*next).or_insert_with(|| edge.clone());
+					if edge.priority < target.priority {
+						*target = edge.clone();
+					}
+				}
+			}
+			renamed_state
+		});
+		nfa.alphabet = self
+			.alphabet
+			.as_ref()
+			.map(|alphabet| alphabet.iter().map(rename).collect());
+		nfa
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	/// Sender handing a message to a channel, which delivers it.
+	fn channel() -> NFA<u8, &'static str> {
+		let mut nfa = NFA::with_state(0, false);
+		nfa.add_state(1, false);
+		nfa.add_state(2, true);
+		nfa.add_transition((0, "send", 1)).unwrap();
+		nfa.add_transition((1, "deliver", 2)).unwrap();
+		nfa.declare_alphabet(vec!["send", "deliver"]);
+		nfa
+	}
+
+	#[test]
+	fn hide() {
+		let mut hidden = channel().hide(vec!["send"]);
+		assert!(hidden.run(&["deliver"]), "Hidden input not internal");
+		assert!(!hidden.run(&["send", "deliver"]), "Hidden input accepted");
+		assert!(hidden.has_epsilons(), "Hidden transition not epsilon");
+		assert_eq!(
+			&Some(vec!["deliver"].into_iter().collect()),
+			&hidden.alphabet,
+			"Hidden input declared"
+		);
+	}
+
+	#[test]
+	fn rename() {
+		let mut renamed = channel().rename(&hashmap!("deliver" => "send"));
+		assert!(renamed.run(&["send", "send"]), "Renamed input rejected");
+		assert!(!renamed.run(&["send", "deliver"]), "Old input accepted");
+		assert_eq!(
+			&Some(vec!["send"].into_iter().collect()),
+			&renamed.alphabet,
+			"Alphabet not renamed"
+		);
+
+		let mut merged = channel();
+		merged.declare_alphabet(vec!["send", "deliver", "post"]);
+		merged.add_transition((0, "post", 1)).unwrap();
+		merged.set_priority((0, "post", 1), 3).unwrap();
+		let merged = merged.rename(&hashmap!("post" => "send"));
+		assert_eq!(
+			Some(0),
+			merged.priority(&0, &"send", &1),
+			"Merged edge lost priority"
+		);
+	}
+}
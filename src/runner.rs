@@ -0,0 +1,114 @@
+use super::DFA;
+use std::{fmt, hash::Hash};
+
+/// Runner stepping several DFAs in lockstep over one sequence of inputs.
+#[derive(Debug)]
+pub struct MultiRunner<'a, S, I, T = ()>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	dfas: Vec<&'a DFA<S, I, T>>,
+	short_circuit: bool,
+}
+
+impl<'a, S, I, T> MultiRunner<'a, S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Creates a new runner for several DFAs.
+	pub fn new<V>(dfas: V) -> Self
+	where
+		V: IntoIterator<Item = &'a DFA<S, I, T>>,
+	{
+		Self {
+			dfas: dfas.into_iter().collect(),
+			short_circuit: false,
+		}
+	}
+
+	/// Sets whether to stop consuming inputs once all DFAs are in an invalid state.
+	pub fn short_circuit(mut self, short_circuit: bool) -> Self {
+		self.short_circuit = short_circuit;
+		self
+	}
+
+	/// Runs all DFAs on a sequence of inputs, starting from their current states.
+	/// Returns the positions of the DFAs accepting the inputs, in the order they were passed.
+	///
+	/// The DFAs themselves are not changed.
+	pub fn run<'b, V>(&self, inputs: V) -> Vec<usize>
+	where
+		V: IntoIterator<Item = &'b I>,
+		I: 'b,
+	{
+		let mut current: Vec<_> = self.dfas.iter().map(|dfa| dfa.current).collect();
+		let mut alive = current.iter().filter(|state| state.is_some()).count();
+		let mut inputs = inputs.into_iter();
+		while !self.short_circuit || alive > 0 {
+			let input = match inputs.next() {
+				Some(input) => input,
+				None => break,
+			};
+			for (dfa, state) in self.dfas.iter().zip(&mut current) {
+				if let Some(index) = *state {
+					*state = dfa.transition(index, input).map(|(next, _)| *next);
+					if state.is_none() {
+						alive -= 1;
+					}
+				}
+			}
+		}
+		self.dfas
+			.iter()
+			.zip(current)
+			.enumerate()
+			.filter(|(_, (dfa, state))| {
+				state.is_some_and(|index| dfa.state(index).is_some_and(|state| state.accepts))
+			})
+			.map(|(position, _)| position)
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::hashmap;
+	use std::cell::Cell;
+
+	/// Accepts words starting with a prefix of inputs.
+	fn prefix(prefix: &[char]) -> DFA<usize, char> {
+		let mut states =
+			hashmap!(prefix.len() => (true, hashmap!('a' => prefix.len(), 'b' => prefix.len())));
+		for (i, input) in prefix.iter().enumerate() {
+			states.insert(i, (false, hashmap!(*input => i + 1)));
+		}
+		DFA::from_map(0, states)
+	}
+
+	#[test]
+	fn run() {
+		let dfas = [prefix(&['a']), prefix(&['b']), prefix(&['a', 'b'])];
+		let runner = MultiRunner::new(&dfas);
+		assert_eq!(
+			vec![0, 2],
+			runner.run(&['a', 'b', 'a']),
+			"Incorrect matches"
+		);
+		assert_eq!(vec![1], runner.run(&['b']), "Incorrect matches");
+		assert!(runner.run(&[]).is_empty(), "Empty word matched");
+	}
+
+	#[test]
+	fn short_circuit() {
+		let dfas = [prefix(&['a']), prefix(&['a', 'a'])];
+		let consumed = Cell::new(0);
+		let inputs = ['b', 'a', 'a'];
+		let counted = inputs.iter().inspect(|_| consumed.set(consumed.get() + 1));
+		let runner = MultiRunner::new(&dfas).short_circuit(true);
+		assert!(runner.run(counted).is_empty(), "Dead DFAs matched");
+		assert_eq!(1, consumed.get(), "Inputs consumed after all DFAs died");
+	}
+}
@@ -0,0 +1,213 @@
+use super::{graph::Graph, nfa::closed, NFA};
+use std::{fmt, hash::Hash};
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Counts the distinct accepting runs on a sequence of inputs, starting from the initial states.
+	///
+	/// A run is a sequence of states connected by transitions for the inputs.
+	/// Epsilon transitions are taken between inputs, so each state of a run reads the next input or ends the run.
+	/// The count saturates at `u64::MAX`.
+	pub fn count_runs<'a, V>(&self, inputs: V) -> u64
+	where
+		V: IntoIterator<Item = &'a I>,
+		I: 'a,
+	{
+		let graph = self.run_graph();
+		let mut counts = vec![0u64; graph.len()];
+		for state in &graph.initial {
+			counts[*state] = 1;
+		}
+		for input in inputs {
+			let mut next = vec![0u64; graph.len()];
+			for (state, count) in counts.iter().enumerate().filter(|(_, count)| **count > 0) {
+				for (_, to) in graph.edges[state].iter().filter(|(el, _)| *el == input) {
+					next[*to] = next[*to].saturating_add(*count);
+				}
+			}
+			counts = next;
+		}
+		counts
+			.into_iter()
+			.enumerate()
+			.filter(|(state, _)| graph.accepts[*state])
+			.fold(0, |sum, (_, count)| sum.saturating_add(count))
+	}
+
+	/// Returns up to `limit` distinct accepting runs on a sequence of inputs, starting from the initial states.
+	///
+	/// Each run consists of the states visited, including the initial state, so it contains one state more than there are inputs.
+	/// States only passed through by epsilon transitions are not part of the run.
+	pub fn accepting_runs<'a>(
+		&'a self,
+		inputs: &[I],
+		limit: usize,
+	) -> impl Iterator<Item = Vec<&'a S>> {
+		let graph = self.run_graph();
+		let successors = |state: usize, input: &I| -> Vec<usize> {
+			graph.edges[state]
+				.iter()
+				.filter(|(el, _)| *el == input)
+				.map(|(_, next)| *next)
+				.collect()
+		};
+
+		// states from which the remaining inputs can lead to an accepting state
+		let mut viable = vec![graph.accepts.clone()];
+		for input in inputs.iter().rev() {
+			let after = viable.last().unwrap();
+			let before = (0..graph.len())
+				.map(|state| successors(state, input).into_iter().any(|next| after[next]))
+				.collect();
+			viable.push(before);
+		}
+		viable.reverse();
+
+		// depth-first search only following viable states
+		let mut runs = Vec::new();
+		let mut stack: Vec<Vec<usize>> = graph
+			.initial
+			.iter()
+			.rev()
+			.filter(|state| viable[0][**state])
+			.map(|state| vec![*state])
+			.collect();
+		while runs.len() < limit {
+			let run = match stack.pop() {
+				Some(run) => run,
+				None => break,
+			};
+			let position = run.len() - 1;
+			if position == inputs.len() {
+				runs.push(run);
+				continue;
+			}
+			let next: Vec<_> = successors(run[position], &inputs[position])
+				.into_iter()
+				.filter(|next| viable[position + 1][*next])
+				.collect();
+			for next in next.into_iter().rev() {
+				let mut run = run.clone();
+				run.push(next);
+				stack.push(run);
+			}
+		}
+		let labels = graph.labels;
+		runs.into_iter()
+			.map(move |run| run.into_iter().map(|state| labels[state]).collect())
+	}
+
+	/// Returns the transition graph with each transition leading to the epsilon closure of its target
+	/// & the initial states replaced by their epsilon closure.
+	///
+	/// Unlike `graph`, states keep only their own transitions & acceptance, so every run corresponds to exactly one path.
+	fn run_graph(&self) -> Graph<'_, S, I> {
+		let mut graph = self.transition_graph();
+		if self.has_epsilons() {
+			let closures = self.graph_closures(&graph);
+			graph.initial = closed(&closures, &graph.initial);
+			for edges in &mut graph.edges {
+				*edges = edges
+					.iter()
+					.flat_map(|(input, next)| {
+						closures[*next].iter().map(move |next| (*input, *next))
+					})
+					.collect();
+			}
+		}
+		graph
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::{hashmap, hashset};
+
+	#[test]
+	fn count() {
+		// ambiguous grammar for sequences of 'a'
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (true, hashmap!('a' => hashset![0, 1])),
+				1 => (true, hashmap!('a' => hashset![0]))
+			),
+		);
+		assert_eq!(1, nfa.count_runs(&[]), "Incorrect count of empty word");
+		assert_eq!(2, nfa.count_runs(&['a']), "Incorrect count");
+		assert_eq!(3, nfa.count_runs(&['a', 'a']), "Incorrect count");
+		assert_eq!(5, nfa.count_runs(&['a', 'a', 'a']), "Incorrect count");
+		assert_eq!(
+			0,
+			nfa.count_runs(&['b']),
+			"Incorrect count of rejected word"
+		);
+	}
+
+	#[test]
+	fn runs() {
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![1, 2])),
+				1 => (false, hashmap!('b' => hashset![3])),
+				2 => (false, hashmap!('b' => hashset![3, 4])),
+				3 => (true, hashmap!()),
+				4 => (false, hashmap!())
+			),
+		);
+		let mut runs: Vec<_> = nfa.accepting_runs(&['a', 'b'], 10).collect();
+		runs.sort();
+		assert_eq!(
+			vec![vec![&0, &1, &3], vec![&0, &2, &3]],
+			runs,
+			"Incorrect accepting runs"
+		);
+		assert_eq!(
+			1,
+			nfa.accepting_runs(&['a', 'b'], 1).count(),
+			"Limit not respected"
+		);
+		assert_eq!(
+			0,
+			nfa.accepting_runs(&['a'], 10).count(),
+			"Runs of rejected word"
+		);
+	}
+
+	#[test]
+	fn saturate() {
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0, 1],
+			hashmap!(
+				0 => (true, hashmap!('a' => hashset![0, 1])),
+				1 => (true, hashmap!('a' => hashset![0, 1]))
+			),
+		);
+		assert_eq!(
+			u64::MAX,
+			nfa.count_runs(&['a'; 100]),
+			"Count did not saturate"
+		);
+	}
+
+	#[test]
+	fn epsilon() {
+		let nfa = crate::regex::compile("ab*").unwrap();
+		assert_eq!(1, nfa.count_runs(&['a', 'b']), "Incorrect count");
+		let runs: Vec<_> = nfa.accepting_runs(&['a', 'b'], 10).collect();
+		assert_eq!(1, runs.len(), "Incorrect number of runs");
+		assert_eq!(3, runs[0].len(), "Incorrect run length");
+		assert_eq!(
+			3,
+			crate::regex::compile("a*a*")
+				.unwrap()
+				.count_runs(&['a', 'a']),
+			"Incorrect count of ambiguous expression"
+		);
+	}
+}
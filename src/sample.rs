@@ -0,0 +1,98 @@
+use super::DFA;
+use rand::{distr::Distribution, Rng, RngExt};
+use std::{collections::HashSet, fmt, hash::Hash};
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Estimates the fraction of random words accepted by the automaton.
+	///
+	/// Each sample draws a word length from the length distribution & uniformly chooses the inputs of the word.
+	/// Inputs are taken from the declared alphabet, or the inputs used in the transitions if there is none.
+	/// Returns `NaN` if no samples are taken.
+	pub fn estimate_acceptance_rate<R, D>(
+		&self,
+		rng: &mut R,
+		samples: usize,
+		len_distribution: D,
+	) -> f64
+	where
+		R: Rng + ?Sized,
+		D: Distribution<usize>,
+	{
+		let alphabet: Vec<&I> = match &self.alphabet {
+			Some(alphabet) => alphabet.iter().collect(),
+			None => self
+				.declared()
+				.flat_map(|(_, state)| state.transitions.keys())
+				.collect::<HashSet<_>>()
+				.into_iter()
+				.collect(),
+		};
+		let mut accepted = 0;
+		for _ in 0..samples {
+			let len = len_distribution.sample(rng);
+			let mut current = self.initial;
+			for _ in 0..len {
+				let index = match current {
+					Some(index) => index,
+					None => break,
+				};
+				current = if alphabet.is_empty() {
+					None
+				} else {
+					let input = alphabet[rng.random_range(0..alphabet.len())];
+					self.transition(index, input).map(|(next, _)| *next)
+				};
+			}
+			if current
+				.and_then(|index| self.state(index))
+				.is_some_and(|state| state.accepts)
+			{
+				accepted += 1;
+			}
+		}
+		accepted as f64 / samples as f64
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use maplit::hashmap;
+	use rand::{distr::Uniform, rngs::StdRng, SeedableRng};
+
+	#[test]
+	fn estimate() {
+		let mut rng = StdRng::seed_from_u64(0);
+		let lengths = Uniform::new_inclusive(0, 8).unwrap();
+		let even = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (true, hashmap!('a' => 0, 'b' => 1)),
+				1 => (false, hashmap!('a' => 1, 'b' => 0))
+			),
+		);
+		let rate = even.estimate_acceptance_rate(&mut rng, 10_000, lengths);
+		// empty words are always accepted, longer words in half of the cases
+		let expected = (1.0 + 8.0 * 0.5) / 9.0;
+		assert!(
+			(rate - expected).abs() < 0.03,
+			"Incorrect estimate {}",
+			rate
+		);
+
+		let none = DFA::<u8, char>::from_map(0, hashmap!(0 => (false, hashmap!('a' => 0))));
+		assert_eq!(
+			0.0,
+			none.estimate_acceptance_rate(&mut rng, 100, lengths),
+			"Accepting rejecting automaton"
+		);
+		assert!(
+			none.estimate_acceptance_rate(&mut rng, 0, lengths).is_nan(),
+			"Estimate without samples"
+		);
+	}
+}
@@ -0,0 +1,209 @@
+use super::{dfa, nfa, AutomatonError, DFA, NFA};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+/// Policy for references to undeclared states, e.g. in deserialized automatons.
+///
+/// A DFA can keep a policy, which is then applied whenever it is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DanglingPolicy {
+	/// Returns an `AutomatonError::InexistentState` error without modifying the automaton.
+	Strict,
+
+	/// Removes transitions to undeclared states.
+	DropDangling,
+
+	/// Declares the missing states as non-accepting states without transitions.
+	AutoCreate,
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Resolves references to undeclared states according to the given policy.
+	///
+	/// This should be called after loading an automaton from an untrusted source.
+	pub fn sanitize(&mut self, policy: DanglingPolicy) -> Result<(), AutomatonError<S>> {
+		let dangling: BTreeSet<usize> = self
+			.declared()
+			.flat_map(|(_, state)| state.transitions.values())
+			.map(|(next, _)| *next)
+			.filter(|next| self.states[*next].is_none())
+			.collect();
+		match policy {
+			DanglingPolicy::Strict => {
+				if let Some(index) = dangling.into_iter().next() {
+					return Err(AutomatonError::InexistentState(
+						self.ids[index].clone(),
+						Vec::new(),
+					));
+				}
+			}
+			DanglingPolicy::DropDangling => {
+				for state in self.states.iter_mut().flatten() {
+					state
+						.transitions
+						.retain(|_, (next, _)| !dangling.contains(next));
+				}
+			}
+			DanglingPolicy::AutoCreate => {
+				for index in dangling {
+					self.states[index] = Some(dfa::State::new(false, HashMap::new()));
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Sets the policy for references to undeclared states & applies it immediately.
+	/// The policy is kept when serializing and applied again when deserializing, so a `Strict` policy rejects corrupted data at load.
+	///
+	/// Returns an `AutomatonError::InexistentState` error without keeping the policy if a `Strict` policy is violated.
+	pub fn set_dangling_policy(&mut self, policy: DanglingPolicy) -> Result<(), AutomatonError<S>> {
+		self.sanitize(policy)?;
+		self.dangling = Some(policy);
+		Ok(())
+	}
+
+	/// Returns the kept policy for references to undeclared states, if there is one.
+	pub fn dangling_policy(&self) -> Option<DanglingPolicy> {
+		self.dangling
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Resolves references to undeclared states according to the given policy.
+	///
+	/// This should be called after loading an automaton from an untrusted source.
+	pub fn sanitize(&mut self, policy: DanglingPolicy) -> Result<(), AutomatonError<S>> {
+		let dangling: BTreeSet<usize> = self
+			.declared()
+			.flat_map(|(_, state)| state.transitions.values().flat_map(|next| next.keys()))
+			.filter(|next| self.states[**next].is_none())
+			.cloned()
+			.collect();
+		match policy {
+			DanglingPolicy::Strict => {
+				if let Some(index) = dangling.into_iter().next() {
+					return Err(AutomatonError::InexistentState(
+						self.ids[index].clone(),
+						Vec::new(),
+					));
+				}
+			}
+			DanglingPolicy::DropDangling => {
+				for state in self.states.iter_mut().flatten() {
+					for next in state.transitions.values_mut() {
+						next.retain(|index, _| !dangling.contains(index));
+					}
+					state.transitions.retain(|_, next| !next.is_empty());
+				}
+			}
+			DanglingPolicy::AutoCreate => {
+				for index in dangling {
+					self.states[index] = Some(nfa::State::new(false, HashMap::new()));
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashset;
+
+	const DFA_YAML: &str =
+		r"{initial: 0, states: {0: {transitions: {a: 1, b: 2}}, 1: {accepts: true}}}";
+	const NFA_YAML: &str =
+		r"{initial: [0], states: {0: {transitions: {a: [1, 2]}}, 1: {accepts: true}}}";
+
+	#[test]
+	fn strict() {
+		let mut dfa: DFA<u8, char> = serde_yaml::from_str(DFA_YAML).unwrap();
+		assert!(
+			dfa.sanitize(DanglingPolicy::Strict).is_err(),
+			"Missing error on dangling transition"
+		);
+		let mut nfa: NFA<u8, char> = serde_yaml::from_str(NFA_YAML).unwrap();
+		assert!(
+			nfa.sanitize(DanglingPolicy::Strict).is_err(),
+			"Missing error on dangling transition"
+		);
+	}
+
+	#[test]
+	fn drop_dangling() {
+		let mut dfa: DFA<u8, char> = serde_yaml::from_str(DFA_YAML).unwrap();
+		dfa.sanitize(DanglingPolicy::DropDangling).unwrap();
+		assert!(!dfa.run(&['b']), "Dangling transition not dropped");
+		assert!(dfa.run(&['a']), "Valid transition dropped");
+
+		let mut nfa: NFA<u8, char> = serde_yaml::from_str(NFA_YAML).unwrap();
+		nfa.sanitize(DanglingPolicy::DropDangling).unwrap();
+		nfa.step(&'a');
+		assert_eq!(
+			Some(&hashset![1]),
+			nfa.get_current(),
+			"Dangling transition not dropped"
+		);
+		nfa.step(&'a');
+		assert!(!nfa.accepts(), "Incorrect result after step");
+	}
+
+	#[test]
+	fn auto_create() {
+		let mut dfa: DFA<u8, char> = serde_yaml::from_str(DFA_YAML).unwrap();
+		dfa.sanitize(DanglingPolicy::AutoCreate).unwrap();
+		assert!(dfa.has_state(&2), "Missing state not created");
+		assert!(!dfa.run(&['b']), "Created state accepting");
+
+		let mut nfa: NFA<u8, char> = serde_yaml::from_str(NFA_YAML).unwrap();
+		nfa.sanitize(DanglingPolicy::AutoCreate).unwrap();
+		assert!(nfa.has_state(&2), "Missing state not created");
+		assert!(nfa.run(&['a']), "Incorrect result after run");
+	}
+
+	#[test]
+	fn policy() {
+		let mut dfa: DFA<u8, char> = serde_yaml::from_str(DFA_YAML).unwrap();
+		assert!(
+			dfa.try_step(&'b').is_err(),
+			"Missing error on dangling transition"
+		);
+		assert_eq!(Some(&0), dfa.get_current(), "State changed on error");
+
+		let strict = DFA_YAML.replace("{initial", "{dangling: strict, initial");
+		assert!(
+			serde_yaml::from_str::<DFA<u8, char>>(&strict).is_err(),
+			"Missing error on load"
+		);
+		let trap = DFA_YAML.replace("{initial", "{dangling: auto_create, initial");
+		let mut dfa: DFA<u8, char> = serde_yaml::from_str(&trap).unwrap();
+		dfa.try_step(&'b').unwrap();
+		assert_eq!(Some(&2), dfa.get_current(), "Trap state not created");
+		assert_eq!(
+			Some(DanglingPolicy::AutoCreate),
+			dfa.dangling_policy(),
+			"Policy not kept"
+		);
+		let yaml = serde_yaml::to_string(&dfa).unwrap();
+		assert!(
+			yaml.contains("dangling: auto_create"),
+			"Policy not serialized"
+		);
+	}
+}
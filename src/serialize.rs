@@ -0,0 +1,120 @@
+use serde::{Deserialize, Deserializer};
+use serde_value::{Value, ValueDeserializer};
+use std::cell::Cell;
+
+thread_local! {
+	/// Whether unknown fields are currently ignored.
+	static LENIENT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Wrapper deserializing an automaton while ignoring unknown fields.
+///
+/// By default, unknown fields of an automaton & its states are rejected.
+/// Deserializing through this wrapper ignores them instead, e.g. for files produced by newer versions of this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Lenient<T>(pub T);
+
+impl<T> Lenient<T> {
+	/// Returns the wrapped value.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<'de, T> Deserialize<'de> for Lenient<T>
+where
+	T: Deserialize<'de>,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let previous = LENIENT.with(|lenient| lenient.replace(true));
+		let result = T::deserialize(deserializer);
+		LENIENT.with(|lenient| lenient.set(previous));
+		result.map(Lenient)
+	}
+}
+
+/// Removes the entries with unknown string keys from a map.
+fn retain_fields(value: &mut Value, fields: &[&str]) {
+	if let Value::Map(map) = value {
+		map.retain(|key, _| matches!(key, Value::String(key) if fields.contains(&key.as_str())));
+	}
+}
+
+/// Deserializes the representation of an automaton with the given fields & fields of its states.
+///
+/// Unknown fields are rejected by the representation itself.
+/// When deserializing through `Lenient`, they are removed beforehand instead.
+pub(crate) fn repr<'de, T, D>(
+	deserializer: D,
+	fields: &[&str],
+	state_fields: &[&str],
+) -> Result<T, D::Error>
+where
+	T: Deserialize<'de>,
+	D: Deserializer<'de>,
+{
+	if !LENIENT.with(Cell::get) {
+		return T::deserialize(deserializer);
+	}
+	let mut value = Value::deserialize(deserializer)?;
+	retain_fields(&mut value, fields);
+	if let Value::Map(map) = &mut value {
+		if let Some(Value::Map(states)) = map.get_mut(&Value::String("states".into())) {
+			for state in states.values_mut() {
+				retain_fields(state, state_fields);
+			}
+		}
+	}
+	T::deserialize(ValueDeserializer::<D::Error>::new(value))
+}
+
+/// Deserializes an optional field distinguishing between a missing field & an explicit `null`.
+///
+/// Use together with `#[serde(default)]`, so a missing field results in `None` & `null` in `Some(None)`.
+pub(crate) fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+	T: Deserialize<'de>,
+	D: Deserializer<'de>,
+{
+	Deserialize::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Automaton, DFA, NFA};
+	use std::collections::BTreeSet;
+
+	#[test]
+	fn lenient() {
+		let yaml = r"{initial: 0, version: 2, states: {0: {accepts: false, transitions: {a: 1}, color: red}, 1: [true, {a: 1}]}}";
+		assert!(
+			serde_yaml::from_str::<DFA<u8, char>>(yaml).is_err(),
+			"Unknown fields accepted in strict mode"
+		);
+		let Lenient(mut dfa): Lenient<DFA<u8, char>> = serde_yaml::from_str(yaml).unwrap();
+		assert!(dfa.run(&['a', 'a']), "Incorrect result after lenient load");
+		assert!(
+			serde_yaml::from_str::<DFA<u8, char>>(r"{initial: 0, states: {0: {accepts: true}}}")
+				.is_ok(),
+			"Strict mode not restored"
+		);
+
+		let yaml = r"{initial: [[0]], states: {[0]: {accepts: true, transitions: {}, extra: 1}}}";
+		let nfa: NFA<BTreeSet<u8>, char> = serde_yaml::from_str::<Lenient<_>>(yaml)
+			.unwrap()
+			.into_inner();
+		assert!(nfa.accepts(), "Incorrect result after lenient load");
+	}
+
+	#[test]
+	fn lenient_epsilon() {
+		let yaml = serde_yaml::to_string(&crate::regex::compile("ab*").unwrap()).unwrap();
+		let Lenient(mut nfa): Lenient<NFA<u32, char>> = serde_yaml::from_str(&yaml).unwrap();
+		assert!(nfa.has_epsilons(), "Epsilon transitions dropped");
+		assert!(nfa.run(&['a', 'b']), "Incorrect result after lenient load");
+	}
+}
@@ -0,0 +1,226 @@
+//! Analyses of automata viewed as presentations of constrained systems.
+//!
+//! In this view every state is both initial & accepting and the system consists of the label sequences of all paths.
+//! Accepting flags & the current state are ignored.
+
+use super::{graph::Graph, DFA, NFA};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Checks whether the graph is strongly connected.
+	fn is_irreducible(&self) -> bool {
+		self.len() > 0 && self.components(&vec![true; self.len()]).len() == 1
+	}
+
+	/// Computes the period of a strongly connected graph.
+	fn period(&self) -> Option<usize> {
+		if !self.is_irreducible() {
+			return None;
+		}
+		let mut levels: Vec<Option<usize>> = vec![None; self.len()];
+		levels[0] = Some(0);
+		let mut queue = vec![0];
+		let mut i = 0;
+		while i < queue.len() {
+			let state = queue[i];
+			for (_, next) in &self.edges[state] {
+				if levels[*next].is_none() {
+					levels[*next] = Some(levels[state].unwrap() + 1);
+					queue.push(*next);
+				}
+			}
+			i += 1;
+		}
+		let mut period = 0;
+		for (from, edges) in self.edges.iter().enumerate() {
+			for (_, to) in edges {
+				let (from, to) = (levels[from].unwrap() + 1, levels[*to].unwrap());
+				period = gcd(period, from.abs_diff(to));
+			}
+		}
+		if period > 0 {
+			Some(period)
+		} else {
+			None
+		}
+	}
+}
+
+/// Computes the greatest common divisor of two numbers.
+fn gcd(a: usize, b: usize) -> usize {
+	if b == 0 {
+		a
+	} else {
+		gcd(b, a % b)
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Checks whether the transition graph is strongly connected.
+	pub fn is_irreducible(&self) -> bool {
+		self.graph().is_irreducible()
+	}
+
+	/// Computes the period of an irreducible transition graph, the greatest common divisor of all cycle lengths.
+	/// Returns `None` if the graph is not irreducible.
+	pub fn period(&self) -> Option<usize> {
+		self.graph().period()
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Checks whether the transition graph is strongly connected.
+	pub fn is_irreducible(&self) -> bool {
+		self.graph().is_irreducible()
+	}
+
+	/// Computes the period of an irreducible transition graph, the greatest common divisor of all cycle lengths.
+	/// Returns `None` if the graph is not irreducible.
+	pub fn period(&self) -> Option<usize> {
+		self.graph().period()
+	}
+
+	/// Constructs a deterministic presentation of the constrained system using follower sets.
+	///
+	/// Each state of the resulting DFA is the set of states reachable by some word from any state.
+	/// The construction starts from the set of all states & all resulting states are accepting.
+	pub fn follower_set_presentation(&self) -> DFA<BTreeSet<S>, I>
+	where
+		S: Ord,
+		I: Clone,
+	{
+		let graph = self.graph();
+		let initial: BTreeSet<usize> = (0..graph.len()).collect();
+		let mut sets = vec![initial.clone()];
+		let mut numbers = HashMap::new();
+		numbers.insert(initial, 0);
+		let mut transitions = Vec::new();
+		let mut i = 0;
+		while i < sets.len() {
+			let mut successors: HashMap<&I, BTreeSet<usize>> = HashMap::new();
+			for state in &sets[i] {
+				for (input, next) in &graph.edges[*state] {
+					successors.entry(*input).or_default().insert(*next);
+				}
+			}
+			let mut edges = Vec::with_capacity(successors.len());
+			for (input, set) in successors {
+				let number = match numbers.get(&set) {
+					Some(number) => *number,
+					None => {
+						numbers.insert(set.clone(), sets.len());
+						sets.push(set);
+						sets.len() - 1
+					}
+				};
+				edges.push((input.clone(), number));
+			}
+			transitions.push(edges);
+			i += 1;
+		}
+		let labels: Vec<BTreeSet<S>> = sets
+			.iter()
+			.map(|set| set.iter().map(|i| graph.labels[*i].clone()).collect())
+			.collect();
+		DFA::from_map(
+			labels[0].clone(),
+			transitions
+				.into_iter()
+				.enumerate()
+				.map(|(i, edges)| {
+					(
+						labels[i].clone(),
+						(
+							true,
+							edges
+								.into_iter()
+								.map(|(input, next)| (input, labels[next].clone()))
+								.collect(),
+						),
+					)
+				})
+				.collect::<HashMap<_, _>>(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{btreeset, hashmap, hashset};
+
+	#[test]
+	fn period() {
+		// cycles of length 2 & 4
+		let dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (true, hashmap!('a' => 1)),
+				1 => (true, hashmap!('a' => 0, 'b' => 2)),
+				2 => (true, hashmap!('a' => 3)),
+				3 => (true, hashmap!('a' => 0))
+			),
+		);
+		assert!(
+			dfa.is_irreducible(),
+			"Strongly connected graph not irreducible"
+		);
+		assert_eq!(Some(2), dfa.period(), "Incorrect period");
+
+		let mut reducible = DFA::<u8, char>::with_state(0, true);
+		reducible.add_state(1, true);
+		reducible.add_transition((0, 'a', 1)).unwrap();
+		assert!(!reducible.is_irreducible(), "Reducible graph irreducible");
+		assert_eq!(None, reducible.period(), "Period of reducible graph");
+	}
+
+	#[test]
+	fn presentation() {
+		// run-length limited: no two consecutive ones
+		let nfa = NFA::<u8, u8>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (true, hashmap!(0 => hashset![0, 1], 1 => hashset![2])),
+				1 => (true, hashmap!(1 => hashset![2])),
+				2 => (true, hashmap!(0 => hashset![0, 1]))
+			),
+		);
+		assert_eq!(Some(1), nfa.period(), "Incorrect period");
+		let mut dfa = nfa.follower_set_presentation();
+		assert!(
+			dfa.has_state(&btreeset![0, 1, 2]),
+			"Presentation is missing initial follower set"
+		);
+		assert!(dfa.run(&[0, 1, 0, 0, 1]), "Constrained word rejected");
+		assert!(!dfa.run(&[0, 1, 1]), "Forbidden word accepted");
+	}
+
+	#[test]
+	fn epsilon() {
+		let mut nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![1])),
+				1 => (false, hashmap!('b' => hashset![2])),
+				2 => (true, hashmap!())
+			),
+		);
+		assert!(!nfa.is_irreducible(), "Reducible graph irreducible");
+		nfa.add_epsilon((2, 0)).unwrap();
+		assert!(nfa.is_irreducible(), "Irreducible graph reducible");
+		assert_eq!(Some(2), nfa.period(), "Incorrect period");
+	}
+}
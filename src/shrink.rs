@@ -0,0 +1,219 @@
+//! Shrinking of counterexamples to locally minimal words.
+//!
+//! Counterexamples found in products or recorded traces are often long & full of inputs irrelevant to the issue.
+//! Shrinking repeatedly removes chunks of inputs, halving the chunk size whenever no chunk can be removed,
+//! until no single input can be removed without losing the issue.
+//! The result is locally minimal, a shorter word exhibiting the issue by other inputs may exist.
+
+use super::{Trace, DFA, NFA};
+use std::{collections::BTreeSet, fmt, hash::Hash};
+
+/// Shrinks a word exhibiting an issue to a locally minimal subsequence still exhibiting it.
+///
+/// Removing any single input from the result makes it no longer exhibit the issue.
+/// If the word does not exhibit the issue, it is returned unchanged.
+pub fn minimize<I, F>(word: &[I], mut exhibits: F) -> Vec<I>
+where
+	I: Clone,
+	F: FnMut(&[I]) -> bool,
+{
+	let mut word = word.to_vec();
+	if !exhibits(&word) {
+		return word;
+	}
+	let mut chunk = (word.len() / 2).max(1);
+	while !word.is_empty() {
+		let mut removed = false;
+		let mut start = 0;
+		while start < word.len() {
+			let end = (start + chunk).min(word.len());
+			let candidate: Vec<I> = word[..start].iter().chain(&word[end..]).cloned().collect();
+			if exhibits(&candidate) {
+				word = candidate;
+				removed = true;
+			} else {
+				start = end;
+			}
+		}
+		if !removed {
+			if chunk == 1 {
+				break;
+			}
+			chunk = (chunk / 2).max(1);
+		}
+	}
+	word
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the index of the state reached from the initial state by a word.
+	/// Returns `Err` with the number of inputs taken if the DFA has no transition for an input.
+	fn walk(&self, word: &[I]) -> Result<Option<usize>, usize> {
+		let mut current = match self.initial {
+			Some(initial) => initial,
+			None => return Ok(None),
+		};
+		for (position, input) in word.iter().enumerate() {
+			current = self.transition(current, input).ok_or(position)?.0;
+		}
+		Ok(Some(current))
+	}
+
+	/// Checks whether the DFA accepts a word from its initial state.
+	fn accepts_word(&self, word: &[I]) -> bool {
+		matches!(
+			self.walk(word),
+			Ok(Some(index)) if self.state(index).is_some_and(|state| state.accepts)
+		)
+	}
+
+	/// Shrinks the inputs of a trace diverging from the model, see `check_trace`, to a locally minimal diverging word.
+	/// Returns `None` if the trace conforms to the model.
+	pub fn shrink_divergence(&self, trace: &Trace<I>) -> Option<Vec<I>> {
+		let inputs: Vec<I> = trace.inputs().cloned().collect();
+		if self.walk(&inputs).is_ok() {
+			return None;
+		}
+		Some(minimize(&inputs, |word| self.walk(word).is_err()))
+	}
+
+	/// Shrinks a word leading from the initial state to a state violating a predicate, see `check_invariant`.
+	///
+	/// Words leading to an invalid state do not violate the predicate.
+	pub fn shrink_violation<F>(&self, word: &[I], predicate: F) -> Vec<I>
+	where
+		F: Fn(&S) -> bool,
+	{
+		minimize(word, |word| match self.walk(word) {
+			Ok(Some(index)) => !predicate(&self.ids[index]),
+			_ => false,
+		})
+	}
+
+	/// Shrinks a word accepted by exactly one of this & another DFA, see `equivalent`.
+	pub fn shrink_distinguishing<R, U>(&self, other: &DFA<R, I, U>, word: &[I]) -> Vec<I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		minimize(word, |word| {
+			self.accepts_word(word) != other.accepts_word(word)
+		})
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Checks whether the NFA accepts a word from its initial states.
+	fn accepts_word(&self, word: &[I]) -> bool {
+		let mut current: BTreeSet<usize> = self.epsilon_closure(self.initial_indices());
+		for input in word {
+			current = self.epsilon_closure(&self.successors(&current, input));
+		}
+		current
+			.iter()
+			.any(|index| matches!(self.state(*index), Some(state) if state.accepts))
+	}
+
+	/// Shrinks a word accepted by this NFA but not by another NFA, see `is_subset_of`.
+	pub fn shrink_inclusion<R, U>(&self, other: &NFA<R, I, U>, word: &[I]) -> Vec<I>
+	where
+		R: Default + Clone + Eq + Hash + fmt::Debug,
+	{
+		minimize(word, |word| {
+			self.accepts_word(word) && !other.accepts_word(word)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Automaton, Record};
+	use maplit::hashmap;
+
+	/// Counts `a` up to 2 & ignores `b`, with no transition for `a` at 2.
+	fn counter() -> DFA<u8, char> {
+		DFA::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1, 'b' => 0)),
+				1 => (false, hashmap!('a' => 2, 'b' => 1)),
+				2 => (true, hashmap!('b' => 2))
+			),
+		)
+	}
+
+	#[test]
+	fn minimize() {
+		let word: Vec<_> = "xxaxxxbxxcxx".chars().collect();
+		let shrunk = super::minimize(&word, |word| {
+			let word: String = word.iter().collect();
+			word.contains('a') && word.contains('b') && word.find('b') < word.find('c')
+		});
+		assert_eq!(vec!['a', 'b', 'c'], shrunk, "Not minimal");
+		assert_eq!(
+			word,
+			super::minimize(&word, |_| false),
+			"Word without issue changed"
+		);
+	}
+
+	#[test]
+	fn dfa() {
+		let dfa = counter();
+		let word: Vec<_> = "babbabbbabb".chars().collect();
+		assert_eq!(
+			vec!['a', 'a'],
+			dfa.shrink_violation(&word[..8], |state| *state < 2),
+			"Violation not minimal"
+		);
+
+		let mut trace = Trace::new();
+		for input in word {
+			trace.records.push(Record {
+				time: Default::default(),
+				input,
+			});
+		}
+		assert_eq!(
+			Some(vec!['a', 'a', 'a']),
+			dfa.shrink_divergence(&trace),
+			"Divergence not minimal"
+		);
+		assert_eq!(
+			None,
+			dfa.shrink_divergence(&Trace::new()),
+			"Conforming trace shrunk"
+		);
+
+		let mut other = counter();
+		other.add_transition((1, 'b', 2)).unwrap();
+		assert_eq!(
+			vec!['a', 'b'],
+			dfa.shrink_distinguishing(&other, &['b', 'a', 'b', 'b']),
+			"Distinguishing word not minimal"
+		);
+	}
+
+	#[test]
+	fn nfa() {
+		let nfa = counter().to_nfa(&Default::default()).unwrap();
+		let mut other = NFA::<u8, char>::with_state(0, false);
+		other.add_state(1, true);
+		other.add_transition((0, 'b', 1)).unwrap();
+		other.add_transition((0, 'a', 0)).unwrap();
+		other.add_transition((0, 'b', 0)).unwrap();
+		assert_eq!(
+			vec!['a', 'a'],
+			nfa.shrink_inclusion(&other, &['b', 'a', 'b', 'b', 'a']),
+			"Inclusion counterexample not minimal"
+		);
+	}
+}
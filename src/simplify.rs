@@ -0,0 +1,339 @@
+use super::{dfa::State, DFA};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt,
+	hash::Hash,
+};
+
+/// Pass of the simplification pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pass {
+	/// Drops the states unreachable from the initial state & the states which can not reach an accepting state.
+	/// The DFA has no states left if its initial state can not reach an accepting state.
+	Trim,
+
+	/// Merges equivalent states, see `DFA::minimize`, which trims the DFA as well.
+	Merge,
+
+	/// Collapses the inputs leading to the same state from every state into a class represented by its smallest input.
+	/// Only transitions for representatives are kept & the other inputs are reported with their representative.
+	Collapse,
+
+	/// Drops the inputs without a transition from a reachable state from the declared alphabet & from all transitions.
+	DropInputs,
+}
+
+/// Options for the simplification of a DFA.
+///
+/// By default, the passes trim, merge, collapse & drop inputs run in this order.
+#[derive(Debug, Clone)]
+pub struct Simplification {
+	passes: Vec<Pass>,
+}
+
+impl Default for Simplification {
+	fn default() -> Self {
+		Self {
+			passes: vec![Pass::Trim, Pass::Merge, Pass::Collapse, Pass::DropInputs],
+		}
+	}
+}
+
+impl Simplification {
+	/// Creates new simplification options with the defaults.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the passes to run in order, replacing the default ones.
+	/// Passes may be repeated.
+	pub fn passes<V>(mut self, passes: V) -> Self
+	where
+		V: IntoIterator<Item = Pass>,
+	{
+		self.passes = passes.into_iter().collect();
+		self
+	}
+}
+
+/// Changes made by a single pass of a simplification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassReport {
+	/// Pass which ran.
+	pub pass: Pass,
+
+	/// Number of states removed.
+	pub states: usize,
+
+	/// Number of transitions removed.
+	pub transitions: usize,
+
+	/// Number of inputs removed from the alphabet.
+	pub inputs: usize,
+}
+
+/// Summary of a simplification of a DFA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimplificationReport<I>
+where
+	I: Eq + Hash,
+{
+	/// Changes made by each pass in order.
+	pub passes: Vec<PassReport>,
+
+	/// Representatives of the inputs collapsed into a class.
+	/// Inputs have to be replaced by their representative when running the simplified DFA.
+	pub classes: HashMap<I, I>,
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Ord + Hash,
+	T: Clone,
+{
+	/// Returns a copy of the DFA keeping the states selected by a mask over the state arena
+	/// & the transitions of the inputs mapped to an input.
+	fn retain<F>(&self, keep: &[bool], input: F) -> Self
+	where
+		F: Fn(&I) -> Option<I>,
+	{
+		let states = self
+			.states
+			.iter()
+			.enumerate()
+			.map(|(i, state)| {
+				state.as_ref().filter(|_| keep[i]).map(|state| {
+					State::new(
+						state.accepts,
+						state
+							.transitions
+							.iter()
+							.filter(|(_, (next, _))| keep[*next])
+							.filter_map(|(prev, (next, payload))| {
+								input(prev).map(|prev| (prev, (*next, payload.clone())))
+							})
+							.collect(),
+					)
+				})
+			})
+			.collect();
+		Self {
+			ids: self.ids.clone(),
+			index: self.index.clone(),
+			states,
+			initial: self.initial.filter(|initial| keep[*initial]),
+			current: self.current.filter(|current| keep[*current]),
+			alphabet: self
+				.alphabet
+				.as_ref()
+				.map(|alphabet| alphabet.iter().filter_map(&input).collect()),
+			names: self
+				.names
+				.iter()
+				.filter(|(index, _)| keep[**index])
+				.map(|(index, name)| (*index, name.clone()))
+				.collect(),
+			dangling: self.dangling,
+		}
+	}
+
+	/// Returns a mask over the state arena from a mask over the graph.
+	fn arena_mask(&self, mask: Vec<bool>) -> Vec<bool> {
+		let mut keep = vec![false; self.states.len()];
+		for (label, selected) in self.graph().labels.into_iter().zip(mask) {
+			keep[self.index[label]] = selected;
+		}
+		keep
+	}
+
+	/// Drops useless states, see `Pass::Trim`.
+	fn trim(&self) -> Self {
+		let keep = self.arena_mask(self.graph().useful());
+		self.retain(&keep, |input| Some(input.clone()))
+	}
+
+	/// Collapses inputs into classes, see `Pass::Collapse`.
+	fn collapse(&self, classes: &mut HashMap<I, I>) -> Self {
+		let mut inputs: HashSet<&I> = self.alphabet.iter().flatten().collect();
+		for (_, state) in self.declared() {
+			inputs.extend(state.transitions.keys());
+		}
+		// inputs grouped by their targets from every declared state
+		let mut groups: HashMap<Vec<Option<usize>>, Vec<&I>> = HashMap::new();
+		for input in inputs {
+			let targets = self
+				.declared()
+				.map(|(index, _)| self.transition(index, input).map(|(next, _)| *next))
+				.collect();
+			groups.entry(targets).or_default().push(input);
+		}
+		let mut representatives = HashSet::new();
+		let mut collapsed = HashMap::new();
+		for mut group in groups.into_values() {
+			group.sort();
+			for input in &group[1..] {
+				collapsed.insert((*input).clone(), group[0].clone());
+			}
+			representatives.insert(group[0]);
+		}
+		// representatives of earlier passes may be collapsed now
+		for representative in classes.values_mut() {
+			if let Some(next) = collapsed.get(representative) {
+				*representative = next.clone();
+			}
+		}
+		classes.extend(collapsed);
+		let keep = vec![true; self.states.len()];
+		self.retain(&keep, |input| {
+			representatives.contains(input).then(|| input.clone())
+		})
+	}
+
+	/// Drops inputs unused by reachable states, see `Pass::DropInputs`.
+	fn drop_inputs(&self) -> Self {
+		let graph = self.graph();
+		let reachable = graph.reachable();
+		let used: HashSet<&I> = graph
+			.edges
+			.iter()
+			.zip(reachable)
+			.filter(|(_, reachable)| *reachable)
+			.flat_map(|(edges, _)| edges.iter().map(|(input, _)| *input))
+			.collect();
+		let keep = vec![true; self.states.len()];
+		self.retain(&keep, |input| used.contains(input).then(|| input.clone()))
+	}
+
+	/// Simplifies the DFA by running a sequence of passes.
+	/// Returns the simplified DFA & a report of the changes made by each pass.
+	///
+	/// The simplified DFA is in its initial state.
+	pub fn simplify(&self, options: &Simplification) -> (Self, SimplificationReport<I>) {
+		let mut report = SimplificationReport {
+			passes: Vec::new(),
+			classes: HashMap::new(),
+		};
+		let mut dfa = self.retain(&vec![true; self.states.len()], |input| Some(input.clone()));
+		for pass in &options.passes {
+			let before = dfa.stats();
+			dfa = match pass {
+				Pass::Trim => dfa.trim(),
+				Pass::Merge => dfa.minimize(),
+				Pass::Collapse => dfa.collapse(&mut report.classes),
+				Pass::DropInputs => dfa.drop_inputs(),
+			};
+			let after = dfa.stats();
+			report.passes.push(PassReport {
+				pass: *pass,
+				states: before.states - after.states,
+				transitions: before.transitions - after.transitions,
+				inputs: before.alphabet - after.alphabet,
+			});
+		}
+		dfa.current = dfa.initial;
+		(dfa, report)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{hashmap, hashset};
+
+	/// Accepts words over digits & `x` containing a digit, with a redundant copy of the accepting state & an orphan.
+	fn dfa() -> DFA<&'static str, char> {
+		let mut dfa = DFA::with_state("start", false);
+		for (state, accepts) in [("digit", true), ("copy", true), ("orphan", false)] {
+			dfa.add_state(state, accepts);
+		}
+		dfa.declare_alphabet(vec!['0', '1', '2', 'x', 'y']);
+		for (prev, input, next) in [
+			("start", '0', "digit"),
+			("start", '1', "copy"),
+			("start", '2', "digit"),
+			("start", 'x', "start"),
+			("digit", '0', "digit"),
+			("digit", '1', "copy"),
+			("digit", '2', "copy"),
+			("digit", 'x', "digit"),
+			("copy", '0', "copy"),
+			("copy", '1', "digit"),
+			("copy", '2', "copy"),
+			("copy", 'x', "copy"),
+			("orphan", 'y', "start"),
+		] {
+			dfa.add_transition((prev, input, next)).unwrap();
+		}
+		dfa
+	}
+
+	#[test]
+	fn simplify() {
+		let (mut simple, report) = dfa().simplify(&Simplification::new());
+		assert_eq!(
+			vec![
+				PassReport {
+					pass: Pass::Trim,
+					states: 1,
+					transitions: 1,
+					inputs: 0
+				},
+				PassReport {
+					pass: Pass::Merge,
+					states: 1,
+					transitions: 4,
+					inputs: 0
+				},
+				PassReport {
+					pass: Pass::Collapse,
+					states: 0,
+					transitions: 4,
+					inputs: 2
+				},
+				PassReport {
+					pass: Pass::DropInputs,
+					states: 0,
+					transitions: 0,
+					inputs: 1
+				},
+			],
+			report.passes,
+			"Incorrect report"
+		);
+		assert_eq!(
+			hashmap!('1' => '0', '2' => '0'),
+			report.classes,
+			"Incorrect classes"
+		);
+		assert_eq!(
+			Some(&hashset!('0', 'x')),
+			simple.alphabet(),
+			"Incorrect alphabet"
+		);
+		assert!(simple.run(&['x', '0', 'x']), "Word rejected");
+		assert!(!simple.run(&['x']), "Word accepted");
+	}
+
+	#[test]
+	fn passes() {
+		// inputs only collapse once the redundant state distinguishing them is merged
+		let options = Simplification::new().passes([Pass::Collapse, Pass::Merge, Pass::Collapse]);
+		let (_, report) = dfa().simplify(&options);
+		assert_eq!(
+			hashmap!('1' => '0', '2' => '0'),
+			report.classes,
+			"Incorrect classes over repeated passes"
+		);
+		assert_eq!(
+			vec![0, 2, 0],
+			report
+				.passes
+				.iter()
+				.map(|pass| pass.states)
+				.collect::<Vec<_>>(),
+			"Incorrect removed states"
+		);
+	}
+}
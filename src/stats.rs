@@ -0,0 +1,178 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt,
+	hash::Hash,
+};
+
+/// Summary of the size & shape of an automaton.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+	/// Number of declared states.
+	pub states: usize,
+
+	/// Number of transitions between declared states.
+	pub transitions: usize,
+
+	/// Number of inputs in the declared alphabet, or used in transitions if there is none.
+	pub alphabet: usize,
+
+	/// Number of accepting states.
+	pub accepting: usize,
+
+	/// Number of states reachable from an initial state.
+	pub reachable: usize,
+
+	/// Number of states from which no accepting state is reachable.
+	pub dead: usize,
+
+	/// Average number of outgoing transitions per state.
+	pub out_degree: f64,
+
+	/// Whether there is at most one initial state & at most one transition per state & input.
+	pub deterministic: bool,
+}
+
+impl<'a, S, I> Graph<'a, S, I>
+where
+	I: Eq + Hash,
+{
+	/// Computes the statistics of the graph, using the size of a declared alphabet if there is one.
+	fn stats(&self, alphabet: Option<usize>) -> Stats {
+		let transitions = self.edges.iter().map(Vec::len).sum();
+		let alphabet = alphabet.unwrap_or_else(|| {
+			self.edges
+				.iter()
+				.flatten()
+				.map(|(input, _)| *input)
+				.collect::<HashSet<_>>()
+				.len()
+		});
+		let deterministic = self.initial.len() <= 1
+			&& self.edges.iter().all(|edges| {
+				let mut inputs = HashMap::new();
+				edges
+					.iter()
+					.all(|(input, next)| *inputs.entry(*input).or_insert(*next) == *next)
+			});
+		Stats {
+			states: self.len(),
+			transitions,
+			alphabet,
+			accepting: self.accepts.iter().filter(|accepts| **accepts).count(),
+			reachable: self.reachable().into_iter().filter(|state| *state).count(),
+			dead: self
+				.coreachable()
+				.into_iter()
+				.filter(|state| !*state)
+				.count(),
+			out_degree: if self.len() > 0 {
+				transitions as f64 / self.len() as f64
+			} else {
+				0.0
+			},
+			deterministic,
+		}
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Returns a summary of the size & shape of the automaton.
+	pub fn stats(&self) -> Stats {
+		self.graph().stats(self.alphabet.as_ref().map(HashSet::len))
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Returns a summary of the size & shape of the automaton.
+	///
+	/// Epsilon transitions count as transitions & make the automaton nondeterministic.
+	/// Reachable & dead states are determined following epsilon transitions.
+	pub fn stats(&self) -> Stats {
+		let mut stats = self
+			.transition_graph()
+			.stats(self.alphabet.as_ref().map(HashSet::len));
+		if self.has_epsilons() {
+			let graph = self.graph();
+			stats.transitions += self
+				.declared()
+				.flat_map(|(_, state)| &state.epsilon)
+				.filter(|next| self.state(**next).is_some())
+				.count();
+			stats.reachable = graph.reachable().into_iter().filter(|state| *state).count();
+			stats.dead = graph
+				.coreachable()
+				.into_iter()
+				.filter(|state| !*state)
+				.count();
+			stats.out_degree = stats.transitions as f64 / stats.states as f64;
+			stats.deterministic = false;
+		}
+		stats
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::hashmap;
+
+	#[test]
+	fn dfa() {
+		let mut dfa = DFA::<u8, char>::from_map(
+			0,
+			hashmap!(
+				0 => (false, hashmap!('a' => 1, 'b' => 2)),
+				1 => (true, hashmap!('a' => 1)),
+				2 => (false, hashmap!('b' => 2)),
+				3 => (true, hashmap!())
+			),
+		);
+		dfa.add_state(4, false);
+		assert_eq!(
+			Stats {
+				states: 5,
+				transitions: 4,
+				alphabet: 2,
+				accepting: 2,
+				reachable: 3,
+				dead: 2,
+				out_degree: 0.8,
+				deterministic: true,
+			},
+			dfa.stats(),
+			"Incorrect stats"
+		);
+	}
+
+	#[test]
+	fn nfa() {
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_transition((0, 'a', 0)).unwrap();
+		assert!(nfa.stats().deterministic, "Incorrect determinism");
+		nfa.add_transition((0, 'a', 1)).unwrap();
+		let stats = nfa.stats();
+		assert!(!stats.deterministic, "Incorrect determinism");
+		assert_eq!(1, stats.alphabet, "Incorrect alphabet size");
+		assert_eq!(0, stats.dead, "Incorrect dead states");
+	}
+
+	#[test]
+	fn epsilon() {
+		let stats = crate::regex::compile("ab*").unwrap().stats();
+		assert_eq!(7, stats.transitions, "Incorrect transitions");
+		assert_eq!(6, stats.reachable, "Incorrect reachable states");
+		assert_eq!(0, stats.dead, "Incorrect dead states");
+		assert!(!stats.deterministic, "Incorrect determinism");
+	}
+}
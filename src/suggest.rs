@@ -0,0 +1,171 @@
+use super::{AutomatonError, DFA, NFA};
+use std::{
+	collections::{BTreeSet, HashSet},
+	fmt,
+	hash::Hash,
+};
+
+/// Maximum number of suggestions included in an error.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Computes the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut row: Vec<usize> = (0..=b.len()).collect();
+	for (i, a) in a.chars().enumerate() {
+		let mut diagonal = row[0];
+		row[0] = i + 1;
+		for (j, b) in b.iter().enumerate() {
+			let cost = if a == *b { diagonal } else { diagonal + 1 };
+			diagonal = row[j + 1];
+			row[j + 1] = cost.min(row[j] + 1).min(diagonal + 1);
+		}
+	}
+	row[b.len()]
+}
+
+/// Strips the quotes of a debug representation of a string.
+fn unquote(name: &str) -> &str {
+	name.strip_prefix('"')
+		.and_then(|name| name.strip_suffix('"'))
+		.unwrap_or(name)
+}
+
+/// Returns the names closest to a missing one without quotes, ordered by distance.
+///
+/// A name is considered close if at most a third of its chars, but at least one, need to be edited.
+fn closest<V>(missing: &str, names: V) -> Vec<String>
+where
+	V: IntoIterator<Item = String>,
+{
+	let missing = unquote(missing);
+	let mut close: Vec<_> = names
+		.into_iter()
+		.map(|name| (edit_distance(missing, unquote(&name)), name))
+		.filter(|(distance, name)| *distance <= (unquote(name).chars().count() / 3).max(1))
+		.collect();
+	close.sort();
+	close.dedup();
+	close
+		.into_iter()
+		.take(MAX_SUGGESTIONS)
+		.map(|(_, name)| unquote(&name).into())
+		.collect()
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Returns the display names of the states closest to an inexistent state ID.
+	pub fn suggestions(&self, id: &S) -> Vec<String> {
+		closest(
+			&format!("{:?}", id),
+			self.declared()
+				.map(|(index, _)| self.display_name(&self.ids[index])),
+		)
+	}
+
+	/// Returns the error for an inexistent state, including suggestions if there are close states.
+	pub(crate) fn inexistent<E>(&self, id: S) -> AutomatonError<S, E> {
+		let suggestions = self.suggestions(&id);
+		AutomatonError::InexistentState(id, suggestions)
+	}
+
+	/// Updates the current state.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn try_set_current(&mut self, id: S) -> Result<(), AutomatonError<S>> {
+		match self.index_of(&id) {
+			Some(index) => {
+				self.current = Some(index);
+				Ok(())
+			}
+			None => Err(self.inexistent(id)),
+		}
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Returns the display names of the states closest to an inexistent state ID.
+	pub fn suggestions(&self, id: &S) -> Vec<String> {
+		closest(
+			&format!("{:?}", id),
+			self.declared()
+				.map(|(index, _)| self.display_name(&self.ids[index])),
+		)
+	}
+
+	/// Returns the error for an inexistent state, including suggestions if there are close states.
+	pub(crate) fn inexistent<E>(&self, id: S) -> AutomatonError<S, E> {
+		let suggestions = self.suggestions(&id);
+		AutomatonError::InexistentState(id, suggestions)
+	}
+
+	/// Updates the current states.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	pub fn try_set_current(&mut self, state: HashSet<S>) -> Result<(), AutomatonError<S>> {
+		let mut current = BTreeSet::new();
+		for id in state {
+			match self.index_of(&id) {
+				Some(index) => current.insert(index),
+				None => return Err(self.inexistent(id)),
+			};
+		}
+		self.set_current_indices(current);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	#[test]
+	fn distance() {
+		assert_eq!(0, edit_distance("idle", "idle"), "Incorrect distance");
+		assert_eq!(1, edit_distance("idle", "ide"), "Incorrect distance");
+		assert_eq!(2, edit_distance("idle", "idel"), "Incorrect distance");
+		assert_eq!(4, edit_distance("", "idle"), "Incorrect distance");
+	}
+
+	#[test]
+	fn suggest() {
+		let mut dfa = DFA::<String, char>::with_state("Waiting".into(), false);
+		dfa.add_state("Running".into(), false);
+		dfa.add_state("Finished".into(), true);
+		let error = dfa
+			.add_transition(("Waiting".into(), 'a', "Runing".into()))
+			.unwrap_err();
+		assert_eq!(
+			"Inexistent State \"Runing\", did you mean \"Running\"?",
+			error.describe(|id| id.clone()),
+			"Incorrect suggestions"
+		);
+		assert!(
+			matches!(
+				dfa.add_transition(("Stopped".into(), 'a', "Running".into())),
+				Err(AutomatonError::InexistentState(_, suggestions)) if suggestions.is_empty()
+			),
+			"Suggestions for distant state"
+		);
+		assert!(
+			dfa.try_set_current("Finishd".into()).is_err(),
+			"Missing error on inexistent state"
+		);
+		assert_eq!(Some(&"Waiting".into()), dfa.get_current(), "State changed");
+
+		let mut nfa: NFA<_, _> = dfa.into();
+		nfa.set_name(&"Finished".into(), "Done").unwrap();
+		assert_eq!(
+			vec!["Done".to_string()],
+			nfa.suggestions(&"Dome".into()),
+			"Display name not suggested"
+		);
+	}
+}
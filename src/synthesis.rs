@@ -0,0 +1,292 @@
+use super::{Automaton, AutomatonError, DFA};
+use std::{collections::HashMap, hash::Hash};
+
+/// Node of a prefix tree of example words.
+#[derive(Debug)]
+struct Prefix<I> {
+	parent: Option<(usize, I)>,
+	children: Vec<(I, usize)>,
+	label: Option<bool>,
+}
+
+/// Search for a DFA over states numbered up to a bound, consistent with a prefix tree.
+#[derive(Debug)]
+struct Search<'a, I> {
+	tree: &'a [Prefix<I>],
+	order: Vec<usize>,
+	bound: usize,
+	used: usize,
+	state: Vec<usize>,
+	accepts: Vec<Option<bool>>,
+	table: HashMap<(usize, I), usize>,
+}
+
+impl<'a, I> Search<'a, I>
+where
+	I: Clone + Eq + Hash,
+{
+	/// Assigns a state to a node & marks the state with the label of the node.
+	/// Returns the previous acceptance of the state if the assignment is consistent.
+	fn assign(&mut self, node: usize, state: usize) -> Result<Option<bool>, ()> {
+		let previous = self.accepts[state];
+		match (previous, self.tree[node].label) {
+			(Some(old), Some(new)) if old != new => return Err(()),
+			(None, Some(new)) => self.accepts[state] = Some(new),
+			_ => {}
+		}
+		self.state[node] = state;
+		Ok(previous)
+	}
+
+	/// Assigns states to the nodes from a position in breadth-first order on.
+	fn solve(&mut self, position: usize) -> bool {
+		let node = match self.order.get(position) {
+			Some(node) => *node,
+			None => return true,
+		};
+		let (parent, input) = self.tree[node]
+			.parent
+			.clone()
+			.expect("Root is assigned beforehand");
+		let key = (self.state[parent], input);
+		if let Some(state) = self.table.get(&key).cloned() {
+			return match self.assign(node, state) {
+				Ok(previous) => {
+					let solved = self.solve(position + 1);
+					self.accepts[state] = previous;
+					solved
+				}
+				Err(()) => false,
+			};
+		}
+		// existing states first & a single fresh state, since fresh states are interchangeable
+		for state in 0..(self.used + 1).min(self.bound) {
+			let previous = match self.assign(node, state) {
+				Ok(previous) => previous,
+				Err(()) => continue,
+			};
+			let fresh = state == self.used;
+			if fresh {
+				self.used += 1;
+			}
+			self.table.insert(key.clone(), state);
+			if self.solve(position + 1) {
+				return true;
+			}
+			self.table.remove(&key);
+			if fresh {
+				self.used -= 1;
+			}
+			self.accepts[state] = previous;
+		}
+		false
+	}
+}
+
+/// Synthesis of a DFA from example words & structural constraints.
+///
+/// The search finds a DFA with the fewest states accepting all positive & rejecting all negative examples,
+/// using at most the maximum number of states & including all required transitions.
+/// It assigns states to the prefixes of the examples by backtracking in breadth-first order,
+/// following existing transitions & trying a single fresh state to avoid symmetric assignments.
+/// The search is exact & takes exponential time in the worst case, so the maximum number of states should be small.
+///
+/// States of the synthesized DFA are numbered from 0, which is the initial state.
+/// Prefixes & states without an example ending in them are rejecting.
+#[derive(Debug, Clone)]
+pub struct Synthesis<I> {
+	positive: Vec<Vec<I>>,
+	negative: Vec<Vec<I>>,
+	required: Vec<(usize, I, usize)>,
+	max_states: usize,
+}
+
+impl<I> Synthesis<I>
+where
+	I: Default + Clone + Eq + Hash,
+{
+	/// Creates a new synthesis of a DFA with at most the given number of states & without examples.
+	pub fn new(max_states: usize) -> Self {
+		Self {
+			positive: Vec::new(),
+			negative: Vec::new(),
+			required: Vec::new(),
+			max_states,
+		}
+	}
+
+	/// Adds a word the DFA has to accept.
+	pub fn accept<V>(mut self, word: V) -> Self
+	where
+		V: IntoIterator<Item = I>,
+	{
+		self.positive.push(word.into_iter().collect());
+		self
+	}
+
+	/// Adds a word the DFA has to reject.
+	pub fn reject<V>(mut self, word: V) -> Self
+	where
+		V: IntoIterator<Item = I>,
+	{
+		self.negative.push(word.into_iter().collect());
+		self
+	}
+
+	/// Adds a transition between numbered states the DFA has to include.
+	pub fn require(mut self, transition: (usize, I, usize)) -> Self {
+		self.required.push(transition);
+		self
+	}
+
+	/// Builds the prefix tree of the examples.
+	/// Returns `None` if a word is both a positive & a negative example.
+	fn tree(&self) -> Option<Vec<Prefix<I>>> {
+		let mut tree = vec![Prefix {
+			parent: None,
+			children: Vec::new(),
+			label: None,
+		}];
+		let examples = self
+			.positive
+			.iter()
+			.map(|word| (word, true))
+			.chain(self.negative.iter().map(|word| (word, false)));
+		for (word, label) in examples {
+			let mut node = 0;
+			for input in word {
+				let child = tree[node]
+					.children
+					.iter()
+					.find(|(other, _)| other == input)
+					.map(|(_, child)| *child);
+				node = match child {
+					Some(child) => child,
+					None => {
+						tree.push(Prefix {
+							parent: Some((node, input.clone())),
+							children: Vec::new(),
+							label: None,
+						});
+						let child = tree.len() - 1;
+						tree[node].children.push((input.clone(), child));
+						child
+					}
+				};
+			}
+			match tree[node].label {
+				Some(other) if other != label => return None,
+				_ => tree[node].label = Some(label),
+			}
+		}
+		Some(tree)
+	}
+
+	/// Searches for a consistent DFA with the fewest states.
+	/// Returns `None` if there is no consistent DFA with at most the maximum number of states,
+	/// e.g. if a word is both a positive & a negative example.
+	pub fn synthesize(&self) -> Option<DFA<usize, I>> {
+		let tree = self.tree()?;
+		let mut order = Vec::with_capacity(tree.len());
+		let mut queue = vec![0];
+		while !queue.is_empty() {
+			let level = std::mem::take(&mut queue);
+			for node in level {
+				order.push(node);
+				queue.extend(tree[node].children.iter().map(|(_, child)| *child));
+			}
+		}
+		order.remove(0);
+		let named = self
+			.required
+			.iter()
+			.map(|(from, _, to)| from.max(to) + 1)
+			.max()
+			.unwrap_or(1);
+		for bound in named..=self.max_states {
+			let mut table = HashMap::new();
+			for (from, input, to) in &self.required {
+				if *table.entry((*from, input.clone())).or_insert(*to) != *to {
+					return None;
+				}
+			}
+			let mut search = Search {
+				tree: &tree,
+				order: order.clone(),
+				bound,
+				used: named,
+				state: vec![0; tree.len()],
+				accepts: vec![None; bound],
+				table,
+			};
+			search.accepts[0] = tree[0].label;
+			if search.solve(0) {
+				let mut dfa = DFA::new();
+				for state in 0..search.used {
+					dfa.add_state(state, search.accepts[state] == Some(true));
+				}
+				for ((from, input), to) in search.table {
+					dfa.add_transition((from, input, to))
+						.map_err(AutomatonError::without_input)
+						.expect("Transition between inexistent states");
+				}
+				dfa.set_initial(0);
+				dfa.reset();
+				return Some(dfa);
+			}
+		}
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Examples of words over `a` & `b` with an even number of `a`.
+	fn even() -> Synthesis<char> {
+		let mut synthesis = Synthesis::new(4);
+		for word in ["", "b", "aa", "aba", "abab", "baab", "bb"] {
+			synthesis = synthesis.accept(word.chars());
+		}
+		for word in ["a", "ab", "ba", "aaa", "bab", "abb"] {
+			synthesis = synthesis.reject(word.chars());
+		}
+		synthesis
+	}
+
+	#[test]
+	fn examples() {
+		let mut dfa = even().synthesize().unwrap();
+		assert_eq!(2, dfa.stats().states, "Not minimal");
+		assert!(
+			dfa.run(&['b', 'a', 'b', 'a', 'b']),
+			"Generalization rejected"
+		);
+		assert!(!dfa.run(&['a', 'b', 'b', 'b']), "Generalization accepted");
+		assert!(
+			even().reject("aa".chars()).synthesize().is_none(),
+			"Conflicting examples synthesized"
+		);
+		assert!(
+			Synthesis::new(1)
+				.accept("a".chars())
+				.reject("aa".chars())
+				.synthesize()
+				.is_none(),
+			"Synthesized above maximum states"
+		);
+	}
+
+	#[test]
+	fn required() {
+		// a required sink for `c` needs a third state
+		let mut dfa = even().require((2, 'c', 2)).synthesize().unwrap();
+		assert_eq!(3, dfa.stats().states, "Required state missing");
+		assert!(dfa.run(&['a', 'a', 'b']), "Example rejected");
+		assert!(
+			even().require((0, 'a', 0)).synthesize().is_none(),
+			"Inconsistent requirement synthesized"
+		);
+	}
+}
@@ -0,0 +1,149 @@
+use super::{DFA, NFA};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt,
+	hash::Hash,
+};
+
+/// Policy for the tag of a deterministic state combining multiple tagged accepting states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagPolicy {
+	/// Keeps the tag of the smallest accepting state by the order of the state IDs.
+	First,
+
+	/// Keeps the greatest tag.
+	///
+	/// Use `std::cmp::Reverse` for tags where smaller values take precedence.
+	HighestPriority,
+
+	/// Keeps the tags of all accepting states.
+	MergeSet,
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Ord + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+	T: Default + Clone,
+{
+	/// Converts the NFA into an equivalent DFA & resolves the tags of its accepting states.
+	///
+	/// Tags of states which are not accepting are ignored.
+	/// Every accepting state of the DFA containing a tagged state is mapped to its resulting tags.
+	/// The set of tags contains a single tag unless the policy is `TagPolicy::MergeSet`.
+	#[allow(clippy::type_complexity)]
+	pub fn determinize_tagged<K>(
+		self,
+		tags: &HashMap<S, K>,
+		policy: TagPolicy,
+	) -> (DFA<BTreeSet<S>, I, T>, HashMap<BTreeSet<S>, BTreeSet<K>>)
+	where
+		K: Clone + Ord,
+	{
+		let accepting: BTreeSet<S> = self
+			.declared()
+			.filter(|(_, state)| state.accepts)
+			.map(|(index, _)| self.ids[index].clone())
+			.collect();
+		let dfa: DFA<BTreeSet<S>, I, T> = self.into();
+		let mut resolved = HashMap::new();
+		for (index, _) in dfa.declared() {
+			let set = &dfa.ids[index];
+			// the set is ordered by the state IDs
+			let candidates: Vec<_> = set
+				.iter()
+				.filter(|id| accepting.contains(*id))
+				.filter_map(|id| tags.get(id))
+				.collect();
+			if candidates.is_empty() {
+				continue;
+			}
+			let tags: BTreeSet<K> = match policy {
+				TagPolicy::First => candidates.into_iter().take(1).cloned().collect(),
+				TagPolicy::HighestPriority => {
+					candidates.into_iter().max().into_iter().cloned().collect()
+				}
+				TagPolicy::MergeSet => candidates.into_iter().cloned().collect(),
+			};
+			resolved.insert(set.clone(), tags);
+		}
+		(dfa, resolved)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{btreeset, hashmap};
+
+	/// Lexer for identifiers of "i" & "f" with smaller states than the keyword "if".
+	fn lexer() -> NFA<u8, char> {
+		let mut nfa = NFA::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_state(2, false);
+		nfa.add_state(3, true);
+		nfa.add_transition((0, 'i', 1)).unwrap();
+		nfa.add_transition((1, 'f', 1)).unwrap();
+		nfa.add_transition((0, 'i', 2)).unwrap();
+		nfa.add_transition((2, 'f', 3)).unwrap();
+		nfa
+	}
+
+	#[test]
+	fn policies() {
+		let tags = hashmap!(1 => "ident", 3 => "keyword");
+		let keyword = btreeset![1, 3];
+		let (mut dfa, first) = lexer().determinize_tagged(&tags, TagPolicy::First);
+		assert!(dfa.run(&['i', 'f']), "Incorrect result after run");
+		assert_eq!(
+			Some(&btreeset!["ident"]),
+			first.get(&keyword),
+			"Incorrect first tag"
+		);
+
+		let (_, highest) = lexer().determinize_tagged(&tags, TagPolicy::HighestPriority);
+		assert_eq!(
+			Some(&btreeset!["keyword"]),
+			highest.get(&keyword),
+			"Incorrect highest priority tag"
+		);
+
+		let (_, merged) = lexer().determinize_tagged(&tags, TagPolicy::MergeSet);
+		assert_eq!(
+			Some(&btreeset!["ident", "keyword"]),
+			merged.get(&keyword),
+			"Incorrect merged tags"
+		);
+		assert_eq!(
+			Some(&btreeset!["ident"]),
+			merged.get(&btreeset![1, 2]),
+			"Incorrect tag of unambiguous state"
+		);
+	}
+
+	#[test]
+	fn untagged() {
+		let (_, tags) = lexer().determinize_tagged(&HashMap::<u8, u8>::new(), TagPolicy::First);
+		assert!(tags.is_empty(), "Tags of untagged states");
+	}
+
+	#[test]
+	fn declaration_order() {
+		// same lexer with the keyword state declared first
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(3, true);
+		nfa.add_state(2, false);
+		nfa.add_state(1, true);
+		for transition in [(0, 'i', 2), (2, 'f', 3), (0, 'i', 1), (1, 'f', 1)] {
+			nfa.add_transition(transition).unwrap();
+		}
+		let tags = hashmap!(1 => "ident", 3 => "keyword");
+		let (_, first) = nfa.determinize_tagged(&tags, TagPolicy::First);
+		assert_eq!(
+			Some(&btreeset!["ident"]),
+			first.get(&btreeset![1, 3]),
+			"Tag depends on declaration order"
+		);
+	}
+}
@@ -0,0 +1,102 @@
+use super::{Automaton, AutomatonError, DFA, NFA};
+use std::{collections::BTreeSet, fmt, hash::Hash};
+
+/// Implements stepping on ticks for an automaton type over sets of events.
+///
+/// Automata over sets of events consume all events happening in a tick at once, keeping concurrent events unordered.
+/// Their transitions are labeled with the exact set of events of a tick, with the empty set for a tick without events.
+macro_rules! impl_ticks {
+	($automaton:ident) => {
+		impl<S, I, T> $automaton<S, BTreeSet<I>, T>
+		where
+			S: Default + Clone + Eq + Hash + fmt::Debug,
+			I: Ord + Hash,
+			T: Default,
+		{
+			/// Adds a transition taken for a tick with exactly the given events.
+			/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+			/// Returns an `AutomatonError::UnknownInput` error if the automaton rejects the set of events.
+			pub fn add_tick_transition<V>(
+				&mut self,
+				transition: (S, V, S),
+			) -> Result<(), AutomatonError<S, BTreeSet<I>>>
+			where
+				V: IntoIterator<Item = I>,
+			{
+				let (prev, events, next) = transition;
+				self.add_transition((prev, events.into_iter().collect(), next))
+			}
+
+			/// Performs a single state transition for a tick with the given simultaneous events.
+			/// Duplicate events count once.
+			pub fn step_tick<V>(&mut self, events: V)
+			where
+				V: IntoIterator<Item = I>,
+			{
+				self.step(&events.into_iter().collect());
+			}
+
+			/// Runs the automaton on a sequence of ticks with the given simultaneous events.
+			/// This restores the current state after the execution, like `run`.
+			pub fn run_ticks<V, E>(&mut self, ticks: V) -> bool
+			where
+				V: IntoIterator<Item = E>,
+				E: IntoIterator<Item = I>,
+			{
+				let ticks: Vec<BTreeSet<I>> = ticks
+					.into_iter()
+					.map(|events| events.into_iter().collect())
+					.collect();
+				self.run(&ticks)
+			}
+		}
+	};
+}
+
+impl_ticks!(DFA);
+impl_ticks!(NFA);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Door opening only when `unlock` & `push` happen in the same tick.
+	fn door() -> DFA<&'static str, BTreeSet<&'static str>> {
+		let mut dfa = DFA::with_state("closed", false);
+		dfa.add_state("open", true);
+		dfa.add_tick_transition(("closed", vec!["unlock", "push"], "open"))
+			.unwrap();
+		for events in [vec!["unlock"], vec!["push"], vec![]] {
+			dfa.add_tick_transition(("closed", events, "closed"))
+				.unwrap();
+		}
+		dfa
+	}
+
+	#[test]
+	fn ticks() {
+		let mut door = door();
+		assert!(
+			door.run_ticks(vec![vec![], vec!["push", "unlock"]]),
+			"Simultaneous events rejected"
+		);
+		assert!(
+			!door.run_ticks(vec![vec!["unlock"], vec!["push"]]),
+			"Sequential events accepted"
+		);
+		door.step_tick(vec!["unlock", "push", "unlock"]);
+		assert_eq!(Some(&"open"), door.get_current(), "Duplicate event counted");
+	}
+
+	#[test]
+	fn nfa() {
+		let mut nfa = NFA::<u8, BTreeSet<char>>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_tick_transition((0, vec!['a', 'b'], 1)).unwrap();
+		nfa.add_tick_transition((0, vec!['a', 'b'], 0)).unwrap();
+		assert!(
+			nfa.run_ticks(vec![vec!['b', 'a'], vec!['a', 'b']]),
+			"Simultaneous events rejected"
+		);
+	}
+}
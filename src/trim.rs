@@ -0,0 +1,151 @@
+use super::{DFA, NFA};
+use std::{collections::BTreeSet, fmt, hash::Hash};
+
+/// Marks the slots of a state arena reachable from initial slots along the successors of each slot.
+fn mark<V, F>(len: usize, initial: V, mut successors: F) -> Vec<bool>
+where
+	V: IntoIterator<Item = usize>,
+	F: FnMut(usize) -> Vec<usize>,
+{
+	let mut reachable = vec![false; len];
+	let mut stack: Vec<usize> = initial.into_iter().collect();
+	while let Some(index) = stack.pop() {
+		if !reachable[index] {
+			reachable[index] = true;
+			stack.extend(successors(index));
+		}
+	}
+	reachable
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Removes the states unreachable from the initial state along with their transitions & display names.
+	/// Returns the number of states removed.
+	///
+	/// All states are removed if there is no initial state.
+	/// The DFA goes into an invalid state if its current state is removed.
+	pub fn trim_unreachable(&mut self) -> usize {
+		let reachable = mark(self.states.len(), self.initial, |index| {
+			self.state(index)
+				.into_iter()
+				.flat_map(|state| state.transitions.values().map(|(next, _)| *next))
+				.collect()
+		});
+		let mut removed = 0;
+		for (state, reachable) in self.states.iter_mut().zip(&reachable) {
+			if !reachable && state.take().is_some() {
+				removed += 1;
+			}
+		}
+		self.names.retain(|index, _| reachable[*index]);
+		self.current = self.current.filter(|current| reachable[*current]);
+		removed
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash,
+{
+	/// Removes the states unreachable from the initial states along with their transitions & display names.
+	/// Returns the number of states removed.
+	///
+	/// Epsilon transitions are followed. All states are removed if there are no initial states.
+	/// Removed states are dropped from the current states.
+	pub fn trim_unreachable(&mut self) -> usize {
+		let reachable = mark(
+			self.states.len(),
+			self.initial_indices().iter().cloned(),
+			|index| {
+				self.state(index)
+					.into_iter()
+					.flat_map(|state| {
+						state
+							.transitions
+							.values()
+							.flat_map(|next| next.keys())
+							.chain(&state.epsilon)
+							.cloned()
+					})
+					.collect()
+			},
+		);
+		let mut removed = 0;
+		for (state, reachable) in self.states.iter_mut().zip(&reachable) {
+			if !reachable && state.take().is_some() {
+				removed += 1;
+			}
+		}
+		self.names.retain(|index, _| reachable[*index]);
+		let current: BTreeSet<usize> = self
+			.current_indices()
+			.iter()
+			.cloned()
+			.filter(|current| reachable[*current])
+			.collect();
+		self.set_current_indices(current);
+		removed
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{hashmap, hashset};
+
+	#[test]
+	fn dfa() {
+		let mut dfa = DFA::<&str, char>::from_map(
+			"start",
+			hashmap!(
+				"start" => (false, hashmap!('a' => "end")),
+				"end" => (true, hashmap!('a' => "end")),
+				"orphan" => (false, hashmap!('a' => "garbage")),
+				"garbage" => (true, hashmap!('a' => "start"))
+			),
+		);
+		dfa.set_current("garbage");
+		assert_eq!(
+			2,
+			dfa.trim_unreachable(),
+			"Incorrect number of removed states"
+		);
+		assert!(!dfa.has_state(&"orphan"), "Unreachable state kept");
+		assert!(dfa.has_state(&"end"), "Reachable state removed");
+		assert_eq!(None, dfa.get_current(), "Removed state still current");
+		assert_eq!(0, dfa.trim_unreachable(), "States removed twice");
+		dfa.reset();
+		assert!(dfa.run(&['a', 'a']), "Language changed");
+	}
+
+	#[test]
+	fn nfa() {
+		let mut nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![1])),
+				1 => (false, hashmap!()),
+				2 => (true, hashmap!()),
+				3 => (true, hashmap!('a' => hashset![2]))
+			),
+		);
+		nfa.add_epsilon((1, 2)).unwrap();
+		assert_eq!(
+			1,
+			nfa.trim_unreachable(),
+			"Incorrect number of removed states"
+		);
+		assert!(
+			nfa.has_state(&2),
+			"State reachable by epsilon transition removed"
+		);
+		assert!(!nfa.has_state(&3), "Unreachable state kept");
+		assert!(nfa.run(&['a']), "Language changed");
+	}
+}
@@ -0,0 +1,47 @@
+/// Disjoint-set forest with path compression & union by size.
+pub(crate) struct UnionFind {
+	parents: Vec<usize>,
+	sizes: Vec<usize>,
+}
+
+impl UnionFind {
+	/// Creates a new forest of singleton sets.
+	pub fn new(len: usize) -> Self {
+		Self {
+			parents: (0..len).collect(),
+			sizes: vec![1; len],
+		}
+	}
+
+	/// Returns the representative of the set containing an element.
+	pub fn find(&mut self, el: usize) -> usize {
+		let mut root = el;
+		while self.parents[root] != root {
+			root = self.parents[root];
+		}
+		let mut el = el;
+		while self.parents[el] != root {
+			let next = self.parents[el];
+			self.parents[el] = root;
+			el = next;
+		}
+		root
+	}
+
+	/// Merges the sets containing two elements.
+	/// Returns `false` if they were already in the same set.
+	pub fn union(&mut self, a: usize, b: usize) -> bool {
+		let (a, b) = (self.find(a), self.find(b));
+		if a == b {
+			return false;
+		}
+		let (large, small) = if self.sizes[a] >= self.sizes[b] {
+			(a, b)
+		} else {
+			(b, a)
+		};
+		self.parents[small] = large;
+		self.sizes[large] += self.sizes[small];
+		true
+	}
+}
@@ -0,0 +1,119 @@
+use super::{graph::Graph, DFA, NFA};
+use std::{fmt, hash::Hash};
+
+impl<'a, S, I> Graph<'a, S, I> {
+	/// Returns the transitions among the given edges which are not part of any accepting path from an initial state.
+	///
+	/// The edges use the same indices as the graph, but may differ from its own edges.
+	fn unused_transitions(&self, edges: &[Vec<(&'a I, usize)>]) -> Vec<(&'a S, &'a I, &'a S)> {
+		let reachable = self.reachable();
+		let coreachable = self.coreachable();
+		edges
+			.iter()
+			.enumerate()
+			.flat_map(|(from, edges)| edges.iter().map(move |(input, to)| (from, *input, *to)))
+			.filter(|(from, _, to)| !reachable[*from] || !coreachable[*to])
+			.map(|(from, input, to)| (self.labels[from], input, self.labels[to]))
+			.collect()
+	}
+}
+
+impl<S, I, T> DFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the transitions which can never be taken on an accepting run.
+	///
+	/// This includes transitions from unreachable states & transitions to states from which no accepting state is reachable.
+	pub fn unused_transitions(&self) -> Vec<(S, I, S)> {
+		let graph = self.graph();
+		graph
+			.unused_transitions(&graph.edges)
+			.into_iter()
+			.map(|(from, input, to)| (from.clone(), input.clone(), to.clone()))
+			.collect()
+	}
+}
+
+impl<S, I, T> NFA<S, I, T>
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Clone + Eq + Hash,
+{
+	/// Returns the transitions which can never be taken on an accepting run.
+	///
+	/// This includes transitions from unreachable states & transitions to states from which no accepting state is reachable.
+	/// Epsilon transitions are followed but not returned.
+	pub fn unused_transitions(&self) -> Vec<(S, I, S)> {
+		self.graph()
+			.unused_transitions(&self.transition_graph().edges)
+			.into_iter()
+			.map(|(from, input, to)| (from.clone(), input.clone(), to.clone()))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+	use maplit::{hashmap, hashset};
+
+	#[test]
+	fn dfa() {
+		let dfa = DFA::<&str, char>::from_map(
+			"start",
+			hashmap!(
+				"start" => (false, hashmap!('a' => "end", 'b' => "sink")),
+				"end" => (true, hashmap!()),
+				"sink" => (false, hashmap!('a' => "sink")),
+				"orphan" => (false, hashmap!('a' => "end"))
+			),
+		);
+		let mut unused = dfa.unused_transitions();
+		unused.sort();
+		assert_eq!(
+			vec![
+				("orphan", 'a', "end"),
+				("sink", 'a', "sink"),
+				("start", 'b', "sink")
+			],
+			unused,
+			"Incorrect unused transitions"
+		);
+	}
+
+	#[test]
+	fn nfa() {
+		let nfa = NFA::<u8, char>::from_map(
+			hashset![0],
+			hashmap!(
+				0 => (false, hashmap!('a' => hashset![1, 2])),
+				1 => (true, hashmap!()),
+				2 => (false, hashmap!())
+			),
+		);
+		assert_eq!(
+			vec![(0, 'a', 2)],
+			nfa.unused_transitions(),
+			"Incorrect unused transitions"
+		);
+	}
+
+	#[test]
+	fn epsilon() {
+		let mut nfa = crate::regex::compile("ab*").unwrap();
+		assert!(
+			nfa.unused_transitions().is_empty(),
+			"Transitions followed by epsilon transitions unused"
+		);
+		nfa.add_state(6, false);
+		nfa.add_transition((1, 'c', 6)).unwrap();
+		assert_eq!(
+			vec![(1, 'c', 6)],
+			nfa.unused_transitions(),
+			"Incorrect unused transitions"
+		);
+	}
+}
@@ -0,0 +1,572 @@
+//! Rendering of automata as [DOT](https://graphviz.org/doc/info/lang.html) graphs & [Mermaid](https://mermaid.js.org/) flowcharts or state diagrams.
+//!
+//! States are labeled with their display names & inputs with their `Debug` representation.
+//! Accepting states are drawn with a double border & initial states have an incoming edge from a point.
+//! State diagrams use the start & end states `[*]` for initial & accepting states instead.
+//! Transitions of a state are ordered by their labels, so the output is stable.
+//!
+//! The diff renderers draw the states & transitions of two DFAs in a single diagram, matching states by their IDs.
+//! Added elements are green, removed elements are red & dashed and changed elements are orange.
+//! A state is changed if its acceptance, being initial or its display name differ,
+//! a transition is changed if the same state has a transition for the same input to a different state.
+
+use super::{graph::Graph, DFA, NFA};
+use std::{collections::HashMap, fmt, fmt::Write, hash::Hash};
+
+/// Status of an element in a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+	Unchanged,
+	Added,
+	Removed,
+	Changed,
+}
+
+impl Status {
+	fn color(self) -> Option<&'static str> {
+		match self {
+			Self::Unchanged => None,
+			Self::Added => Some("green"),
+			Self::Removed => Some("red"),
+			Self::Changed => Some("orange"),
+		}
+	}
+}
+
+#[derive(Debug)]
+struct Node {
+	label: String,
+	accepts: bool,
+	initial: bool,
+	status: Status,
+}
+
+#[derive(Debug)]
+struct Edge {
+	from: usize,
+	to: usize,
+	label: String,
+	status: Status,
+}
+
+/// Diagram of one DFA or of the diff between two DFAs.
+#[derive(Debug, Default)]
+struct Diagram {
+	nodes: Vec<Node>,
+	edges: Vec<Edge>,
+}
+
+impl Diagram {
+	/// Creates a diagram of the states & transitions of a graph, labeling states by their display names.
+	fn from_graph<S, I, F>(graph: &Graph<'_, S, I>, display_name: F) -> Self
+	where
+		I: fmt::Debug,
+		F: Fn(&S) -> String,
+	{
+		let nodes = graph
+			.labels
+			.iter()
+			.enumerate()
+			.map(|(i, id)| Node {
+				label: display_name(id),
+				accepts: graph.accepts[i],
+				initial: graph.initial.contains(&i),
+				status: Status::Unchanged,
+			})
+			.collect();
+		let edges = transitions(&graph.edges)
+			.into_iter()
+			.map(|(from, label, to)| Edge {
+				from,
+				to,
+				label,
+				status: Status::Unchanged,
+			})
+			.collect();
+		Self { nodes, edges }
+	}
+
+	/// Creates a diagram of the states & transitions of a DFA.
+	fn new<S, I, T>(dfa: &DFA<S, I, T>) -> Self
+	where
+		S: Default + Clone + Eq + Hash + fmt::Debug,
+		I: Default + Eq + Hash + fmt::Debug,
+	{
+		Self::from_graph(&dfa.graph(), |id| dfa.display_name(id))
+	}
+
+	/// Creates a diagram of the states & transitions of an NFA.
+	/// Epsilon transitions follow the other transitions & are labeled with `ε`.
+	fn nfa<S, I, T>(nfa: &NFA<S, I, T>) -> Self
+	where
+		S: Default + Clone + Eq + Hash + fmt::Debug,
+		I: Default + Eq + Hash + fmt::Debug,
+	{
+		let graph = nfa.transition_graph();
+		let indices = graph.indices();
+		let mut diagram = Self::from_graph(&graph, |id| nfa.display_name(id));
+		for (from, id) in graph.labels.iter().enumerate() {
+			let state = nfa.index_of(id).and_then(|index| nfa.state(index));
+			for next in state.into_iter().flat_map(|state| &state.epsilon) {
+				if let Some(to) = indices.get(&nfa.ids[*next]) {
+					diagram.edges.push(Edge {
+						from,
+						to: *to,
+						label: "ε".into(),
+						status: Status::Unchanged,
+					});
+				}
+			}
+		}
+		diagram
+	}
+
+	/// Creates a diagram of the states & transitions of the new DFA marked with their changes since the old DFA.
+	/// States & transitions only present in the old DFA follow the ones of the new DFA.
+	fn diff<S, I, T, U>(old: &DFA<S, I, T>, new: &DFA<S, I, U>) -> Self
+	where
+		S: Default + Clone + Eq + Hash + fmt::Debug,
+		I: Default + Eq + Hash + fmt::Debug,
+	{
+		let (old_graph, new_graph) = (old.graph(), new.graph());
+		let (old_indices, new_indices) = (old_graph.indices(), new_graph.indices());
+		let mut diagram = Self::new(new);
+
+		// node of each old state
+		let mut nodes = Vec::new();
+		for (i, id) in old_graph.labels.iter().enumerate() {
+			let accepts = old_graph.accepts[i];
+			let initial = old_graph.initial.contains(&i);
+			match new_indices.get(id) {
+				Some(node) => {
+					let new_node = &mut diagram.nodes[*node];
+					if new_node.accepts != accepts
+						|| new_node.initial != initial
+						|| new_node.label != old.display_name(id)
+					{
+						new_node.status = Status::Changed;
+					}
+					nodes.push(*node);
+				}
+				None => {
+					nodes.push(diagram.nodes.len());
+					diagram.nodes.push(Node {
+						label: old.display_name(id),
+						accepts,
+						initial,
+						status: Status::Removed,
+					});
+				}
+			}
+		}
+		for (node, id) in diagram.nodes.iter_mut().zip(&new_graph.labels) {
+			if !old_indices.contains_key(id) {
+				node.status = Status::Added;
+			}
+		}
+
+		// targets of the old transitions by node & label
+		let old_edges: HashMap<_, _> = transitions(&old_graph.edges)
+			.into_iter()
+			.map(|(from, label, to)| ((nodes[from], label), nodes[to]))
+			.collect();
+		let new_edges: HashMap<_, _> = diagram
+			.edges
+			.iter()
+			.map(|edge| ((edge.from, edge.label.clone()), edge.to))
+			.collect();
+		for edge in &mut diagram.edges {
+			edge.status = match old_edges.get(&(edge.from, edge.label.clone())) {
+				Some(to) if *to == edge.to => Status::Unchanged,
+				Some(_) => Status::Changed,
+				None => Status::Added,
+			};
+		}
+		for (from, label, to) in transitions(&old_graph.edges) {
+			let (from, to) = (nodes[from], nodes[to]);
+			if new_edges.get(&(from, label.clone())) != Some(&to) {
+				diagram.edges.push(Edge {
+					from,
+					to,
+					label,
+					status: Status::Removed,
+				});
+			}
+		}
+		diagram
+	}
+
+	/// Writes the diagram in the DOT language.
+	fn dot(&self) -> String {
+		let mut code = String::from("digraph {\n\trankdir=LR;\n");
+		for (i, node) in self.nodes.iter().enumerate() {
+			let shape = if node.accepts {
+				"doublecircle"
+			} else {
+				"circle"
+			};
+			write!(
+				code,
+				"\tn{} [label=\"{}\", shape={}",
+				i,
+				escape(&node.label),
+				shape
+			)
+			.unwrap();
+			let status = dot_status(node.status);
+			writeln!(code, "{}];", status).unwrap();
+			if node.initial {
+				writeln!(code, "\ti{} [shape=point];", i).unwrap();
+				match status.strip_prefix(", ") {
+					Some(status) => writeln!(code, "\ti{} -> n{} [{}];", i, i, status),
+					None => writeln!(code, "\ti{} -> n{};", i, i),
+				}
+				.unwrap();
+			}
+		}
+		for edge in &self.edges {
+			write!(
+				code,
+				"\tn{} -> n{} [label=\"{}\"",
+				edge.from,
+				edge.to,
+				escape(&edge.label)
+			)
+			.unwrap();
+			writeln!(code, "{}];", dot_status(edge.status)).unwrap();
+		}
+		code.push_str("}\n");
+		code
+	}
+
+	/// Writes the diagram as Mermaid flowchart.
+	fn mermaid(&self) -> String {
+		let mut code = String::from("flowchart LR\n");
+		let mut links = Vec::new();
+		for (i, node) in self.nodes.iter().enumerate() {
+			let label = node.label.replace('"', "#quot;");
+			if node.accepts {
+				writeln!(code, "\tn{}(((\"{}\")))", i, label).unwrap();
+			} else {
+				writeln!(code, "\tn{}((\"{}\"))", i, label).unwrap();
+			}
+			if node.initial {
+				writeln!(code, "\ti{}[ ] --> n{}", i, i).unwrap();
+				links.push(node.status);
+			}
+		}
+		for edge in &self.edges {
+			writeln!(
+				code,
+				"\tn{} -->|\"{}\"| n{}",
+				edge.from,
+				edge.label.replace('"', "#quot;"),
+				edge.to
+			)
+			.unwrap();
+			links.push(edge.status);
+		}
+		for (i, node) in self.nodes.iter().enumerate() {
+			if let Some(color) = node.status.color() {
+				write!(code, "\tstyle n{} stroke:{}", i, color).unwrap();
+				if node.status == Status::Removed {
+					code.push_str(",stroke-dasharray:5");
+				}
+				code.push('\n');
+			}
+		}
+		for (i, status) in links.into_iter().enumerate() {
+			if let Some(color) = status.color() {
+				write!(code, "\tlinkStyle {} stroke:{}", i, color).unwrap();
+				if status == Status::Removed {
+					code.push_str(",stroke-dasharray:5");
+				}
+				code.push('\n');
+			}
+		}
+		code
+	}
+
+	/// Writes the diagram as Mermaid state diagram.
+	///
+	/// Initial states have a transition from the start `[*]` & accepting states a transition to the end `[*]`.
+	fn state_diagram(&self) -> String {
+		let mut code = String::from("stateDiagram-v2\n\tdirection LR\n");
+		for (i, node) in self.nodes.iter().enumerate() {
+			writeln!(
+				code,
+				"\tstate \"{}\" as n{}",
+				node.label.replace('"', "#quot;"),
+				i
+			)
+			.unwrap();
+		}
+		for (i, node) in self.nodes.iter().enumerate() {
+			if node.initial {
+				writeln!(code, "\t[*] --> n{}", i).unwrap();
+			}
+		}
+		for edge in &self.edges {
+			writeln!(
+				code,
+				"\tn{} --> n{} : {}",
+				edge.from,
+				edge.to,
+				edge.label.replace(':', "#colon;")
+			)
+			.unwrap();
+		}
+		for (i, node) in self.nodes.iter().enumerate() {
+			if node.accepts {
+				writeln!(code, "\tn{} --> [*]", i).unwrap();
+			}
+		}
+		for status in [Status::Added, Status::Removed, Status::Changed] {
+			let nodes: Vec<_> = (0..self.nodes.len())
+				.filter(|i| self.nodes[*i].status == status)
+				.map(|i| format!("n{}", i))
+				.collect();
+			if let (Some(color), false) = (status.color(), nodes.is_empty()) {
+				let class = format!("{:?}", status).to_lowercase();
+				write!(code, "\tclassDef {} stroke:{}", class, color).unwrap();
+				if status == Status::Removed {
+					code.push_str(",stroke-dasharray:5");
+				}
+				writeln!(code, "\n\tclass {} {}", nodes.join(","), class).unwrap();
+			}
+		}
+		code
+	}
+}
+
+/// Returns the transitions of a graph as origin, label & target, ordered by origin & label.
+fn transitions<I>(edges: &[Vec<(&I, usize)>]) -> Vec<(usize, String, usize)>
+where
+	I: fmt::Debug,
+{
+	let mut transitions: Vec<_> = edges
+		.iter()
+		.enumerate()
+		.flat_map(|(from, edges)| {
+			edges
+				.iter()
+				.map(move |(input, to)| (from, format!("{:?}", input), *to))
+		})
+		.collect();
+	transitions.sort();
+	transitions
+}
+
+/// Returns the DOT attributes for the status of an element, each preceded by a comma.
+fn dot_status(status: Status) -> String {
+	let mut attributes = String::new();
+	if let Some(color) = status.color() {
+		write!(attributes, ", color={}, fontcolor={}", color, color).unwrap();
+	}
+	if status == Status::Removed {
+		attributes.push_str(", style=dashed");
+	}
+	attributes
+}
+
+/// Escapes a label for a quoted DOT string.
+fn escape(label: &str) -> String {
+	label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Renders a DFA as DOT graph.
+pub fn dot<S, I, T>(dfa: &DFA<S, I, T>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	Diagram::new(dfa).dot()
+}
+
+/// Renders a DFA as Mermaid flowchart.
+pub fn mermaid<S, I, T>(dfa: &DFA<S, I, T>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	Diagram::new(dfa).mermaid()
+}
+
+/// Renders a DFA as Mermaid state diagram.
+pub fn state_diagram<S, I, T>(dfa: &DFA<S, I, T>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	Diagram::new(dfa).state_diagram()
+}
+
+/// Renders an NFA as Mermaid state diagram.
+pub fn nfa_state_diagram<S, I, T>(nfa: &NFA<S, I, T>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	Diagram::nfa(nfa).state_diagram()
+}
+
+/// Renders the changes from an old to a new DFA as DOT graph.
+pub fn diff_dot<S, I, T, U>(old: &DFA<S, I, T>, new: &DFA<S, I, U>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	Diagram::diff(old, new).dot()
+}
+
+/// Renders the changes from an old to a new DFA as Mermaid flowchart.
+pub fn diff_mermaid<S, I, T, U>(old: &DFA<S, I, T>, new: &DFA<S, I, U>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	Diagram::diff(old, new).mermaid()
+}
+
+/// Renders the changes from an old to a new DFA as Mermaid state diagram.
+/// Only states are marked, since state diagrams cannot style transitions.
+pub fn diff_state_diagram<S, I, T, U>(old: &DFA<S, I, T>, new: &DFA<S, I, U>) -> String
+where
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	I: Default + Eq + Hash + fmt::Debug,
+{
+	Diagram::diff(old, new).state_diagram()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Automaton;
+
+	/// Accepts words over `a` & `b` ending with `b`.
+	fn dfa() -> DFA<u8, char> {
+		let mut dfa = DFA::with_state(0, false);
+		dfa.add_state(1, true);
+		for (from, input, to) in [(0, 'a', 0), (0, 'b', 1), (1, 'a', 0), (1, 'b', 1)] {
+			dfa.add_transition((from, input, to)).unwrap();
+		}
+		dfa
+	}
+
+	#[test]
+	fn dot() {
+		let mut dfa = dfa();
+		dfa.set_name(&1, "\"End\"").unwrap();
+		assert_eq!(
+			"digraph {\n\trankdir=LR;\n\
+			\tn0 [label=\"0\", shape=circle];\n\
+			\ti0 [shape=point];\n\
+			\ti0 -> n0;\n\
+			\tn1 [label=\"\\\"End\\\"\", shape=doublecircle];\n\
+			\tn0 -> n0 [label=\"'a'\"];\n\
+			\tn0 -> n1 [label=\"'b'\"];\n\
+			\tn1 -> n0 [label=\"'a'\"];\n\
+			\tn1 -> n1 [label=\"'b'\"];\n\
+			}\n",
+			super::dot(&dfa),
+			"Incorrect graph"
+		);
+	}
+
+	#[test]
+	fn diff() {
+		let old = dfa();
+		// state `1` replaced by `2` without transitions & new transition for `c`
+		let mut new = DFA::<u8, char>::with_state(0, false);
+		new.add_state(2, true);
+		for (from, input, to) in [(0, 'a', 0), (0, 'b', 2), (0, 'c', 0)] {
+			new.add_transition((from, input, to)).unwrap();
+		}
+		let diff = diff_dot(&old, &new);
+		assert!(
+			diff.contains("n1 [label=\"2\", shape=doublecircle, color=green"),
+			"Added state not marked"
+		);
+		assert!(
+			diff.contains(
+				"n2 [label=\"1\", shape=doublecircle, color=red, fontcolor=red, style=dashed]"
+			),
+			"Removed state not marked"
+		);
+		assert!(
+			diff.contains("n0 -> n1 [label=\"'b'\", color=orange"),
+			"Changed transition not marked"
+		);
+		assert!(
+			diff.contains("n0 -> n0 [label=\"'c'\", color=green"),
+			"Added transition not marked"
+		);
+		assert!(
+			diff.contains("n2 -> n0 [label=\"'a'\", color=red"),
+			"Removed transition not marked"
+		);
+		assert!(
+			diff.contains("n0 -> n0 [label=\"'a'\"]"),
+			"Unchanged transition marked"
+		);
+	}
+
+	#[test]
+	fn mermaid() {
+		let mut new = dfa();
+		new.set_initial(1);
+		let diff = diff_mermaid(&dfa(), &new);
+		assert!(diff.starts_with("flowchart LR\n"), "Missing header");
+		assert!(
+			diff.contains("\tn1(((\"1\")))\n\ti1[ ] --> n1\n"),
+			"Missing initial state"
+		);
+		assert!(
+			diff.contains("\tstyle n0 stroke:orange\n"),
+			"Changed state not marked"
+		);
+		assert!(
+			diff.contains("\tstyle n1 stroke:orange\n"),
+			"Changed state not marked"
+		);
+		assert!(
+			!super::mermaid(&new).contains("style"),
+			"Unchanged DFA marked"
+		);
+	}
+
+	#[test]
+	fn state_diagram() {
+		assert_eq!(
+			"stateDiagram-v2\n\tdirection LR\n\
+			\tstate \"0\" as n0\n\
+			\tstate \"1\" as n1\n\
+			\t[*] --> n0\n\
+			\tn0 --> n0 : 'a'\n\
+			\tn0 --> n1 : 'b'\n\
+			\tn1 --> n0 : 'a'\n\
+			\tn1 --> n1 : 'b'\n\
+			\tn1 --> [*]\n",
+			super::state_diagram(&dfa()),
+			"Incorrect state diagram"
+		);
+
+		let mut nfa = NFA::<u8, char>::with_state(0, false);
+		nfa.add_state(1, true);
+		nfa.add_transition((0, ':', 0)).unwrap();
+		nfa.add_epsilon((0, 1)).unwrap();
+		let diagram = nfa_state_diagram(&nfa);
+		assert!(
+			diagram.contains("\tn0 --> n0 : '#colon;'\n\tn0 --> n1 : ε\n"),
+			"Missing transitions"
+		);
+
+		let mut new = dfa();
+		new.set_initial(1);
+		assert!(
+			diff_state_diagram(&dfa(), &new)
+				.ends_with("\tclassDef changed stroke:orange\n\tclass n0,n1 changed\n"),
+			"Changed states not marked"
+		);
+	}
+}
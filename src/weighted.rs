@@ -0,0 +1,344 @@
+use super::AutomatonError;
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Trait for the weights of a `WeightedAutomaton`.
+///
+/// Weights along a path are combined by `times` & weights of alternative paths by `plus`.
+/// `zero` is the weight of no path & `one` the weight of the empty path.
+/// Implementations have to satisfy the semiring laws, i.e. `plus` is associative & commutative with identity `zero`,
+/// `times` is associative with identity `one` & annihilator `zero` & distributes over `plus`.
+pub trait Semiring: Clone {
+	/// Returns the identity of `plus`.
+	fn zero() -> Self;
+
+	/// Returns the identity of `times`.
+	fn one() -> Self;
+
+	/// Combines the weights of alternative paths.
+	fn plus(&self, other: &Self) -> Self;
+
+	/// Combines the weights of consecutive parts of a path.
+	fn times(&self, other: &Self) -> Self;
+}
+
+/// Boolean semiring, where the weight of a word is whether it is accepted.
+impl Semiring for bool {
+	fn zero() -> Self {
+		false
+	}
+
+	fn one() -> Self {
+		true
+	}
+
+	fn plus(&self, other: &Self) -> Self {
+		*self || *other
+	}
+
+	fn times(&self, other: &Self) -> Self {
+		*self && *other
+	}
+}
+
+/// Tropical semiring of costs, where the weight of a word is the cost of its cheapest path.
+///
+/// Costs along a path are added & the minimum is taken over alternative paths.
+/// The weight of no path is infinite.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Tropical(pub f64);
+
+impl Semiring for Tropical {
+	fn zero() -> Self {
+		Self(f64::INFINITY)
+	}
+
+	fn one() -> Self {
+		Self(0.0)
+	}
+
+	fn plus(&self, other: &Self) -> Self {
+		Self(self.0.min(other.0))
+	}
+
+	fn times(&self, other: &Self) -> Self {
+		Self(self.0 + other.0)
+	}
+}
+
+/// Probability semiring, where the weight of a word is the total probability of its paths.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Probability(pub f64);
+
+impl Semiring for Probability {
+	fn zero() -> Self {
+		Self(0.0)
+	}
+
+	fn one() -> Self {
+		Self(1.0)
+	}
+
+	fn plus(&self, other: &Self) -> Self {
+		Self(self.0 + other.0)
+	}
+
+	fn times(&self, other: &Self) -> Self {
+		Self(self.0 * other.0)
+	}
+}
+
+/// Counting semiring, where the weight of a word is the number of its paths, saturating at `u64::MAX`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Count(pub u64);
+
+impl Semiring for Count {
+	fn zero() -> Self {
+		Self(0)
+	}
+
+	fn one() -> Self {
+		Self(1)
+	}
+
+	fn plus(&self, other: &Self) -> Self {
+		Self(self.0.saturating_add(other.0))
+	}
+
+	fn times(&self, other: &Self) -> Self {
+		Self(self.0.saturating_mul(other.0))
+	}
+}
+
+/// Transition of a `WeightedAutomaton` to a state index.
+#[derive(Debug, Clone)]
+pub(crate) struct Transition<W> {
+	pub next: usize,
+	pub weight: W,
+}
+
+/// A weighted automaton with weights of type `W` from a semiring attached to its transitions.
+///
+/// Each state has an initial & a final weight, which are `zero` unless set.
+/// The weight of a path is the product of the initial weight of its first state, the weights of its transitions
+/// & the final weight of its last state. The weight of a word is the sum of the weights of all paths reading it.
+/// With the tropical semiring this is the cost of the cheapest path, with the counting semiring the number of paths.
+#[derive(Debug, Clone)]
+pub struct WeightedAutomaton<S, I, W>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+	W: Semiring,
+{
+	pub(crate) ids: Vec<S>,
+	pub(crate) index: HashMap<S, usize>,
+	pub(crate) initial: Vec<W>,
+	pub(crate) finals: Vec<W>,
+	pub(crate) transitions: Vec<HashMap<I, Vec<Transition<W>>>>,
+}
+
+impl<S, I, W> Default for WeightedAutomaton<S, I, W>
+where
+	S: Eq + Hash,
+	I: Eq + Hash,
+	W: Semiring,
+{
+	fn default() -> Self {
+		Self {
+			ids: Vec::new(),
+			index: HashMap::new(),
+			initial: Vec::new(),
+			finals: Vec::new(),
+			transitions: Vec::new(),
+		}
+	}
+}
+
+impl<S, I, W> WeightedAutomaton<S, I, W>
+where
+	S: Clone + Eq + Hash + fmt::Debug,
+	I: Eq + Hash,
+	W: Semiring,
+{
+	/// Creates a new weighted automaton without states.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a new state with a final weight or updates the final weight of an existing state.
+	/// A final weight of `zero` makes the state non-accepting.
+	pub fn add_state(&mut self, id: S, weight: W) {
+		match self.index.get(&id) {
+			Some(index) => self.finals[*index] = weight,
+			None => {
+				self.index.insert(id.clone(), self.ids.len());
+				self.ids.push(id);
+				self.initial.push(W::zero());
+				self.finals.push(weight);
+				self.transitions.push(HashMap::new());
+			}
+		}
+	}
+
+	/// Checks whether a state exists.
+	pub fn has_state(&self, id: &S) -> bool {
+		self.index.contains_key(id)
+	}
+
+	/// Returns the number of states.
+	pub fn len(&self) -> usize {
+		self.ids.len()
+	}
+
+	/// Checks whether there are no states.
+	pub fn is_empty(&self) -> bool {
+		self.ids.is_empty()
+	}
+
+	/// Sets the initial weight of a state.
+	/// Returns an `AutomatonError::InexistentState` error if the state is inexistent.
+	pub fn set_initial(&mut self, id: S, weight: W) -> Result<(), AutomatonError<S>> {
+		let index = self.index_of(id)?;
+		self.initial[index] = weight;
+		Ok(())
+	}
+
+	/// Adds a weighted transition.
+	/// Returns an `AutomatonError::InexistentState` error if one of the states is inexistent.
+	///
+	/// Parallel transitions for the same input are kept as alternative paths.
+	pub fn add_transition(
+		&mut self,
+		transition: (S, I, S),
+		weight: W,
+	) -> Result<(), AutomatonError<S>> {
+		let (prev, input, next) = transition;
+		let prev = self.index_of(prev)?;
+		let next = self.index_of(next)?;
+		self.transitions[prev]
+			.entry(input)
+			.or_default()
+			.push(Transition { next, weight });
+		Ok(())
+	}
+
+	/// Returns the weights of each state index after reading a word.
+	fn weights(&self, word: &[I]) -> Vec<W> {
+		word.iter().fold(self.initial.clone(), |weights, input| {
+			let mut next = vec![W::zero(); self.ids.len()];
+			for (prev, weight) in weights.iter().enumerate() {
+				for transition in self.transitions[prev].get(input).into_iter().flatten() {
+					next[transition.next] =
+						next[transition.next].plus(&weight.times(&transition.weight));
+				}
+			}
+			next
+		})
+	}
+
+	/// Returns the weights of each state after reading a word, i.e. the sums of the weights of the paths
+	/// from the initial states reading the word & ending in the state, without final weights.
+	pub fn forward(&self, word: &[I]) -> HashMap<&S, W> {
+		self.ids.iter().zip(self.weights(word)).collect()
+	}
+
+	/// Returns the weight of a word, which is the sum of the weights of all paths reading it.
+	/// Returns `zero` if there is no such path.
+	pub fn weight(&self, word: &[I]) -> W {
+		self.weights(word)
+			.iter()
+			.zip(&self.finals)
+			.fold(W::zero(), |sum, (weight, last)| {
+				sum.plus(&weight.times(last))
+			})
+	}
+
+	/// Looks up the index of a state or returns an `AutomatonError::InexistentState` error.
+	fn index_of(&self, id: S) -> Result<usize, AutomatonError<S>> {
+		self.index
+			.get(&id)
+			.cloned()
+			.ok_or(AutomatonError::InexistentState(id, Vec::new()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Reads `a` either directly from 0 to 2 or via 1, with the given weights of the three transitions.
+	fn diamond<W>(weights: [W; 3]) -> WeightedAutomaton<u8, char, W>
+	where
+		W: Semiring,
+	{
+		let mut automaton = WeightedAutomaton::new();
+		automaton.add_state(0, W::zero());
+		automaton.add_state(1, W::zero());
+		automaton.add_state(2, W::one());
+		automaton.set_initial(0, W::one()).unwrap();
+		let [direct, first, second] = weights;
+		automaton.add_transition((0, 'a', 2), direct).unwrap();
+		automaton.add_transition((0, 'a', 1), first).unwrap();
+		automaton.add_transition((1, 'b', 2), second).unwrap();
+		automaton.add_transition((2, 'b', 2), W::one()).unwrap();
+		automaton
+	}
+
+	#[test]
+	fn tropical() {
+		let mut automaton = diamond([Tropical(5.0), Tropical(1.0), Tropical(2.0)]);
+		assert_eq!(Tropical(5.0), automaton.weight(&['a']), "Incorrect cost");
+		assert_eq!(
+			Tropical(3.0),
+			automaton.weight(&['a', 'b']),
+			"Cheapest path not chosen"
+		);
+		automaton.add_state(2, Tropical(1.0));
+		assert_eq!(
+			Tropical(4.0),
+			automaton.weight(&['a', 'b']),
+			"Final cost ignored"
+		);
+		assert_eq!(
+			Tropical::zero(),
+			automaton.weight(&['b']),
+			"Cost without path"
+		);
+	}
+
+	#[test]
+	fn count() {
+		let automaton = diamond([Count(1), Count(1), Count(1)]);
+		assert_eq!(Count(2), automaton.weight(&['a', 'b']), "Incorrect count");
+		assert_eq!(
+			Count(2),
+			automaton.weight(&['a', 'b', 'b']),
+			"Incorrect count"
+		);
+		assert_eq!(Count(0), automaton.weight(&[]), "Empty word counted");
+		assert!(
+			diamond([true, false, true]).weight(&['a', 'b']),
+			"Word rejected"
+		);
+	}
+
+	#[test]
+	fn probability() {
+		let automaton = diamond([Probability(0.25), Probability(0.75), Probability(0.5)]);
+		assert_eq!(
+			Probability(0.625),
+			automaton.weight(&['a', 'b']),
+			"Incorrect probability"
+		);
+		assert_eq!(
+			Probability(0.75),
+			automaton.forward(&['a'])[&1],
+			"Incorrect forward weight"
+		);
+		assert!(
+			WeightedAutomaton::<u8, char, Count>::new()
+				.set_initial(0, Count(1))
+				.is_err(),
+			"Inexistent state set initial"
+		);
+	}
+}
@@ -0,0 +1,307 @@
+use super::{InputKey, Machine, MachineError};
+use std::{collections::HashMap, fmt, hash::Hash};
+
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+/// Current states of workflow instances keyed by their ID.
+///
+/// Instances in an invalid state are stored as `None`.
+pub type Snapshot<K, S> = HashMap<K, Option<S>>;
+
+/// Workflow instance with its current state.
+#[derive(Debug, Clone)]
+struct Instance<S> {
+	state: Option<S>,
+	/// Time the current state was entered, unknown for restored instances.
+	#[cfg(feature = "metrics")]
+	entered: Option<Instant>,
+}
+
+/// Store for many instances of one machine definition, keyed by an ID.
+///
+/// Each instance only keeps its current state.
+/// Events are handled directly, bypassing the queue of the machine.
+///
+/// With the `metrics` feature, each instance also keeps the time its current state was entered,
+/// so the machine records the durations of the instances. Instances restored from a snapshot
+/// record no duration for the state they were restored into.
+pub struct WorkflowStore<K, S, E, C>
+where
+	K: Eq + Hash,
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+{
+	machine: Machine<S, E, C>,
+	instances: HashMap<K, Instance<S>>,
+}
+
+impl<K, S, E, C> WorkflowStore<K, S, E, C>
+where
+	K: Eq + Hash,
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+{
+	/// Creates a new store without instances for a machine definition.
+	pub fn new(machine: Machine<S, E, C>) -> Self {
+		Self {
+			machine,
+			instances: HashMap::new(),
+		}
+	}
+
+	/// Returns the machine definition of the store.
+	pub fn machine(&self) -> &Machine<S, E, C> {
+		&self.machine
+	}
+
+	/// Returns the number of instances.
+	pub fn len(&self) -> usize {
+		self.instances.len()
+	}
+
+	/// Checks whether the store has no instances.
+	pub fn is_empty(&self) -> bool {
+		self.instances.is_empty()
+	}
+
+	/// Checks whether the store has an instance with an ID.
+	pub fn contains(&self, id: &K) -> bool {
+		self.instances.contains_key(id)
+	}
+
+	/// Returns the current state of an instance.
+	/// Returns `None` if there is no instance with the ID or it is in an invalid state.
+	pub fn state(&self, id: &K) -> Option<&S> {
+		self.instances
+			.get(id)
+			.and_then(|instance| instance.state.as_ref())
+	}
+
+	/// Creates a new instance, starting the machine for it.
+	/// An existing instance with the same ID is replaced.
+	///
+	/// Returns a `MachineError::CompletionLoop` error if completion transitions loop.
+	pub fn create(&mut self, id: K, ctx: &mut C) -> Result<(), MachineError<S>> {
+		let result = self.machine.start(ctx);
+		self.instances.insert(id, Self::instance(&self.machine));
+		result
+	}
+
+	/// Removes an instance.
+	/// Returns its current state if there was an instance with the ID.
+	pub fn remove(&mut self, id: &K) -> Option<Option<S>> {
+		self.instances.remove(id).map(|instance| instance.state)
+	}
+
+	/// Feeds an event to an instance.
+	/// Returns whether a transition was taken or `None` if there is no instance with the ID.
+	///
+	/// Returns a `MachineError::CompletionLoop` error if completion transitions loop.
+	pub fn feed(
+		&mut self,
+		id: &K,
+		event: &E,
+		ctx: &mut C,
+	) -> Option<Result<bool, MachineError<S>>> {
+		let instance = self.instances.get_mut(id)?;
+		let current = match &instance.state {
+			Some(current) => current.clone(),
+			None => return Some(Ok(false)),
+		};
+		#[cfg(feature = "metrics")]
+		self.machine.resume(current, instance.entered);
+		#[cfg(not(feature = "metrics"))]
+		self.machine.set_current(current);
+		let result = self.machine.handle(event, ctx);
+		*instance = Self::instance(&self.machine);
+		Some(result)
+	}
+
+	/// Returns an instance in the current state of a machine.
+	fn instance(machine: &Machine<S, E, C>) -> Instance<S> {
+		Instance {
+			state: machine.current().cloned(),
+			#[cfg(feature = "metrics")]
+			entered: machine.entered(),
+		}
+	}
+
+	/// Returns a snapshot of the current states of all instances.
+	pub fn snapshot(&self) -> Snapshot<K, S>
+	where
+		K: Clone,
+	{
+		self.instances
+			.iter()
+			.map(|(id, instance)| (id.clone(), instance.state.clone()))
+			.collect()
+	}
+
+	/// Replaces all instances with the ones from a snapshot.
+	pub fn restore(&mut self, snapshot: Snapshot<K, S>) {
+		self.instances = snapshot
+			.into_iter()
+			.map(|(id, state)| {
+				let instance = Instance {
+					state,
+					#[cfg(feature = "metrics")]
+					entered: None,
+				};
+				(id, instance)
+			})
+			.collect();
+	}
+}
+
+impl<K, S, E, C> fmt::Debug for WorkflowStore<K, S, E, C>
+where
+	K: Eq + Hash + fmt::Debug,
+	S: Default + Clone + Eq + Hash + fmt::Debug,
+	E: InputKey,
+	E::Key: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("WorkflowStore")
+			.field("machine", &self.machine)
+			.field("instances", &self.instances)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Automaton, Behavior, DFA};
+	use maplit::hashmap;
+
+	#[cfg(feature = "metrics")]
+	use metrics::{Counter, Gauge, Key, KeyName, Metadata, SharedString, Unit};
+	#[cfg(feature = "metrics")]
+	use std::{
+		sync::{Arc, Mutex},
+		thread,
+		time::Duration,
+	};
+
+	/// Store of orders which can be paid & shipped.
+	fn orders() -> WorkflowStore<u32, String, &'static str, ()> {
+		let mut dfa = DFA::<String, &str, Behavior<(), &str>>::with_state("new".into(), false);
+		dfa.add_state("paid".into(), false);
+		dfa.add_state("shipped".into(), true);
+		dfa.add_transition(("new".into(), "pay", "paid".into()))
+			.unwrap();
+		dfa.add_transition(("paid".into(), "ship", "shipped".into()))
+			.unwrap();
+		WorkflowStore::new(Machine::new(dfa))
+	}
+
+	#[test]
+	fn feed() {
+		let mut store = orders();
+		store.create(1, &mut ()).unwrap();
+		store.create(2, &mut ()).unwrap();
+		assert_eq!(
+			Some(true),
+			store.feed(&1, &"pay", &mut ()).map(Result::unwrap),
+			"Transition not taken"
+		);
+		assert_eq!(Some(&"paid".into()), store.state(&1), "Incorrect state");
+		assert_eq!(
+			Some(&"new".into()),
+			store.state(&2),
+			"Other instance changed"
+		);
+		assert!(
+			store.feed(&3, &"pay", &mut ()).is_none(),
+			"Event fed to inexistent instance"
+		);
+		store.feed(&2, &"ship", &mut ()).unwrap().unwrap();
+		assert!(store.contains(&2), "Invalid instance removed");
+		assert_eq!(None, store.state(&2), "Missing transition taken");
+	}
+
+	#[test]
+	fn snapshot() {
+		let mut store = orders();
+		store.create(1, &mut ()).unwrap();
+		store.feed(&1, &"pay", &mut ()).unwrap().unwrap();
+		let yaml = serde_yaml::to_string(&store.snapshot()).unwrap();
+
+		let mut restored = orders();
+		restored.restore(serde_yaml::from_str(&yaml).unwrap());
+		assert_eq!(
+			hashmap!(1 => Some("paid".to_string())),
+			restored.snapshot(),
+			"Incorrect snapshot after serialization"
+		);
+		restored.feed(&1, &"ship", &mut ()).unwrap().unwrap();
+		assert_eq!(
+			Some(&"shipped".into()),
+			restored.state(&1),
+			"Incorrect state"
+		);
+	}
+
+	/// Recorder collecting the recorded state durations together with their state label.
+	#[cfg(feature = "metrics")]
+	#[derive(Default)]
+	struct Durations(Arc<Mutex<Vec<(String, f64)>>>);
+
+	#[cfg(feature = "metrics")]
+	struct Histogram(String, Arc<Mutex<Vec<(String, f64)>>>);
+
+	#[cfg(feature = "metrics")]
+	impl metrics::HistogramFn for Histogram {
+		fn record(&self, value: f64) {
+			self.1.lock().unwrap().push((self.0.clone(), value));
+		}
+	}
+
+	#[cfg(feature = "metrics")]
+	impl metrics::Recorder for Durations {
+		fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+		fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+		fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+		fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+			Counter::noop()
+		}
+
+		fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+			Gauge::noop()
+		}
+
+		fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> metrics::Histogram {
+			let state = key
+				.labels()
+				.find(|label| label.key() == "state")
+				.map(|label| label.value().to_string())
+				.unwrap_or_default();
+			metrics::Histogram::from_arc(Arc::new(Histogram(state, Arc::clone(&self.0))))
+		}
+	}
+
+	#[cfg(feature = "metrics")]
+	#[test]
+	fn durations() {
+		let recorder = Durations::default();
+		let mut store = orders();
+		metrics::with_local_recorder(&recorder, || {
+			store.create(1, &mut ()).unwrap();
+			thread::sleep(Duration::from_millis(50));
+			store.create(2, &mut ()).unwrap();
+			store.feed(&2, &"pay", &mut ()).unwrap().unwrap();
+			store.feed(&1, &"pay", &mut ()).unwrap().unwrap();
+			store.restore(store.snapshot());
+			store.feed(&1, &"ship", &mut ()).unwrap().unwrap();
+		});
+		let durations = recorder.0.lock().unwrap();
+		assert_eq!(2, durations.len(), "Duration of restored instance recorded");
+		assert_eq!("\"new\"", durations[0].0, "Incorrect state label");
+		assert!(durations[0].1 < 0.05, "Entry time of other instance used");
+		assert!(durations[1].1 >= 0.05, "Incorrect duration");
+	}
+}